@@ -22,9 +22,19 @@
 //! pattern = "GodProcess"
 //! threshold = 0.8
 //! fail_on_violation = true
+//!
+//! # Custom condition (Sprint 68)
+//! [[assertion]]
+//! name = "fast_fsync"
+//! type = "custom"
+//! expression = 'p99(duration, name="fsync") < 5ms'
 //! ```
+//!
+//! Custom expressions are parsed and type checked on load; errors point at
+//! the offending part of the expression in renacer.toml.
 
-use crate::assertion_types::Assertion;
+use crate::assertion_expr::{ExprError, Expression};
+use crate::assertion_types::{Assertion, AssertionType};
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::fs;
@@ -65,7 +75,7 @@ impl AssertionConfig {
         let content = fs::read_to_string(path)
             .with_context(|| format!("Failed to read {}", path.display()))?;
 
-        Self::from_toml_str(&content)
+        Self::parse(&content, &path.display().to_string())
     }
 
     /// Load assertions from a TOML string
@@ -78,7 +88,24 @@ impl AssertionConfig {
     ///
     /// Parsed assertion configuration
     pub fn from_toml_str(content: &str) -> Result<Self> {
-        toml::from_str(content).context("Failed to parse TOML")
+        Self::parse(content, "renacer.toml")
+    }
+
+    /// Parse and validate the custom expressions, naming `origin` in errors
+    fn parse(content: &str, origin: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content).context("Failed to parse TOML")?;
+        for (index, assertion) in config.assertion.iter().enumerate() {
+            if let AssertionType::Custom(custom) = &assertion.assertion_type {
+                if let Err(e) = Expression::parse(&custom.expression) {
+                    anyhow::bail!(
+                        "error in assertion '{}': {}",
+                        assertion.name,
+                        render_expression_error(content, origin, index, &custom.expression, &e)
+                    );
+                }
+            }
+        }
+        Ok(config)
     }
 
     /// Get enabled assertions only
@@ -99,6 +126,126 @@ impl AssertionConfig {
     }
 }
 
+/// Show an expression error at its line and column in the TOML source,
+/// or under the bare expression when it cannot be located
+fn render_expression_error(
+    content: &str,
+    origin: &str,
+    index: usize,
+    expression: &str,
+    error: &ExprError,
+) -> String {
+    let offsets = match expression_offsets(content, index) {
+        Some(offsets) if offsets.len() == expression.len() + 1 => offsets,
+        _ => return error.render(expression),
+    };
+    let start = offsets[error.span.start];
+    let end = offsets[error.span.end].max(start);
+
+    let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = content[start..]
+        .find('\n')
+        .map_or(content.len(), |i| start + i);
+    let line = content[line_start..line_end].trim_end_matches('\r');
+    let number = content[..start].matches('\n').count() + 1;
+    let column = content[line_start..start].chars().count();
+    let width = content[start..end.min(line_end)].chars().count().max(1);
+    let gutter = " ".repeat(number.to_string().len());
+
+    format!(
+        "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+        error.message,
+        gutter,
+        origin,
+        number,
+        column + 1,
+        gutter,
+        number,
+        line,
+        gutter,
+        " ".repeat(column),
+        "^".repeat(width)
+    )
+}
+
+/// Byte offset in `content` of every byte of the `index`-th assertion's
+/// decoded `expression` value (plus one for its end)
+fn expression_offsets(content: &str, index: usize) -> Option<Vec<usize>> {
+    let mut assertions = 0;
+    let mut current = None;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("[[assertion]]") {
+            current = Some(assertions);
+            assertions += 1;
+        } else if trimmed.starts_with('[') {
+            current = None;
+        } else if current == Some(index) {
+            let value = trimmed
+                .strip_prefix("expression")
+                .and_then(|rest| rest.trim_start().strip_prefix('='));
+            if let Some(value) = value.map(str::trim_start) {
+                // `value` is a suffix of `line`
+                return string_offsets(content, offset + line.len() - value.len());
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Offsets of the decoded bytes of the TOML string starting at `start`
+fn string_offsets(content: &str, start: usize) -> Option<Vec<usize>> {
+    let raw = &content[start..];
+    let mut offsets = Vec::new();
+
+    if let Some(body) = raw.strip_prefix("'''") {
+        let skip = if body.starts_with("\r\n") {
+            2
+        } else {
+            usize::from(body.starts_with('\n'))
+        };
+        let body_start = start + 3 + skip;
+        let len = content[body_start..].find("'''")?;
+        offsets.extend(body_start..=body_start + len);
+    } else if let Some(body) = raw.strip_prefix('\'') {
+        let len = body.find(['\'', '\n'])?;
+        offsets.extend(start + 1..=start + 1 + len);
+    } else if raw.starts_with('"') && !raw.starts_with("\"\"\"") {
+        let mut chars = raw.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            let at = start + i;
+            let decoded_len = match c {
+                '"' => {
+                    offsets.push(at);
+                    return Some(offsets);
+                }
+                '\\' => match chars.next()?.1 {
+                    escape @ ('u' | 'U') => {
+                        let digits = if escape == 'u' { 4 } else { 8 };
+                        let hex: String = (0..digits)
+                            .filter_map(|_| chars.next())
+                            .map(|(_, c)| c)
+                            .collect();
+                        char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?.len_utf8()
+                    }
+                    _ => 1,
+                },
+                c => {
+                    offsets.extend((0..c.len_utf8()).map(|k| at + k));
+                    continue;
+                }
+            };
+            offsets.extend(std::iter::repeat_n(at, decoded_len));
+        }
+        return None;
+    } else {
+        return None;
+    }
+    Some(offsets)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +413,53 @@ mod tests {
         assert_eq!(config.assertion.len(), 0);
     }
 
+    #[test]
+    fn test_parse_syscall_assertions() {
+        let toml = r#"
+            [[assertion]]
+            name = "offline"
+            type = "forbidden_syscall"
+            syscall_pattern = "connect"
+
+            [[assertion]]
+            name = "missing_files"
+            type = "error_budget"
+            syscall_pattern = "openat"
+            errno = "ENOENT"
+            max_errors = 5
+
+            [[assertion]]
+            name = "fsync_p95"
+            type = "latency_percentile"
+            syscall_pattern = "fsync"
+            percentile = 95
+            max_duration_us = 10000
+
+            [[assertion]]
+            name = "spawns"
+            type = "syscall_count"
+            syscall_pattern = "execve"
+            max_count = 10
+        "#;
+
+        let config = AssertionConfig::from_toml_str(toml).unwrap();
+        assert_eq!(config.assertion.len(), 4);
+        match &config.assertion[1].assertion_type {
+            AssertionType::ErrorBudget(eb) => {
+                assert_eq!(eb.errno.as_deref(), Some("ENOENT"));
+                assert_eq!(eb.max_errors, 5);
+            }
+            _ => panic!("Expected ErrorBudget assertion"),
+        }
+        match &config.assertion[2].assertion_type {
+            AssertionType::LatencyPercentile(lp) => {
+                assert_eq!(lp.percentile, 95.0);
+                assert_eq!(lp.max_duration_us, 10_000);
+            }
+            _ => panic!("Expected LatencyPercentile assertion"),
+        }
+    }
+
     #[test]
     fn test_parse_with_trace_name_pattern() {
         let toml = r#"
@@ -297,6 +491,46 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_invalid_custom_expression_points_into_toml() {
+        let toml = r#"[[assertion]]
+name = "fast"
+type = "custom"
+expression = "max(duration) < 5ms"
+
+[[assertion]]
+name = "fsync"
+type = "custom"
+expression = 'p99(duration, name="fsync") < 5'
+"#;
+
+        let error = AssertionConfig::from_toml_str(toml).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "error in assertion 'fsync': cannot compare a duration with a number; \
+             give numbers a unit such as 5ms\n \
+             --> renacer.toml:9:15\n  |\n\
+             9 | expression = 'p99(duration, name=\"fsync\") < 5'\n  \
+             |               ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_error_offsets_follow_string_escapes() {
+        let toml = r#"
+            [[assertion]]
+            name = "escaped"
+            type = "custom"
+            expression = "count(name == \"\u00e9\") == 0 && bytes > 1"
+        "#;
+
+        let error = AssertionConfig::from_toml_str(toml)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("--> renacer.toml:5:61"), "{}", error);
+        assert!(error.ends_with(&format!("| {}^^^^^", " ".repeat(60))));
+    }
+
     #[test]
     fn test_parse_missing_required_field() {
         let toml = r#"
@@ -16,11 +16,30 @@
 //! 4. If any fail_on_violation → panic! (fail CI)
 //! ```
 
+use crate::anti_patterns::{detect_anti_patterns, AntiPattern};
+use crate::assertion_expr::Expression;
 use crate::assertion_types::{
-    AntiPatternAssertion, Assertion, AssertionResult, AssertionType, AssertionValue,
-    CriticalPathAssertion, MemoryUsageAssertion, SpanCountAssertion,
+    AntiPatternAssertion, AntiPatternType, Assertion, AssertionResult, AssertionType,
+    AssertionValue, CriticalPathAssertion, CustomAssertion, ErrorBudgetAssertion,
+    ForbiddenSyscallAssertion, HermeticViolation, HermeticViolationKind,
+    LatencyPercentileAssertion, MemoryTrackingMode, MemoryUsageAssertion, SpanCountAssertion,
+    SyscallCountAssertion,
 };
-use crate::unified_trace::UnifiedTrace;
+use crate::causal_graph::CausalGraph;
+use crate::critical_path::find_critical_path;
+use crate::hermetic::{BuildAccesses, HermeticRules};
+use crate::memory::MemoryTimeline;
+use crate::network::family_name;
+use crate::syscalls::errno_number;
+use crate::unified_trace::{SyscallSpan, UnifiedTrace};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+
+/// Characters in the memory timeline of `memory_usage` results
+const MEMORY_SPARKLINE_WIDTH: usize = 20;
+
+/// Call sites listed in failures of syscall-level assertions
+const TOP_CALL_SITES: usize = 3;
 
 /// Assertion evaluation engine
 ///
@@ -28,9 +47,10 @@ use crate::unified_trace::UnifiedTrace;
 ///
 /// # Note
 ///
-/// Sprint 44 implementation uses simplified evaluation logic.
-/// Full integration with CausalGraph and AntiPatternDetector
-/// will be completed in a future sprint.
+/// Anti-pattern assertions run on the trace's causal graph (Sprint 66) and
+/// memory assertions on its address space or RSS samples (Sprint 67);
+/// critical path assertions still use simplified evaluation logic from
+/// Sprint 44.
 pub struct AssertionEngine {}
 
 impl AssertionEngine {
@@ -65,13 +85,279 @@ impl AssertionEngine {
             AssertionType::MemoryUsage(mu) => {
                 self.evaluate_memory_usage(&assertion.name, mu, trace)
             }
-            AssertionType::Custom(c) => {
-                // Custom assertions not yet implemented
+            AssertionType::Custom(c) => self.evaluate_custom(&assertion.name, c, trace),
+            AssertionType::ForbiddenSyscall(fs) => {
+                self.evaluate_forbidden_syscall(&assertion.name, fs, trace)
+            }
+            AssertionType::ErrorBudget(eb) => {
+                self.evaluate_error_budget(&assertion.name, eb, trace)
+            }
+            AssertionType::LatencyPercentile(lp) => {
+                self.evaluate_latency_percentile(&assertion.name, lp, trace)
+            }
+            AssertionType::SyscallCount(sc) => {
+                self.evaluate_syscall_count(&assertion.name, sc, trace)
+            }
+            AssertionType::Hermetic(_) => {
+                // Sprint 62: needs resolved file accesses, see `renacer hermetic`
                 AssertionResult::pass(
                     assertion.name.clone(),
-                    format!("Custom assertion '{}' not yet implemented", c.expression),
+                    "Hermetic assertions are checked by `renacer hermetic`".to_string(),
+                )
+            }
+        }
+    }
+
+    /// Evaluate custom expression assertion (Sprint 68)
+    fn evaluate_custom(
+        &self,
+        name: &str,
+        assertion: &CustomAssertion,
+        trace: &UnifiedTrace,
+    ) -> AssertionResult {
+        let expression = match Expression::parse(&assertion.expression) {
+            Ok(expression) => expression,
+            Err(e) => {
+                return AssertionResult::fail(
+                    name.to_string(),
+                    format!("Invalid expression: {}", e.render(&assertion.expression)),
                 )
             }
+        };
+
+        let evaluation = expression.evaluate(trace);
+        let result = if evaluation.holds {
+            AssertionResult::pass(name.to_string(), format!("{} holds", expression.source()))
+        } else {
+            AssertionResult::fail(
+                name.to_string(),
+                format!("Custom assertion failed: {}", evaluation.reasons.join("; ")),
+            )
+        };
+        match evaluation.values {
+            Some((actual, expected)) => result.with_values(
+                AssertionValue::String(actual),
+                AssertionValue::String(expected),
+            ),
+            None => result,
+        }
+    }
+
+    /// Evaluate forbidden syscall assertion (Sprint 69)
+    fn evaluate_forbidden_syscall(
+        &self,
+        name: &str,
+        assertion: &ForbiddenSyscallAssertion,
+        trace: &UnifiedTrace,
+    ) -> AssertionResult {
+        let pattern = &assertion.syscall_pattern;
+        let calls = match matching_syscalls(name, pattern, trace) {
+            Ok(calls) => calls,
+            Err(result) => return result,
+        };
+
+        if calls.is_empty() {
+            return AssertionResult::pass(
+                name.to_string(),
+                format!("No calls matching '{}'", pattern),
+            )
+            .with_values(AssertionValue::Calls(0), AssertionValue::Calls(0));
+        }
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for span in &calls {
+            match counts.iter_mut().find(|(n, _)| *n == span.name) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&span.name, 1)),
+            }
+        }
+        let counts: Vec<String> = counts
+            .iter()
+            .map(|(syscall, count)| format!("{} x{}", syscall, count))
+            .collect();
+        AssertionResult::fail(
+            name.to_string(),
+            format!(
+                "Forbidden syscall matching '{}' called {} ({}). Top call sites: {}",
+                pattern,
+                times(calls.len()),
+                counts.join(", "),
+                top_call_sites(&calls, trace, false)
+            ),
+        )
+        .with_values(AssertionValue::Calls(calls.len()), AssertionValue::Calls(0))
+    }
+
+    /// Evaluate error budget assertion (Sprint 69)
+    fn evaluate_error_budget(
+        &self,
+        name: &str,
+        assertion: &ErrorBudgetAssertion,
+        trace: &UnifiedTrace,
+    ) -> AssertionResult {
+        let pattern = &assertion.syscall_pattern;
+        let errno = match assertion.errno.as_deref() {
+            None => None,
+            Some(errno_name) => match errno_number(errno_name) {
+                Some(errno) => Some(errno),
+                None => {
+                    return AssertionResult::fail(
+                        name.to_string(),
+                        format!("Unknown errno '{}'", errno_name),
+                    )
+                }
+            },
+        };
+        let calls = match matching_syscalls(name, pattern, trace) {
+            Ok(calls) => calls,
+            Err(result) => return result,
+        };
+        let errors: Vec<&SyscallSpan> = calls
+            .into_iter()
+            .filter(|span| span.is_error() && errno.is_none_or(|errno| span.errno == Some(errno)))
+            .collect();
+
+        let what = format!(
+            "{} {} from '{}'",
+            errors.len(),
+            assertion
+                .errno
+                .as_ref()
+                .map_or("errors".to_string(), |errno| format!("{} errors", errno)),
+            pattern
+        );
+        let values = (
+            AssertionValue::Calls(errors.len()),
+            AssertionValue::Calls(assertion.max_errors),
+        );
+        if errors.len() <= assertion.max_errors {
+            AssertionResult::pass(
+                name.to_string(),
+                format!("{} within budget of {}", what, assertion.max_errors),
+            )
+            .with_values(values.0, values.1)
+        } else {
+            AssertionResult::fail(
+                name.to_string(),
+                format!(
+                    "{} exceed budget of {}. Top call sites: {}",
+                    what,
+                    assertion.max_errors,
+                    top_call_sites(&errors, trace, false)
+                ),
+            )
+            .with_values(values.0, values.1)
+        }
+    }
+
+    /// Evaluate latency percentile assertion (Sprint 69)
+    ///
+    /// Uses the nearest-rank percentile of the matching calls' durations.
+    fn evaluate_latency_percentile(
+        &self,
+        name: &str,
+        assertion: &LatencyPercentileAssertion,
+        trace: &UnifiedTrace,
+    ) -> AssertionResult {
+        let pattern = &assertion.syscall_pattern;
+        let percentile = assertion.percentile;
+        if !(percentile > 0.0 && percentile <= 100.0) {
+            return AssertionResult::fail(
+                name.to_string(),
+                format!("percentile must be in (0, 100], got {}", percentile),
+            );
+        }
+        let calls = match matching_syscalls(name, pattern, trace) {
+            Ok(calls) => calls,
+            Err(result) => return result,
+        };
+        if calls.is_empty() {
+            return AssertionResult::pass(
+                name.to_string(),
+                format!("No calls matching '{}'", pattern),
+            );
+        }
+
+        let mut durations: Vec<u64> = calls.iter().map(|span| span.duration_nanos).collect();
+        durations.sort_unstable();
+        let rank = (percentile / 100.0 * durations.len() as f64).ceil() as usize;
+        let value = durations[rank.clamp(1, durations.len()) - 1];
+        let max_nanos = assertion.max_duration_us.saturating_mul(1000);
+
+        let what = format!(
+            "p{} latency of '{}' is {} over {} calls",
+            percentile,
+            pattern,
+            format_nanos(value),
+            calls.len()
+        );
+        let values = (
+            AssertionValue::String(format_nanos(value)),
+            AssertionValue::String(format_nanos(max_nanos)),
+        );
+        if value <= max_nanos {
+            AssertionResult::pass(
+                name.to_string(),
+                format!("{} <= {}", what, format_nanos(max_nanos)),
+            )
+            .with_values(values.0, values.1)
+        } else {
+            let slow: Vec<&SyscallSpan> = calls
+                .into_iter()
+                .filter(|span| span.duration_nanos > max_nanos)
+                .collect();
+            AssertionResult::fail(
+                name.to_string(),
+                format!(
+                    "{}, exceeds maximum {}. Slowest call sites: {}",
+                    what,
+                    format_nanos(max_nanos),
+                    top_call_sites(&slow, trace, true)
+                ),
+            )
+            .with_values(values.0, values.1)
+        }
+    }
+
+    /// Evaluate syscall count assertion (Sprint 69)
+    fn evaluate_syscall_count(
+        &self,
+        name: &str,
+        assertion: &SyscallCountAssertion,
+        trace: &UnifiedTrace,
+    ) -> AssertionResult {
+        let pattern = &assertion.syscall_pattern;
+        let calls = match matching_syscalls(name, pattern, trace) {
+            Ok(calls) => calls,
+            Err(result) => return result,
+        };
+
+        let values = (
+            AssertionValue::Calls(calls.len()),
+            AssertionValue::Calls(assertion.max_count),
+        );
+        if calls.len() <= assertion.max_count {
+            AssertionResult::pass(
+                name.to_string(),
+                format!(
+                    "'{}' called {} <= {}",
+                    pattern,
+                    times(calls.len()),
+                    assertion.max_count
+                ),
+            )
+            .with_values(values.0, values.1)
+        } else {
+            AssertionResult::fail(
+                name.to_string(),
+                format!(
+                    "'{}' called {}, exceeds maximum {}. Top call sites: {}",
+                    pattern,
+                    times(calls.len()),
+                    assertion.max_count,
+                    top_call_sites(&calls, trace, false)
+                ),
+            )
+            .with_values(values.0, values.1)
         }
     }
 
@@ -124,24 +410,141 @@ impl AssertionEngine {
         }
     }
 
-    /// Evaluate anti-pattern assertion
+    /// Evaluate anti-pattern assertion (Sprint 66)
+    ///
+    /// Runs [`detect_anti_patterns`] on the trace's causal graph. A detected
+    /// pattern of the asserted kind is a violation when its process name
+    /// matches `process_name_pattern` and its score reaches `threshold`:
+    ///
+    /// - GodProcess: share of the critical path spent in the dominant process
+    /// - TightLoop: share of the trace's syscalls inside the loop
+    /// - PcieBottleneck: GPU transfer time relative to kernel time
+    ///
+    /// Patterns below the detector's own limits (80% of the critical path,
+    /// 1000 repetitions, 50% of kernel time) are never reported.
+    ///
+    /// `analysis::anti_pattern::AntiPatternDetector` is not used here: it
+    /// scores the trace as a whole, so its GodProcess always covers 100% of
+    /// the syscalls and its TightLoop fires on any run averaging under 10ms
+    /// between calls, and it names no process or spans to report.
     fn evaluate_anti_pattern(
         &self,
         name: &str,
         assertion: &AntiPatternAssertion,
-        _trace: &UnifiedTrace,
+        trace: &UnifiedTrace,
     ) -> AssertionResult {
-        // TODO Sprint 44: Full integration with CausalGraph + AntiPatternDetector
-        // For now, use placeholder logic
+        let process_filter = match assertion.process_name_pattern.as_deref().map(Regex::new) {
+            Some(Ok(regex)) => Some(regex),
+            Some(Err(e)) => {
+                return AssertionResult::fail(
+                    name.to_string(),
+                    format!("Invalid process_name_pattern: {}", e),
+                )
+            }
+            None => None,
+        };
+        let graph = match CausalGraph::from_unified_trace(trace) {
+            Ok(graph) => graph,
+            Err(e) => {
+                return AssertionResult::fail(
+                    name.to_string(),
+                    format!("Cannot build causal graph: {}", e),
+                )
+            }
+        };
+        let patterns = match detect_anti_patterns(&graph) {
+            Ok(patterns) => patterns,
+            Err(e) => {
+                return AssertionResult::fail(
+                    name.to_string(),
+                    format!("Anti-pattern detection failed: {}", e),
+                )
+            }
+        };
 
-        // Placeholder: No anti-patterns detected
-        AssertionResult::pass(
+        let names = process_names(trace);
+        let process_name = |pid: i32| {
+            names
+                .get(&pid)
+                .map_or(trace.process_span.name.as_str(), String::as_str)
+        };
+        let detected = patterns
+            .iter()
+            .filter_map(|pattern| {
+                let (pid, score) = match (pattern, &assertion.pattern) {
+                    (
+                        AntiPattern::GodProcess {
+                            process_id,
+                            critical_path_percentage,
+                            ..
+                        },
+                        AntiPatternType::GodProcess,
+                    ) => (*process_id as i32, critical_path_percentage / 100.0),
+                    (
+                        AntiPattern::TightLoop {
+                            repetition_count,
+                            node_range,
+                            ..
+                        },
+                        AntiPatternType::TightLoop,
+                    ) => (
+                        graph
+                            .get_span(node_range.0)
+                            .map_or(trace.process_span.pid, |span| span.process_id as i32),
+                        *repetition_count as f64 / trace.syscall_spans.len().max(1) as f64,
+                    ),
+                    (
+                        AntiPattern::PcieBottleneck {
+                            transfer_percentage,
+                            ..
+                        },
+                        AntiPatternType::PcieBottleneck,
+                    ) => (trace.process_span.pid, transfer_percentage / 100.0),
+                    _ => return None,
+                };
+                let matches = process_filter
+                    .as_ref()
+                    .is_none_or(|regex| regex.is_match(process_name(pid)));
+                matches.then_some((pattern, pid, score))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        let Some((pattern, pid, score)) = detected else {
+            return AssertionResult::pass(
+                name.to_string(),
+                format!("Anti-pattern {:?} not detected", assertion.pattern),
+            );
+        };
+        let values = (
+            AssertionValue::Percentage(score),
+            AssertionValue::Percentage(assertion.threshold),
+        );
+        if score < assertion.threshold {
+            return AssertionResult::pass(
+                name.to_string(),
+                format!(
+                    "{} in {} (pid {}) below threshold",
+                    pattern.name(),
+                    process_name(pid),
+                    pid
+                ),
+            )
+            .with_values(values.0, values.1);
+        }
+
+        AssertionResult::fail(
             name.to_string(),
             format!(
-                "Anti-pattern {:?} not detected (placeholder implementation)",
-                assertion.pattern
+                "{} in {} (pid {}): {} Offending spans: {}. Recommendation: {}",
+                pattern.name(),
+                process_name(pid),
+                pid,
+                pattern.description(),
+                offending_spans(pattern, &graph, trace),
+                pattern.recommendation()
             ),
         )
+        .with_values(values.0, values.1)
     }
 
     /// Evaluate span count assertion
@@ -177,52 +580,151 @@ impl AssertionEngine {
         }
     }
 
-    /// Evaluate memory usage assertion
+    /// Evaluate memory usage assertion (Sprint 67)
+    ///
+    /// `Allocations` replays the trace's mmap/munmap/mremap/brk calls into
+    /// per-process address spaces; `Rss` uses the resident set samples taken
+    /// while recording. Either way the peak total is compared to `max_bytes`,
+    /// and the message carries a sparkline of the timeline.
     fn evaluate_memory_usage(
         &self,
         name: &str,
         assertion: &MemoryUsageAssertion,
         trace: &UnifiedTrace,
     ) -> AssertionResult {
-        // Sum up memory allocations from syscalls (mmap, brk)
-        let mut total_bytes = 0u64;
-
-        for span in &trace.syscall_spans {
-            // Check if this is a memory allocation syscall
-            if span.name == "mmap" || span.name == "brk" {
-                // Parse allocation size from args (simplified)
-                // In reality, we'd need proper syscall argument parsing
-                total_bytes += 4096; // Placeholder: assume 4KB per allocation
+        let (what, timeline) = match assertion.tracking_mode {
+            MemoryTrackingMode::Allocations => (
+                "address-space growth",
+                MemoryTimeline::from_address_space(trace),
+            ),
+            MemoryTrackingMode::Rss => {
+                if trace.rss_samples.is_empty() {
+                    return AssertionResult::fail(
+                        name.to_string(),
+                        "No RSS samples in the trace (record it with `renacer record`)".to_string(),
+                    );
+                }
+                ("RSS", MemoryTimeline::from_rss_samples(&trace.rss_samples))
             }
-        }
+        };
+        let (peak_at, peak) = timeline.peak().unwrap_or((0, 0));
+        let sparkline = timeline.sparkline(MEMORY_SPARKLINE_WIDTH);
+        let timeline = if sparkline.is_empty() {
+            String::new()
+        } else {
+            format!(", timeline {}", sparkline)
+        };
 
-        if total_bytes <= assertion.max_bytes {
+        if peak <= assertion.max_bytes {
             AssertionResult::pass(
                 name.to_string(),
                 format!(
-                    "Memory usage {} bytes <= {}",
-                    total_bytes, assertion.max_bytes
+                    "Peak {} {} bytes <= {}{}",
+                    what, peak, assertion.max_bytes, timeline
                 ),
             )
             .with_values(
-                AssertionValue::Bytes(total_bytes),
+                AssertionValue::Bytes(peak),
                 AssertionValue::Bytes(assertion.max_bytes),
             )
         } else {
             AssertionResult::fail(
                 name.to_string(),
                 format!(
-                    "Memory usage {} bytes exceeds maximum {}",
-                    total_bytes, assertion.max_bytes
+                    "Peak {} {} bytes at {}ms exceeds maximum {}{}",
+                    what,
+                    peak,
+                    peak_at / 1_000_000,
+                    assertion.max_bytes,
+                    timeline
                 ),
             )
             .with_values(
-                AssertionValue::Bytes(total_bytes),
+                AssertionValue::Bytes(peak),
                 AssertionValue::Bytes(assertion.max_bytes),
             )
         }
     }
 
+    /// Find the accesses of a traced build that break hermeticity rules (Sprint 62)
+    ///
+    /// Each path or socket family is reported once, for the first process
+    /// that touched it.
+    pub fn hermetic_violations(
+        &self,
+        rules: &HermeticRules,
+        accesses: &BuildAccesses,
+    ) -> Vec<HermeticViolation> {
+        let mut violations = Vec::new();
+        let mut seen = HashSet::new();
+        let mut report = |violation: HermeticViolation| {
+            if seen.insert((violation.kind, violation.detail.clone())) {
+                violations.push(violation);
+            }
+        };
+
+        for process in accesses.files().processes() {
+            for path in process.executed.iter().chain(&process.reads) {
+                if let Some(violation) = rules.check_input(process.pid, path) {
+                    report(violation);
+                }
+            }
+            let changed = process
+                .writes
+                .iter()
+                .chain(&process.creates)
+                .chain(&process.deletes);
+            for path in changed {
+                if let Some(violation) = rules.check_output(process.pid, path) {
+                    report(violation);
+                }
+            }
+        }
+
+        if !rules.allow_network {
+            for access in accesses.network() {
+                report(HermeticViolation {
+                    kind: HermeticViolationKind::Network,
+                    pid: access.pid,
+                    detail: format!("{}({})", access.syscall, family_name(access.family)),
+                });
+            }
+        }
+        violations
+    }
+
+    /// Evaluate a hermetic assertion from its violations (Sprint 62)
+    pub fn evaluate_hermetic(
+        &self,
+        name: &str,
+        violations: &[HermeticViolation],
+    ) -> AssertionResult {
+        if violations.is_empty() {
+            return AssertionResult::pass(
+                name.to_string(),
+                "Build only touched declared inputs, toolchain and outputs".to_string(),
+            )
+            .with_values(AssertionValue::Count(0), AssertionValue::Count(0));
+        }
+
+        let mut kinds: Vec<HermeticViolationKind> = violations.iter().map(|v| v.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        let kinds: Vec<String> = kinds.iter().map(|kind| kind.to_string()).collect();
+        AssertionResult::fail(
+            name.to_string(),
+            format!(
+                "Build is not hermetic: {} violations ({})",
+                violations.len(),
+                kinds.join(", ")
+            ),
+        )
+        .with_values(
+            AssertionValue::Count(violations.len()),
+            AssertionValue::Count(0),
+        )
+    }
+
     /// Evaluate all assertions against a trace
     ///
     /// # Arguments
@@ -252,6 +754,148 @@ impl AssertionEngine {
     }
 }
 
+/// Program of each process, from its last `execve` (Sprint 66)
+pub(crate) fn process_names(trace: &UnifiedTrace) -> HashMap<i32, String> {
+    let mut names = HashMap::new();
+    names.insert(trace.process_span.pid, trace.process_span.name.clone());
+    for span in &trace.syscall_spans {
+        if span.name == "execve" && span.return_value == 0 {
+            if let Some((_, path)) = span.args.first() {
+                let pid = span.pid.unwrap_or(trace.process_span.pid);
+                names.insert(pid, path.trim_matches('"').to_string());
+            }
+        }
+    }
+    names
+}
+
+/// The spans behind a detected anti-pattern, for failure messages (Sprint 66)
+fn offending_spans(pattern: &AntiPattern, graph: &CausalGraph, trace: &UnifiedTrace) -> String {
+    match pattern {
+        AntiPattern::GodProcess { process_id, .. } => {
+            let Ok(critical_path) = find_critical_path(graph) else {
+                return "critical path unavailable".to_string();
+            };
+            let mut spans: Vec<_> = critical_path
+                .path
+                .iter()
+                .filter_map(|&node| graph.get_span(node))
+                .filter(|span| span.process_id == *process_id && span.parent_span_id.is_some())
+                .collect();
+            let count = spans.len();
+            spans.sort_by_key(|span| std::cmp::Reverse(span.duration_nanos));
+            let longest: Vec<String> = spans
+                .iter()
+                .take(3)
+                .map(|span| format!("{} {}ns", span.span_name, span.duration_nanos))
+                .collect();
+            format!(
+                "{} of {} critical path spans (longest: {})",
+                count,
+                critical_path.path.len(),
+                longest.join(", ")
+            )
+        }
+        // Node 0 is the process span, so node N is the Nth syscall
+        AntiPattern::TightLoop {
+            syscall_name,
+            repetition_count,
+            node_range: (start, end),
+            ..
+        } => format!(
+            "syscalls #{}-#{} ({} x{})",
+            start.0, end.0, syscall_name, repetition_count
+        ),
+        AntiPattern::PcieBottleneck { .. } => {
+            let transfers: Vec<String> = trace
+                .gpu_memory_transfers
+                .iter()
+                .map(|t| format!("{} {}us", t.label, t.duration_us))
+                .collect();
+            format!("transfers {}", transfers.join(", "))
+        }
+    }
+}
+
+/// Syscall spans whose whole name matches `pattern` (Sprint 69)
+///
+/// An invalid pattern becomes a failed result for assertion `name`.
+fn matching_syscalls<'a>(
+    name: &str,
+    pattern: &str,
+    trace: &'a UnifiedTrace,
+) -> Result<Vec<&'a SyscallSpan>, AssertionResult> {
+    let regex = Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+        AssertionResult::fail(
+            name.to_string(),
+            format!("Invalid syscall_pattern '{}': {}", pattern, e),
+        )
+    })?;
+    Ok(trace
+        .syscall_spans
+        .iter()
+        .filter(|span| regex.is_match(&span.name))
+        .collect())
+}
+
+fn times(count: usize) -> String {
+    format!("{} time{}", count, if count == 1 { "" } else { "s" })
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format!("{:.3}ms", nanos as f64 / 1_000_000.0)
+}
+
+/// The most frequent call sites of `spans`, for failure messages (Sprint 69)
+///
+/// Spans without a known source location or stack are grouped by program
+/// and syscall instead. With `slowest`, each site also shows its longest
+/// call.
+fn top_call_sites(spans: &[&SyscallSpan], trace: &UnifiedTrace, slowest: bool) -> String {
+    let names = process_names(trace);
+    let mut sites: Vec<(String, usize, u64)> = Vec::new();
+    for span in spans {
+        let site = match &span.call_site {
+            Some(site) => site.clone(),
+            None => {
+                let pid = span.pid.unwrap_or(trace.process_span.pid);
+                let program = names.get(&pid).map_or("?", String::as_str);
+                let program = program.rsplit('/').next().unwrap_or(program);
+                format!("{}: {}", program, span.name)
+            }
+        };
+        match sites.iter_mut().find(|(s, _, _)| *s == site) {
+            Some((_, count, longest)) => {
+                *count += 1;
+                *longest = (*longest).max(span.duration_nanos);
+            }
+            None => sites.push((site, 1, span.duration_nanos)),
+        }
+    }
+    // Stable sort keeps first-seen order among equals
+    if slowest {
+        sites.sort_by_key(|(_, _, longest)| std::cmp::Reverse(*longest));
+    } else {
+        sites.sort_by_key(|(_, count, _)| std::cmp::Reverse(*count));
+    }
+
+    let mut shown: Vec<String> = sites
+        .iter()
+        .take(TOP_CALL_SITES)
+        .map(|(site, count, longest)| {
+            if slowest {
+                format!("{}x {} (slowest {})", count, site, format_nanos(*longest))
+            } else {
+                format!("{}x {}", count, site)
+            }
+        })
+        .collect();
+    if sites.len() > TOP_CALL_SITES {
+        shown.push(format!("{} more", sites.len() - TOP_CALL_SITES));
+    }
+    shown.join(", ")
+}
+
 impl Default for AssertionEngine {
     fn default() -> Self {
         Self::new()
@@ -263,7 +907,6 @@ mod tests {
     use super::*;
     use crate::assertion_types::{CriticalPathAssertion, SpanCountAssertion};
     use crate::trace_context::LamportClock;
-    use crate::unified_trace::SyscallSpan;
     use std::borrow::Cow;
 
     fn create_test_span(syscall_name: &str, start_ns: u64, duration_ns: u64) -> SyscallSpan {
@@ -515,8 +1158,169 @@ mod tests {
         };
 
         let result = engine.evaluate(&assertion, &trace);
-        // Should pass because anti-pattern detection is placeholder
+        // A single process cannot be a god process
         assert!(result.passed);
+        assert_eq!(result.message, "Anti-pattern GodProcess not detected");
+    }
+
+    fn anti_pattern(
+        pattern: AntiPatternType,
+        threshold: f64,
+        process_name_pattern: Option<&str>,
+    ) -> Assertion {
+        Assertion {
+            name: "anti_pattern".to_string(),
+            assertion_type: AssertionType::AntiPattern(AntiPatternAssertion {
+                pattern,
+                threshold,
+                process_name_pattern: process_name_pattern.map(str::to_string),
+            }),
+            fail_on_violation: true,
+            enabled: true,
+        }
+    }
+
+    fn add_syscall(
+        trace: &mut UnifiedTrace,
+        pid: i32,
+        name: &'static str,
+        args: &[&str],
+        result: i64,
+        duration_ns: u64,
+    ) {
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| (Cow::Owned(format!("arg{}", i)), arg.to_string()))
+            .collect();
+        let span = SyscallSpan::new(
+            trace.process_span.span_id,
+            Cow::Borrowed(name),
+            args,
+            result,
+            0,
+            duration_ns,
+            (result < 0).then(|| -result as i32),
+            &trace.clock,
+        )
+        .with_pid(pid);
+        trace.add_syscall(span);
+    }
+
+    #[test]
+    fn test_evaluate_tight_loop() {
+        let engine = AssertionEngine::new();
+        let mut trace = UnifiedTrace::new(7, "/usr/bin/poller".to_string());
+        for _ in 0..100 {
+            add_syscall(&mut trace, 7, "mmap", &[], 0, 10);
+        }
+        for _ in 0..1200 {
+            add_syscall(&mut trace, 7, "getppid", &[], 1, 10);
+        }
+
+        // 1200 of 1300 syscalls are in the loop
+        let result = engine.evaluate(&anti_pattern(AntiPatternType::TightLoop, 0.9, None), &trace);
+        assert!(!result.passed);
+        assert!(result.message.starts_with(
+            "Tight Loop in /usr/bin/poller (pid 7): Syscall 'getppid' repeated 1200 times"
+        ));
+        assert!(result
+            .message
+            .contains("Offending spans: syscalls #101-#1300 (getppid x1200)."));
+        assert!(result
+            .message
+            .contains("Recommendation: Use vectorized I/O (readv/writev)"));
+        assert_eq!(result.actual_value.unwrap().to_string(), "92.3%");
+
+        let result = engine.evaluate(
+            &anti_pattern(AntiPatternType::TightLoop, 0.95, None),
+            &trace,
+        );
+        assert!(result.passed);
+        assert_eq!(
+            result.message,
+            "Tight Loop in /usr/bin/poller (pid 7) below threshold"
+        );
+
+        let result = engine.evaluate(
+            &anti_pattern(AntiPatternType::TightLoop, 0.5, Some("^api_")),
+            &trace,
+        );
+        assert!(result.passed);
+        assert_eq!(result.message, "Anti-pattern TightLoop not detected");
+
+        let result = engine.evaluate(
+            &anti_pattern(AntiPatternType::GodProcess, 0.5, None),
+            &trace,
+        );
+        assert!(result.passed);
+    }
+
+    #[test]
+    fn test_evaluate_god_process() {
+        let engine = AssertionEngine::new();
+        let mut trace = UnifiedTrace::new(1, "/bin/sh".to_string());
+        add_syscall(&mut trace, 1, "clone", &[], 2, 10);
+        add_syscall(&mut trace, 2, "execve", &["\"/usr/bin/worker\""], 0, 10);
+        add_syscall(&mut trace, 1, "wait4", &[], 2, 100);
+        add_syscall(&mut trace, 2, "read", &[], 4096, 10_000);
+
+        let assertion = anti_pattern(AntiPatternType::GodProcess, 0.9, Some("worker$"));
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert!(result
+            .message
+            .starts_with("God Process in /usr/bin/worker (pid 2): Process 2 dominates"));
+        assert!(result.message.contains(
+            "Offending spans: 2 of 4 critical path spans (longest: read 10000ns, execve 10ns)."
+        ));
+        assert!(result.message.contains("Recommendation: Decompose"));
+
+        let assertion = anti_pattern(AntiPatternType::GodProcess, 0.9, Some("^/bin/sh$"));
+        assert!(engine.evaluate(&assertion, &trace).passed);
+    }
+
+    #[test]
+    fn test_evaluate_pcie_bottleneck() {
+        use crate::otlp_exporter::{GpuKernel, GpuMemoryTransfer, TransferDirection};
+
+        let engine = AssertionEngine::new();
+        let mut trace = UnifiedTrace::new(1, "gpu_app".to_string());
+        trace.add_gpu_kernel(GpuKernel {
+            kernel: "matmul".to_string(),
+            duration_us: 100,
+            backend: "wgpu",
+            workgroup_size: None,
+            elements: None,
+            is_slow: false,
+        });
+        trace.add_gpu_memory_transfer(GpuMemoryTransfer::new(
+            "weights".to_string(),
+            TransferDirection::CpuToGpu,
+            1 << 20,
+            150,
+            None,
+            100,
+        ));
+
+        let assertion = anti_pattern(AntiPatternType::PcieBottleneck, 0.85, None);
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert!(result
+            .message
+            .contains("Offending spans: transfers weights 150us."));
+        assert!(result.message.contains("Recommendation: Fuse GPU kernels"));
+        assert_eq!(result.actual_value.unwrap().to_string(), "150.0%");
+    }
+
+    #[test]
+    fn test_evaluate_anti_pattern_invalid_regex() {
+        let engine = AssertionEngine::new();
+        let trace = UnifiedTrace::new(1, "test".to_string());
+        let assertion = anti_pattern(AntiPatternType::TightLoop, 0.5, Some("("));
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert!(result.message.starts_with("Invalid process_name_pattern"));
     }
 
     #[test]
@@ -543,68 +1347,95 @@ mod tests {
         assert!(result.passed);
     }
 
-    #[test]
-    fn test_evaluate_memory_usage_with_mmap() {
-        use crate::assertion_types::{MemoryTrackingMode, MemoryUsageAssertion};
-        use std::borrow::Cow;
-
-        let engine = AssertionEngine::new();
-        let mut trace = UnifiedTrace::new(1, "test".to_string());
-
-        // Add mmap syscalls
-        let clock = LamportClock::new();
-        let mmap_span =
-            SyscallSpan::new(1, Cow::Borrowed("mmap"), vec![], 0, 0, 1000, None, &clock);
-        trace.add_syscall(mmap_span);
-
-        let assertion = Assertion {
+    fn memory_usage(max_bytes: u64, tracking_mode: MemoryTrackingMode) -> Assertion {
+        Assertion {
             name: "test_memory".to_string(),
             assertion_type: AssertionType::MemoryUsage(MemoryUsageAssertion {
-                max_bytes: 10_000, // 10KB (more than 4KB placeholder)
-                tracking_mode: MemoryTrackingMode::Allocations,
+                max_bytes,
+                tracking_mode,
             }),
             fail_on_violation: true,
             enabled: true,
-        };
+        }
+    }
 
-        let result = engine.evaluate(&assertion, &trace);
+    #[test]
+    fn test_evaluate_memory_usage_with_mmap() {
+        let engine = AssertionEngine::new();
+        let mut trace = UnifiedTrace::new(1, "test".to_string());
+
+        // 8KB mapped, then unmapped again
+        add_syscall(
+            &mut trace,
+            1,
+            "mmap",
+            &["0x0", "0x2000", "0x3"],
+            0x7000_0000,
+            10,
+        );
+        add_syscall(&mut trace, 1, "munmap", &["0x70000000", "0x2000"], 0, 10);
+
+        let result = engine.evaluate(
+            &memory_usage(10_000, MemoryTrackingMode::Allocations),
+            &trace,
+        );
         assert!(result.passed);
+        assert!(result
+            .message
+            .starts_with("Peak address-space growth 8192 bytes <= 10000, timeline "));
     }
 
     #[test]
     fn test_evaluate_memory_usage_fail() {
-        use crate::assertion_types::{MemoryTrackingMode, MemoryUsageAssertion};
-        use std::borrow::Cow;
-
         let engine = AssertionEngine::new();
         let mut trace = UnifiedTrace::new(1, "test".to_string());
 
-        // Add many mmap syscalls to exceed limit
-        let clock = LamportClock::new();
-        for _i in 0..10 {
-            let mmap_span =
-                SyscallSpan::new(1, Cow::Borrowed("mmap"), vec![], 0, 0, 1000, None, &clock);
-            trace.add_syscall(mmap_span);
+        // Ten separate 1MB mappings
+        for i in 0..10 {
+            let addr = 0x7000_0000 + i * 0x20_0000;
+            add_syscall(&mut trace, 1, "mmap", &["0x0", "0x100000", "0x3"], addr, 10);
         }
 
-        let assertion = Assertion {
-            name: "test_memory".to_string(),
-            assertion_type: AssertionType::MemoryUsage(MemoryUsageAssertion {
-                max_bytes: 1000, // Very low limit (< 10 * 4KB placeholder)
-                tracking_mode: MemoryTrackingMode::Allocations,
-            }),
-            fail_on_violation: true,
-            enabled: true,
-        };
+        let result = engine.evaluate(
+            &memory_usage(5_000_000, MemoryTrackingMode::Allocations),
+            &trace,
+        );
+        assert!(!result.passed);
+        assert!(result.message.starts_with(
+            "Peak address-space growth 10485760 bytes at 0ms exceeds maximum 5000000"
+        ));
+        assert_eq!(result.actual_value.unwrap().to_string(), "10485760 bytes");
+    }
+
+    #[test]
+    fn test_evaluate_memory_usage_rss() {
+        use crate::memory::MemorySample;
 
+        let engine = AssertionEngine::new();
+        let mut trace = UnifiedTrace::new(1, "test".to_string());
+        let assertion = memory_usage(4096, MemoryTrackingMode::Rss);
+
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert!(result.message.starts_with("No RSS samples in the trace"));
+
+        for (timestamp_us, bytes) in [(0, 1024), (2_000, 8192), (4_000, 2048)] {
+            trace.rss_samples.push(MemorySample {
+                timestamp_us,
+                pid: 1,
+                bytes,
+            });
+        }
         let result = engine.evaluate(&assertion, &trace);
         assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            "Peak RSS 8192 bytes at 2ms exceeds maximum 4096, timeline ▁▁▁▁▁▁▁▁▁▁█████████▂"
+        );
     }
 
     #[test]
     fn test_evaluate_custom_assertion() {
-        use crate::assertion_types::CustomAssertion;
-
         let engine = AssertionEngine::new();
         let mut trace = UnifiedTrace::new(1, "test".to_string());
         trace.add_syscall(create_test_span("write", 0, 100_000));
@@ -612,16 +1443,51 @@ mod tests {
         let assertion = Assertion {
             name: "test_custom".to_string(),
             assertion_type: AssertionType::Custom(CustomAssertion {
-                expression: "duration < 100ms".to_string(),
+                expression: "max(duration) < 100ms".to_string(),
             }),
             fail_on_violation: true,
             enabled: true,
         };
 
         let result = engine.evaluate(&assertion, &trace);
-        // Should pass because custom assertions not yet implemented
         assert!(result.passed);
-        assert!(result.message.contains("not yet implemented"));
+        assert_eq!(result.message, "max(duration) < 100ms holds");
+        assert_eq!(
+            result.actual_value,
+            Some(AssertionValue::String("100.00us".to_string()))
+        );
+
+        let assertion = Assertion {
+            assertion_type: AssertionType::Custom(CustomAssertion {
+                expression: r#"count(name="write") == 0 && syscalls < 5"#.to_string(),
+            }),
+            ..assertion
+        };
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            r#"Custom assertion failed: count(syscall == "write") is 1, expected == 0"#
+        );
+    }
+
+    #[test]
+    fn test_evaluate_invalid_custom_assertion() {
+        let assertion = Assertion {
+            name: "test_custom".to_string(),
+            assertion_type: AssertionType::Custom(CustomAssertion {
+                expression: "duration < 100ms".to_string(),
+            }),
+            fail_on_violation: true,
+            enabled: true,
+        };
+
+        let result =
+            AssertionEngine::new().evaluate(&assertion, &UnifiedTrace::new(1, "test".to_string()));
+        assert!(!result.passed);
+        assert!(result
+            .message
+            .starts_with("Invalid expression: `duration` is a per-syscall field"));
     }
 
     #[test]
@@ -695,28 +1561,286 @@ mod tests {
 
     #[test]
     fn test_evaluate_brk_syscall() {
-        use crate::assertion_types::{MemoryTrackingMode, MemoryUsageAssertion};
-        use std::borrow::Cow;
-
         let engine = AssertionEngine::new();
         let mut trace = UnifiedTrace::new(1, "test".to_string());
 
-        // Add brk syscall (alternative to mmap)
-        let clock = LamportClock::new();
-        let brk_span = SyscallSpan::new(1, Cow::Borrowed("brk"), vec![], 0, 0, 1000, None, &clock);
-        trace.add_syscall(brk_span);
+        // Heap grown by 132KB from the initial break
+        add_syscall(&mut trace, 1, "brk", &["0x0"], 0x55_0000, 10);
+        add_syscall(&mut trace, 1, "brk", &["0x571000"], 0x57_1000, 10);
 
-        let assertion = Assertion {
-            name: "test_memory".to_string(),
-            assertion_type: AssertionType::MemoryUsage(MemoryUsageAssertion {
-                max_bytes: 10_000,
-                tracking_mode: MemoryTrackingMode::Allocations,
-            }),
+        let assertion = memory_usage(100_000, MemoryTrackingMode::Allocations);
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert_eq!(result.actual_value.unwrap().to_string(), "135168 bytes");
+    }
+
+    #[test]
+    fn test_hermetic_violations() {
+        use crate::assertion_types::HermeticAssertion;
+        use crate::trace_file::SyscallRecord;
+        use std::path::Path;
+
+        let syscall = |name: &str, args: &[&str], raw_args: [u64; 3], result: i64| SyscallRecord {
+            pid: 42,
+            timestamp_us: 0,
+            duration_us: 0,
+            number: 0,
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            raw_args,
+            result,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: vec![],
+            wait_object: None,
+            stderr: None,
+        };
+        let o_wronly_creat = (libc::O_WRONLY | libc::O_CREAT) as u64;
+        let accesses = BuildAccesses::from_run(
+            &[
+                syscall("open", &["\"src/lib.rs\"", "O_RDONLY"], [0, 0, 0], 3),
+                syscall("open", &["\"/etc/hosts\"", "O_RDONLY"], [0, 0, 0], 4),
+                syscall("open", &["\"/etc/hosts\"", "O_RDONLY"], [0, 0, 0], 5),
+                syscall(
+                    "open",
+                    &["\"out/lib.o\"", "O_WRONLY"],
+                    [0, o_wronly_creat, 0],
+                    6,
+                ),
+                syscall(
+                    "open",
+                    &["\"/tmp/x\"", "O_WRONLY"],
+                    [0, o_wronly_creat, 0],
+                    7,
+                ),
+                syscall("socket", &[], [libc::AF_INET6 as u64, 1, 0], 8),
+            ],
+            Some("cc"),
+        );
+
+        let engine = AssertionEngine::new();
+        let mut assertion = HermeticAssertion {
+            inputs: vec!["src".to_string()],
+            outputs: vec!["out".to_string()],
+            ..HermeticAssertion::default()
+        };
+        let rules = HermeticRules::new(&assertion, Path::new("/build"), |_| None);
+        let violations = engine.hermetic_violations(&rules, &accesses);
+        let found: Vec<_> = violations
+            .iter()
+            .map(|v| (v.kind, v.detail.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (HermeticViolationKind::UndeclaredInput, "/etc/hosts"),
+                (HermeticViolationKind::UndeclaredOutput, "/tmp/x"),
+                (HermeticViolationKind::Network, "socket(AF_INET6)"),
+            ]
+        );
+
+        let result = engine.evaluate_hermetic("hermetic", &violations);
+        assert!(!result.passed);
+        assert!(result.message.contains("3 violations"));
+
+        assertion.inputs.push("/etc/hosts".to_string());
+        assertion.outputs.push("/tmp".to_string());
+        assertion.allow_network = true;
+        let rules = HermeticRules::new(&assertion, Path::new("/build"), |_| None);
+        let violations = engine.hermetic_violations(&rules, &accesses);
+        assert!(violations.is_empty());
+        assert!(engine.evaluate_hermetic("hermetic", &violations).passed);
+    }
+
+    fn syscall_assertion(assertion_type: AssertionType) -> Assertion {
+        Assertion {
+            name: "syscalls".to_string(),
+            assertion_type,
             fail_on_violation: true,
             enabled: true,
-        };
+        }
+    }
+
+    /// `make` opens files, runs `cc` twice and fsyncs its output
+    fn build_trace() -> UnifiedTrace {
+        let mut trace = UnifiedTrace::new(1, "/usr/bin/make".to_string());
+        add_syscall(
+            &mut trace,
+            1,
+            "openat",
+            &["AT_FDCWD", "\"a.mk\""],
+            -2,
+            1_000,
+        );
+        add_syscall(
+            &mut trace,
+            1,
+            "openat",
+            &["AT_FDCWD", "\"a.mk\""],
+            -2,
+            1_000,
+        );
+        add_syscall(
+            &mut trace,
+            1,
+            "openat",
+            &["AT_FDCWD", "\"b.mk\""],
+            -13,
+            1_000,
+        );
+        add_syscall(
+            &mut trace,
+            1,
+            "openat",
+            &["AT_FDCWD", "\"Makefile\""],
+            3,
+            1_000,
+        );
+        add_syscall(&mut trace, 2, "execve", &["\"/usr/bin/cc\""], 0, 50_000);
+        add_syscall(&mut trace, 3, "execve", &["\"/usr/bin/cc\""], 0, 50_000);
+        add_syscall(&mut trace, 2, "fsync", &["3"], 0, 2_000_000);
+        add_syscall(&mut trace, 3, "fsync", &["3"], 0, 12_000_000);
+        trace
+    }
+
+    #[test]
+    fn test_forbidden_syscall() {
+        let engine = AssertionEngine::new();
+        let mut trace = build_trace();
 
+        let assertion =
+            syscall_assertion(AssertionType::ForbiddenSyscall(ForbiddenSyscallAssertion {
+                syscall_pattern: "connect|sendto".to_string(),
+            }));
+        let result = engine.evaluate(&assertion, &trace);
+        assert!(result.passed);
+        assert_eq!(result.message, "No calls matching 'connect|sendto'");
+
+        add_syscall(
+            &mut trace,
+            2,
+            "connect",
+            &["3", "{sa_family=AF_INET}"],
+            0,
+            10,
+        );
+        trace.syscall_spans.last_mut().unwrap().call_site =
+            Some("src/net.rs:12 (fetch)".to_string());
         let result = engine.evaluate(&assertion, &trace);
+        assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            "Forbidden syscall matching 'connect|sendto' called 1 time (connect x1). \
+             Top call sites: 1x src/net.rs:12 (fetch)"
+        );
+    }
+
+    #[test]
+    fn test_syscall_patterns_match_whole_names() {
+        let engine = AssertionEngine::new();
+        let assertion =
+            syscall_assertion(AssertionType::ForbiddenSyscall(ForbiddenSyscallAssertion {
+                syscall_pattern: "open".to_string(),
+            }));
+        assert!(engine.evaluate(&assertion, &build_trace()).passed);
+
+        let assertion = syscall_assertion(AssertionType::SyscallCount(SyscallCountAssertion {
+            syscall_pattern: "(".to_string(),
+            max_count: 1,
+        }));
+        let result = engine.evaluate(&assertion, &build_trace());
+        assert!(!result.passed);
+        assert!(result.message.starts_with("Invalid syscall_pattern '('"));
+    }
+
+    #[test]
+    fn test_error_budget() {
+        let engine = AssertionEngine::new();
+        let budget = |errno: Option<&str>, max_errors| {
+            syscall_assertion(AssertionType::ErrorBudget(ErrorBudgetAssertion {
+                syscall_pattern: "open.*".to_string(),
+                errno: errno.map(str::to_string),
+                max_errors,
+            }))
+        };
+
+        let result = engine.evaluate(&budget(Some("ENOENT"), 2), &build_trace());
+        assert!(result.passed);
+        assert_eq!(
+            result.message,
+            "2 ENOENT errors from 'open.*' within budget of 2"
+        );
+
+        let result = engine.evaluate(&budget(None, 1), &build_trace());
+        assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            "3 errors from 'open.*' exceed budget of 1. Top call sites: 3x make: openat"
+        );
+        assert_eq!(result.actual_value, Some(AssertionValue::Calls(3)));
+
+        let result = engine.evaluate(&budget(Some("ENOPE"), 1), &build_trace());
+        assert!(!result.passed);
+        assert_eq!(result.message, "Unknown errno 'ENOPE'");
+    }
+
+    #[test]
+    fn test_latency_percentile() {
+        let engine = AssertionEngine::new();
+        let latency = |percentile, max_duration_us| {
+            syscall_assertion(AssertionType::LatencyPercentile(
+                LatencyPercentileAssertion {
+                    syscall_pattern: "fsync".to_string(),
+                    percentile,
+                    max_duration_us,
+                },
+            ))
+        };
+
+        let result = engine.evaluate(&latency(50.0, 10_000), &build_trace());
         assert!(result.passed);
+        assert_eq!(
+            result.message,
+            "p50 latency of 'fsync' is 2.000ms over 2 calls <= 10.000ms"
+        );
+
+        let result = engine.evaluate(&latency(95.0, 10_000), &build_trace());
+        assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            "p95 latency of 'fsync' is 12.000ms over 2 calls, exceeds maximum 10.000ms. \
+             Slowest call sites: 1x cc: fsync (slowest 12.000ms)"
+        );
+
+        let result = engine.evaluate(&latency(0.0, 10_000), &build_trace());
+        assert!(!result.passed);
+        assert_eq!(result.message, "percentile must be in (0, 100], got 0");
+
+        let empty = UnifiedTrace::new(1, "test".to_string());
+        assert!(engine.evaluate(&latency(99.0, 1), &empty).passed);
+    }
+
+    #[test]
+    fn test_syscall_count() {
+        let engine = AssertionEngine::new();
+        let count = |max_count| {
+            syscall_assertion(AssertionType::SyscallCount(SyscallCountAssertion {
+                syscall_pattern: "execve|execveat".to_string(),
+                max_count,
+            }))
+        };
+
+        let result = engine.evaluate(&count(2), &build_trace());
+        assert!(result.passed);
+        assert_eq!(result.message, "'execve|execveat' called 2 times <= 2");
+
+        let result = engine.evaluate(&count(1), &build_trace());
+        assert!(!result.passed);
+        assert_eq!(
+            result.message,
+            "'execve|execveat' called 2 times, exceeds maximum 1. \
+             Top call sites: 2x cc: execve"
+        );
     }
 }
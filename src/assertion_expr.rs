@@ -0,0 +1,1765 @@
+//! Expression language for custom assertions (Sprint 68)
+//!
+//! `type = "custom"` assertions hold a condition over the traced run:
+//!
+//! ```toml
+//! [[assertion]]
+//! name = "no_failed_opens"
+//! type = "custom"
+//! expression = 'count(syscall == "openat" && result < 0) == 0'
+//!
+//! [[assertion]]
+//! name = "fast_fsync"
+//! type = "custom"
+//! expression = 'p99(duration, name="fsync") < 5ms && sum(bytes, name="write") < 10MB'
+//! ```
+//!
+//! # Language
+//!
+//! - **Aggregates** over syscall spans: `count(cond...)`, and `sum`, `avg`,
+//!   `min`, `max`, `p50`, `p90`, `p95`, `p99` of a value followed by
+//!   conditions. Every condition must hold for a span to be included;
+//!   `field=value` is shorthand for `field == value`.
+//! - **Span fields** (inside aggregates): `syscall` (or `name`), `process`
+//!   (program of the process, from its last `execve`), `pid`, `result`,
+//!   `errno`, `duration` and `bytes` (transferred by read/write-like calls).
+//! - **Run values**: `syscalls`, `processes` and `wall_time`.
+//! - **Units**: `ns`, `us`, `ms`, `s`, `min` for durations; `B`, `KB`, `MB`,
+//!   `GB` (powers of 1000) and `KiB`, `MiB`, `GiB` for sizes. Durations only
+//!   compare with durations, so `p99(duration) < 5` is an error.
+//! - **Operators**: `|| && !`, `== != < <= > >=`, `=~` (regex match on
+//!   strings), `+ - * /` and parentheses.
+//!
+//! The language has no loops, assignments or access to anything outside the
+//! trace, and expressions are type checked when `renacer.toml` is loaded, so
+//! mistakes are reported before anything is traced.
+
+use crate::assertion_engine::process_names;
+use crate::unified_trace::{SyscallSpan, UnifiedTrace};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Byte range of a piece of an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn to(self, other: Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+        }
+    }
+}
+
+/// A syntax or type error, with the part of the expression it points at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ExprError {
+    fn new(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// The expression with a caret line under the error
+    pub fn render(&self, source: &str) -> String {
+        let start = source[..self.span.start.min(source.len())].chars().count();
+        let width = source
+            .get(self.span.start..self.span.end)
+            .map_or(0, |s| s.chars().count())
+            .max(1);
+        format!(
+            "{}\n  {}\n  {}{}",
+            self.message,
+            source,
+            " ".repeat(start),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (at {}..{})",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// Unit suffix of a number literal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Nanos,
+    Micros,
+    Millis,
+    Seconds,
+    Minutes,
+    Bytes,
+    Kilobytes,
+    Megabytes,
+    Gigabytes,
+    Kibibytes,
+    Mebibytes,
+    Gibibytes,
+}
+
+impl Unit {
+    const ALL: [Unit; 12] = [
+        Unit::Nanos,
+        Unit::Micros,
+        Unit::Millis,
+        Unit::Seconds,
+        Unit::Minutes,
+        Unit::Bytes,
+        Unit::Kilobytes,
+        Unit::Megabytes,
+        Unit::Gigabytes,
+        Unit::Kibibytes,
+        Unit::Mebibytes,
+        Unit::Gibibytes,
+    ];
+
+    fn suffix(self) -> &'static str {
+        match self {
+            Unit::Nanos => "ns",
+            Unit::Micros => "us",
+            Unit::Millis => "ms",
+            Unit::Seconds => "s",
+            Unit::Minutes => "min",
+            Unit::Bytes => "B",
+            Unit::Kilobytes => "KB",
+            Unit::Megabytes => "MB",
+            Unit::Gigabytes => "GB",
+            Unit::Kibibytes => "KiB",
+            Unit::Mebibytes => "MiB",
+            Unit::Gibibytes => "GiB",
+        }
+    }
+
+    fn from_suffix(suffix: &str) -> Option<Self> {
+        if suffix == "µs" {
+            return Some(Unit::Micros);
+        }
+        Self::ALL.into_iter().find(|unit| unit.suffix() == suffix)
+    }
+
+    /// Nanoseconds or bytes per unit
+    fn scale(self) -> f64 {
+        match self {
+            Unit::Nanos | Unit::Bytes => 1.0,
+            Unit::Micros => 1e3,
+            Unit::Millis => 1e6,
+            Unit::Seconds => 1e9,
+            Unit::Minutes => 60e9,
+            Unit::Kilobytes => 1e3,
+            Unit::Megabytes => 1e6,
+            Unit::Gigabytes => 1e9,
+            Unit::Kibibytes => 1024.0,
+            Unit::Mebibytes => 1024.0 * 1024.0,
+            Unit::Gibibytes => 1024.0 * 1024.0 * 1024.0,
+        }
+    }
+
+    fn ty(self) -> Type {
+        match self {
+            Unit::Nanos | Unit::Micros | Unit::Millis | Unit::Seconds | Unit::Minutes => {
+                Type::Duration
+            }
+            _ => Type::Bytes,
+        }
+    }
+}
+
+/// Type of a (sub)expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Bool,
+    Number,
+    Duration,
+    Bytes,
+    Str,
+}
+
+impl Type {
+    fn is_numeric(self) -> bool {
+        matches!(self, Type::Number | Type::Duration | Type::Bytes)
+    }
+
+    /// Plain numbers mix with byte counts, never with durations
+    fn comparable(self, other: Type) -> bool {
+        self == other
+            || matches!(
+                (self, other),
+                (Type::Number, Type::Bytes) | (Type::Bytes, Type::Number)
+            )
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Type::Bool => "a condition",
+            Type::Number => "a number",
+            Type::Duration => "a duration",
+            Type::Bytes => "a size",
+            Type::Str => "a string",
+        })
+    }
+}
+
+/// Per-span fields, usable inside aggregates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Syscall,
+    Process,
+    Pid,
+    Result,
+    Errno,
+    Duration,
+    Bytes,
+    /// Run values, usable anywhere
+    Syscalls,
+    Processes,
+    WallTime,
+}
+
+impl Field {
+    const NAMES: [(&'static str, Field); 11] = [
+        ("syscall", Field::Syscall),
+        ("name", Field::Syscall),
+        ("process", Field::Process),
+        ("pid", Field::Pid),
+        ("result", Field::Result),
+        ("errno", Field::Errno),
+        ("duration", Field::Duration),
+        ("bytes", Field::Bytes),
+        ("syscalls", Field::Syscalls),
+        ("processes", Field::Processes),
+        ("wall_time", Field::WallTime),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, field)| *field == self)
+            .map_or("?", |(name, _)| name)
+    }
+
+    fn ty(self) -> Type {
+        match self {
+            Field::Syscall | Field::Process => Type::Str,
+            Field::Pid | Field::Result | Field::Errno | Field::Syscalls | Field::Processes => {
+                Type::Number
+            }
+            Field::Duration | Field::WallTime => Type::Duration,
+            Field::Bytes => Type::Bytes,
+        }
+    }
+
+    fn per_span(self) -> bool {
+        !matches!(self, Field::Syscalls | Field::Processes | Field::WallTime)
+    }
+}
+
+/// Aggregate functions over spans
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+    /// Nearest-rank percentile
+    Percentile(u8),
+}
+
+impl Func {
+    const NAMES: [(&'static str, Func); 9] = [
+        ("count", Func::Count),
+        ("sum", Func::Sum),
+        ("avg", Func::Avg),
+        ("min", Func::Min),
+        ("max", Func::Max),
+        ("p50", Func::Percentile(50)),
+        ("p90", Func::Percentile(90)),
+        ("p95", Func::Percentile(95)),
+        ("p99", Func::Percentile(99)),
+    ];
+
+    fn name(self) -> &'static str {
+        Self::NAMES
+            .iter()
+            .find(|(_, func)| *func == self)
+            .map_or("?", |(name, _)| name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinaryOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Or => "||",
+            BinaryOp::And => "&&",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+        }
+    }
+
+    fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And => 2,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Lt
+            | BinaryOp::Le
+            | BinaryOp::Gt
+            | BinaryOp::Ge => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div => 5,
+        }
+    }
+
+    fn is_comparison(self) -> bool {
+        self.precedence() == 3
+    }
+}
+
+/// Syntax tree of an expression
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Number(f64, Option<Unit>, Span),
+    Str(String, Span),
+    Bool(bool, Span),
+    Field(Field, Span),
+    Unary(UnaryOp, Box<Expr>, Span),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>, Span),
+    /// `lhs =~ "pattern"`
+    Match(Box<Expr>, Regex, Span),
+    /// `func(value, conditions...)`; count has no value
+    Aggregate {
+        func: Func,
+        value: Option<Box<Expr>>,
+        conditions: Vec<Expr>,
+        span: Span,
+    },
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Number(_, _, span)
+            | Expr::Str(_, span)
+            | Expr::Bool(_, span)
+            | Expr::Field(_, span)
+            | Expr::Unary(_, _, span)
+            | Expr::Binary(_, _, _, span)
+            | Expr::Match(_, _, span)
+            | Expr::Aggregate { span, .. } => *span,
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary(op, ..) => op.precedence(),
+            Expr::Match(..) => 3,
+            Expr::Unary(..) => 6,
+            _ => 7,
+        }
+    }
+}
+
+/// Canonical source form, with only the parentheses precedence needs
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wrap = |f: &mut fmt::Formatter<'_>, expr: &Expr, parens: bool| {
+            if parens {
+                write!(f, "({})", expr)
+            } else {
+                write!(f, "{}", expr)
+            }
+        };
+        match self {
+            Expr::Number(value, unit, _) => {
+                write!(f, "{}{}", value, unit.map_or("", |unit| unit.suffix()))
+            }
+            Expr::Str(value, _) => write_string(f, value),
+            Expr::Bool(value, _) => write!(f, "{}", value),
+            Expr::Field(field, _) => f.write_str(field.name()),
+            Expr::Unary(op, operand, _) => {
+                f.write_str(match op {
+                    UnaryOp::Not => "!",
+                    UnaryOp::Neg => "-",
+                })?;
+                wrap(f, operand, operand.precedence() < 6)
+            }
+            Expr::Binary(op, lhs, rhs, _) => {
+                let prec = op.precedence();
+                wrap(
+                    f,
+                    lhs,
+                    lhs.precedence() < prec || (lhs.precedence() == prec && op.is_comparison()),
+                )?;
+                write!(f, " {} ", op.symbol())?;
+                wrap(f, rhs, rhs.precedence() <= prec)
+            }
+            Expr::Match(lhs, regex, _) => {
+                wrap(f, lhs, lhs.precedence() <= 3)?;
+                f.write_str(" =~ ")?;
+                write_string(f, regex.as_str())
+            }
+            Expr::Aggregate {
+                func,
+                value,
+                conditions,
+                ..
+            } => {
+                write!(f, "{}(", func.name())?;
+                for (i, arg) in value.iter().map(|v| &**v).chain(conditions).enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+// ---------------------------------------------------------------------------
+// Lexer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64, Option<Unit>),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(..) => f.write_str("a number"),
+            Token::Str(_) => f.write_str("a string"),
+            Token::Ident(name) => write!(f, "`{}`", name),
+            Token::Op(op) => write!(f, "`{}`", op),
+            Token::End => f.write_str("end of expression"),
+        }
+    }
+}
+
+const OPERATORS: [&str; 19] = [
+    "==", "!=", "<=", ">=", "&&", "||", "=~", "<", ">", "!", "+", "-", "*", "/", "(", ")", ",",
+    "=", "&",
+];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, Span)>, ExprError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let token = if c.is_ascii_digit() || (c == '.' && next_is_digit(source, start + 1)) {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_ascii_digit() || c == '.' || c == '_' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let text = source[start..end].replace('_', "");
+            let number: f64 = text
+                .parse()
+                .map_err(|_| ExprError::new("invalid number", Span { start, end }))?;
+
+            let unit_start = end;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_alphabetic() {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            let unit = match &source[unit_start..end] {
+                "" => None,
+                suffix => Some(Unit::from_suffix(suffix).ok_or_else(|| {
+                    ExprError::new(
+                        format!(
+                            "unknown unit `{}`; expected one of {}",
+                            suffix,
+                            Unit::ALL.map(Unit::suffix).join(", ")
+                        ),
+                        Span {
+                            start: unit_start,
+                            end,
+                        },
+                    )
+                })?),
+            };
+            tokens.push((Token::Number(number, unit), Span { start, end }));
+            continue;
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    end = i + c.len_utf8();
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push((
+                Token::Ident(source[start..end].to_string()),
+                Span { start, end },
+            ));
+            continue;
+        } else if c == '"' {
+            chars.next();
+            let mut value = String::new();
+            let mut end = None;
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        end = Some(i + 1);
+                        break;
+                    }
+                    '\\' => match chars.next() {
+                        Some((_, '"')) => value.push('"'),
+                        Some((_, '\\')) => value.push('\\'),
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((j, other)) => {
+                            return Err(ExprError::new(
+                                format!("unknown escape `\\{}`", other),
+                                Span {
+                                    start: i,
+                                    end: j + other.len_utf8(),
+                                },
+                            ))
+                        }
+                        None => break,
+                    },
+                    c => value.push(c),
+                }
+            }
+            let end = end.ok_or_else(|| {
+                ExprError::new(
+                    "unterminated string",
+                    Span {
+                        start,
+                        end: source.len(),
+                    },
+                )
+            })?;
+            tokens.push((Token::Str(value), Span { start, end }));
+            continue;
+        } else {
+            let rest = &source[start..];
+            let op = OPERATORS
+                .into_iter()
+                .find(|op| rest.starts_with(op))
+                .ok_or_else(|| {
+                    ExprError::new(
+                        format!("unexpected character `{}`", c),
+                        Span {
+                            start,
+                            end: start + c.len_utf8(),
+                        },
+                    )
+                })?;
+            if op == "&" {
+                return Err(ExprError::new(
+                    "unexpected `&`; did you mean `&&`?",
+                    Span {
+                        start,
+                        end: start + 1,
+                    },
+                ));
+            }
+            for _ in 0..op.len() {
+                chars.next();
+            }
+            Token::Op(op)
+        };
+        let end = chars.peek().map_or(source.len(), |&(i, _)| i);
+        tokens.push((token, Span { start, end }));
+    }
+    tokens.push((
+        Token::End,
+        Span {
+            start: source.len(),
+            end: source.len(),
+        },
+    ));
+    Ok(tokens)
+}
+
+fn next_is_digit(source: &str, at: usize) -> bool {
+    source[at..]
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit())
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+/// Deepest expression tree the parser builds; the parser, checker and
+/// evaluator all recurse per level, and must fit in a 2 MiB test thread stack
+const MAX_DEPTH: usize = 64;
+
+struct Parser {
+    tokens: Vec<(Token, Span)>,
+    pos: usize,
+    /// Levels of the expression tree above the current token
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &(Token, Span) {
+        &self.tokens[self.pos.min(self.tokens.len() - 1)]
+    }
+
+    fn next(&mut self) -> (Token, Span) {
+        let token = self.peek().clone();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: &str) -> Option<Span> {
+        match self.peek() {
+            (Token::Op(o), span) if *o == op => {
+                let span = *span;
+                self.pos += 1;
+                Some(span)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<Span, ExprError> {
+        self.eat(op).ok_or_else(|| {
+            let (token, span) = self.peek();
+            ExprError::new(format!("expected `{}`, found {}", op, token), *span)
+        })
+    }
+
+    /// Enter one more level of the expression tree
+    fn descend(&mut self, span: Span) -> Result<(), ExprError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(ExprError::new(
+                format!("expression is nested more than {} levels deep", MAX_DEPTH),
+                span,
+            ));
+        }
+        Ok(())
+    }
+
+    fn binary(&mut self, level: u8) -> Result<Expr, ExprError> {
+        if level > 5 {
+            return self.unary();
+        }
+        // Each operator of a chain like `a + b + c` wraps the ones before it
+        let depth = self.depth;
+        let mut lhs = self.binary(level + 1)?;
+        loop {
+            let (token, span) = self.peek().clone();
+            let op = match token {
+                Token::Op("=~") if level == 3 => {
+                    self.pos += 1;
+                    self.descend(span)?;
+                    let (pattern, pattern_span) = match self.next() {
+                        (Token::Str(pattern), span) => (pattern, span),
+                        (token, span) => {
+                            return Err(ExprError::new(
+                                format!("expected a regex string after `=~`, found {}", token),
+                                span,
+                            ))
+                        }
+                    };
+                    let regex = Regex::new(&pattern).map_err(|e| {
+                        ExprError::new(format!("invalid regex: {}", e), pattern_span)
+                    })?;
+                    let span = lhs.span().to(pattern_span);
+                    lhs = Expr::Match(Box::new(lhs), regex, span);
+                    self.no_chained_comparison(level)?;
+                    continue;
+                }
+                Token::Op("=") if level == 3 => {
+                    return Err(ExprError::new("unexpected `=`; did you mean `==`?", span))
+                }
+                Token::Op(symbol) => match binary_op(symbol) {
+                    Some(op) if op.precedence() == level => op,
+                    _ => break,
+                },
+                _ => break,
+            };
+            self.pos += 1;
+            self.descend(span)?;
+            let rhs = self.binary(level + 1)?;
+            let span = lhs.span().to(rhs.span());
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs), span);
+            if op.is_comparison() {
+                self.no_chained_comparison(level)?;
+            }
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    fn no_chained_comparison(&self, level: u8) -> Result<(), ExprError> {
+        match self.peek() {
+            (Token::Op(symbol), span)
+                if *symbol == "=~"
+                    || binary_op(symbol).is_some_and(|op| op.precedence() == level) =>
+            {
+                Err(ExprError::new(
+                    "comparisons cannot be chained; combine them with `&&`",
+                    *span,
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        for (symbol, op) in [("!", UnaryOp::Not), ("-", UnaryOp::Neg)] {
+            if let Some(span) = self.eat(symbol) {
+                self.descend(span)?;
+                let operand = self.unary()?;
+                self.depth -= 1;
+                let span = span.to(operand.span());
+                return Ok(Expr::Unary(op, Box::new(operand), span));
+            }
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        let (token, span) = self.next();
+        match token {
+            Token::Number(value, unit) => Ok(Expr::Number(value, unit, span)),
+            Token::Str(value) => Ok(Expr::Str(value, span)),
+            Token::Op("(") => {
+                self.descend(span)?;
+                let expr = self.binary(1)?;
+                self.expect(")")?;
+                self.depth -= 1;
+                Ok(expr)
+            }
+            Token::Ident(name) if name == "true" || name == "false" => {
+                Ok(Expr::Bool(name == "true", span))
+            }
+            Token::Ident(name) => {
+                if self.peek().0 == Token::Op("(") {
+                    self.descend(span)?;
+                    let aggregate = self.aggregate(&name, span)?;
+                    self.depth -= 1;
+                    return Ok(aggregate);
+                }
+                let field = Field::NAMES
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, field)| *field)
+                    .ok_or_else(|| {
+                        ExprError::new(
+                            format!(
+                                "unknown field `{}`; expected one of {}",
+                                name,
+                                Field::NAMES.map(|(n, _)| n).join(", ")
+                            ),
+                            span,
+                        )
+                    })?;
+                Ok(Expr::Field(field, span))
+            }
+            token => Err(ExprError::new(
+                format!("expected a value, found {}", token),
+                span,
+            )),
+        }
+    }
+
+    fn aggregate(&mut self, name: &str, name_span: Span) -> Result<Expr, ExprError> {
+        let func = Func::NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, func)| *func)
+            .ok_or_else(|| {
+                ExprError::new(
+                    format!(
+                        "unknown function `{}`; expected one of {}",
+                        name,
+                        Func::NAMES.map(|(n, _)| n).join(", ")
+                    ),
+                    name_span,
+                )
+            })?;
+        self.expect("(")?;
+
+        let mut args = Vec::new();
+        if self.eat(")").is_none() {
+            loop {
+                args.push(self.argument()?);
+                if self.eat(",").is_none() {
+                    break;
+                }
+            }
+            self.expect(")")?;
+        }
+        let span = name_span.to(self.tokens[self.pos - 1].1);
+
+        let mut args = args.into_iter();
+        let value = match func {
+            Func::Count => None,
+            _ => Some(Box::new(args.next().ok_or_else(|| {
+                ExprError::new(format!("{}() needs a value to aggregate", name), span)
+            })?)),
+        };
+        Ok(Expr::Aggregate {
+            func,
+            value,
+            conditions: args.collect(),
+            span,
+        })
+    }
+
+    /// An argument, where `field=value` is shorthand for `field == value`
+    fn argument(&mut self) -> Result<Expr, ExprError> {
+        let assigns = matches!(self.tokens.get(self.pos + 1), Some((Token::Op("="), _)));
+        if matches!(self.peek().0, Token::Ident(_)) && assigns {
+            let field = self.primary()?;
+            self.pos += 1;
+            let value = self.binary(4)?;
+            let span = field.span().to(value.span());
+            return Ok(Expr::Binary(
+                BinaryOp::Eq,
+                Box::new(field),
+                Box::new(value),
+                span,
+            ));
+        }
+        self.binary(1)
+    }
+}
+
+fn binary_op(symbol: &str) -> Option<BinaryOp> {
+    Some(match symbol {
+        "||" => BinaryOp::Or,
+        "&&" => BinaryOp::And,
+        "==" => BinaryOp::Eq,
+        "!=" => BinaryOp::Ne,
+        "<" => BinaryOp::Lt,
+        "<=" => BinaryOp::Le,
+        ">" => BinaryOp::Gt,
+        ">=" => BinaryOp::Ge,
+        "+" => BinaryOp::Add,
+        "-" => BinaryOp::Sub,
+        "*" => BinaryOp::Mul,
+        "/" => BinaryOp::Div,
+        _ => return None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Type checking
+// ---------------------------------------------------------------------------
+
+/// Where an expression appears: at the top or inside an aggregate
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Run,
+    Span,
+}
+
+fn check(expr: &Expr, scope: Scope) -> Result<Type, ExprError> {
+    let expect = |expr: &Expr, ty: Type, what: &str| -> Result<(), ExprError> {
+        let found = check(expr, scope)?;
+        if found == ty {
+            Ok(())
+        } else {
+            Err(ExprError::new(
+                format!("{} needs {}, found {}", what, ty, found),
+                expr.span(),
+            ))
+        }
+    };
+
+    match expr {
+        Expr::Number(_, unit, _) => Ok(unit.map_or(Type::Number, Unit::ty)),
+        Expr::Str(..) => Ok(Type::Str),
+        Expr::Bool(..) => Ok(Type::Bool),
+        Expr::Field(field, span) => {
+            if field.per_span() && scope == Scope::Run {
+                return Err(ExprError::new(
+                    format!(
+                        "`{}` is a per-syscall field; use it inside an aggregate such as count({} == ...) or max({})",
+                        field.name(),
+                        field.name(),
+                        field.name()
+                    ),
+                    *span,
+                ));
+            }
+            Ok(field.ty())
+        }
+        Expr::Unary(UnaryOp::Not, operand, _) => {
+            expect(operand, Type::Bool, "`!`")?;
+            Ok(Type::Bool)
+        }
+        Expr::Unary(UnaryOp::Neg, operand, _) => {
+            let ty = check(operand, scope)?;
+            if !ty.is_numeric() {
+                return Err(ExprError::new(
+                    format!("`-` needs a number, found {}", ty),
+                    operand.span(),
+                ));
+            }
+            Ok(ty)
+        }
+        Expr::Binary(op @ (BinaryOp::And | BinaryOp::Or), lhs, rhs, _) => {
+            let what = format!("`{}`", op.symbol());
+            expect(lhs, Type::Bool, &what)?;
+            expect(rhs, Type::Bool, &what)?;
+            Ok(Type::Bool)
+        }
+        Expr::Binary(op, lhs, rhs, span) if op.is_comparison() => {
+            let (l, r) = (check(lhs, scope)?, check(rhs, scope)?);
+            if !l.comparable(r) {
+                let hint = if l == Type::Duration || r == Type::Duration {
+                    "; give numbers a unit such as 5ms"
+                } else {
+                    ""
+                };
+                return Err(ExprError::new(
+                    format!("cannot compare {} with {}{}", l, r, hint),
+                    *span,
+                ));
+            }
+            let ordered = !matches!(op, BinaryOp::Eq | BinaryOp::Ne);
+            if ordered && !l.is_numeric() {
+                return Err(ExprError::new(
+                    format!("`{}` needs numbers, found {}", op.symbol(), l),
+                    *span,
+                ));
+            }
+            Ok(Type::Bool)
+        }
+        Expr::Binary(op, lhs, rhs, span) => {
+            let (l, r) = (check(lhs, scope)?, check(rhs, scope)?);
+            arithmetic_type(*op, l, r).ok_or_else(|| {
+                ExprError::new(
+                    format!("cannot apply `{}` to {} and {}", op.symbol(), l, r),
+                    *span,
+                )
+            })
+        }
+        Expr::Match(lhs, _, _) => {
+            expect(lhs, Type::Str, "`=~`")?;
+            Ok(Type::Bool)
+        }
+        Expr::Aggregate {
+            func,
+            value,
+            conditions,
+            span,
+        } => {
+            if scope == Scope::Span {
+                return Err(ExprError::new("aggregates cannot be nested", *span));
+            }
+            for condition in conditions {
+                let ty = check(condition, Scope::Span)?;
+                if ty != Type::Bool {
+                    let hint = if *func == Func::Count {
+                        ""
+                    } else {
+                        "; the value to aggregate comes first"
+                    };
+                    return Err(ExprError::new(
+                        format!(
+                            "{}() filters need {}, found {}{}",
+                            func.name(),
+                            Type::Bool,
+                            ty,
+                            hint
+                        ),
+                        condition.span(),
+                    ));
+                }
+            }
+            match value {
+                None => Ok(Type::Number),
+                Some(value) => {
+                    let ty = check(value, Scope::Span)?;
+                    if !ty.is_numeric() {
+                        return Err(ExprError::new(
+                            format!(
+                                "{}() needs a number to aggregate, found {}",
+                                func.name(),
+                                ty
+                            ),
+                            value.span(),
+                        ));
+                    }
+                    Ok(ty)
+                }
+            }
+        }
+    }
+}
+
+fn arithmetic_type(op: BinaryOp, l: Type, r: Type) -> Option<Type> {
+    use Type::{Bytes, Duration, Number};
+    match (op, l, r) {
+        (_, Number, Number) => Some(Number),
+        (BinaryOp::Add | BinaryOp::Sub, Duration, Duration) => Some(Duration),
+        (BinaryOp::Add | BinaryOp::Sub, Bytes, Bytes | Number) => Some(Bytes),
+        (BinaryOp::Add, Number, Bytes) => Some(Bytes),
+        (BinaryOp::Mul, Duration, Number) | (BinaryOp::Mul, Number, Duration) => Some(Duration),
+        (BinaryOp::Mul, Bytes, Number) | (BinaryOp::Mul, Number, Bytes) => Some(Bytes),
+        (BinaryOp::Div, Duration, Number) => Some(Duration),
+        (BinaryOp::Div, Bytes, Number) => Some(Bytes),
+        (BinaryOp::Div, Duration, Duration) | (BinaryOp::Div, Bytes, Bytes) => Some(Number),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Evaluation
+// ---------------------------------------------------------------------------
+
+/// Value of an evaluated (sub)expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value<'a> {
+    Bool(bool),
+    Number(f64),
+    /// Nanoseconds
+    Duration(f64),
+    Bytes(f64),
+    Str(&'a str),
+}
+
+impl Value<'_> {
+    fn number(&self) -> f64 {
+        match self {
+            Value::Number(n) | Value::Duration(n) | Value::Bytes(n) => *n,
+            Value::Bool(b) => f64::from(u8::from(*b)),
+            Value::Str(_) => f64::NAN,
+        }
+    }
+
+    fn of_type(ty: Type, n: f64) -> Value<'static> {
+        match ty {
+            Type::Duration => Value::Duration(n),
+            Type::Bytes => Value::Bytes(n),
+            _ => Value::Number(n),
+        }
+    }
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", *n as i64),
+            Value::Number(n) => write!(f, "{:.3}", n),
+            Value::Duration(ns) => {
+                let (value, unit) = match ns.abs() {
+                    x if x >= 1e9 => (ns / 1e9, "s"),
+                    x if x >= 1e6 => (ns / 1e6, "ms"),
+                    x if x >= 1e3 => (ns / 1e3, "us"),
+                    _ => return write!(f, "{}ns", ns.round()),
+                };
+                write!(f, "{:.2}{}", value, unit)
+            }
+            Value::Bytes(b) => {
+                let (value, unit) = match b.abs() {
+                    x if x >= 1024.0 * 1024.0 * 1024.0 => (b / (1024.0 * 1024.0 * 1024.0), "GiB"),
+                    x if x >= 1024.0 * 1024.0 => (b / (1024.0 * 1024.0), "MiB"),
+                    x if x >= 1024.0 => (b / 1024.0, "KiB"),
+                    _ => return write!(f, "{} bytes", b.round()),
+                };
+                write!(f, "{:.2} {}", value, unit)
+            }
+            Value::Str(s) => write!(f, "{:?}", s),
+        }
+    }
+}
+
+/// Syscalls whose non-negative result is a byte count
+const TRANSFER_SYSCALLS: [&str; 18] = [
+    "read",
+    "write",
+    "pread64",
+    "pwrite64",
+    "readv",
+    "writev",
+    "preadv",
+    "pwritev",
+    "preadv2",
+    "pwritev2",
+    "sendto",
+    "recvfrom",
+    "sendmsg",
+    "recvmsg",
+    "sendfile",
+    "copy_file_range",
+    "splice",
+    "tee",
+];
+
+struct Context<'a> {
+    trace: &'a UnifiedTrace,
+    names: HashMap<i32, String>,
+}
+
+impl<'a> Context<'a> {
+    fn pid(&self, span: &SyscallSpan) -> i32 {
+        span.pid.unwrap_or(self.trace.process_span.pid)
+    }
+
+    fn field<'b>(&'b self, field: Field, span: Option<&'b SyscallSpan>) -> Value<'b> {
+        let spans = &self.trace.syscall_spans;
+        match (field, span) {
+            (Field::Syscalls, _) => Value::Number(spans.len() as f64),
+            (Field::Processes, _) => Value::Number(
+                spans
+                    .iter()
+                    .map(|s| self.pid(s))
+                    .collect::<HashSet<_>>()
+                    .len() as f64,
+            ),
+            (Field::WallTime, _) => {
+                let start = spans.iter().map(|s| s.timestamp_nanos).min().unwrap_or(0);
+                let end = spans
+                    .iter()
+                    .map(|s| s.timestamp_nanos + s.duration_nanos)
+                    .max()
+                    .unwrap_or(0);
+                Value::Duration(end.saturating_sub(start) as f64)
+            }
+            (_, None) => Value::Number(f64::NAN),
+            (Field::Syscall, Some(span)) => Value::Str(&span.name),
+            (Field::Process, Some(span)) => Value::Str(
+                self.names
+                    .get(&self.pid(span))
+                    .map_or(self.trace.process_span.name.as_str(), String::as_str),
+            ),
+            (Field::Pid, Some(span)) => Value::Number(self.pid(span) as f64),
+            (Field::Result, Some(span)) => Value::Number(span.return_value as f64),
+            (Field::Errno, Some(span)) => Value::Number(span.errno.unwrap_or(0) as f64),
+            (Field::Duration, Some(span)) => Value::Duration(span.duration_nanos as f64),
+            (Field::Bytes, Some(span)) => {
+                let transfer = TRANSFER_SYSCALLS.contains(&&*span.name) && span.return_value > 0;
+                Value::Bytes(if transfer {
+                    span.return_value as f64
+                } else {
+                    0.0
+                })
+            }
+        }
+    }
+
+    fn eval<'b>(&'b self, expr: &'b Expr, span: Option<&'b SyscallSpan>) -> Value<'b> {
+        match expr {
+            Expr::Number(value, unit, _) => match unit {
+                None => Value::Number(*value),
+                Some(unit) => Value::of_type(unit.ty(), value * unit.scale()),
+            },
+            Expr::Str(value, _) => Value::Str(value),
+            Expr::Bool(value, _) => Value::Bool(*value),
+            Expr::Field(field, _) => self.field(*field, span),
+            Expr::Unary(UnaryOp::Not, operand, _) => Value::Bool(!self.truth(operand, span)),
+            Expr::Unary(UnaryOp::Neg, operand, _) => match self.eval(operand, span) {
+                Value::Duration(n) => Value::Duration(-n),
+                Value::Bytes(n) => Value::Bytes(-n),
+                value => Value::Number(-value.number()),
+            },
+            Expr::Binary(BinaryOp::And, lhs, rhs, _) => {
+                Value::Bool(self.truth(lhs, span) && self.truth(rhs, span))
+            }
+            Expr::Binary(BinaryOp::Or, lhs, rhs, _) => {
+                Value::Bool(self.truth(lhs, span) || self.truth(rhs, span))
+            }
+            Expr::Binary(op, lhs, rhs, _) if op.is_comparison() => {
+                let (l, r) = (self.eval(lhs, span), self.eval(rhs, span));
+                Value::Bool(compare(*op, &l, &r))
+            }
+            Expr::Binary(op, lhs, rhs, _) => {
+                let (l, r) = (self.eval(lhs, span), self.eval(rhs, span));
+                let n = match op {
+                    BinaryOp::Add => l.number() + r.number(),
+                    BinaryOp::Sub => l.number() - r.number(),
+                    BinaryOp::Mul => l.number() * r.number(),
+                    _ => l.number() / r.number(),
+                };
+                let ty = arithmetic_type(*op, value_type(&l), value_type(&r));
+                Value::of_type(ty.unwrap_or(Type::Number), n)
+            }
+            Expr::Match(lhs, regex, _) => match self.eval(lhs, span) {
+                Value::Str(s) => Value::Bool(regex.is_match(s)),
+                _ => Value::Bool(false),
+            },
+            Expr::Aggregate {
+                func,
+                value,
+                conditions,
+                ..
+            } => {
+                let matching = self
+                    .trace
+                    .syscall_spans
+                    .iter()
+                    .filter(|s| conditions.iter().all(|c| self.truth(c, Some(s))));
+                let Some(value) = value else {
+                    return Value::Number(matching.count() as f64);
+                };
+                let mut ty = Type::Number;
+                let mut values: Vec<f64> = matching
+                    .map(|s| {
+                        let v = self.eval(value, Some(s));
+                        ty = value_type(&v);
+                        v.number()
+                    })
+                    .collect();
+                if values.is_empty() {
+                    ty = check(value, Scope::Span).unwrap_or(Type::Number);
+                }
+                Value::of_type(ty, aggregate(*func, &mut values))
+            }
+        }
+    }
+
+    fn truth(&self, expr: &Expr, span: Option<&SyscallSpan>) -> bool {
+        self.eval(expr, span) == Value::Bool(true)
+    }
+}
+
+fn value_type(value: &Value) -> Type {
+    match value {
+        Value::Bool(_) => Type::Bool,
+        Value::Number(_) => Type::Number,
+        Value::Duration(_) => Type::Duration,
+        Value::Bytes(_) => Type::Bytes,
+        Value::Str(_) => Type::Str,
+    }
+}
+
+fn compare(op: BinaryOp, l: &Value, r: &Value) -> bool {
+    if let (Value::Str(a), Value::Str(b)) = (l, r) {
+        return match op {
+            BinaryOp::Eq => a == b,
+            _ => a != b,
+        };
+    }
+    if let (Value::Bool(a), Value::Bool(b)) = (l, r) {
+        return match op {
+            BinaryOp::Eq => a == b,
+            _ => a != b,
+        };
+    }
+    let (a, b) = (l.number(), r.number());
+    match op {
+        BinaryOp::Eq => a == b,
+        BinaryOp::Ne => a != b,
+        BinaryOp::Lt => a < b,
+        BinaryOp::Le => a <= b,
+        BinaryOp::Gt => a > b,
+        _ => a >= b,
+    }
+}
+
+/// Aggregate of the matching spans' values (0 when nothing matched)
+fn aggregate(func: Func, values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    match func {
+        Func::Count => values.len() as f64,
+        Func::Sum => values.iter().sum(),
+        Func::Avg => values.iter().sum::<f64>() / values.len() as f64,
+        Func::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        Func::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        Func::Percentile(p) => {
+            values.sort_by(|a, b| a.total_cmp(b));
+            let rank = (f64::from(p) / 100.0 * values.len() as f64).ceil() as usize;
+            values[rank.clamp(1, values.len()) - 1]
+        }
+    }
+}
+
+/// A parsed and type-checked custom assertion
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Expr,
+}
+
+/// Result of evaluating an [`Expression`] over a trace
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// Whether the condition holds
+    pub holds: bool,
+    /// What made it false: each failed comparison with its actual values
+    pub reasons: Vec<String>,
+    /// Left side and expected right side, when the whole expression is one comparison
+    pub values: Option<(String, String)>,
+}
+
+impl Expression {
+    /// Parse and type check an expression
+    pub fn parse(source: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            depth: 0,
+        };
+        let root = parser.binary(1)?;
+        let (token, span) = parser.peek();
+        if *token != Token::End {
+            return Err(ExprError::new(
+                format!("expected an operator, found {}", token),
+                *span,
+            ));
+        }
+
+        let ty = check(&root, Scope::Run)?;
+        if ty != Type::Bool {
+            return Err(ExprError::new(
+                format!(
+                    "the expression must be a condition, found {}; compare it, e.g. `... < 5ms`",
+                    ty
+                ),
+                root.span(),
+            ));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            root,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn root(&self) -> &Expr {
+        &self.root
+    }
+
+    /// Evaluate the condition over a trace
+    pub fn evaluate(&self, trace: &UnifiedTrace) -> Evaluation {
+        let context = Context {
+            trace,
+            names: process_names(trace),
+        };
+        let holds = context.truth(&self.root, None);
+        let mut reasons = Vec::new();
+        if !holds {
+            explain(&context, &self.root, &mut reasons);
+        }
+        let values = match &self.root {
+            Expr::Binary(op, lhs, rhs, _) if op.is_comparison() => Some((
+                context.eval(lhs, None).to_string(),
+                format!("{} {}", op.symbol(), context.eval(rhs, None)),
+            )),
+            _ => None,
+        };
+        Evaluation {
+            holds,
+            reasons,
+            values,
+        }
+    }
+}
+
+/// Describe the false parts of a false condition
+fn explain(context: &Context, expr: &Expr, reasons: &mut Vec<String>) {
+    match expr {
+        Expr::Binary(BinaryOp::And, lhs, rhs, _) => {
+            for side in [lhs, rhs] {
+                if !context.truth(side, None) {
+                    explain(context, side, reasons);
+                }
+            }
+        }
+        Expr::Binary(op, lhs, rhs, _) if op.is_comparison() => {
+            let actual = context.eval(lhs, None);
+            let expected = context.eval(rhs, None);
+            let literal = matches!(**rhs, Expr::Number(..) | Expr::Str(..) | Expr::Bool(..));
+            reasons.push(if literal {
+                format!("{} is {}, expected {} {}", lhs, actual, op.symbol(), rhs)
+            } else {
+                format!(
+                    "{} is {}, expected {} {} ({})",
+                    lhs,
+                    actual,
+                    op.symbol(),
+                    rhs,
+                    expected
+                )
+            });
+        }
+        _ => reasons.push(format!("{} is false", expr)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn trace() -> UnifiedTrace {
+        let mut trace = UnifiedTrace::new(1, "/usr/bin/make".to_string());
+        let mut add = |pid: i32, name: &'static str, args: &[&str], result: i64, micros: u64| {
+            let args = args
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| (Cow::Owned(format!("arg{}", i)), arg.to_string()))
+                .collect();
+            let timestamp = trace.syscall_spans.len() as u64 * 10_000;
+            let span = SyscallSpan::new(
+                trace.process_span.span_id,
+                Cow::Borrowed(name),
+                args,
+                result,
+                timestamp,
+                micros * 1000,
+                (result < 0).then_some(-result as i32),
+                &trace.clock,
+            )
+            .with_pid(pid);
+            trace.add_syscall(span);
+        };
+        add(1, "openat", &["AT_FDCWD", "\"Makefile\""], 3, 5);
+        add(1, "openat", &["AT_FDCWD", "\"missing.mk\""], -2, 4);
+        add(1, "read", &["3"], 2048, 20);
+        add(2, "execve", &["\"/usr/bin/cc\""], 0, 100);
+        add(2, "write", &["1"], 4096, 30);
+        add(2, "fsync", &["1"], 0, 3_000);
+        add(2, "fsync", &["1"], 0, 7_000);
+        trace
+    }
+
+    fn eval(source: &str) -> Evaluation {
+        Expression::parse(source).unwrap().evaluate(&trace())
+    }
+
+    fn error(source: &str) -> ExprError {
+        Expression::parse(source).unwrap_err()
+    }
+
+    #[test]
+    fn test_aggregates() {
+        assert!(!eval(r#"count(syscall == "openat" && result < 0) == 0"#).holds);
+        assert!(eval(r#"count(syscall == "openat", result >= 0) == 1"#).holds);
+        assert!(eval(r#"sum(bytes, name="write") == 4KiB"#).holds);
+        assert!(eval(r#"sum(bytes) == 6144"#).holds);
+        assert!(eval(r#"p99(duration, name="fsync") == 7ms"#).holds);
+        assert!(eval(r#"p50(duration, name="fsync") == 3ms"#).holds);
+        assert!(eval(r#"avg(duration, name="fsync") == 5ms"#).holds);
+        assert!(eval(r#"min(result) == -2 && max(errno) == 2"#).holds);
+        assert!(eval(r#"max(duration, syscall == "nothing") == 0ns"#).holds);
+    }
+
+    #[test]
+    fn test_process_filters_and_run_values() {
+        assert!(eval(r#"count(process =~ "/cc$") == 4"#).holds);
+        assert!(eval(r#"count(pid == 1, process == "/usr/bin/make") == 3"#).holds);
+        assert!(eval("processes == 2 && syscalls == 7").holds);
+        assert!(eval("wall_time > 60us && wall_time <= 67ms").holds);
+        assert!(eval("!(syscalls > 10) || false").holds);
+        assert!(eval("sum(duration) / syscalls > 1ms && 2 * 3 - 6 == 0").holds);
+    }
+
+    #[test]
+    fn test_failure_reasons() {
+        let evaluation = eval(r#"p99(duration, name="fsync") < 5ms && syscalls < 100"#);
+        assert!(!evaluation.holds);
+        assert_eq!(
+            evaluation.reasons,
+            vec![r#"p99(duration, syscall == "fsync") is 7.00ms, expected < 5ms"#]
+        );
+
+        let evaluation = eval(r#"count(syscall == "openat" && result < 0) == 0"#);
+        assert_eq!(
+            evaluation.values,
+            Some(("1".to_string(), "== 0".to_string()))
+        );
+
+        let evaluation = eval("sum(bytes) < sum(bytes, name=\"read\")");
+        assert_eq!(
+            evaluation.reasons,
+            vec![r#"sum(bytes) is 6.00 KiB, expected < sum(bytes, syscall == "read") (2.00 KiB)"#]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let e = error(r#"count(syscall == "openat""#);
+        assert_eq!(e.message, "expected `)`, found end of expression");
+        assert_eq!(e.span, Span { start: 25, end: 25 });
+
+        let e = error("p99(duration) < 5");
+        assert_eq!(
+            e.message,
+            "cannot compare a duration with a number; give numbers a unit such as 5ms"
+        );
+
+        let e = error("p99(duration) < 5xs");
+        assert!(e.message.starts_with("unknown unit `xs`"));
+        assert_eq!(e.span, Span { start: 17, end: 19 });
+
+        let e = error("duration < 5ms");
+        assert!(e.message.starts_with("`duration` is a per-syscall field"));
+        assert_eq!(e.span, Span { start: 0, end: 8 });
+
+        assert!(error("p98(duration) < 1ms")
+            .message
+            .starts_with("unknown function `p98`"));
+        assert!(error("count(sum(bytes) > 0) == 0")
+            .message
+            .starts_with("aggregates cannot be nested"));
+        assert!(error("sum(bytes)")
+            .message
+            .starts_with("the expression must be a condition"));
+        assert!(error("syscalls = 3").message.contains("did you mean `==`"));
+        assert!(error("1 < 2 < 3")
+            .message
+            .starts_with("comparisons cannot be chained"));
+        assert!(error(r#"count(process =~ "(") == 0"#)
+            .message
+            .starts_with("invalid regex"));
+        assert!(error("count(bytes) == 0")
+            .message
+            .starts_with("count() filters need a condition, found a size"));
+        assert!(error("syscalls > 1 & true")
+            .message
+            .contains("did you mean `&&`"));
+        assert!(error(r#"count(name == "a) == 0"#)
+            .message
+            .starts_with("unterminated string"));
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let nested = |open: &str, inner: &str, close: &str, levels: usize| {
+            format!("{}{}{}", open.repeat(levels), inner, close.repeat(levels))
+        };
+        assert!(eval(&nested("!", "!true", "", 63)).holds);
+        assert!(eval(&nested("(", "syscalls > 0", ")", 63)).holds);
+
+        let e = error(&nested("!", "true", "", 200_000));
+        assert_eq!(e.message, "expression is nested more than 64 levels deep");
+        assert_eq!(e.span, Span { start: 64, end: 65 });
+        let e = error(&"(".repeat(50_000));
+        assert_eq!(e.span, Span { start: 64, end: 65 });
+        let e = error(&format!("syscalls{} > 0", " + 1".repeat(100)));
+        assert_eq!(
+            e.span,
+            Span {
+                start: 265,
+                end: 266
+            }
+        );
+        let e = error(&nested("count(", "true", ") > 0", 100));
+        assert!(e.message.starts_with("expression is nested"));
+    }
+
+    #[test]
+    fn test_render_points_at_the_error() {
+        let source = "syscalls > 5 && p99(duration) < 5";
+        let rendered = error(source).render(source);
+        assert_eq!(
+            rendered,
+            "cannot compare a duration with a number; give numbers a unit such as 5ms\n  \
+             syscalls > 5 && p99(duration) < 5\n  \
+             \x20               ^^^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn test_display_is_canonical() {
+        let expression =
+            Expression::parse(r#"count(name="x",(result<0||errno==2)&&!(pid==1)) == 0"#).unwrap();
+        assert_eq!(
+            expression.root().to_string(),
+            r#"count(syscall == "x", (result < 0 || errno == 2) && !(pid == 1)) == 0"#
+        );
+        let expression = Expression::parse("wall_time - (1s - 500ms) * 2 >= 1_000us").unwrap();
+        assert_eq!(
+            expression.root().to_string(),
+            "wall_time - (1s - 500ms) * 2 >= 1000us"
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn duration() -> impl Strategy<Value = String> {
+        (
+            0u32..10_000,
+            prop::sample::select(vec!["ns", "us", "ms", "s"]),
+        )
+            .prop_map(|(n, unit)| format!("{}{}", n, unit))
+    }
+
+    /// Random well-typed per-syscall filters
+    fn filter() -> impl Strategy<Value = String> {
+        let name = prop::sample::select(vec!["read", "write", "openat", "a\\\"b"]);
+        let leaf = prop_oneof![
+            name.prop_map(|name| format!("syscall == \"{}\"", name)),
+            (-5i32..5).prop_map(|n| format!("result < {}", n)),
+            duration().prop_map(|limit| format!("duration >= {}", limit)),
+            Just("process =~ \"^/usr\"".to_string()),
+            Just("bytes * 2 > 1KB - 10".to_string()),
+        ];
+        leaf.prop_recursive(3, 16, 2, |inner| {
+            prop_oneof![
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("({}) && {}", a, b)),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("{} || ({})", a, b)),
+                inner.prop_map(|a| format!("!({})", a)),
+            ]
+        })
+    }
+
+    /// Random well-typed conditions over a run
+    fn condition() -> impl Strategy<Value = String> {
+        let func = prop::sample::select(vec!["p50", "p99", "max", "sum", "avg"]);
+        let leaf = prop_oneof![
+            (func, filter(), duration())
+                .prop_map(|(func, f, limit)| format!("{}(duration, {}) < {}", func, f, limit)),
+            (filter(), 0u32..100).prop_map(|(f, n)| format!("count({}) <= {}", f, n)),
+            (filter(), 0u32..100_000)
+                .prop_map(|(f, n)| format!("sum(bytes, {}, pid=1) > {}KiB", f, n)),
+            duration().prop_map(|limit| format!("wall_time - -{} >= 1s", limit)),
+            Just("processes != 1".to_string()),
+            Just("true".to_string()),
+        ];
+        leaf.prop_recursive(3, 16, 2, |inner| {
+            prop_oneof![
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("({}) && ({})", a, b)),
+                (inner.clone(), inner.clone()).prop_map(|(a, b)| format!("{} || {}", a, b)),
+                inner.prop_map(|a| format!("!({})", a)),
+            ]
+        })
+    }
+
+    proptest! {
+        /// Property: the parser never panics, and errors point inside the input
+        #[test]
+        fn prop_parse_never_panics(source in "\\PC{0,40}") {
+            if let Err(e) = Expression::parse(&source) {
+                prop_assert!(e.span.start <= e.span.end);
+                prop_assert!(e.span.end <= source.len());
+                prop_assert!(source.is_char_boundary(e.span.start));
+                prop_assert!(source.is_char_boundary(e.span.end));
+                let _ = e.render(&source);
+            }
+        }
+
+        /// Property: token soup from the language's own alphabet never panics either
+        #[test]
+        fn prop_token_soup_never_panics(tokens in prop::collection::vec(prop::sample::select(vec![
+            "count", "p99", "(", ")", ",", "duration", "name", "=", "==", "&&", "||", "!",
+            "<", "5ms", "3", "\"read\"", "=~", "-", "*", "wall_time", "1.5.2", "7KiB",
+        ]), 0..12)) {
+            let source = tokens.join(" ");
+            if let Err(e) = Expression::parse(&source) {
+                prop_assert!(e.span.end <= source.len());
+            }
+        }
+
+        /// Property: generated conditions parse, and printing is a fixed point
+        #[test]
+        fn prop_display_round_trips(source in condition()) {
+            let parsed = Expression::parse(&source);
+            prop_assert!(parsed.is_ok(), "{}: {:?}", source, parsed.err());
+            let printed = parsed.unwrap().root().to_string();
+            let reparsed = Expression::parse(&printed);
+            prop_assert!(reparsed.is_ok(), "{}: {:?}", printed, reparsed.err());
+            prop_assert_eq!(reparsed.unwrap().root().to_string(), printed);
+        }
+
+        /// Property: number literals keep their value and unit
+        #[test]
+        fn prop_units_scale(n in 0u32..1_000_000, unit in prop::sample::select(Unit::ALL.to_vec())) {
+            let source = format!("{}{}", n, unit.suffix());
+            let tokens = tokenize(&source).unwrap();
+            prop_assert_eq!(&tokens[0].0, &Token::Number(f64::from(n), Some(unit)));
+            prop_assert_eq!(tokens[0].1, Span { start: 0, end: source.len() });
+        }
+    }
+}
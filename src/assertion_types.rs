@@ -53,7 +53,8 @@ pub struct Assertion {
     #[serde(flatten)]
     pub assertion_type: AssertionType,
 
-    /// If true, fail cargo test when this assertion is violated
+    /// If true, fail `renacer check` and cargo tests using
+    /// [`trace_and_assert`](crate::testing::trace_and_assert) when this assertion is violated
     #[serde(default = "default_true")]
     pub fail_on_violation: bool,
 
@@ -130,7 +131,9 @@ pub enum AssertionType {
 
     /// Custom assertion (user-defined)
     ///
-    /// Allows users to define custom assertions via a Rust expression.
+    /// A condition in the expression language of
+    /// [`assertion_expr`](crate::assertion_expr) (Sprint 68), checked when
+    /// the config is loaded.
     ///
     /// # Example TOML
     ///
@@ -138,9 +141,88 @@ pub enum AssertionType {
     /// [[assertion]]
     /// name = "custom_check"
     /// type = "custom"
-    /// expression = "trace.spans.iter().all(|s| s.duration_ms < 50)"
+    /// expression = 'p99(duration, name="fsync") < 5ms'
     /// ```
     Custom(CustomAssertion),
+
+    /// Build hermeticity assertion (Sprint 62)
+    ///
+    /// Validates that a build only reads its declared inputs and toolchain,
+    /// only writes its declared outputs, and stays off the network. Checked
+    /// against file access traces by `renacer hermetic`.
+    ///
+    /// # Example TOML
+    ///
+    /// ```toml
+    /// [[assertion]]
+    /// name = "hermetic_build"
+    /// type = "hermetic"
+    /// inputs = ["src", "Cargo.toml", "Cargo.lock"]
+    /// toolchain = ["/usr", "~/.rustup"]
+    /// outputs = ["target"]
+    /// ```
+    Hermetic(HermeticAssertion),
+
+    /// Forbidden syscall assertion (Sprint 69)
+    ///
+    /// Fails if any syscall whose name matches the pattern is made.
+    ///
+    /// # Example TOML
+    ///
+    /// ```toml
+    /// [[assertion]]
+    /// name = "offline_build"
+    /// type = "forbidden_syscall"
+    /// syscall_pattern = "connect|sendto"
+    /// ```
+    ForbiddenSyscall(ForbiddenSyscallAssertion),
+
+    /// Error budget assertion (Sprint 69)
+    ///
+    /// Limits how many matching syscalls may fail, optionally with one errno.
+    ///
+    /// # Example TOML
+    ///
+    /// ```toml
+    /// [[assertion]]
+    /// name = "few_missing_files"
+    /// type = "error_budget"
+    /// syscall_pattern = "openat"
+    /// errno = "ENOENT"
+    /// max_errors = 5
+    /// ```
+    ErrorBudget(ErrorBudgetAssertion),
+
+    /// Latency percentile assertion (Sprint 69)
+    ///
+    /// Validates a percentile of the durations of matching syscalls.
+    ///
+    /// # Example TOML
+    ///
+    /// ```toml
+    /// [[assertion]]
+    /// name = "fast_fsync"
+    /// type = "latency_percentile"
+    /// syscall_pattern = "fsync|fdatasync"
+    /// percentile = 95
+    /// max_duration_us = 10000
+    /// ```
+    LatencyPercentile(LatencyPercentileAssertion),
+
+    /// Syscall count assertion (Sprint 69)
+    ///
+    /// Limits how often syscalls matching the pattern are made.
+    ///
+    /// # Example TOML
+    ///
+    /// ```toml
+    /// [[assertion]]
+    /// name = "few_spawns"
+    /// type = "syscall_count"
+    /// syscall_pattern = "execve|execveat"
+    /// max_count = 10
+    /// ```
+    SyscallCount(SyscallCountAssertion),
 }
 
 /// Critical path latency assertion
@@ -219,13 +301,140 @@ pub enum MemoryTrackingMode {
     Rss,
 }
 
-/// Custom assertion (user-defined Rust expression)
+/// Custom assertion (user-defined expression)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CustomAssertion {
-    /// Rust expression to evaluate (returns bool)
+    /// Condition to evaluate, e.g. `count(syscall == "openat" && result < 0) == 0`
     pub expression: String,
 }
 
+/// Build hermeticity assertion
+///
+/// Paths are prefixes: relative ones are taken from the build's working
+/// directory, and `~` or a leading `$VAR` are expanded from the environment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HermeticAssertion {
+    /// Declared build inputs
+    #[serde(default)]
+    pub inputs: Vec<String>,
+
+    /// Compilers, libraries and other toolchain files the build may read
+    #[serde(default)]
+    pub toolchain: Vec<String>,
+
+    /// Directories (or files) the build may write
+    #[serde(default)]
+    pub outputs: Vec<String>,
+
+    /// Allow sockets of families other than AF_UNIX
+    #[serde(default)]
+    pub allow_network: bool,
+
+    /// Environment variables whose directories make a build environment-dependent
+    #[serde(default = "default_env_vars")]
+    pub env_vars: Vec<String>,
+}
+
+fn default_env_vars() -> Vec<String> {
+    vec!["HOME".to_string()]
+}
+
+impl Default for HermeticAssertion {
+    fn default() -> Self {
+        Self {
+            inputs: Vec::new(),
+            toolchain: Vec::new(),
+            outputs: Vec::new(),
+            allow_network: false,
+            env_vars: default_env_vars(),
+        }
+    }
+}
+
+/// Forbidden syscall assertion
+///
+/// Syscall patterns are regexes matched against the whole syscall name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForbiddenSyscallAssertion {
+    /// Syscall name pattern (regex)
+    pub syscall_pattern: String,
+}
+
+/// Error budget assertion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorBudgetAssertion {
+    /// Syscall name pattern (regex)
+    pub syscall_pattern: String,
+
+    /// Only count this error, e.g. "ENOENT" (all errors if unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errno: Option<String>,
+
+    /// Maximum number of failed calls allowed
+    pub max_errors: usize,
+}
+
+/// Latency percentile assertion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyPercentileAssertion {
+    /// Syscall name pattern (regex)
+    pub syscall_pattern: String,
+
+    /// Percentile of call durations (0 - 100], nearest rank
+    pub percentile: f64,
+
+    /// Maximum duration of the percentile in microseconds
+    pub max_duration_us: u64,
+}
+
+/// Syscall count assertion
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SyscallCountAssertion {
+    /// Syscall name pattern (regex)
+    pub syscall_pattern: String,
+
+    /// Maximum number of calls allowed
+    pub max_count: usize,
+}
+
+/// Kind of hermeticity violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HermeticViolationKind {
+    /// Read of a file that is neither an input nor part of the toolchain
+    UndeclaredInput,
+
+    /// Read of an undeclared file under an environment-dependent directory like $HOME
+    EnvironmentInput,
+
+    /// Write, create or delete outside the declared outputs
+    UndeclaredOutput,
+
+    /// Socket of a family other than AF_UNIX
+    Network,
+}
+
+impl std::fmt::Display for HermeticViolationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            HermeticViolationKind::UndeclaredInput => "undeclared input",
+            HermeticViolationKind::EnvironmentInput => "environment input",
+            HermeticViolationKind::UndeclaredOutput => "undeclared output",
+            HermeticViolationKind::Network => "network access",
+        };
+        f.pad(label)
+    }
+}
+
+/// One access that breaks a [`HermeticAssertion`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HermeticViolation {
+    pub kind: HermeticViolationKind,
+    /// First process that made the access
+    pub pid: i32,
+    /// Path, or syscall and socket family for network access
+    pub detail: String,
+}
+
 /// Assertion evaluation result
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssertionResult {
@@ -281,6 +490,8 @@ impl AssertionResult {
 pub enum AssertionValue {
     Duration(u64),
     Count(usize),
+    /// Number of syscalls (Sprint 69)
+    Calls(usize),
     Bytes(u64),
     Percentage(f64),
     String(String),
@@ -291,6 +502,8 @@ impl std::fmt::Display for AssertionValue {
         match self {
             AssertionValue::Duration(ms) => write!(f, "{}ms", ms),
             AssertionValue::Count(n) => write!(f, "{} spans", n),
+            AssertionValue::Calls(1) => write!(f, "1 call"),
+            AssertionValue::Calls(n) => write!(f, "{} calls", n),
             AssertionValue::Bytes(b) => write!(f, "{} bytes", b),
             AssertionValue::Percentage(p) => write!(f, "{:.1}%", p * 100.0),
             AssertionValue::String(s) => write!(f, "{}", s),
@@ -302,6 +515,29 @@ impl std::fmt::Display for AssertionValue {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_hermetic_assertion_deserialize() {
+        let toml = r#"
+            name = "hermetic_build"
+            type = "hermetic"
+            inputs = ["src", "Cargo.toml"]
+            outputs = ["target"]
+        "#;
+
+        let assertion: Assertion = toml::from_str(toml).unwrap();
+
+        match assertion.assertion_type {
+            AssertionType::Hermetic(h) => {
+                assert_eq!(h.inputs, vec!["src", "Cargo.toml"]);
+                assert!(h.toolchain.is_empty());
+                assert_eq!(h.outputs, vec!["target"]);
+                assert!(!h.allow_network);
+                assert_eq!(h.env_vars, vec!["HOME"]);
+            }
+            _ => panic!("Expected Hermetic assertion"),
+        }
+    }
+
     #[test]
     fn test_critical_path_assertion_deserialize() {
         let toml = r#"
@@ -428,6 +664,8 @@ mod tests {
     fn test_assertion_value_display() {
         assert_eq!(AssertionValue::Duration(100).to_string(), "100ms");
         assert_eq!(AssertionValue::Count(42).to_string(), "42 spans");
+        assert_eq!(AssertionValue::Calls(1).to_string(), "1 call");
+        assert_eq!(AssertionValue::Calls(0).to_string(), "0 calls");
         assert_eq!(AssertionValue::Bytes(1024).to_string(), "1024 bytes");
         assert_eq!(AssertionValue::Percentage(0.85).to_string(), "85.0%");
     }
@@ -0,0 +1,481 @@
+//! Multi-run baselines for statistical regression detection
+//!
+//! Sprint 57: `renacer bench --runs N -- COMMAND` traces a command several
+//! times (after optional warm-up runs) and stores per-syscall count and time
+//! samples, one per run. `regression::assess_regression` needs at least
+//! `min_sample_size` samples per syscall, which a single trace can't provide.
+//!
+//! Each run is recorded by a child `renacer record` process (Sprint 55) so
+//! every run gets a fresh tracer; the recordings are then read back with
+//! `trace_diff::TraceSet`.
+
+use crate::regression::{assess_regression, RegressionConfig, RegressionVerdict};
+use crate::trace_diff::{TraceSet, EXIT_REGRESSION};
+use crate::trace_file::{SyscallRecord, TraceReader};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Baseline file format version
+pub const BASELINE_FORMAT_VERSION: u32 = 1;
+
+/// Per-run samples for one syscall
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyscallSamples {
+    /// Number of calls in each run
+    pub counts: Vec<u64>,
+    /// Total time spent in the syscall in each run (microseconds)
+    pub total_time_us: Vec<f32>,
+}
+
+impl SyscallSamples {
+    fn mean_count(&self) -> f64 {
+        mean(self.counts.iter().map(|&c| c as f64))
+    }
+
+    fn mean_time_us(&self) -> f64 {
+        mean(self.total_time_us.iter().map(|&t| t as f64))
+    }
+}
+
+fn mean(values: impl ExactSizeIterator<Item = f64>) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        0.0
+    } else {
+        values.sum::<f64>() / n as f64
+    }
+}
+
+/// Syscall samples from repeated runs of one command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub version: u32,
+    pub command: Vec<String>,
+    pub runs: usize,
+    pub warmup: usize,
+    pub created_unix: u64,
+    pub renacer_version: String,
+    pub syscalls: BTreeMap<String, SyscallSamples>,
+}
+
+impl Baseline {
+    /// Build a baseline from recorded runs
+    ///
+    /// Syscalls missing from a run get a zero sample for it, so every
+    /// syscall has exactly one sample per run.
+    pub fn from_runs(command: Vec<String>, warmup: usize, runs: &[Vec<SyscallRecord>]) -> Self {
+        let mut syscalls: BTreeMap<String, SyscallSamples> = BTreeMap::new();
+        for (i, run) in runs.iter().enumerate() {
+            for record in run {
+                let samples = match syscalls.get_mut(&record.name) {
+                    Some(samples) => samples,
+                    None => syscalls
+                        .entry(record.name.clone())
+                        .or_insert_with(|| SyscallSamples {
+                            counts: vec![0; runs.len()],
+                            total_time_us: vec![0.0; runs.len()],
+                        }),
+                };
+                samples.counts[i] += 1;
+                samples.total_time_us[i] += record.duration_us as f32;
+            }
+        }
+
+        Self {
+            version: BASELINE_FORMAT_VERSION,
+            command,
+            runs: runs.len(),
+            warmup,
+            created_unix: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            renacer_version: env!("CARGO_PKG_VERSION").to_string(),
+            syscalls,
+        }
+    }
+
+    /// Load a baseline file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        let baseline: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid baseline file {}", path.display()))?;
+        if baseline.version != BASELINE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Unsupported baseline version {} (expected {})",
+                baseline.version,
+                BASELINE_FORMAT_VERSION
+            );
+        }
+        Ok(baseline)
+    }
+
+    /// Write the baseline as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize baseline")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    /// Per-run time samples in the shape `assess_regression` expects
+    pub fn time_samples(&self) -> HashMap<String, Vec<f32>> {
+        self.syscalls
+            .iter()
+            .map(|(name, samples)| (name.clone(), samples.total_time_us.clone()))
+            .collect()
+    }
+
+    /// Mean calls per run for each syscall (Sprint 58: `--cluster-baseline`)
+    pub fn mean_counts(&self) -> HashMap<String, f64> {
+        self.syscalls
+            .iter()
+            .map(|(name, samples)| (name.clone(), samples.mean_count()))
+            .collect()
+    }
+
+    /// Per-syscall mean count and time, sorted by time (descending)
+    pub fn summary_string(&self) -> String {
+        let mut rows: Vec<(&String, &SyscallSamples)> = self.syscalls.iter().collect();
+        rows.sort_by(|a, b| b.1.mean_time_us().total_cmp(&a.1.mean_time_us()));
+
+        let mut out = format!(
+            "{:<20} {:>12} {:>14}\n",
+            "syscall", "calls/run", "time/run (μs)"
+        );
+        for (name, samples) in rows {
+            out.push_str(&format!(
+                "{:<20} {:>12.1} {:>14.1}\n",
+                name,
+                samples.mean_count(),
+                samples.mean_time_us()
+            ));
+        }
+        out
+    }
+}
+
+/// Settings for `renacer bench`
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    /// Measured runs
+    pub runs: usize,
+    /// Unmeasured runs before the measured ones
+    pub warmup: usize,
+    /// Where to save the new baseline
+    pub output: Option<String>,
+    /// Baseline to compare against
+    pub compare_baseline: Option<String>,
+    /// Trace forked children too (`-f`)
+    pub follow_forks: bool,
+    /// Regression test settings for `--compare-baseline`
+    pub regression: RegressionConfig,
+}
+
+/// Benchmark a command, returning the process exit code
+///
+/// 0 unless `--compare-baseline` detects a regression ([`EXIT_REGRESSION`]).
+pub fn run_bench(command: &[String], options: &BenchOptions) -> Result<i32> {
+    if command.is_empty() {
+        anyhow::bail!("Must specify a command. Usage: renacer bench --runs N -- COMMAND [ARGS...]");
+    }
+    if options.runs == 0 {
+        anyhow::bail!("--runs must be at least 1");
+    }
+    options.regression.validate().map_err(anyhow::Error::msg)?;
+
+    // Load the comparison baseline up front so a bad path fails fast
+    let previous = options
+        .compare_baseline
+        .as_deref()
+        .map(Baseline::load)
+        .transpose()?;
+
+    let runs = record_runs(
+        command,
+        options.runs,
+        options.warmup,
+        options.follow_forks,
+        false,
+    )?;
+
+    let baseline = Baseline::from_runs(command.to_vec(), options.warmup, &runs.runs);
+    eprintln!(
+        "[renacer: {} runs of {} ({} warm-up)]",
+        baseline.runs,
+        command.join(" "),
+        baseline.warmup
+    );
+    print!("{}", baseline.summary_string());
+
+    if let Some(path) = &options.output {
+        baseline.save(path)?;
+        eprintln!("[renacer: baseline saved to {}]", path);
+        if baseline.runs < options.regression.min_sample_size {
+            eprintln!(
+                "[renacer: Warning: regression checks need at least {} runs per baseline]",
+                options.regression.min_sample_size
+            );
+        }
+    }
+
+    let Some(previous) = previous else {
+        return Ok(0);
+    };
+    if previous.command != baseline.command {
+        eprintln!(
+            "[renacer: Warning: baseline was recorded for `{}`]",
+            previous.command.join(" ")
+        );
+    }
+
+    let assessment = assess_regression(
+        &previous.time_samples(),
+        &baseline.time_samples(),
+        &options.regression,
+    )?;
+    println!(
+        "\n=== Regression vs {} ===",
+        options.compare_baseline.as_deref().unwrap_or_default()
+    );
+    print!("{}", assessment.to_report_string());
+
+    Ok(match assessment.verdict {
+        RegressionVerdict::Regression { .. } => EXIT_REGRESSION,
+        _ => 0,
+    })
+}
+
+/// Record warm-up and measured runs, returning the measured ones
+///
+/// Also used by `renacer learn` (Sprint 59) to collect training runs.
+/// Call stacks are only unwound with `stacks`, so timings are not skewed
+/// by the unwinding. A run of the command exiting non-zero is an error.
+pub fn record_runs(
+    command: &[String],
+    runs: usize,
+    warmup: usize,
+    follow_forks: bool,
+    stacks: bool,
+) -> Result<TraceSet> {
+    let work_dir = tempfile::Builder::new()
+        .prefix("renacer-bench-")
+        .tempdir()
+        .context("Failed to create a directory for the runs")?;
+    record_runs_in(command, runs, warmup, follow_forks, stacks, work_dir.path())
+}
+
+fn record_runs_in(
+    command: &[String],
+    runs: usize,
+    warmup: usize,
+    follow_forks: bool,
+    stacks: bool,
+    work_dir: &Path,
+) -> Result<TraceSet> {
+    let renacer = std::env::current_exe().context("Failed to locate the renacer binary")?;
+    let record = |trace: &Path| -> Result<()> {
+        let code = record_once(&renacer, command, follow_forks, stacks, false, trace)?;
+        if code != 0 {
+            anyhow::bail!(
+                "`{}` failed under trace (exit status {})",
+                command.join(" "),
+                code
+            );
+        }
+        Ok(())
+    };
+
+    let warmup_trace = work_dir.join("warmup.trace");
+    for i in 0..warmup {
+        eprintln!("[renacer: warm-up run {}/{}]", i + 1, warmup);
+        record(&warmup_trace)?;
+    }
+
+    for i in 0..runs {
+        eprintln!("[renacer: run {}/{}]", i + 1, runs);
+        record(&work_dir.join(format!("run{:04}.rnc", i)))?;
+    }
+
+    TraceSet::load(work_dir)
+}
+
+/// Record a single run, returning it with the command's exit code
+///
+/// Used by `renacer hermetic` (Sprint 62) and `renacer check` (Sprint 65),
+/// which report on failing commands too. The command's stdin, stdout and
+/// stderr are inherited with `inherit_stdio`.
+pub fn record_run(
+    command: &[String],
+    follow_forks: bool,
+    stacks: bool,
+    inherit_stdio: bool,
+) -> Result<(TraceSet, i32)> {
+    let renacer = std::env::current_exe().context("Failed to locate the renacer binary")?;
+    let work_dir = tempfile::Builder::new()
+        .prefix("renacer-run-")
+        .tempdir()
+        .context("Failed to create a directory for the run")?;
+    let trace = work_dir.path().join("run.rnc");
+    let code = record_once(
+        &renacer,
+        command,
+        follow_forks,
+        stacks,
+        inherit_stdio,
+        &trace,
+    )?;
+    Ok((TraceSet::load(&trace)?, code))
+}
+
+/// Trace one execution into `trace` with `renacer record`
+///
+/// Returns the command's exit code (128 + signal if it was killed). The
+/// trace is kept whatever the exit code; it is an error only if the tracer
+/// failed before the command exited.
+pub(crate) fn record_once(
+    renacer: &Path,
+    command: &[String],
+    follow_forks: bool,
+    stacks: bool,
+    inherit_stdio: bool,
+    trace: &Path,
+) -> Result<i32> {
+    let mut cmd = Command::new(renacer);
+    cmd.arg("record").arg("-o").arg(trace);
+    if follow_forks {
+        cmd.arg("-f");
+    }
+    if stacks {
+        cmd.arg("--stacks");
+    }
+    cmd.arg("--").args(command);
+    if inherit_stdio {
+        cmd.stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit());
+    } else {
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+    }
+    let output = cmd.output().context("Failed to run renacer record")?;
+
+    let exit_code = TraceReader::open(trace).and_then(TraceReader::main_exit_code);
+    match exit_code {
+        Ok(Some(code)) => Ok(code),
+        _ => {
+            let mut message = format!(
+                "Failed to trace `{}` ({})",
+                command.join(" "),
+                output.status
+            );
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.trim().is_empty() {
+                message = format!("{}:\n{}", message, stderr.trim_end());
+            }
+            anyhow::bail!(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(name: &str, duration_us: u64) -> SyscallRecord {
+        SyscallRecord {
+            pid: 1,
+            timestamp_us: 0,
+            duration_us,
+            number: 0,
+            name: name.to_string(),
+            args: Vec::new(),
+            raw_args: [0; 3],
+            result: 0,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: Vec::new(),
+            wait_object: None,
+            stderr: None,
+        }
+    }
+
+    fn command() -> Vec<String> {
+        vec!["true".to_string()]
+    }
+
+    #[test]
+    fn test_baseline_has_one_sample_per_run() {
+        let runs = vec![
+            vec![record("read", 10), record("read", 5), record("close", 1)],
+            vec![record("read", 20)],
+        ];
+        let baseline = Baseline::from_runs(command(), 1, &runs);
+
+        assert_eq!(baseline.runs, 2);
+        assert_eq!(baseline.warmup, 1);
+        assert_eq!(baseline.syscalls["read"].counts, vec![2, 1]);
+        assert_eq!(baseline.syscalls["read"].total_time_us, vec![15.0, 20.0]);
+        // Missing from the second run: zero sample
+        assert_eq!(baseline.syscalls["close"].counts, vec![1, 0]);
+        assert_eq!(baseline.syscalls["close"].total_time_us, vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+        let baseline = Baseline::from_runs(command(), 0, &[vec![record("openat", 7)]]);
+
+        baseline.save(&path).unwrap();
+        assert_eq!(Baseline::load(&path).unwrap(), baseline);
+    }
+
+    #[test]
+    fn test_baseline_rejects_other_versions() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("baseline.json");
+        let mut baseline = Baseline::from_runs(command(), 0, &[]);
+        baseline.version = 99;
+        baseline.save(&path).unwrap();
+
+        let err = Baseline::load(&path).unwrap_err();
+        assert!(err.to_string().contains("Unsupported baseline version 99"));
+    }
+
+    #[test]
+    fn test_time_samples_feed_regression_assessment() {
+        let runs: Vec<_> = (0..5).map(|i| vec![record("read", 100 + i)]).collect();
+        let baseline = Baseline::from_runs(command(), 0, &runs);
+        let slower: Vec<_> = (0..5).map(|i| vec![record("read", 400 + i)]).collect();
+        let current = Baseline::from_runs(command(), 0, &slower);
+
+        let assessment = assess_regression(
+            &baseline.time_samples(),
+            &current.time_samples(),
+            &RegressionConfig::default(),
+        )
+        .unwrap();
+        assert!(matches!(
+            assessment.verdict,
+            RegressionVerdict::Regression { .. }
+        ));
+    }
+
+    #[test]
+    fn test_summary_sorted_by_time() {
+        let runs = vec![vec![record("close", 1), record("read", 50)]];
+        let summary = Baseline::from_runs(command(), 0, &runs).summary_string();
+        let read = summary.find("read").unwrap();
+        let close = summary.find("close").unwrap();
+        assert!(read < close);
+    }
+}
@@ -91,7 +91,9 @@
 //! # }
 //! ```
 
-use crate::span_record::SpanRecord;
+use crate::otlp_exporter::TransferDirection;
+use crate::span_record::{SpanKind, SpanRecord, StatusCode};
+use crate::unified_trace::UnifiedTrace;
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use trueno_graph::{CsrGraph, NodeId};
@@ -242,6 +244,140 @@ impl CausalGraph {
         })
     }
 
+    /// Build a causal graph from a unified trace (Sprint 66)
+    ///
+    /// The process span is the root. Each syscall follows the previous
+    /// syscall of its process, so the critical path runs through program
+    /// order; the first syscall of a child process follows the `clone`,
+    /// `fork` or `vfork` that returned its pid. GPU kernels and memory
+    /// transfers hang off the root, named so that PCIe bottleneck detection
+    /// recognizes them.
+    ///
+    /// The process span has no duration of its own, so the critical path
+    /// only counts time spent in syscalls and GPU work. Spans are numbered by
+    /// position (node N is the Nth syscall), which also serves as their
+    /// logical clock.
+    pub fn from_unified_trace(trace: &UnifiedTrace) -> Result<Self> {
+        let root_pid = trace.process_span.pid;
+        let mut trace_id = [0u8; 16];
+        trace_id[..4].copy_from_slice(&root_pid.to_be_bytes());
+        let start = trace
+            .syscall_spans
+            .iter()
+            .map(|span| span.timestamp_nanos)
+            .min()
+            .unwrap_or(0);
+
+        let root_id = 0u64.to_be_bytes();
+        let record = |span_id: u64,
+                      parent: [u8; 8],
+                      name: String,
+                      start: u64,
+                      duration: u64,
+                      status: StatusCode,
+                      pid: i32| {
+            SpanRecord::new(
+                trace_id,
+                span_id.to_be_bytes(),
+                Some(parent),
+                name,
+                SpanKind::Internal,
+                start,
+                start + duration,
+                span_id,
+                status,
+                String::new(),
+                HashMap::new(),
+                HashMap::new(),
+                pid as u32,
+                pid as u64,
+            )
+        };
+
+        let mut spans = vec![SpanRecord::new(
+            trace_id,
+            root_id,
+            None,
+            trace.process_span.name.clone(),
+            SpanKind::Internal,
+            start,
+            start,
+            0,
+            StatusCode::Ok,
+            String::new(),
+            HashMap::new(),
+            HashMap::new(),
+            root_pid as u32,
+            root_pid as u64,
+        )];
+
+        // Latest span of each process, and the spawning span of new processes
+        let mut last: HashMap<i32, [u8; 8]> = HashMap::new();
+        let mut spawned_by: HashMap<i32, [u8; 8]> = HashMap::new();
+        for (span, span_id) in trace.syscall_spans.iter().zip(1u64..) {
+            let pid = span.pid.unwrap_or(root_pid);
+            let parent = last
+                .get(&pid)
+                .or_else(|| spawned_by.get(&pid))
+                .copied()
+                .unwrap_or(root_id);
+            let status = if span.is_error() {
+                StatusCode::Error
+            } else {
+                StatusCode::Ok
+            };
+            spans.push(record(
+                span_id,
+                parent,
+                span.name.to_string(),
+                span.timestamp_nanos,
+                span.duration_nanos,
+                status,
+                pid,
+            ));
+
+            let span_id = span_id.to_be_bytes();
+            last.insert(pid, span_id);
+            if matches!(&*span.name, "clone" | "clone3" | "fork" | "vfork") && span.return_value > 0
+            {
+                spawned_by.insert(span.return_value as i32, span_id);
+            }
+        }
+
+        // GPU work has no timestamps: it comes after the syscalls
+        let mut next_id = trace.syscall_spans.len() as u64;
+        for kernel in &trace.gpu_spans {
+            next_id += 1;
+            spans.push(record(
+                next_id,
+                root_id,
+                format!("GPU kernel {}", kernel.kernel),
+                start,
+                kernel.duration_us * 1000,
+                StatusCode::Ok,
+                root_pid,
+            ));
+        }
+        for transfer in &trace.gpu_memory_transfers {
+            let direction = match transfer.direction {
+                TransferDirection::CpuToGpu => "H2D",
+                TransferDirection::GpuToCpu => "D2H",
+            };
+            next_id += 1;
+            spans.push(record(
+                next_id,
+                root_id,
+                format!("memcpy {} {}", direction, transfer.label),
+                start,
+                transfer.duration_us * 1000,
+                StatusCode::Ok,
+                root_pid,
+            ));
+        }
+
+        Self::from_spans(&spans)
+    }
+
     /// Get the number of nodes in the graph
     pub fn node_count(&self) -> usize {
         self.span_metadata.len()
@@ -339,28 +475,39 @@ impl CausalGraph {
     }
 
     /// DFS helper for cycle detection
+    ///
+    /// Walks with an explicit stack of child iterators; `rec_stack` holds
+    /// the nodes on the current path, so reaching one of them again is a
+    /// back edge. A recursive walk would overflow on the long single-process
+    /// syscall chains of a real trace.
     fn has_cycle_dfs(
         &self,
-        node: NodeId,
+        root: NodeId,
         visited: &mut std::collections::HashSet<NodeId>,
         rec_stack: &mut std::collections::HashSet<NodeId>,
     ) -> Result<bool> {
-        visited.insert(node);
-        rec_stack.insert(node);
-
-        let children = self.children(node)?;
-        for (child, _) in children {
-            if !visited.contains(&child) {
-                if self.has_cycle_dfs(child, visited, rec_stack)? {
-                    return Ok(true);
+        visited.insert(root);
+        rec_stack.insert(root);
+        let mut pending = vec![(root, self.children(root)?.into_iter())];
+
+        while let Some((node, children)) = pending.last_mut() {
+            let node = *node;
+            match children.next() {
+                Some((child, _)) if !visited.contains(&child) => {
+                    visited.insert(child);
+                    rec_stack.insert(child);
+                    pending.push((child, self.children(child)?.into_iter()));
                 }
-            } else if rec_stack.contains(&child) {
                 // Back edge = cycle
-                return Ok(true);
+                Some((child, _)) if rec_stack.contains(&child) => return Ok(true),
+                Some(_) => {}
+                None => {
+                    rec_stack.remove(&node);
+                    pending.pop();
+                }
             }
         }
 
-        rec_stack.remove(&node);
         Ok(false)
     }
 
@@ -390,12 +537,39 @@ impl CausalGraph {
         self.get_node_by_span_id(span_id)
             .and_then(|node| self.get_span(node))
     }
+
+    /// Add a happens-before edge that is not a parent link (Sprint 71)
+    ///
+    /// A span has one parent, but can wait on more than one cause: a
+    /// `wait4` returns only after its parent's previous syscall *and* the
+    /// reaped child's last syscall.
+    ///
+    /// # Errors
+    ///
+    /// Returns error if either span is not in the graph.
+    pub fn add_edge(&mut self, from: &[u8; 8], to: &[u8; 8]) -> Result<()> {
+        let from_node = self
+            .get_node_by_span_id(from)
+            .with_context(|| format!("Unknown span {}", hex::encode(from)))?;
+        let to_node = self
+            .get_node_by_span_id(to)
+            .with_context(|| format!("Unknown span {}", hex::encode(to)))?;
+        let weight = self.span_metadata[&to_node].duration_nanos as f32;
+
+        self.graph
+            .add_edge(from_node, to_node, weight)
+            .context("Failed to add happens-before edge")?;
+        self.roots.retain(|&root| root != to_node);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::span_record::{SpanKind, StatusCode};
+    use crate::otlp_exporter::{GpuKernel, GpuMemoryTransfer};
+    use crate::unified_trace::SyscallSpan;
+    use std::borrow::Cow;
 
     fn create_test_span(
         span_id: u8,
@@ -515,6 +689,26 @@ mod tests {
         assert!(graph.is_dag().unwrap());
     }
 
+    #[test]
+    fn test_add_edge_joins_two_chains() {
+        let root = create_test_span(1, None, 0, 1000);
+        let left = create_test_span(2, Some(1), 1, 500);
+        let right = create_test_span(3, Some(1), 2, 700);
+        let orphan = create_test_span(4, None, 3, 100);
+
+        let mut graph = CausalGraph::from_spans(&[root, left, right, orphan]).unwrap();
+        assert_eq!(graph.roots().len(), 2);
+
+        graph.add_edge(&[2; 8], &[3; 8]).unwrap();
+        graph.add_edge(&[3; 8], &[4; 8]).unwrap();
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.roots(), &[NodeId(0)]);
+        assert_eq!(graph.descendants(NodeId(0)).unwrap().len(), 4);
+        assert!(graph.is_dag().unwrap());
+
+        assert!(graph.add_edge(&[2; 8], &[9; 8]).is_err());
+    }
+
     #[test]
     fn test_inconsistent_trace_id() {
         let mut span1 = create_test_span(1, None, 0, 1000);
@@ -525,4 +719,97 @@ mod tests {
         let result = CausalGraph::from_spans(&[span1, span2]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_from_unified_trace() {
+        let mut trace = UnifiedTrace::new(10, "/bin/sh".to_string());
+        let mut syscall = |pid: i32, name: &'static str, result: i64| {
+            let span = SyscallSpan::new(
+                trace.process_span.span_id,
+                Cow::Borrowed(name),
+                Vec::new(),
+                result,
+                0,
+                100,
+                None,
+                &trace.clock,
+            )
+            .with_pid(pid);
+            trace.add_syscall(span);
+        };
+        syscall(10, "clone", 11);
+        syscall(11, "execve", 0);
+        syscall(10, "wait4", -4);
+        syscall(11, "read", 5);
+        trace.add_gpu_kernel(GpuKernel {
+            kernel: "sum".to_string(),
+            duration_us: 5,
+            backend: "wgpu",
+            workgroup_size: None,
+            elements: None,
+            is_slow: false,
+        });
+        trace.add_gpu_memory_transfer(GpuMemoryTransfer::new(
+            "upload".to_string(),
+            TransferDirection::CpuToGpu,
+            1024,
+            3,
+            None,
+            100,
+        ));
+
+        let graph = CausalGraph::from_unified_trace(&trace).unwrap();
+        assert_eq!(graph.node_count(), 7);
+        assert_eq!(graph.roots(), &[NodeId(0)]);
+        assert_eq!(graph.get_span(NodeId(0)).unwrap().duration_nanos, 0);
+
+        // The child's first syscall follows the clone that created it
+        let children = |node: u32| -> Vec<u32> {
+            graph
+                .children(NodeId(node))
+                .unwrap()
+                .iter()
+                .map(|(child, _)| child.0)
+                .collect()
+        };
+        assert_eq!(children(0), vec![1, 5, 6]);
+        assert_eq!(children(1), vec![2, 3]);
+        assert_eq!(children(2), vec![4]);
+
+        let wait = graph.get_span(NodeId(3)).unwrap();
+        assert_eq!(wait.process_id, 10);
+        assert_eq!(wait.status_code, StatusCode::Error);
+        assert_eq!(graph.get_span(NodeId(4)).unwrap().process_id, 11);
+        assert_eq!(
+            graph.get_span(NodeId(5)).unwrap().span_name,
+            "GPU kernel sum"
+        );
+        assert_eq!(
+            graph.get_span(NodeId(6)).unwrap().span_name,
+            "memcpy H2D upload"
+        );
+    }
+
+    #[test]
+    fn test_deep_syscall_chain() {
+        let mut trace = UnifiedTrace::new(1, "loop".to_string());
+        for _ in 0..20_000 {
+            let span = SyscallSpan::new(
+                trace.process_span.span_id,
+                Cow::Borrowed("getppid"),
+                Vec::new(),
+                1,
+                0,
+                10,
+                None,
+                &trace.clock,
+            );
+            trace.add_syscall(span);
+        }
+
+        let graph = CausalGraph::from_unified_trace(&trace).unwrap();
+        assert!(graph.is_dag().unwrap());
+        let critical_path = crate::critical_path::find_critical_path(&graph).unwrap();
+        assert_eq!(critical_path.path.len(), 20_001);
+    }
 }
@@ -0,0 +1,490 @@
+//! `renacer check`: renacer.toml assertions against a traced run (Sprint 65)
+//!
+//! The run, traced live or loaded from a `renacer record` file, becomes a
+//! [`UnifiedTrace`] for [`AssertionEngine::evaluate_all`]. Hermetic assertions
+//! are checked against the run's file and socket accesses instead, as in
+//! `renacer hermetic`.
+//!
+//! # Toyota Way: Andon
+//!
+//! The report stops the line (non-zero exit) when an assertion with
+//! `fail_on_violation = true` fails; other failures are shown as warnings.
+//! A traced command that exits non-zero is reported but still checked, and
+//! runs that cannot be traced or checked exit with [`EXIT_CHECK_ERROR`].
+
+use crate::assertion_dsl::AssertionConfig;
+use crate::assertion_engine::AssertionEngine;
+use crate::assertion_types::{Assertion, AssertionResult, AssertionType};
+use crate::dwarf::SourceLocation;
+use crate::hermetic::{BuildAccesses, HermeticRules};
+use crate::memory::MemorySample;
+use crate::stack_unwind::ResolvedFrame;
+use crate::trace_file::SyscallRecord;
+use crate::unified_trace::{SyscallSpan, UnifiedTrace};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Process exit code when the check itself fails (bad config, tracer
+/// error), as opposed to a failed assertion (1)
+pub const EXIT_CHECK_ERROR: i32 = 2;
+
+/// Build a unified trace from the completed syscalls of one run
+///
+/// Spans carry their process, and their call site when the source location
+/// or stack is known.
+pub fn unified_trace(records: &[SyscallRecord], command: &[String]) -> UnifiedTrace {
+    let pid = records.first().map_or(0, |record| record.pid);
+    let program = command.first().cloned().unwrap_or_default();
+    let mut trace = UnifiedTrace::new(pid, program);
+    for record in records {
+        let args = record
+            .args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| (Cow::Owned(format!("arg{}", i)), arg.clone()))
+            .collect();
+        let span = SyscallSpan::new(
+            trace.process_span.span_id,
+            Cow::Owned(record.name.clone()),
+            args,
+            record.result,
+            record.timestamp_us * 1000,
+            record.duration_us * 1000,
+            (record.result < 0).then(|| -record.result as i32),
+            &trace.clock,
+        )
+        .with_pid(record.pid)
+        .with_call_site(call_site(record));
+        trace.add_syscall(span);
+    }
+    trace
+}
+
+/// `file:line (function)` of the syscall site, from DWARF or the innermost
+/// named stack frame outside libc and the dynamic loader
+fn call_site(record: &SyscallRecord) -> Option<String> {
+    format_call_site(
+        record.source.as_ref(),
+        record.function_name.as_ref(),
+        &record.stack,
+    )
+}
+
+/// [`call_site`] from the parts of a live or recorded syscall (Sprint 74:
+/// shared with the syscall anti-pattern detectors)
+pub(crate) fn format_call_site(
+    source: Option<&SourceLocation>,
+    function_name: Option<&String>,
+    stack: &[ResolvedFrame],
+) -> Option<String> {
+    let frame = stack
+        .iter()
+        .find(|frame| frame.function.is_some() && !is_libc_frame(frame));
+    let (file, line, function) = match (source, frame) {
+        (Some(source), _) => (
+            Some(&source.file),
+            Some(source.line),
+            source.function.as_ref().or(function_name),
+        ),
+        (None, Some(frame)) => (frame.file.as_ref(), frame.line, frame.function.as_ref()),
+        (None, None) => (None, None, function_name),
+    };
+    match (file, line, function) {
+        (Some(file), Some(line), Some(function)) => {
+            Some(format!("{}:{} ({})", file, line, function))
+        }
+        (Some(file), Some(line), None) => Some(format!("{}:{}", file, line)),
+        (_, _, function) => function.cloned(),
+    }
+}
+
+/// Frames of libc and the dynamic loader, which wrap the syscall instruction
+/// for the code that actually made the call (`__GI___libc_write`)
+fn is_libc_frame(frame: &ResolvedFrame) -> bool {
+    let module = frame.module.as_deref().unwrap_or("");
+    let function = frame.function.as_deref().unwrap_or("");
+    ["libc.so", "libc-", "libpthread", "ld-linux", "ld-musl"]
+        .iter()
+        .any(|prefix| module.starts_with(prefix))
+        || ["__GI_", "__libc_", "_dl_"]
+            .iter()
+            .any(|prefix| function.starts_with(prefix))
+}
+
+/// Results of every enabled assertion in a renacer.toml
+#[derive(Debug, Clone)]
+pub struct CheckReport {
+    /// Where the assertions were loaded from
+    pub source: String,
+    /// Enabled assertions, in file order
+    pub assertions: Vec<Assertion>,
+    /// One result per enabled assertion
+    pub results: Vec<AssertionResult>,
+    /// Names of assertions with `enabled = false`
+    pub skipped: Vec<String>,
+    /// Processes and syscalls in the checked run
+    pub processes: usize,
+    pub syscalls: usize,
+}
+
+impl CheckReport {
+    /// Evaluate the enabled assertions of `config` against one run
+    ///
+    /// `rss_samples` feed `tracking_mode = "rss"` memory assertions; `cwd`
+    /// and `env` resolve the relative and `$VAR` paths of hermetic
+    /// assertions.
+    pub fn evaluate(
+        source: &str,
+        config: &AssertionConfig,
+        records: &[SyscallRecord],
+        rss_samples: &[MemorySample],
+        command: &[String],
+        cwd: &Path,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let assertions: Vec<Assertion> = config.enabled_assertions().into_iter().cloned().collect();
+        let engine = AssertionEngine::new();
+        let mut trace = unified_trace(records, command);
+        trace.rss_samples = rss_samples.to_vec();
+        let mut results = engine.evaluate_all(&assertions, &trace);
+
+        // Hermetic assertions need resolved file accesses rather than spans
+        let mut accesses = None;
+        for (result, assertion) in results.iter_mut().zip(&assertions) {
+            if let AssertionType::Hermetic(hermetic) = &assertion.assertion_type {
+                let accesses = accesses.get_or_insert_with(|| {
+                    BuildAccesses::from_run(records, command.first().map(String::as_str))
+                });
+                let rules = HermeticRules::new(hermetic, cwd, &env);
+                let violations = engine.hermetic_violations(&rules, accesses);
+                *result = engine.evaluate_hermetic(&assertion.name, &violations);
+            }
+        }
+
+        Self {
+            source: source.to_string(),
+            assertions,
+            results,
+            skipped: config
+                .assertion
+                .iter()
+                .filter(|a| !a.enabled)
+                .map(|a| a.name.clone())
+                .collect(),
+            processes: records
+                .iter()
+                .map(|record| record.pid)
+                .collect::<HashSet<_>>()
+                .len(),
+            syscalls: records.len(),
+        }
+    }
+
+    /// Whether an assertion with `fail_on_violation = true` failed
+    pub fn has_failures(&self) -> bool {
+        AssertionEngine::has_failures(&self.results, &self.assertions)
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (&AssertionResult, &Assertion)> {
+        self.results.iter().zip(&self.assertions)
+    }
+
+    fn failures(&self) -> usize {
+        self.pairs()
+            .filter(|(result, assertion)| !result.passed && assertion.fail_on_violation)
+            .count()
+    }
+
+    /// Andon-style text report
+    pub fn to_text(&self) -> String {
+        let mut out = format!("\n=== Assertion Check ({}) ===\n", self.source);
+        out.push_str(&format!(
+            "Processes: {}, syscalls: {}\n",
+            self.processes, self.syscalls
+        ));
+        for (result, assertion) in self.pairs() {
+            let status = match (result.passed, assertion.fail_on_violation) {
+                (true, _) => "✅ PASS",
+                (false, true) => "❌ FAIL",
+                (false, false) => "⚠️ WARN",
+            };
+            out.push_str(&format!(
+                "  {} {}: {}\n",
+                status, result.name, result.message
+            ));
+            if let (false, Some(actual), Some(expected)) =
+                (result.passed, &result.actual_value, &result.expected_value)
+            {
+                out.push_str(&format!(
+                    "           actual {}, expected {}\n",
+                    actual, expected
+                ));
+            }
+        }
+        for name in &self.skipped {
+            out.push_str(&format!("  ⏭ SKIP {}: disabled\n", name));
+        }
+
+        let failures = self.failures();
+        if failures > 0 {
+            out.push_str(&format!(
+                "🛑 ANDON: stop the line, {} of {} assertions failed\n",
+                failures,
+                self.assertions.len()
+            ));
+        } else {
+            out.push_str(&format!(
+                "✅ All {} assertions passed\n",
+                self.assertions.len()
+            ));
+        }
+        out
+    }
+
+    /// JUnit XML for CI (warnings pass, disabled assertions are skipped)
+    pub fn to_junit(&self) -> String {
+        let tests = self.assertions.len() + self.skipped.len();
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" skipped=\"{}\"",
+            tests,
+            self.failures(),
+            self.skipped.len()
+        );
+
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(&format!("<testsuites name=\"renacer check\" {}>\n", counts));
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" {}>\n",
+            xml_escape(&self.source),
+            counts
+        ));
+        for (result, assertion) in self.pairs() {
+            let name = xml_escape(&result.name);
+            let message = xml_escape(&result.message);
+            if result.passed {
+                out.push_str(&format!(
+                    "    <testcase classname=\"renacer.check\" name=\"{}\"/>\n",
+                    name
+                ));
+                continue;
+            }
+            out.push_str(&format!(
+                "    <testcase classname=\"renacer.check\" name=\"{}\">\n",
+                name
+            ));
+            if assertion.fail_on_violation {
+                let detail = match (&result.actual_value, &result.expected_value) {
+                    (Some(actual), Some(expected)) => {
+                        xml_escape(&format!("actual {}, expected {}", actual, expected))
+                    }
+                    _ => message.clone(),
+                };
+                out.push_str(&format!(
+                    "      <failure message=\"{}\">{}</failure>\n",
+                    message, detail
+                ));
+            } else {
+                out.push_str(&format!(
+                    "      <system-out>WARN: {}</system-out>\n",
+                    message
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        for name in &self.skipped {
+            out.push_str(&format!(
+                "    <testcase classname=\"renacer.check\" name=\"{}\">\n      <skipped/>\n    </testcase>\n",
+                xml_escape(name)
+            ));
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        out
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pid: i32, name: &str, args: &[&str], result: i64, duration_us: u64) -> SyscallRecord {
+        SyscallRecord {
+            pid,
+            timestamp_us: 0,
+            duration_us,
+            number: 0,
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            raw_args: [0; 3],
+            result,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: Vec::new(),
+            wait_object: None,
+            stderr: None,
+        }
+    }
+
+    const CONFIG: &str = r#"
+        [[assertion]]
+        name = "max_syscalls"
+        type = "span_count"
+        max_spans = 2
+
+        [[assertion]]
+        name = "latency"
+        type = "critical_path"
+        max_duration_ms = 1
+        fail_on_violation = false
+
+        [[assertion]]
+        name = "off"
+        type = "span_count"
+        max_spans = 0
+        enabled = false
+
+        [[assertion]]
+        name = "sealed <build>"
+        type = "hermetic"
+        inputs = ["src"]
+    "#;
+
+    fn report(records: &[SyscallRecord]) -> CheckReport {
+        let config = AssertionConfig::from_toml_str(CONFIG).unwrap();
+        let command = vec!["/bin/tool".to_string()];
+        CheckReport::evaluate(
+            "renacer.toml",
+            &config,
+            records,
+            &[],
+            &command,
+            Path::new("/work"),
+            |_| None,
+        )
+    }
+
+    #[test]
+    fn test_unified_trace_from_records() {
+        let records = vec![
+            record(5, "openat", &["AT_FDCWD", "\"a\""], 3, 10),
+            record(6, "read", &["3"], -9, 20),
+        ];
+        let trace = unified_trace(&records, &["cc".to_string()]);
+        assert_eq!(trace.process_span.pid, 5);
+        assert_eq!(trace.process_span.name, "cc");
+        assert_eq!(trace.syscall_spans.len(), 2);
+        assert_eq!(trace.syscall_spans[0].args[1].1, "\"a\"");
+        assert_eq!(trace.syscall_spans[1].duration_nanos, 20_000);
+        assert_eq!(trace.syscall_spans[1].pid, Some(6));
+        assert_eq!(trace.syscall_spans[1].errno, Some(9));
+        assert_eq!(trace.syscall_spans[1].call_site, None);
+    }
+
+    #[test]
+    fn test_call_sites() {
+        let mut records = vec![
+            record(5, "openat", &["AT_FDCWD", "\"a\""], 3, 10),
+            record(5, "read", &["3"], 1, 20),
+            record(5, "close", &["3"], 0, 1),
+        ];
+        records[0].source = Some(crate::dwarf::SourceLocation {
+            file: "src/main.rs".to_string(),
+            line: 42,
+            column: None,
+            function: Some("load".to_string()),
+        });
+        let frame = |function: &str, module: &str| crate::stack_unwind::ResolvedFrame {
+            address: 0x1000,
+            function: Some(function.to_string()),
+            file: None,
+            line: None,
+            module: Some(module.to_string()),
+            inlined: false,
+        };
+        // libc's wrappers are skipped for the code that called them
+        records[1].stack = vec![
+            frame("__GI___libc_read", "libc.so.6"),
+            frame("read", "libc.so.6"),
+            frame("read_all", "cc"),
+        ];
+        records[2].function_name = Some("finish".to_string());
+
+        let trace = unified_trace(&records, &["cc".to_string()]);
+        let sites: Vec<_> = trace
+            .syscall_spans
+            .iter()
+            .map(|s| s.call_site.as_deref())
+            .collect();
+        assert_eq!(
+            sites,
+            vec![
+                Some("src/main.rs:42 (load)"),
+                Some("read_all"),
+                Some("finish")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_passing_run() {
+        let records = vec![record(
+            5,
+            "openat",
+            &["AT_FDCWD", "\"/work/src/a.rs\"", "0x0"],
+            3,
+            10,
+        )];
+        let report = report(&records);
+        assert!(!report.has_failures(), "{}", report.to_text());
+        assert_eq!(report.results.len(), 3);
+        assert_eq!(report.skipped, vec!["off".to_string()]);
+
+        let text = report.to_text();
+        assert!(text.contains("=== Assertion Check (renacer.toml) ==="));
+        assert!(text.contains("✅ PASS max_syscalls: Span count 1 <= 2"));
+        assert!(text.contains("⏭ SKIP off: disabled"));
+        assert!(text.contains("✅ All 3 assertions passed"));
+    }
+
+    #[test]
+    fn test_failures_stop_the_line() {
+        let records = vec![
+            record(5, "openat", &["AT_FDCWD", "\"/etc/passwd\"", "0x0"], 3, 10),
+            record(5, "read", &["3"], 10, 3_000),
+            record(5, "close", &["3"], 0, 1),
+        ];
+        let report = report(&records);
+        assert!(report.has_failures());
+
+        let text = report.to_text();
+        assert!(text.contains("❌ FAIL max_syscalls: Span count 3 exceeds maximum 2"));
+        assert!(text.contains("actual 3 spans, expected 2 spans"));
+        assert!(text.contains("⚠️ WARN latency"));
+        assert!(text.contains("❌ FAIL sealed <build>: Build is not hermetic"));
+        assert!(text.contains("🛑 ANDON: stop the line, 2 of 3 assertions failed"));
+
+        let junit = report.to_junit();
+        assert!(junit.contains(
+            "<testsuites name=\"renacer check\" tests=\"4\" failures=\"2\" skipped=\"1\">"
+        ));
+        assert!(
+            junit.contains("<testcase classname=\"renacer.check\" name=\"sealed &lt;build&gt;\">")
+        );
+        assert!(junit.contains(
+            "<failure message=\"Span count 3 exceeds maximum 2\">actual 3 spans, expected 2 spans</failure>"
+        ));
+        assert!(junit.contains(
+            "<system-out>WARN: Critical path duration 3ms exceeds maximum 1ms</system-out>"
+        ));
+        assert!(junit.contains("<skipped/>"));
+    }
+}
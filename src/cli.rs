@@ -1,6 +1,8 @@
 //! CLI argument parsing for Renacer
 
-use clap::{Parser, ValueEnum};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 
 /// Output format for syscall traces
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -15,156 +17,415 @@ pub enum OutputFormat {
     Html,
 }
 
+/// Sample weight for flamegraph output (Sprint 50)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FlamegraphWeight {
+    /// One sample per syscall
+    Count,
+    /// Microseconds spent inside each syscall
+    Time,
+}
+
+/// Flamegraph orientation (Sprint 50)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FlamegraphStyle {
+    /// Root at the bottom, stacks grow upwards
+    Flame,
+    /// Root at the top, stacks grow downwards
+    Icicle,
+}
+
+/// Seccomp profile format (Sprint 60)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SeccompFormat {
+    /// OCI runtime / Docker seccomp JSON profile
+    Oci,
+    /// libseccomp C function
+    C,
+    /// seccompiler Rust function
+    Rust,
+}
+
+/// Subcommands (Sprint 55)
+///
+/// Root flags are global, so they can follow a subcommand:
+/// `renacer replay trace.rnc -c --format json`. Only `record` and `replay`
+/// trace or analyze a trace with them; [`Cli::try_parse_args`] rejects them
+/// for the other subcommands.
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Record a trace to a file for offline analysis
+    Record {
+        /// Trace file to write
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: String,
+
+        /// Unwind and store the call stack of every syscall
+        ///
+        /// Needed to replay the trace with --flamegraph, --pprof, --off-cpu,
+        /// --locks or --anti-patterns call sites; slows the traced program down.
+        #[arg(long = "stacks")]
+        stacks: bool,
+
+        /// Command to trace (everything after --)
+        #[arg(last = true)]
+        command: Option<Vec<String>>,
+    },
+    /// Re-run output and analysis modes on a recorded trace
+    Replay {
+        /// Trace file written by `renacer record`
+        #[arg(value_name = "FILE")]
+        file: String,
+    },
+    /// Compare recorded traces for performance regressions (Sprint 56)
+    ///
+    /// BASELINE and CURRENT are trace files or directories of `.rnc` runs;
+    /// clusters come from --clusters.
+    /// Exit status: 0 = no regression, 2 = regression detected, 1 = error.
+    Diff {
+        /// Baseline trace file, or a directory of runs
+        #[arg(value_name = "BASELINE")]
+        baseline: String,
+
+        /// Current trace file, or a directory of runs
+        #[arg(value_name = "CURRENT")]
+        current: String,
+
+        /// N-gram size for syscall sequence comparison
+        #[arg(long = "ngram", value_name = "N", default_value = "3")]
+        ngram: usize,
+
+        /// Significance level for the regression t-tests
+        #[arg(long = "significance", value_name = "ALPHA", default_value = "0.05")]
+        significance: f64,
+
+        /// Cluster pack TOML file
+        ///
+        /// Defaults to renacer-clusters.toml in the working directory, then
+        /// the built-in pack.
+        #[arg(long = "clusters", value_name = "FILE")]
+        clusters: Option<String>,
+
+        /// Output format (text or json)
+        #[arg(long = "format", value_enum, default_value = "text")]
+        format: OutputFormat,
+    },
+    /// Trace a command repeatedly to save or check a multi-run baseline (Sprint 57)
+    ///
+    /// Stores per-run syscall count and time samples for regression testing.
+    /// Exit status: 0 = no regression, 2 = regression vs --compare-baseline, 1 = error.
+    Bench {
+        /// Measured runs
+        #[arg(long = "runs", value_name = "N", default_value = "10")]
+        runs: usize,
+
+        /// Unmeasured warm-up runs before the measured ones
+        #[arg(long = "warmup", value_name = "N", default_value = "0")]
+        warmup: usize,
+
+        /// Save the runs as a baseline file (JSON)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<String>,
+
+        /// Compare the runs against a saved baseline
+        #[arg(long = "compare-baseline", value_name = "FILE")]
+        compare_baseline: Option<String>,
+
+        /// Significance level for the regression t-tests
+        #[arg(long = "significance", value_name = "ALPHA", default_value = "0.05")]
+        significance: f64,
+
+        /// Follow forks (trace child processes)
+        #[arg(short = 'f', long = "follow-forks")]
+        follow_forks: bool,
+
+        /// Command to benchmark (everything after --)
+        #[arg(last = true)]
+        command: Option<Vec<String>>,
+    },
+
+    /// Learn the normal syscall sequences of a program (Sprint 59)
+    ///
+    /// Trains a model from several traced runs of COMMAND, or from recorded
+    /// traces, for use with --anomaly-realtime --sequence-model.
+    Learn {
+        /// Model file to write (.apr)
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: String,
+
+        /// Traced runs of COMMAND to learn from
+        #[arg(long = "runs", value_name = "N", default_value = "5")]
+        runs: usize,
+
+        /// Learn from a recorded trace, or a directory of them, instead of running COMMAND
+        #[arg(long = "trace", value_name = "PATH", conflicts_with = "command")]
+        traces: Vec<String>,
+
+        /// Syscalls per sequence window
+        #[arg(long = "window", value_name = "N", default_value = "3")]
+        window: usize,
+
+        /// Follow forks (trace child processes)
+        #[arg(short = 'f', long = "follow-forks")]
+        follow_forks: bool,
+
+        /// Command to learn from (everything after --)
+        #[arg(last = true)]
+        command: Option<Vec<String>>,
+    },
+
+    /// Generate a seccomp allow-list from recorded traces (Sprint 60)
+    ///
+    /// Syscall arguments such as socket domains and ioctl requests are
+    /// narrowed to the observed values.
+    SeccompProfile {
+        /// Recorded traces, or directories of them
+        #[arg(value_name = "TRACE", required = true)]
+        traces: Vec<String>,
+
+        /// Profile format
+        #[arg(long = "emit", value_enum, default_value = "oci")]
+        emit: SeccompFormat,
+
+        /// Write the profile to a file instead of stdout
+        #[arg(short = 'o', long = "output", value_name = "FILE")]
+        output: Option<String>,
+
+        /// Leave syscalls that only ever failed out of the allow-list
+        #[arg(long = "deny-error-only")]
+        deny_error_only: bool,
+    },
+
+    /// Check that a build is hermetic (Sprint 62)
+    ///
+    /// Traces COMMAND and its child processes and fails when they read files
+    /// outside the declared inputs and toolchain, write outside the outputs,
+    /// open network sockets, or read environment-dependent paths like $HOME.
+    Hermetic {
+        /// TOML file with inputs, toolchain and outputs path lists
+        #[arg(long = "allow-list", value_name = "FILE")]
+        allow_list: String,
+
+        /// Check a recorded trace instead of running COMMAND
+        #[arg(long = "trace", value_name = "FILE", conflicts_with = "command")]
+        trace: Option<String>,
+
+        /// Build command to check (everything after --)
+        #[arg(last = true)]
+        command: Option<Vec<String>>,
+    },
+
+    /// Evaluate renacer.toml assertions against a traced run (Sprint 65)
+    ///
+    /// Traces COMMAND (or loads a recorded trace), evaluates every enabled
+    /// assertion and exits 1 when one with fail_on_violation fails.
+    Check {
+        /// Assertion file
+        #[arg(long = "config", value_name = "FILE", default_value = "renacer.toml")]
+        config: String,
+
+        /// Check a recorded trace instead of running COMMAND
+        #[arg(long = "trace", value_name = "FILE", conflicts_with = "command")]
+        trace: Option<String>,
+
+        /// Also write the results as JUnit XML to FILE
+        #[arg(long = "junit", value_name = "FILE")]
+        junit: Option<String>,
+
+        /// Follow forks (trace child processes)
+        #[arg(short = 'f', long = "follow-forks")]
+        follow_forks: bool,
+
+        /// Unwind call stacks so failure messages name the call sites
+        #[arg(long = "stacks")]
+        stacks: bool,
+
+        /// Command to check (everything after --)
+        #[arg(last = true)]
+        command: Option<Vec<String>>,
+    },
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "renacer")]
 #[command(version)]
 #[command(about = "Pure Rust system call tracer with source correlation", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Cli {
     /// Enable source code correlation using DWARF debug info
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub source: bool,
 
     /// Filter syscalls to trace (e.g., -e trace=open,read,write or -e trace=file)
-    #[arg(short = 'e', long = "expr", value_name = "EXPR")]
+    #[arg(short = 'e', long = "expr", value_name = "EXPR", global = true)]
     pub filter: Option<String>,
 
     /// Show statistics summary (syscall counts and timing) instead of individual calls
-    #[arg(short = 'c', long = "summary")]
+    #[arg(short = 'c', long = "summary", global = true)]
     pub statistics: bool,
 
     /// Show time spent in each syscall
-    #[arg(short = 'T', long = "timing")]
+    #[arg(short = 'T', long = "timing", global = true)]
     pub timing: bool,
 
     /// Output format (text or json)
-    #[arg(long = "format", value_enum, default_value = "text")]
+    #[arg(long = "format", value_enum, default_value = "text", global = true)]
     pub format: OutputFormat,
 
     /// Attach to running process by PID (mutually exclusive with command)
-    #[arg(short = 'p', long = "pid", value_name = "PID")]
+    #[arg(short = 'p', long = "pid", value_name = "PID", global = true)]
     pub pid: Option<i32>,
 
     /// Follow forks (trace child processes)
-    #[arg(short = 'f', long = "follow-forks")]
+    #[arg(short = 'f', long = "follow-forks", global = true)]
     pub follow_forks: bool,
 
     /// Enable self-profiling to measure Renacer's own overhead
-    #[arg(long = "profile-self")]
+    #[arg(long = "profile-self", global = true)]
     pub profile_self: bool,
 
     /// Enable function-level timing with DWARF correlation
-    #[arg(long = "function-time")]
+    #[arg(long = "function-time", global = true)]
     pub function_time: bool,
 
     /// Enable extended statistics with percentiles and anomaly detection (requires -c)
-    #[arg(long = "stats-extended")]
+    #[arg(long = "stats-extended", global = true)]
     pub stats_extended: bool,
 
     /// Anomaly detection threshold in standard deviations (default: 3.0)
     #[arg(
         long = "anomaly-threshold",
         value_name = "SIGMA",
-        default_value = "3.0"
+        default_value = "3.0",
+        global = true
     )]
     pub anomaly_threshold: f32,
 
     /// Enable real-time anomaly detection (Sprint 20)
-    #[arg(long = "anomaly-realtime")]
+    #[arg(long = "anomaly-realtime", global = true)]
     pub anomaly_realtime: bool,
 
     /// Sliding window size for real-time anomaly detection (default: 100)
     #[arg(
         long = "anomaly-window-size",
         value_name = "SIZE",
-        default_value = "100"
+        default_value = "100",
+        global = true
     )]
     pub anomaly_window_size: usize,
 
     /// Enable HPU-accelerated analysis (GPU if available) (Sprint 21)
-    #[arg(long = "hpu-analysis")]
+    #[arg(long = "hpu-analysis", global = true)]
     pub hpu_analysis: bool,
 
     /// Force CPU backend (disable GPU acceleration)
-    #[arg(long = "hpu-cpu-only")]
+    #[arg(long = "hpu-cpu-only", global = true)]
     pub hpu_cpu_only: bool,
 
     /// Enable ML-based anomaly detection using Aprender (Sprint 23)
-    #[arg(long = "ml-anomaly")]
+    #[arg(long = "ml-anomaly", global = true)]
     pub ml_anomaly: bool,
 
     /// Number of clusters for ML anomaly detection (default: 3, min: 2)
-    #[arg(long = "ml-clusters", value_name = "N", default_value = "3")]
+    #[arg(
+        long = "ml-clusters",
+        value_name = "N",
+        default_value = "3",
+        global = true
+    )]
     pub ml_clusters: usize,
 
     /// Compare ML results with z-score anomaly detection
-    #[arg(long = "ml-compare")]
+    #[arg(long = "ml-compare", global = true)]
     pub ml_compare: bool,
 
     /// Enable Isolation Forest-based outlier detection (Sprint 22)
-    #[arg(long = "ml-outliers")]
+    #[arg(long = "ml-outliers", global = true)]
     pub ml_outliers: bool,
 
     /// Contamination threshold for Isolation Forest (default: 0.1, range: 0.0-0.5)
     #[arg(
         long = "ml-outlier-threshold",
         value_name = "THRESHOLD",
-        default_value = "0.1"
+        default_value = "0.1",
+        global = true
     )]
     pub ml_outlier_threshold: f32,
 
     /// Number of trees in Isolation Forest (default: 100, min: 10)
-    #[arg(long = "ml-outlier-trees", value_name = "N", default_value = "100")]
+    #[arg(
+        long = "ml-outlier-trees",
+        value_name = "N",
+        default_value = "100",
+        global = true
+    )]
     pub ml_outlier_trees: usize,
 
     /// Enable explainability for ML outlier detection (Sprint 22)
-    #[arg(long = "explain")]
+    #[arg(long = "explain", global = true)]
     pub explain: bool,
 
     /// Enable deep learning (Autoencoder) anomaly detection (Sprint 23)
-    #[arg(long = "dl-anomaly")]
+    #[arg(long = "dl-anomaly", global = true)]
     pub dl_anomaly: bool,
 
     /// Reconstruction error threshold for Autoencoder (default: 2.0)
-    #[arg(long = "dl-threshold", value_name = "THRESHOLD", default_value = "2.0")]
+    #[arg(
+        long = "dl-threshold",
+        value_name = "THRESHOLD",
+        default_value = "2.0",
+        global = true
+    )]
     pub dl_threshold: f32,
 
     /// Hidden layer size for Autoencoder (default: 3)
-    #[arg(long = "dl-hidden-size", value_name = "SIZE", default_value = "3")]
+    #[arg(
+        long = "dl-hidden-size",
+        value_name = "SIZE",
+        default_value = "3",
+        global = true
+    )]
     pub dl_hidden_size: usize,
 
     /// Number of training epochs for Autoencoder (default: 100)
-    #[arg(long = "dl-epochs", value_name = "N", default_value = "100")]
+    #[arg(
+        long = "dl-epochs",
+        value_name = "N",
+        default_value = "100",
+        global = true
+    )]
     pub dl_epochs: usize,
 
     /// Path to transpiler source map JSON file (Sprint 24)
-    #[arg(long = "transpiler-map", value_name = "FILE")]
+    #[arg(long = "transpiler-map", value_name = "FILE", global = true)]
     pub transpiler_map: Option<String>,
 
     /// Show verbose transpiler context (Python/Rust correlation) (Sprint 25)
-    #[arg(long = "show-transpiler-context")]
+    #[arg(long = "show-transpiler-context", global = true)]
     pub show_transpiler_context: bool,
 
     /// Rewrite stack traces to show original source locations (Sprint 26)
-    #[arg(long = "rewrite-stacktrace")]
+    #[arg(long = "rewrite-stacktrace", global = true)]
     pub rewrite_stacktrace: bool,
 
     /// Rewrite compilation errors to show original source locations (Sprint 27)
-    #[arg(long = "rewrite-errors")]
+    #[arg(long = "rewrite-errors", global = true)]
     pub rewrite_errors: bool,
 
     /// Trace transpiler compile-time decisions for debugging (Sprint 26)
-    #[arg(long = "trace-transpiler-decisions")]
+    #[arg(long = "trace-transpiler-decisions", global = true)]
     pub trace_transpiler_decisions: bool,
 
     /// OpenTelemetry OTLP endpoint for trace export (Sprint 30)
-    #[arg(long = "otlp-endpoint", value_name = "URL")]
+    #[arg(long = "otlp-endpoint", value_name = "URL", global = true)]
     pub otlp_endpoint: Option<String>,
 
     /// Service name for OpenTelemetry traces (Sprint 30)
     #[arg(
         long = "otlp-service-name",
         value_name = "NAME",
-        default_value = "renacer"
+        default_value = "renacer",
+        global = true
     )]
     pub otlp_service_name: String,
 
@@ -176,7 +437,7 @@ pub struct Cli {
     ///
     /// If not provided, checks TRACEPARENT or OTEL_TRACEPARENT environment variables.
     /// If no context found, creates new root trace (existing behavior).
-    #[arg(long = "trace-parent", value_name = "TRACEPARENT")]
+    #[arg(long = "trace-parent", value_name = "TRACEPARENT", global = true)]
     pub trace_parent: Option<String>,
 
     /// Enable compute block tracing (Trueno SIMD operations) - Sprint 32
@@ -185,7 +446,7 @@ pub struct Cli {
     /// OpenTelemetry spans. Uses adaptive sampling: only traces blocks with
     /// duration >= threshold (default: 100μs). Toyota Way compliant: safe by
     /// default, cannot DoS tracing backend.
-    #[arg(long = "trace-compute")]
+    #[arg(long = "trace-compute", global = true)]
     pub trace_compute: bool,
 
     /// Trace ALL compute blocks (bypass adaptive sampling threshold) - Sprint 32
@@ -193,7 +454,7 @@ pub struct Cli {
     /// Debug mode: traces even fast compute blocks (<100μs). Use for development
     /// and debugging only. Can generate high span volume (~500 spans/sec).
     /// Requires --trace-compute flag.
-    #[arg(long = "trace-compute-all", requires = "trace_compute")]
+    #[arg(long = "trace-compute-all", requires = "trace_compute", global = true)]
     pub trace_compute_all: bool,
 
     /// Custom threshold for compute block tracing (microseconds) - Sprint 32
@@ -204,12 +465,13 @@ pub struct Cli {
         long = "trace-compute-threshold",
         value_name = "MICROS",
         default_value = "100",
-        requires = "trace_compute"
+        requires = "trace_compute",
+        global = true
     )]
     pub trace_compute_threshold: u64,
 
     /// Enable debug tracing output to stderr
-    #[arg(long = "debug")]
+    #[arg(long = "debug", global = true)]
     pub debug: bool,
 
     // Sprint 47: Chaos Engineering CLI (Issue #17)
@@ -218,35 +480,35 @@ pub struct Cli {
     /// Presets configure resource limits for robustness testing:
     /// - gentle: memory=512MB, cpu=80%, timeout=120s
     /// - aggressive: memory=64MB, cpu=25%, timeout=10s, signals=on
-    #[arg(long = "chaos", value_name = "PRESET")]
+    #[arg(long = "chaos", value_name = "PRESET", global = true)]
     pub chaos_preset: Option<String>,
 
     /// Memory limit for chaos testing (e.g., 64M, 512M, 1G, or bytes)
     ///
     /// Limits the traced process's virtual memory using setrlimit.
     /// Supports suffixes: K (kilobytes), M (megabytes), G (gigabytes).
-    #[arg(long = "chaos-memory-limit", value_name = "SIZE")]
+    #[arg(long = "chaos-memory-limit", value_name = "SIZE", global = true)]
     pub chaos_memory_limit: Option<String>,
 
     /// CPU limit as fraction (0.0-1.0) for chaos testing
     ///
     /// Limits CPU time relative to real time. For example, 0.5 means
     /// the process gets 50% of CPU time.
-    #[arg(long = "chaos-cpu-limit", value_name = "FRACTION")]
+    #[arg(long = "chaos-cpu-limit", value_name = "FRACTION", global = true)]
     pub chaos_cpu_limit: Option<f64>,
 
     /// Execution timeout for chaos testing (e.g., 10s, 2m, 1h)
     ///
     /// Terminates the traced process after the specified duration.
     /// Supports suffixes: s (seconds), m (minutes), h (hours).
-    #[arg(long = "chaos-timeout", value_name = "DURATION")]
+    #[arg(long = "chaos-timeout", value_name = "DURATION", global = true)]
     pub chaos_timeout: Option<String>,
 
     /// Enable random signal injection for chaos testing
     ///
     /// Periodically injects signals (SIGALRM, SIGUSR1) to test
     /// signal handling robustness.
-    #[arg(long = "chaos-signals")]
+    #[arg(long = "chaos-signals", global = true)]
     pub chaos_signals: bool,
 
     // Sprint 48: Model Persistence CLI (Toyota Way: Muda elimination)
@@ -255,7 +517,7 @@ pub struct Cli {
     /// After ML anomaly detection training, saves the model to the
     /// specified path. Enables 10-50x faster startup on subsequent runs.
     /// Requires --ml-anomaly flag.
-    #[arg(long = "save-model", value_name = "FILE")]
+    #[arg(long = "save-model", value_name = "FILE", global = true)]
     pub save_model: Option<String>,
 
     /// Load pre-trained ML model from .apr file
@@ -263,7 +525,7 @@ pub struct Cli {
     /// Skips model training and loads a previously saved model.
     /// Provides instant startup for anomaly detection.
     /// Requires --ml-anomaly flag.
-    #[arg(long = "load-model", value_name = "FILE")]
+    #[arg(long = "load-model", value_name = "FILE", global = true)]
     pub load_model: Option<String>,
 
     /// Compare against baseline model for regression detection
@@ -271,12 +533,229 @@ pub struct Cli {
     /// Loads a baseline model and compares current trace against it.
     /// Reports deviations from the baseline as potential regressions.
     /// Requires --ml-anomaly flag.
-    #[arg(long = "baseline", value_name = "FILE")]
+    #[arg(long = "baseline", value_name = "FILE", global = true)]
     pub baseline_model: Option<String>,
 
+    // Sprint 50: Flamegraph output from unwound syscall stacks
+    /// Write an interactive SVG flamegraph of syscall call stacks
+    ///
+    /// Stacks are unwound at every syscall entry and resolved with DWARF/ELF
+    /// symbols. The SVG is self-contained (click to zoom, Ctrl+F to search).
+    #[arg(long = "flamegraph", value_name = "FILE", global = true)]
+    pub flamegraph: Option<String>,
+
+    /// Write Brendan Gregg folded stacks ("frame;frame;syscall weight")
+    ///
+    /// Compatible with flamegraph.pl, inferno and speedscope.
+    #[arg(long = "flamegraph-folded", value_name = "FILE", global = true)]
+    pub flamegraph_folded: Option<String>,
+
+    /// Flamegraph sample weight: syscall count or time spent in syscalls
+    #[arg(
+        long = "flamegraph-weight",
+        value_enum,
+        default_value = "count",
+        global = true
+    )]
+    pub flamegraph_weight: FlamegraphWeight,
+
+    /// Flamegraph orientation (flame or icicle)
+    #[arg(
+        long = "flamegraph-style",
+        value_enum,
+        default_value = "flame",
+        global = true
+    )]
+    pub flamegraph_style: FlamegraphStyle,
+
+    /// Only include blocking syscalls, weighted by time (off-CPU flamegraph)
+    #[arg(long = "flamegraph-off-cpu", global = true)]
+    pub flamegraph_off_cpu: bool,
+
+    // Sprint 51: pprof export
+    /// Write a gzip-compressed pprof profile (profile.proto) of syscall stacks
+    ///
+    /// Sample types: syscall count, syscall time (ns) and slow I/O count.
+    /// Open with `go tool pprof` or speedscope.
+    #[arg(long = "pprof", value_name = "FILE", global = true)]
+    pub pprof: Option<String>,
+
+    // Sprint 52: Off-CPU analysis
+    /// Report where time is spent blocked in syscalls (off-CPU analysis)
+    ///
+    /// Blocking syscalls (futex waits, poll/epoll, reads on pipes/sockets,
+    /// sleeps...) are grouped per thread by call stack and by wait object
+    /// (fd path or futex address).
+    #[arg(long = "off-cpu", global = true)]
+    pub off_cpu: bool,
+
+    /// Write blocked stacks as folded stacks weighted by microseconds
+    ///
+    /// The leaf frame is the wait object. Implies --off-cpu.
+    #[arg(long = "off-cpu-folded", value_name = "FILE", global = true)]
+    pub off_cpu_folded: Option<String>,
+
+    /// Number of top blockers reported per thread
+    #[arg(
+        long = "off-cpu-top",
+        value_name = "N",
+        default_value = "10",
+        global = true
+    )]
+    pub off_cpu_top: usize,
+
+    // Sprint 58: Cluster packs
+    /// Syscall cluster definitions (TOML)
+    ///
+    /// Defaults to renacer-clusters.toml in the working directory, then the
+    /// built-in pack (clusters-default.toml). Used by --by-cluster, --hotspots
+    /// and diff.
+    #[arg(long = "clusters", value_name = "FILE", global = true)]
+    pub clusters: Option<String>,
+
+    /// Summarize -c statistics per syscall cluster
+    ///
+    /// Classifies each call with the cluster pack, including argument
+    /// filters such as ioctl on /dev/nvidia* (fds are tracked live).
+    #[arg(long = "by-cluster", requires = "statistics", global = true)]
+    pub by_cluster: bool,
+
+    /// Baseline from `renacer bench -o` for --by-cluster anomaly flags
+    #[arg(
+        long = "cluster-baseline",
+        value_name = "FILE",
+        requires = "by_cluster",
+        global = true
+    )]
+    pub cluster_baseline: Option<String>,
+
+    /// Flag syscall sequences outside a model from `renacer learn` (Sprint 59)
+    #[arg(
+        long = "sequence-model",
+        value_name = "FILE",
+        requires = "anomaly_realtime",
+        global = true
+    )]
+    pub sequence_model: Option<String>,
+
+    /// Report the files each traced process reads, writes, creates and deletes (Sprint 61)
+    #[arg(long = "file-audit", global = true)]
+    pub file_audit: bool,
+
+    /// Write the file access audit as JSON to FILE (Sprint 61)
+    #[arg(long = "file-audit-json", value_name = "FILE", global = true)]
+    pub file_audit_json: Option<String>,
+
+    /// Print the socket connection table: endpoints, bytes and lifetime per fd (Sprint 63)
+    ///
+    /// The table is also printed after the -c summary and added to HTML reports.
+    #[arg(long = "connections", global = true)]
+    pub connections: bool,
+
+    /// Print wall-clock time per syscall cluster and flag unexpected hotspots (Sprint 64)
+    ///
+    /// Clusters come from --clusters. With --format json or html the
+    /// attribution is added to the report instead.
+    #[arg(long = "hotspots", global = true)]
+    pub hotspots: bool,
+
+    /// Print the longest dependency chain through all traced processes (Sprint 71)
+    ///
+    /// Follows forks (implies -f). Each step of the chain is a stretch of one
+    /// process, from the fork that started it to the wait4 that reaped it,
+    /// with the time it adds to the run.
+    #[arg(long = "critical-path", global = true)]
+    pub critical_path: bool,
+
+    // Sprint 73: Lock contention
+    /// Report contended locks from futex waits (implies -f)
+    ///
+    /// Waits are grouped by futex address, named after the static lock
+    /// symbol or memory mapping holding it, with a wait-time histogram,
+    /// waiter and waker threads, and the call stacks acquiring it.
+    #[arg(long = "locks", global = true)]
+    pub locks: bool,
+
+    /// Number of contended locks (and acquiring stacks per lock) reported
+    #[arg(
+        long = "locks-top",
+        value_name = "N",
+        default_value = "10",
+        global = true
+    )]
+    pub locks_top: usize,
+
+    // Sprint 74: Syscall-level anti-patterns
+    /// Report everyday syscall-level inefficiencies with their call sites
+    ///
+    /// Unbuffered tiny writes, 1-byte reads, repeated stat/openat of one
+    /// path, search path probing, fsync loops, nanosleep/sched_yield
+    /// polling and mmap/munmap churn, each with a recommendation.
+    #[arg(long = "anti-patterns", global = true)]
+    pub anti_patterns: bool,
+
     /// Command to trace (everything after --)
     #[arg(last = true)]
     pub command: Option<Vec<String>>,
+
+    /// Subcommand: record, replay, diff, bench, learn, seccomp-profile, hermetic or check (Sprint 55-65)
+    #[command(subcommand)]
+    pub subcommand: Option<Commands>,
+}
+
+impl Cli {
+    /// Parse the process arguments, exiting with usage on errors
+    pub fn parse_args() -> Self {
+        Self::try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse arguments, rejecting tracing flags given to a subcommand that
+    /// neither traces nor replays
+    ///
+    /// Sprint 55: Root flags are global so that `record` and `replay` take
+    /// them; `renacer diff --chaos gentle` would otherwise parse and ignore it.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let mut command = Self::command();
+        let matches = command.try_get_matches_from_mut(args)?;
+        if let Some((name, sub_matches)) = matches.subcommand() {
+            if !matches!(name, "record" | "replay") {
+                // Globals are only propagated into the built command
+                let own: Vec<String> = Self::command()
+                    .find_subcommand(name)
+                    .map(|sub| {
+                        sub.get_arguments()
+                            .map(|a| a.get_id().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let given = command.get_arguments().find(|arg| {
+                    arg.is_global_set()
+                        && !own.iter().any(|id| id == arg.get_id())
+                        && sub_matches.value_source(arg.get_id().as_str())
+                            == Some(ValueSource::CommandLine)
+                });
+                if let Some(arg) = given {
+                    let flag = match (arg.get_long(), arg.get_short()) {
+                        (Some(long), _) => format!("--{}", long),
+                        (None, Some(short)) => format!("-{}", short),
+                        (None, None) => arg.get_id().to_string(),
+                    };
+                    return Err(command.error(
+                        ErrorKind::ArgumentConflict,
+                        format!(
+                            "{} is a tracing flag; `renacer {}` does not take it",
+                            flag, name
+                        ),
+                    ));
+                }
+            }
+        }
+        Self::from_arg_matches(&matches)
+    }
 }
 
 #[cfg(test)]
@@ -1244,4 +1723,456 @@ mod tests {
         assert_eq!(cli.save_model.as_deref(), Some("updated.apr"));
         assert_eq!(cli.baseline_model.as_deref(), Some("golden.apr"));
     }
+
+    // Sprint 50: Flamegraph CLI tests
+
+    #[test]
+    fn test_cli_flamegraph_defaults() {
+        let cli = Cli::parse_from(["renacer", "--", "echo", "test"]);
+        assert!(cli.flamegraph.is_none());
+        assert!(cli.flamegraph_folded.is_none());
+        assert_eq!(cli.flamegraph_weight, FlamegraphWeight::Count);
+        assert_eq!(cli.flamegraph_style, FlamegraphStyle::Flame);
+        assert!(!cli.flamegraph_off_cpu);
+    }
+
+    #[test]
+    fn test_cli_flamegraph_flags() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "--flamegraph",
+            "out.svg",
+            "--flamegraph-folded",
+            "out.folded",
+            "--flamegraph-weight",
+            "time",
+            "--flamegraph-style",
+            "icicle",
+            "--flamegraph-off-cpu",
+            "--",
+            "echo",
+            "test",
+        ]);
+        assert_eq!(cli.flamegraph.as_deref(), Some("out.svg"));
+        assert_eq!(cli.flamegraph_folded.as_deref(), Some("out.folded"));
+        assert_eq!(cli.flamegraph_weight, FlamegraphWeight::Time);
+        assert_eq!(cli.flamegraph_style, FlamegraphStyle::Icicle);
+        assert!(cli.flamegraph_off_cpu);
+    }
+
+    #[test]
+    fn test_cli_flamegraph_invalid_weight() {
+        let result = Cli::try_parse_from(["renacer", "--flamegraph-weight", "bytes", "--", "echo"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_pprof_flag() {
+        let cli = Cli::parse_from(["renacer", "--", "echo"]);
+        assert!(cli.pprof.is_none());
+
+        let cli = Cli::parse_from(["renacer", "--pprof", "profile.pb.gz", "--", "echo"]);
+        assert_eq!(cli.pprof.as_deref(), Some("profile.pb.gz"));
+    }
+
+    #[test]
+    fn test_cli_off_cpu_flags() {
+        let cli = Cli::parse_from(["renacer", "--", "echo"]);
+        assert!(!cli.off_cpu);
+        assert!(cli.off_cpu_folded.is_none());
+        assert_eq!(cli.off_cpu_top, 10);
+
+        let cli = Cli::parse_from([
+            "renacer",
+            "--off-cpu",
+            "--off-cpu-folded",
+            "off.folded",
+            "--off-cpu-top",
+            "3",
+            "--",
+            "echo",
+        ]);
+        assert!(cli.off_cpu);
+        assert_eq!(cli.off_cpu_folded.as_deref(), Some("off.folded"));
+        assert_eq!(cli.off_cpu_top, 3);
+    }
+
+    #[test]
+    fn test_cli_record_subcommand() {
+        let cli = Cli::parse_from(["renacer", "record", "-o", "trace.rnc", "--", "ls", "-l"]);
+        match cli.subcommand {
+            Some(Commands::Record {
+                output,
+                stacks,
+                command,
+            }) => {
+                assert_eq!(output, "trace.rnc");
+                assert!(!stacks);
+                assert_eq!(command, Some(vec!["ls".to_string(), "-l".to_string()]));
+            }
+            other => panic!("expected record subcommand, got {:?}", other),
+        }
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn test_cli_record_stacks_and_tracing_flags() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "record",
+            "-o",
+            "t.rnc",
+            "--stacks",
+            "-f",
+            "-e",
+            "trace=file",
+            "--",
+            "make",
+        ]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Record { stacks: true, .. })
+        ));
+        assert!(cli.follow_forks);
+        assert_eq!(cli.filter.as_deref(), Some("trace=file"));
+    }
+
+    #[test]
+    fn test_cli_replay_accepts_global_flags() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "replay",
+            "trace.rnc",
+            "-c",
+            "--format",
+            "json",
+            "--ml-anomaly",
+        ]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Replay { ref file }) if file == "trace.rnc"
+        ));
+        assert!(cli.statistics);
+        assert!(matches!(cli.format, OutputFormat::Json));
+        assert!(cli.ml_anomaly);
+    }
+
+    #[test]
+    fn test_cli_tracing_flags_rejected_by_other_subcommands() {
+        for args in [
+            &["renacer", "diff", "a.rnc", "b.rnc", "--chaos", "gentle"][..],
+            &[
+                "renacer",
+                "seccomp-profile",
+                "a.rnc",
+                "--flamegraph",
+                "f.svg",
+            ],
+            &["renacer", "check", "--off-cpu", "--", "make"],
+            &["renacer", "-c", "replay", "trace.rnc"],
+        ] {
+            assert!(Cli::try_parse_args(args).is_err(), "{:?} parsed", args);
+        }
+
+        // Subcommand flags sharing a global's name are the subcommand's own
+        let cli = Cli::try_parse_args(["renacer", "bench", "-f", "--", "make"]).unwrap();
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Bench {
+                follow_forks: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_cli_diff_subcommand() {
+        let cli = Cli::parse_from([
+            "renacer", "diff", "base.rnc", "runs/", "--ngram", "2", "--format", "json",
+        ]);
+        match cli.subcommand {
+            Some(Commands::Diff {
+                baseline,
+                current,
+                ngram,
+                significance,
+                clusters,
+                format,
+            }) => {
+                assert_eq!(baseline, "base.rnc");
+                assert_eq!(current, "runs/");
+                assert_eq!(ngram, 2);
+                assert_eq!(significance, 0.05);
+                assert!(clusters.is_none());
+                assert!(matches!(format, OutputFormat::Json));
+            }
+            other => panic!("expected diff subcommand, got {:?}", other),
+        }
+        assert!(matches!(cli.format, OutputFormat::Json));
+    }
+
+    #[test]
+    fn test_cli_bench_subcommand() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "bench",
+            "--runs",
+            "5",
+            "--warmup",
+            "1",
+            "--compare-baseline",
+            "base.json",
+            "--",
+            "make",
+            "test",
+        ]);
+        match cli.subcommand {
+            Some(Commands::Bench {
+                runs,
+                warmup,
+                output,
+                compare_baseline,
+                command,
+                ..
+            }) => {
+                assert_eq!(runs, 5);
+                assert_eq!(warmup, 1);
+                assert!(output.is_none());
+                assert_eq!(compare_baseline.as_deref(), Some("base.json"));
+                assert_eq!(command, Some(vec!["make".to_string(), "test".to_string()]));
+            }
+            other => panic!("expected bench subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_by_cluster_flags() {
+        let cli = Cli::parse_from(["renacer", "--", "echo"]);
+        assert!(cli.clusters.is_none());
+        assert!(!cli.by_cluster);
+        assert!(cli.cluster_baseline.is_none());
+
+        let cli = Cli::parse_from([
+            "renacer",
+            "-c",
+            "--by-cluster",
+            "--clusters",
+            "gpu.toml",
+            "--cluster-baseline",
+            "base.json",
+            "--",
+            "echo",
+        ]);
+        assert!(cli.by_cluster);
+        assert_eq!(cli.clusters.as_deref(), Some("gpu.toml"));
+        assert_eq!(cli.cluster_baseline.as_deref(), Some("base.json"));
+    }
+
+    #[test]
+    fn test_cli_by_cluster_requires_statistics() {
+        let result = Cli::try_parse_from(["renacer", "--by-cluster", "--", "echo"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_learn_subcommand() {
+        let cli = Cli::parse_from([
+            "renacer", "learn", "-o", "ls.apr", "--runs", "3", "--window", "4", "--", "ls",
+        ]);
+        match cli.subcommand {
+            Some(Commands::Learn {
+                output,
+                runs,
+                traces,
+                window,
+                follow_forks,
+                command,
+            }) => {
+                assert!(!follow_forks);
+                assert_eq!(output, "ls.apr");
+                assert_eq!(runs, 3);
+                assert!(traces.is_empty());
+                assert_eq!(window, 4);
+                assert_eq!(command, Some(vec!["ls".to_string()]));
+            }
+            other => panic!("expected learn subcommand, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_cli_seccomp_profile_subcommand() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "seccomp-profile",
+            "a.rnc",
+            "runs/",
+            "--emit",
+            "rust",
+            "--deny-error-only",
+        ]);
+        match cli.subcommand {
+            Some(Commands::SeccompProfile {
+                traces,
+                emit,
+                output,
+                deny_error_only,
+            }) => {
+                assert_eq!(traces, vec!["a.rnc".to_string(), "runs/".to_string()]);
+                assert_eq!(emit, SeccompFormat::Rust);
+                assert!(output.is_none());
+                assert!(deny_error_only);
+            }
+            other => panic!("expected seccomp-profile subcommand, got {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["renacer", "seccomp-profile"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_hermetic_subcommand() {
+        let cli = Cli::parse_from([
+            "renacer",
+            "hermetic",
+            "--allow-list",
+            "inputs.toml",
+            "--",
+            "cargo",
+            "build",
+        ]);
+        match cli.subcommand {
+            Some(Commands::Hermetic {
+                allow_list,
+                trace,
+                command,
+            }) => {
+                assert_eq!(allow_list, "inputs.toml");
+                assert!(trace.is_none());
+                assert_eq!(
+                    command,
+                    Some(vec!["cargo".to_string(), "build".to_string()])
+                );
+            }
+            other => panic!("expected hermetic subcommand, got {:?}", other),
+        }
+
+        assert!(Cli::try_parse_from(["renacer", "hermetic", "--", "make"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_check_subcommand() {
+        let cli = Cli::parse_from(["renacer", "check", "--junit", "out.xml", "--", "make"]);
+        match cli.subcommand {
+            Some(Commands::Check {
+                config,
+                trace,
+                junit,
+                follow_forks,
+                stacks,
+                command,
+            }) => {
+                assert!(!follow_forks);
+                assert!(!stacks);
+                assert_eq!(config, "renacer.toml");
+                assert!(trace.is_none());
+                assert_eq!(junit.as_deref(), Some("out.xml"));
+                assert_eq!(command, Some(vec!["make".to_string()]));
+            }
+            other => panic!("expected check subcommand, got {:?}", other),
+        }
+
+        let cli = Cli::parse_from(["renacer", "check", "--config", "a.toml", "--trace", "t.rnc"]);
+        assert!(matches!(
+            cli.subcommand,
+            Some(Commands::Check { config, trace: Some(_), .. }) if config == "a.toml"
+        ));
+    }
+
+    #[test]
+    fn test_cli_file_audit_flags() {
+        let cli = Cli::parse_from(["renacer", "--", "make"]);
+        assert!(!cli.file_audit);
+        assert!(cli.file_audit_json.is_none());
+
+        let cli = Cli::parse_from([
+            "renacer",
+            "-f",
+            "--file-audit",
+            "--file-audit-json",
+            "files.json",
+            "--",
+            "make",
+        ]);
+        assert!(cli.file_audit);
+        assert_eq!(cli.file_audit_json.as_deref(), Some("files.json"));
+    }
+
+    #[test]
+    fn test_cli_connections_flag() {
+        let cli = Cli::parse_from(["renacer", "--", "curl"]);
+        assert!(!cli.connections);
+
+        let cli = Cli::parse_from(["renacer", "replay", "t.rnc", "--connections"]);
+        assert!(cli.connections);
+    }
+
+    #[test]
+    fn test_cli_hotspots_flag() {
+        let cli = Cli::parse_from(["renacer", "--", "ls"]);
+        assert!(!cli.hotspots);
+
+        let cli = Cli::parse_from(["renacer", "--hotspots", "--clusters", "c.toml", "--", "ls"]);
+        assert!(cli.hotspots);
+        assert_eq!(cli.clusters.as_deref(), Some("c.toml"));
+    }
+
+    #[test]
+    fn test_cli_critical_path_flag() {
+        let cli = Cli::parse_from(["renacer", "--", "make"]);
+        assert!(!cli.critical_path);
+
+        let cli = Cli::parse_from(["renacer", "--critical-path", "--", "make"]);
+        assert!(cli.critical_path);
+
+        let cli = Cli::parse_from(["renacer", "replay", "build.rnc", "--critical-path"]);
+        assert!(cli.critical_path);
+    }
+
+    #[test]
+    fn test_cli_locks_flags() {
+        let cli = Cli::parse_from(["renacer", "--", "server"]);
+        assert!(!cli.locks);
+        assert_eq!(cli.locks_top, 10);
+
+        let cli = Cli::parse_from(["renacer", "--locks", "--locks-top", "3", "--", "server"]);
+        assert!(cli.locks);
+        assert_eq!(cli.locks_top, 3);
+    }
+
+    #[test]
+    fn test_cli_anti_patterns_flag() {
+        let cli = Cli::parse_from(["renacer", "--", "ls"]);
+        assert!(!cli.anti_patterns);
+
+        let cli = Cli::parse_from(["renacer", "--anti-patterns", "--", "ls"]);
+        assert!(cli.anti_patterns);
+
+        let cli = Cli::parse_from(["renacer", "replay", "run.rnc", "--anti-patterns"]);
+        assert!(cli.anti_patterns);
+    }
+
+    #[test]
+    fn test_cli_sequence_model_requires_anomaly_realtime() {
+        let result = Cli::try_parse_from(["renacer", "--sequence-model", "m.apr", "--", "echo"]);
+        assert!(result.is_err());
+
+        let cli = Cli::parse_from([
+            "renacer",
+            "--anomaly-realtime",
+            "--sequence-model",
+            "m.apr",
+            "--",
+            "echo",
+        ]);
+        assert_eq!(cli.sequence_model.as_deref(), Some("m.apr"));
+    }
 }
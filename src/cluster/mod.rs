@@ -14,9 +14,11 @@
 mod definition;
 mod filter;
 mod registry;
+mod tracker;
 
 pub use definition::{ArgsFilter, ClusterDefinition, Severity};
-pub use registry::{ClusterRegistry, FdTable};
+pub use registry::{ClusterRegistry, FdTable, PROJECT_CLUSTERS_FILE};
+pub use tracker::{ClusterStats, ClusterSummaryConfig, ClusterTracker, UNCLASSIFIED};
 
 #[cfg(test)]
 mod tests;
@@ -4,6 +4,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+/// Project-specific cluster pack picked up from the working directory (Sprint 58)
+pub const PROJECT_CLUSTERS_FILE: &str = "renacer-clusters.toml";
+
 /// Registry of user-defined syscall clusters loaded from TOML configuration
 ///
 /// Implements Open-Closed Principle: extensible via configuration without recompilation.
@@ -18,7 +21,7 @@ use std::path::Path;
 /// }
 /// # Ok::<(), anyhow::Error>(())
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClusterRegistry {
     /// All defined clusters
     clusters: Vec<ClusterDefinition>,
@@ -109,6 +112,27 @@ impl ClusterRegistry {
         })
     }
 
+    /// Load the cluster pack selected on the command line (Sprint 58)
+    ///
+    /// Uses `path` if given, else [`PROJECT_CLUSTERS_FILE`] in the working
+    /// directory, else the embedded default pack. Returns the registry and a
+    /// description of where it came from.
+    pub fn resolve(path: Option<&str>) -> Result<(Self, String)> {
+        if let Some(path) = path {
+            return Ok((Self::from_toml(path)?, path.to_string()));
+        }
+        if Path::new(PROJECT_CLUSTERS_FILE).is_file() {
+            return Ok((
+                Self::from_toml(PROJECT_CLUSTERS_FILE)?,
+                PROJECT_CLUSTERS_FILE.to_string(),
+            ));
+        }
+        Ok((
+            Self::default_transpiler_clusters()?,
+            "built-in clusters-default.toml".to_string(),
+        ))
+    }
+
     /// Classify a syscall into its semantic cluster
     ///
     /// # Arguments
@@ -148,7 +172,7 @@ impl ClusterRegistry {
         if let Some(pattern) = &filter.fd_path_pattern {
             if syscall == "ioctl" {
                 if let Some(fd_str) = args.first() {
-                    if let Ok(fd) = fd_str.parse::<i32>() {
+                    if let Some(fd) = parse_fd(fd_str) {
                         if let Some(path) = fds.get_path(fd) {
                             // Simplified pattern matching (use regex in production)
                             return path.contains(pattern.trim_end_matches('*'));
@@ -207,6 +231,18 @@ impl FdTable {
     pub fn get_path(&self, fd: i32) -> Option<&str> {
         self.table.get(&fd).map(|s| s.as_str())
     }
+
+    pub fn remove(&mut self, fd: i32) -> Option<String> {
+        self.table.remove(&fd)
+    }
+}
+
+/// Parse an fd argument, either decimal ("3") or as the tracer formats it ("0x3")
+fn parse_fd(arg: &str) -> Option<i32> {
+    match arg.strip_prefix("0x") {
+        Some(hex) => i32::from_str_radix(hex, 16).ok(),
+        None => arg.parse().ok(),
+    }
 }
 
 #[cfg(test)]
@@ -294,6 +330,37 @@ fd_path_pattern = "/dev/nvidia*"
         Ok(())
     }
 
+    #[test]
+    fn test_classify_with_hex_fd_arg() -> Result<()> {
+        let file = create_test_toml()?;
+        let registry = ClusterRegistry::from_toml(file.path())?;
+
+        let mut fds = FdTable::new();
+        fds.insert(10, "/dev/nvidia0".to_string());
+        let cluster = registry.classify("ioctl", &["0xa".to_string()], &fds);
+        assert_eq!(cluster.map(|c| c.name.as_str()), Some("GPU"));
+
+        fds.remove(10);
+        assert!(registry
+            .classify("ioctl", &["0xa".to_string()], &fds)
+            .is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_explicit_path() -> Result<()> {
+        let file = create_test_toml()?;
+        let path = file.path().to_str().unwrap();
+        let (registry, source) = ClusterRegistry::resolve(Some(path))?;
+
+        assert_eq!(source, path);
+        assert!(registry.get_cluster("GPU").is_some());
+        assert!(ClusterRegistry::resolve(Some("/nonexistent/clusters.toml")).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_duplicate_syscall_error() {
         let mut file = NamedTempFile::new().unwrap();
@@ -0,0 +1,410 @@
+// Live cluster classification for `-c --by-cluster` (Sprint 58)
+//
+// `ClusterRegistry::classify` can only apply `fd_path_pattern` filters with an
+// `FdTable`. The tracker keeps one per process, updated from open/dup/close
+// results, and resolves fds it hasn't seen (inherited, or opened before an
+// attach) through a caller-supplied lookup such as `/proc/PID/fd`.
+
+use crate::cluster::{ClusterRegistry, FdTable, Severity};
+use std::collections::HashMap;
+
+/// Cluster name for syscalls no cluster matches
+pub const UNCLASSIFIED: &str = "Unclassified";
+
+/// fcntl commands that duplicate an fd (F_DUPFD, F_DUPFD_CLOEXEC)
+const FCNTL_DUP_COMMANDS: [u64; 2] = [0, 1030];
+
+/// Settings for the per-cluster summary
+#[derive(Debug, Clone)]
+pub struct ClusterSummaryConfig {
+    /// Cluster pack used for classification
+    pub registry: ClusterRegistry,
+    /// Where the cluster pack was loaded from (shown in the report header)
+    pub source: String,
+    /// Mean calls per run for each syscall, from a `renacer bench` baseline
+    pub baseline: Option<HashMap<String, f64>>,
+}
+
+/// Accumulated calls and time for one cluster
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClusterStats {
+    pub calls: u64,
+    pub errors: u64,
+    pub time_us: u64,
+}
+
+/// Classifies traced syscalls into clusters with live fd tracking
+#[derive(Debug)]
+pub struct ClusterTracker {
+    config: ClusterSummaryConfig,
+    fd_tables: HashMap<i32, FdTable>,
+    stats: HashMap<String, ClusterStats>,
+}
+
+impl ClusterTracker {
+    pub fn new(config: ClusterSummaryConfig) -> Self {
+        Self {
+            config,
+            fd_tables: HashMap::new(),
+            stats: HashMap::new(),
+        }
+    }
+
+    /// Classify and count one completed syscall
+    ///
+    /// `resolve_fd` is asked for the path of fds the table hasn't seen;
+    /// return `None` when the process can't be inspected (e.g. replays).
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        args: &[String],
+        raw_args: [u64; 3],
+        result: i64,
+        duration_us: u64,
+        resolve_fd: impl Fn(i32) -> Option<String>,
+    ) -> &str {
+        let fds = self.fd_tables.entry(pid).or_default();
+
+        // fd_path_pattern filters apply to the ioctl fd
+        if syscall == "ioctl" {
+            let fd = raw_args[0] as i32;
+            if fds.get_path(fd).is_none() {
+                if let Some(path) = resolve_fd(fd) {
+                    fds.insert(fd, path);
+                }
+            }
+        }
+
+        // Without decoded args, match on the raw registers as the tracer formats them
+        let raw: Vec<String>;
+        let classify_args = if args.is_empty() {
+            raw = raw_args.iter().map(|arg| format!("{:#x}", arg)).collect();
+            &raw
+        } else {
+            args
+        };
+        let cluster = self
+            .config
+            .registry
+            .classify(syscall, classify_args, fds)
+            .map_or(UNCLASSIFIED, |c| c.name.as_str());
+
+        update_fd_table(fds, syscall, args, raw_args, result, &resolve_fd);
+
+        let stats = self.stats.entry(cluster.to_string()).or_default();
+        stats.calls += 1;
+        stats.time_us += duration_us;
+        if result < 0 {
+            stats.errors += 1;
+        }
+        cluster
+    }
+
+    /// Per-cluster totals
+    pub fn stats(&self) -> &HashMap<String, ClusterStats> {
+        &self.stats
+    }
+
+    /// Tracked fds of a process
+    pub fn fd_table(&self, pid: i32) -> Option<&FdTable> {
+        self.fd_tables.get(&pid)
+    }
+
+    /// Baseline calls per cluster, classified by syscall name alone
+    ///
+    /// Baselines carry no fd paths, so syscalls of argument-filtered
+    /// clusters count as unclassified.
+    fn baseline_calls(&self) -> Option<HashMap<&str, f64>> {
+        let baseline = self.config.baseline.as_ref()?;
+        let mut calls: HashMap<&str, f64> = HashMap::new();
+        for (syscall, mean) in baseline {
+            let cluster = self
+                .config
+                .registry
+                .classify(syscall, &[], &FdTable::new())
+                .map_or(UNCLASSIFIED, |c| c.name.as_str());
+            *calls.entry(cluster).or_insert(0.0) += mean;
+        }
+        Some(calls)
+    }
+
+    /// Whether a cluster's call count is anomalous
+    ///
+    /// Against a baseline when one was given; otherwise only clusters that
+    /// aren't expected in single-shot compiles are judged (baseline of zero).
+    fn anomaly(
+        &self,
+        cluster: &str,
+        calls: u64,
+        baseline: Option<&HashMap<&str, f64>>,
+    ) -> Option<bool> {
+        let definition = self.config.registry.get_cluster(cluster)?;
+        let baseline_calls = match baseline {
+            Some(baseline) => baseline.get(cluster).copied().unwrap_or(0.0).round() as usize,
+            None if !definition.expected_for_transpiler => 0,
+            None => return None,
+        };
+        Some(definition.is_anomalous(baseline_calls, calls as usize))
+    }
+
+    /// Clusters that were flagged as anomalous, most severe first
+    pub fn anomalies(&self) -> Vec<(&str, Severity)> {
+        let baseline = self.baseline_calls();
+        let mut anomalies: Vec<(&str, Severity)> = self
+            .stats
+            .iter()
+            .filter(|(name, stats)| {
+                self.anomaly(name, stats.calls, baseline.as_ref()) == Some(true)
+            })
+            .filter_map(|(name, _)| {
+                let definition = self.config.registry.get_cluster(name)?;
+                Some((name.as_str(), definition.severity))
+            })
+            .collect();
+        anomalies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        anomalies
+    }
+
+    /// strace-style summary table, sorted by time
+    pub fn report(&self) -> String {
+        let baseline = self.baseline_calls();
+        let total_time_us: u64 = self.stats.values().map(|s| s.time_us).sum();
+        let total_calls: u64 = self.stats.values().map(|s| s.calls).sum();
+        let total_errors: u64 = self.stats.values().map(|s| s.errors).sum();
+
+        let mut rows: Vec<(&String, &ClusterStats)> = self.stats.iter().collect();
+        rows.sort_by(|a, b| b.1.time_us.cmp(&a.1.time_us).then_with(|| a.0.cmp(b.0)));
+
+        let separator =
+            "------ ----------- ----------- --------- --------- -------------------- ----------\n";
+        let mut out = format!(
+            "\nCluster summary ({}{}):\n",
+            self.config.source,
+            if baseline.is_some() {
+                ", vs baseline"
+            } else {
+                ""
+            }
+        );
+        out.push_str(
+            "% time     seconds  usecs/call     calls    errors cluster              anomaly\n",
+        );
+        out.push_str(separator);
+        for (name, stats) in rows {
+            let percent = if total_time_us > 0 {
+                stats.time_us as f64 / total_time_us as f64 * 100.0
+            } else {
+                0.0
+            };
+            let anomaly = match self.anomaly(name, stats.calls, baseline.as_ref()) {
+                Some(true) => {
+                    let severity = self
+                        .config
+                        .registry
+                        .get_cluster(name)
+                        .map(|d| format!("{:?}", d.severity).to_lowercase())
+                        .unwrap_or_default();
+                    format!("YES ({})", severity)
+                }
+                Some(false) => "no".to_string(),
+                None => "-".to_string(),
+            };
+            out.push_str(&format!(
+                "{:6.2} {:>11.6} {:>11} {:>9} {:>9} {:<20} {}\n",
+                percent,
+                stats.time_us as f64 / 1_000_000.0,
+                stats.time_us.checked_div(stats.calls).unwrap_or(0),
+                stats.calls,
+                if stats.errors > 0 {
+                    stats.errors.to_string()
+                } else {
+                    String::new()
+                },
+                name,
+                anomaly
+            ));
+        }
+        out.push_str(separator);
+        out.push_str(&format!(
+            "100.00 {:>11.6} {:>11} {:>9} {:>9} total\n",
+            total_time_us as f64 / 1_000_000.0,
+            total_time_us.checked_div(total_calls).unwrap_or(0),
+            total_calls,
+            total_errors
+        ));
+        out
+    }
+}
+
+/// Track fds opened, duplicated and closed by a completed syscall
+fn update_fd_table(
+    fds: &mut FdTable,
+    syscall: &str,
+    args: &[String],
+    raw_args: [u64; 3],
+    result: i64,
+    resolve_fd: &impl Fn(i32) -> Option<String>,
+) {
+    if syscall == "close" {
+        fds.remove(raw_args[0] as i32);
+        return;
+    }
+    if result < 0 {
+        return;
+    }
+    let new_fd = result as i32;
+
+    match syscall {
+        "open" | "openat" | "openat2" | "creat" => {
+            // The kernel's view of the path, else the decoded path argument
+            let path = resolve_fd(new_fd).or_else(|| {
+                args.iter()
+                    .find_map(|arg| arg.strip_prefix('"')?.strip_suffix('"'))
+                    .map(str::to_string)
+            });
+            match path {
+                Some(path) => fds.insert(new_fd, path),
+                None => {
+                    fds.remove(new_fd);
+                }
+            }
+        }
+        "dup" | "dup2" | "dup3" => copy_fd(fds, raw_args[0] as i32, new_fd),
+        "fcntl" if FCNTL_DUP_COMMANDS.contains(&raw_args[1]) => {
+            copy_fd(fds, raw_args[0] as i32, new_fd)
+        }
+        _ => {}
+    }
+}
+
+fn copy_fd(fds: &mut FdTable, old_fd: i32, new_fd: i32) {
+    match fds.get_path(old_fd).map(str::to_string) {
+        Some(path) => fds.insert(new_fd, path),
+        None => {
+            fds.remove(new_fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPU_PACK: &str = r#"
+[[cluster]]
+name = "FileIO"
+description = "File operations"
+syscalls = ["openat", "read", "close", "dup2"]
+expected_for_transpiler = true
+anomaly_threshold = 0.30
+severity = "medium"
+
+[[cluster]]
+name = "GPU"
+description = "CUDA kernel launches"
+syscalls = ["ioctl"]
+expected_for_transpiler = false
+anomaly_threshold = 0.0
+severity = "high"
+
+[cluster.args_filter]
+fd_path_pattern = "/dev/nvidia*"
+"#;
+
+    fn tracker(baseline: Option<HashMap<String, f64>>) -> ClusterTracker {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("clusters.toml");
+        std::fs::write(&path, GPU_PACK).unwrap();
+        ClusterTracker::new(ClusterSummaryConfig {
+            registry: ClusterRegistry::from_toml(&path).unwrap(),
+            source: "test.toml".to_string(),
+            baseline,
+        })
+    }
+
+    fn no_proc(_: i32) -> Option<String> {
+        None
+    }
+
+    fn hex_args(raw: [u64; 3]) -> Vec<String> {
+        raw.iter().map(|a| format!("{:#x}", a)).collect()
+    }
+
+    #[test]
+    fn test_ioctl_on_opened_gpu_device() {
+        let mut tracker = tracker(None);
+        let open_args = vec![
+            "0xffffff9c".to_string(),
+            "\"/dev/nvidia0\"".to_string(),
+            "0x2".to_string(),
+        ];
+        tracker.record(1, "openat", &open_args, [0, 0, 2], 5, 10, no_proc);
+
+        let cluster = tracker.record(1, "ioctl", &hex_args([5, 0, 0]), [5, 0, 0], 0, 20, no_proc);
+        assert_eq!(cluster, "GPU");
+
+        // A different fd is not the GPU
+        let cluster = tracker.record(1, "ioctl", &hex_args([1, 0, 0]), [1, 0, 0], 0, 5, no_proc);
+        assert_eq!(cluster, UNCLASSIFIED);
+
+        assert_eq!(tracker.stats()["GPU"].calls, 1);
+        assert_eq!(tracker.stats()["GPU"].time_us, 20);
+        assert_eq!(tracker.stats()[UNCLASSIFIED].calls, 1);
+    }
+
+    #[test]
+    fn test_fd_table_follows_dup_and_close() {
+        let mut tracker = tracker(None);
+        let open_args = vec!["0x0".to_string(), "\"/dev/nvidia1\"".to_string()];
+        tracker.record(1, "openat", &open_args, [0, 0, 0], 3, 1, no_proc);
+        tracker.record(1, "dup2", &hex_args([3, 7, 0]), [3, 7, 0], 7, 1, no_proc);
+        tracker.record(1, "close", &hex_args([3, 0, 0]), [3, 0, 0], 0, 1, no_proc);
+
+        let fds = tracker.fd_table(1).unwrap();
+        assert_eq!(fds.get_path(3), None);
+        assert_eq!(fds.get_path(7), Some("/dev/nvidia1"));
+
+        // Other processes have their own table
+        assert!(tracker.fd_table(2).is_none());
+    }
+
+    #[test]
+    fn test_unknown_fds_are_resolved_lazily() {
+        let mut tracker = tracker(None);
+        let cluster = tracker.record(4, "ioctl", &hex_args([9, 0, 0]), [9, 0, 0], 0, 1, |fd| {
+            (fd == 9).then(|| "/dev/nvidiactl".to_string())
+        });
+        assert_eq!(cluster, "GPU");
+    }
+
+    #[test]
+    fn test_unexpected_cluster_is_anomalous_without_baseline() {
+        let mut tracker = tracker(None);
+        tracker.record(1, "read", &[], [0; 3], 10, 1, no_proc);
+        tracker.record(1, "ioctl", &[], [5, 0, 0], 0, 1, |_| {
+            Some("/dev/nvidia0".into())
+        });
+
+        assert_eq!(tracker.anomalies(), vec![("GPU", Severity::High)]);
+        let report = tracker.report();
+        assert!(report.contains("Cluster summary (test.toml)"));
+        assert!(report.contains("YES (high)"));
+        assert!(report
+            .lines()
+            .any(|l| l.contains("FileIO") && l.ends_with('-')));
+    }
+
+    #[test]
+    fn test_baseline_anomaly_flags() {
+        let baseline = HashMap::from([("read".to_string(), 10.0)]);
+        let mut tracker = tracker(Some(baseline));
+        for _ in 0..20 {
+            tracker.record(1, "read", &[], [0; 3], 10, 1, no_proc);
+        }
+
+        assert_eq!(tracker.anomalies(), vec![("FileIO", Severity::Medium)]);
+        assert!(tracker.report().contains("vs baseline"));
+    }
+}
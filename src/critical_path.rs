@@ -301,22 +301,33 @@ fn topological_sort(graph: &CausalGraph) -> Result<Vec<NodeId>> {
 }
 
 /// DFS helper for topological sort
+///
+/// Pushes each node onto `stack` once all of its children are done (post
+/// order), keeping the pending children on the heap rather than the call
+/// stack.
 fn dfs_topo(
     graph: &CausalGraph,
-    node: NodeId,
+    root: NodeId,
     visited: &mut std::collections::HashSet<NodeId>,
     stack: &mut Vec<NodeId>,
 ) -> Result<()> {
-    visited.insert(node);
-
-    let children = graph.children(node)?;
-    for (child, _) in children {
-        if !visited.contains(&child) {
-            dfs_topo(graph, child, visited, stack)?;
+    visited.insert(root);
+    let mut pending = vec![(root, graph.children(root)?.into_iter())];
+
+    while let Some((node, children)) = pending.last_mut() {
+        let node = *node;
+        match children.find(|(child, _)| !visited.contains(child)) {
+            Some((child, _)) => {
+                visited.insert(child);
+                pending.push((child, graph.children(child)?.into_iter()));
+            }
+            None => {
+                stack.push(node);
+                pending.pop();
+            }
         }
     }
 
-    stack.push(node);
     Ok(())
 }
 
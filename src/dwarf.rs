@@ -5,12 +5,13 @@
 //! Uses addr2line crate for robust DWARF parsing
 
 use anyhow::{Context, Result};
-use object::{Object, ObjectSection};
+use object::{Object, ObjectKind, ObjectSection, ObjectSegment, ObjectSymbol, SymbolKind};
+use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::path::Path;
 
 /// Source location information
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SourceLocation {
     /// Source file path
     pub file: String,
@@ -28,6 +29,35 @@ pub struct SourceLocation {
 pub struct DwarfContext {
     /// addr2line context for DWARF lookups
     context: addr2line::Context<gimli::EndianRcSlice<gimli::RunTimeEndian>>,
+    /// ELF symbol table sorted by address (Sprint 50: fallback when DWARF has no frame)
+    symbols: Vec<ElfSymbol>,
+    /// True for ET_DYN (PIE) binaries whose runtime addresses need relocating
+    position_independent: bool,
+    /// Lowest PT_LOAD virtual address in the binary
+    image_base: u64,
+    /// Runtime load bias subtracted from instruction pointers before lookup
+    load_bias: u64,
+}
+
+/// An ELF symbol table entry (Sprint 50)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSymbol {
+    /// Symbol address (link-time virtual address)
+    pub address: u64,
+    /// Symbol size in bytes (0 if unknown)
+    pub size: u64,
+    /// Demangled symbol name
+    pub name: String,
+    /// True for code symbols, false for data objects
+    pub is_function: bool,
+}
+
+/// Demangle a Rust (legacy or v0) symbol, leaving other names untouched
+///
+/// The trailing hash of legacy Rust symbols is dropped so identical functions
+/// from different builds fold into the same flamegraph frame.
+pub fn demangle(name: &str) -> String {
+    format!("{:#}", rustc_demangle::demangle(name))
 }
 
 impl std::fmt::Debug for DwarfContext {
@@ -83,7 +113,114 @@ impl DwarfContext {
         let context =
             addr2line::Context::from_dwarf(dwarf).context("Failed to create DWARF context")?;
 
-        Ok(Self { context })
+        // Sprint 50: Keep the symbol table so stripped-of-DWARF frames still get names
+        let mut symbols: Vec<ElfSymbol> = object
+            .symbols()
+            .filter(|sym| matches!(sym.kind(), SymbolKind::Text | SymbolKind::Data))
+            .filter(|sym| sym.address() != 0)
+            .filter_map(|sym| {
+                sym.name().ok().map(|name| ElfSymbol {
+                    address: sym.address(),
+                    size: sym.size(),
+                    name: demangle(name),
+                    is_function: sym.kind() == SymbolKind::Text,
+                })
+            })
+            .collect();
+        symbols.sort_by_key(|sym| sym.address);
+
+        let image_base = object
+            .segments()
+            .map(|segment| segment.address())
+            .min()
+            .unwrap_or(0);
+
+        Ok(Self {
+            context,
+            symbols,
+            position_independent: object.kind() == ObjectKind::Dynamic,
+            image_base,
+            load_bias: 0,
+        })
+    }
+
+    /// Whether the binary is position independent (PIE) and needs a load bias
+    pub fn is_position_independent(&self) -> bool {
+        self.position_independent
+    }
+
+    /// Lowest virtual address of the binary's loadable segments
+    pub fn image_base(&self) -> u64 {
+        self.image_base
+    }
+
+    /// Set the runtime load bias (runtime address - link-time address)
+    ///
+    /// Sprint 50: PIE executables are mapped at a random base, so addresses
+    /// read from the tracee must be relocated before DWARF lookups.
+    pub fn set_load_bias(&mut self, bias: u64) {
+        self.load_bias = bias;
+    }
+
+    /// Current runtime load bias
+    pub fn load_bias(&self) -> u64 {
+        self.load_bias
+    }
+
+    /// All symbols from the ELF symbol table, sorted by address
+    pub fn symbols(&self) -> &[ElfSymbol] {
+        &self.symbols
+    }
+
+    /// Find the symbol containing a runtime address
+    ///
+    /// Returns the symbol and the offset of `addr` into it.
+    pub fn symbol_at(&self, addr: u64) -> Option<(&ElfSymbol, u64)> {
+        let addr = addr.wrapping_sub(self.load_bias);
+        let idx = self.symbols.partition_point(|sym| sym.address <= addr);
+        let sym = self.symbols[..idx].last()?;
+        let offset = addr - sym.address;
+        if sym.size == 0 || offset < sym.size {
+            Some((sym, offset))
+        } else {
+            None
+        }
+    }
+
+    /// Runtime address of a symbol by (demangled) name
+    pub fn symbol_address(&self, name: &str) -> Option<u64> {
+        self.symbols
+            .iter()
+            .find(|sym| sym.name == name)
+            .map(|sym| sym.address.wrapping_add(self.load_bias))
+    }
+
+    /// Resolve all function names at an instruction pointer, innermost first
+    ///
+    /// Sprint 50: Unlike [`DwarfContext::lookup`] this includes inlined frames
+    /// and standard library code, which flamegraphs need to show full stacks.
+    /// Falls back to the ELF symbol table when DWARF has no entry.
+    pub fn function_names(&self, ip: u64) -> Vec<String> {
+        let addr = ip.wrapping_sub(self.load_bias);
+        let mut names = Vec::new();
+
+        if let Ok(mut frames) = self.context.find_frames(addr).skip_all_loads() {
+            while let Ok(Some(frame)) = frames.next() {
+                if let Some(name) = frame.function.as_ref().and_then(|f| f.raw_name().ok()) {
+                    names.push(demangle(&name));
+                }
+            }
+        }
+
+        if names.is_empty() {
+            if let Some((sym, _)) = self.symbol_at(ip) {
+                if sym.is_function {
+                    names.push(sym.name.clone());
+                }
+            }
+        }
+
+        names
     }
 
     /// Look up source location for an instruction pointer
@@ -91,6 +228,9 @@ impl DwarfContext {
     /// Sprint 5-6: Full implementation with DWARF .debug_line parsing
     /// Returns the first valid source location found in DWARF
     pub fn lookup(&self, ip: u64) -> Result<Option<SourceLocation>> {
+        // Sprint 50: Relocate runtime address for PIE binaries
+        let ip = ip.wrapping_sub(self.load_bias);
+
         // Try multiple IP offsets to find user code
         // At syscall-entry-stop, IP might be in libc, so we try backing up
         for offset in [0, 1, 2, 4, 8, 16] {
@@ -261,6 +401,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_demangle_rust_symbols() {
+        assert_eq!(demangle("_ZN4test4main17h0123456789abcdefE"), "test::main");
+        assert_eq!(demangle("main"), "main");
+        assert_eq!(demangle("__libc_start_main"), "__libc_start_main");
+    }
+
+    #[test]
+    fn test_symbol_table_lookup() {
+        let (_temp_dir, bin_file) = compile_test_binary();
+        let ctx = DwarfContext::load(&bin_file).unwrap();
+
+        let main_addr = ctx.symbol_address("main").expect("main symbol");
+        let (sym, offset) = ctx.symbol_at(main_addr).expect("symbol at main");
+        assert_eq!(sym.name, "main");
+        assert_eq!(offset, 0);
+        assert!(sym.is_function);
+
+        // Rust's generated main is found through DWARF or the symbol table
+        assert!(!ctx.function_names(main_addr).is_empty());
+    }
+
+    #[test]
+    fn test_load_bias_relocates_lookups() {
+        let (_temp_dir, bin_file) = compile_test_binary();
+        let mut ctx = DwarfContext::load(&bin_file).unwrap();
+        let main_addr = ctx.symbol_address("main").unwrap();
+
+        ctx.set_load_bias(0x5555_0000_0000);
+        assert_eq!(ctx.load_bias(), 0x5555_0000_0000);
+        assert_eq!(
+            ctx.symbol_address("main"),
+            Some(main_addr + 0x5555_0000_0000)
+        );
+        assert!(ctx.symbol_at(main_addr + 0x5555_0000_0000).is_some());
+    }
+
     #[test]
     fn test_dwarf_lookup_zero_address() {
         let (_temp_dir, bin_file) = compile_test_binary();
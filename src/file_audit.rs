@@ -0,0 +1,623 @@
+//! File access audit: what a process tree reads, writes, creates and deletes
+//!
+//! Sprint 61: Builds a filesystem activity model from decoded path syscalls
+//! for build hermeticity checks. Relative paths are resolved against each
+//! process's tracked working directory (`chdir`/`fchdir`) or the directory fd
+//! of `*at` syscalls; forked children inherit both.
+//!
+//! Paths are normalized lexically (`.`/`..`), without following symlinks.
+//! A file opened with `O_CREAT` counts as created unless the trace already
+//! saw it exist. Without a live process to ask (`renacer replay`), paths
+//! relative to an unknown directory are kept as they were passed.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// `AT_FDCWD` as passed in a dirfd argument
+const AT_FDCWD: i32 = -100;
+
+/// `ENOENT`, reported as a missing-file probe
+const ENOENT: i64 = -(libc::ENOENT as i64);
+
+/// Syscalls creating a process that inherits the caller's cwd and fds
+const SPAWN_SYSCALLS: &[&str] = &["clone", "clone3", "fork", "vfork"];
+
+/// Positions of the path arguments decoded for a syscall
+///
+/// Shared with the tracer's argument decoding, so recorded traces carry the
+/// paths this module needs.
+pub fn path_arg_indices(syscall: &str) -> &'static [usize] {
+    match syscall {
+        "open" | "creat" | "stat" | "lstat" | "access" | "mkdir" | "rmdir" | "unlink" | "chdir"
+        | "readlink" | "execve" | "truncate" => &[0],
+        "openat" | "openat2" | "newfstatat" | "statx" | "faccessat" | "faccessat2" | "mkdirat"
+        | "unlinkat" | "readlinkat" | "execveat" => &[1],
+        "rename" | "link" | "symlink" => &[0, 1],
+        "renameat" | "renameat2" => &[1, 3],
+        _ => &[],
+    }
+}
+
+/// File access audit output configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileAuditConfig {
+    /// Print the process tree report to stderr
+    pub text: bool,
+    /// Write the JSON report to this file
+    pub json_path: Option<PathBuf>,
+}
+
+impl FileAuditConfig {
+    /// Build a config from CLI arguments (None when the audit is off)
+    pub fn from_cli(text: bool, json_path: Option<&str>) -> Option<Self> {
+        if !text && json_path.is_none() {
+            return None;
+        }
+        Some(Self {
+            text,
+            json_path: json_path.map(PathBuf::from),
+        })
+    }
+}
+
+/// Information only the live process can provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcLookup {
+    /// Current working directory
+    Cwd,
+    /// Path behind a file descriptor
+    Fd(i32),
+    /// Running executable
+    Exe,
+}
+
+/// Files one process touched, by kind of access
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProcessFiles {
+    pub pid: i32,
+    pub parent: Option<i32>,
+    pub exe: Option<String>,
+    pub reads: BTreeSet<String>,
+    pub writes: BTreeSet<String>,
+    pub creates: BTreeSet<String>,
+    pub deletes: BTreeSet<String>,
+    /// Paths probed with `stat`/`access`/`readlink` that existed
+    pub stats: BTreeSet<String>,
+    /// Paths that failed with `ENOENT`
+    pub missing: BTreeSet<String>,
+    /// Binaries successfully executed
+    pub executed: BTreeSet<String>,
+    #[serde(skip)]
+    cwd: Option<String>,
+    #[serde(skip)]
+    fds: HashMap<i32, String>,
+}
+
+impl ProcessFiles {
+    /// Accesses as (label, paths), in report order
+    fn categories(&self) -> [(&'static str, &BTreeSet<String>); 7] {
+        [
+            ("exec", &self.executed),
+            ("read", &self.reads),
+            ("write", &self.writes),
+            ("create", &self.creates),
+            ("delete", &self.deletes),
+            ("stat", &self.stats),
+            ("missing", &self.missing),
+        ]
+    }
+
+    fn is_empty(&self) -> bool {
+        self.categories().iter().all(|(_, paths)| paths.is_empty())
+    }
+}
+
+/// Filesystem activity of a traced process tree
+#[derive(Debug, Default)]
+pub struct FileAudit {
+    config: FileAuditConfig,
+    processes: BTreeMap<i32, ProcessFiles>,
+    /// Paths the trace has seen exist (to tell creates from rewrites)
+    existing: HashSet<String>,
+}
+
+impl FileAudit {
+    pub fn new(config: FileAuditConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &FileAuditConfig {
+        &self.config
+    }
+
+    /// Set the executable of a process whose exec was not traced
+    pub fn set_exe(&mut self, pid: i32, exe: impl Into<String>) {
+        self.processes
+            .entry(pid)
+            .or_insert_with(|| ProcessFiles {
+                pid,
+                ..ProcessFiles::default()
+            })
+            .exe = Some(exe.into());
+    }
+
+    /// Processes in pid order
+    pub fn processes(&self) -> impl Iterator<Item = &ProcessFiles> {
+        self.processes.values()
+    }
+
+    /// Record a completed syscall
+    ///
+    /// `args` are the decoded arguments (paths quoted), `resolve` answers
+    /// lookups the trace cannot (None when not tracing live).
+    pub fn record(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        args: &[String],
+        raw_args: [u64; 3],
+        result: i64,
+        resolve: impl Fn(i32, ProcLookup) -> Option<String>,
+    ) {
+        let process = self.processes.entry(pid).or_insert_with(|| ProcessFiles {
+            pid,
+            cwd: resolve(pid, ProcLookup::Cwd),
+            exe: resolve(pid, ProcLookup::Exe),
+            ..ProcessFiles::default()
+        });
+
+        if SPAWN_SYSCALLS.contains(&syscall) {
+            if result > 0 {
+                self.spawn(pid, result as i32);
+            }
+            return;
+        }
+        if syscall == "close" {
+            process.fds.remove(&(raw_args[0] as i32));
+            return;
+        }
+        if let Some(new_fd) = dup_target(syscall, raw_args, result) {
+            match process.fds.get(&(raw_args[0] as i32)).cloned() {
+                Some(path) => process.fds.insert(new_fd, path),
+                None => process.fds.remove(&new_fd),
+            };
+            return;
+        }
+        if syscall == "fchdir" {
+            if result == 0 {
+                let fd = raw_args[0] as i32;
+                process.cwd = process
+                    .fds
+                    .get(&fd)
+                    .cloned()
+                    .or_else(|| resolve(pid, ProcLookup::Fd(fd)));
+            }
+            return;
+        }
+
+        let indices = path_arg_indices(syscall);
+        if indices.is_empty() {
+            return;
+        }
+        let paths: Vec<String> = indices
+            .iter()
+            .filter_map(|&index| {
+                let path = unquote(args.get(index)?)?;
+                let dirfd = dirfd_for(syscall, index, args);
+                Some(resolve_path(process, pid, path, dirfd, &resolve))
+            })
+            .collect();
+        let Some(path) = paths.first().cloned() else {
+            return;
+        };
+
+        if result == ENOENT {
+            process.missing.insert(path);
+            return;
+        }
+        if result < 0 {
+            return;
+        }
+
+        match syscall {
+            "open" | "openat" | "openat2" | "creat" => {
+                let flags = match syscall {
+                    "open" => raw_args[1],
+                    "openat" => raw_args[2],
+                    "creat" => (libc::O_CREAT | libc::O_WRONLY | libc::O_TRUNC) as u64,
+                    // open_how lives in tracee memory; treated as a read
+                    _ => 0,
+                };
+                let flags = flags as i32;
+                let writes = flags & libc::O_ACCMODE != libc::O_RDONLY;
+                if flags & libc::O_CREAT != 0 && !self.existing.contains(&path) {
+                    process.creates.insert(path.clone());
+                } else if writes {
+                    process.writes.insert(path.clone());
+                }
+                if !writes || flags & libc::O_ACCMODE == libc::O_RDWR {
+                    process.reads.insert(path.clone());
+                }
+                process.fds.insert(result as i32, path.clone());
+                self.existing.insert(path);
+            }
+            "stat" | "lstat" | "newfstatat" | "statx" | "access" | "faccessat" | "faccessat2"
+            | "readlink" | "readlinkat" => {
+                process.stats.insert(path.clone());
+                self.existing.insert(path);
+            }
+            "mkdir" | "mkdirat" | "symlink" | "link" => {
+                // link/symlink create their second path
+                let created = paths.last().cloned().unwrap_or(path);
+                process.creates.insert(created.clone());
+                self.existing.insert(created);
+            }
+            "unlink" | "unlinkat" | "rmdir" => {
+                process.deletes.insert(path.clone());
+                self.existing.remove(&path);
+            }
+            "rename" | "renameat" | "renameat2" => {
+                process.deletes.insert(path.clone());
+                self.existing.remove(&path);
+                if let Some(target) = paths.get(1) {
+                    process.creates.insert(target.clone());
+                    self.existing.insert(target.clone());
+                }
+            }
+            "truncate" => {
+                process.writes.insert(path);
+            }
+            "execve" | "execveat" => {
+                process.executed.insert(path.clone());
+                process.exe = Some(path.clone());
+                self.existing.insert(path);
+            }
+            "chdir" => process.cwd = Some(path),
+            _ => {}
+        }
+    }
+
+    /// A child inherits the cwd and fds of its parent
+    fn spawn(&mut self, parent: i32, child: i32) {
+        let Some(parent_files) = self.processes.get(&parent) else {
+            return;
+        };
+        let (cwd, fds, exe) = (
+            parent_files.cwd.clone(),
+            parent_files.fds.clone(),
+            parent_files.exe.clone(),
+        );
+        let child_files = self.processes.entry(child).or_insert_with(|| ProcessFiles {
+            pid: child,
+            ..ProcessFiles::default()
+        });
+        child_files.parent = Some(parent);
+        child_files.cwd = child_files.cwd.take().or(cwd);
+        child_files.exe = child_files.exe.take().or(exe);
+        for (fd, path) in fds {
+            child_files.fds.entry(fd).or_insert(path);
+        }
+    }
+
+    /// JSON report: one entry per process
+    pub fn to_json(&self) -> Result<String> {
+        let processes: Vec<&ProcessFiles> = self.processes().collect();
+        Ok(serde_json::to_string_pretty(
+            &serde_json::json!({ "processes": processes }),
+        )?)
+    }
+
+    /// Write the JSON report if configured, returning its path
+    pub fn write_json(&self) -> Result<Option<PathBuf>> {
+        let Some(path) = &self.config.json_path else {
+            return Ok(None);
+        };
+        std::fs::write(path, self.to_json()?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Some(path.clone()))
+    }
+
+    /// Text report as a process tree
+    pub fn to_text(&self) -> String {
+        let mut out = String::from("\n=== File Access Audit ===\n");
+        let roots = self.processes().filter(|p| {
+            p.parent
+                .is_none_or(|parent| !self.processes.contains_key(&parent))
+        });
+        for root in roots {
+            self.write_process(&mut out, root, 0);
+        }
+        out
+    }
+
+    fn write_process(&self, out: &mut String, process: &ProcessFiles, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let _ = writeln!(
+            out,
+            "{}[pid {}] {}",
+            indent,
+            process.pid,
+            process.exe.as_deref().unwrap_or("?")
+        );
+        for (label, paths) in process.categories() {
+            for path in paths {
+                let _ = writeln!(out, "{}  {:<8} {}", indent, label, path);
+            }
+        }
+        if process.is_empty() {
+            let _ = writeln!(out, "{}  (no file activity)", indent);
+        }
+        for child in self.processes().filter(|p| p.parent == Some(process.pid)) {
+            self.write_process(out, child, depth + 1);
+        }
+    }
+}
+
+/// New fd of a successful dup-style syscall
+fn dup_target(syscall: &str, raw_args: [u64; 3], result: i64) -> Option<i32> {
+    if result < 0 {
+        return None;
+    }
+    match syscall {
+        "dup" | "dup2" | "dup3" => Some(result as i32),
+        "fcntl" if [libc::F_DUPFD, libc::F_DUPFD_CLOEXEC].contains(&(raw_args[1] as i32)) => {
+            Some(result as i32)
+        }
+        _ => None,
+    }
+}
+
+/// Directory fd a path argument is relative to (AT_FDCWD for plain syscalls)
+fn dirfd_for(syscall: &str, path_index: usize, args: &[String]) -> i32 {
+    let dirfd_index = match (syscall, path_index) {
+        ("renameat" | "renameat2", 3) => 2,
+        (_, 1) if syscall != "rename" && syscall != "link" && syscall != "symlink" => 0,
+        _ => return AT_FDCWD,
+    };
+    args.get(dirfd_index)
+        .and_then(|arg| parse_int(arg))
+        .map(|fd| fd as i32)
+        .unwrap_or(AT_FDCWD)
+}
+
+/// Absolute, normalized path for a path argument
+fn resolve_path(
+    process: &ProcessFiles,
+    pid: i32,
+    path: &str,
+    dirfd: i32,
+    resolve: &impl Fn(i32, ProcLookup) -> Option<String>,
+) -> String {
+    if path.starts_with('/') {
+        return normalize(path);
+    }
+    let base = if dirfd == AT_FDCWD {
+        process.cwd.clone()
+    } else {
+        process
+            .fds
+            .get(&dirfd)
+            .cloned()
+            .or_else(|| resolve(pid, ProcLookup::Fd(dirfd)))
+    };
+    match base {
+        // AT_EMPTY_PATH: the path is the dirfd itself
+        Some(base) if path.is_empty() => normalize(&base),
+        Some(base) => normalize(&format!("{}/{}", base, path)),
+        None => path.to_string(),
+    }
+}
+
+/// Lexically resolve `.` and `..` components of a path
+///
+/// Relative paths (from an unknown cwd) stay relative.
+fn normalize(path: &str) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." if parts.last().is_some_and(|last| *last != "..") => {
+                parts.pop();
+            }
+            ".." if path.starts_with('/') => {}
+            part => parts.push(part),
+        }
+    }
+    if path.starts_with('/') {
+        format!("/{}", parts.join("/"))
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Decoded path argument without its quotes
+pub(crate) fn unquote(arg: &str) -> Option<&str> {
+    arg.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// Integer argument as the tracer formats it ("0x..."), as a 32-bit value
+fn parse_int(arg: &str) -> Option<i64> {
+    let value = match arg.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => arg.parse::<i64>().ok()? as u64,
+    };
+    Some(value as u32 as i32 as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quoted(path: &str) -> String {
+        format!("\"{}\"", path)
+    }
+
+    fn hex(value: i64) -> String {
+        format!("{:#x}", value as u64)
+    }
+
+    /// Resolver for a process started in /work
+    fn live(_pid: i32, lookup: ProcLookup) -> Option<String> {
+        match lookup {
+            ProcLookup::Cwd => Some("/work".to_string()),
+            ProcLookup::Exe => Some("/usr/bin/make".to_string()),
+            ProcLookup::Fd(_) => None,
+        }
+    }
+
+    fn openat(audit: &mut FileAudit, pid: i32, dirfd: i64, path: &str, flags: i32, result: i64) {
+        audit.record(
+            pid,
+            "openat",
+            &[hex(dirfd), quoted(path), hex(flags as i64)],
+            [dirfd as u64, 0, flags as u64],
+            result,
+            live,
+        );
+    }
+
+    #[test]
+    fn test_relative_paths_use_cwd_and_dirfds() {
+        let mut audit = FileAudit::default();
+        openat(&mut audit, 1, -100, "src/../main.c", libc::O_RDONLY, 3);
+        openat(
+            &mut audit,
+            1,
+            -100,
+            "out",
+            libc::O_RDONLY | libc::O_DIRECTORY,
+            4,
+        );
+        openat(
+            &mut audit,
+            1,
+            4,
+            "main.o",
+            libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC,
+            5,
+        );
+
+        let process = audit.processes().next().unwrap();
+        assert!(process.reads.contains("/work/main.c"));
+        assert!(process.creates.contains("/work/out/main.o"));
+        assert_eq!(process.exe.as_deref(), Some("/usr/bin/make"));
+    }
+
+    #[test]
+    fn test_missing_probes_creates_and_deletes() {
+        let mut audit = FileAudit::default();
+        audit.record(
+            1,
+            "access",
+            &[quoted("config.h"), hex(0)],
+            [0; 3],
+            ENOENT,
+            live,
+        );
+        openat(
+            &mut audit,
+            1,
+            -100,
+            "/etc/hosts",
+            libc::O_RDWR | libc::O_CREAT,
+            3,
+        );
+        audit.record(1, "unlink", &[quoted("/tmp/x")], [0; 3], 0, live);
+        audit.record(
+            1,
+            "rename",
+            &[quoted("a.tmp"), quoted("a.out")],
+            [0; 3],
+            0,
+            live,
+        );
+
+        let process = audit.processes().next().unwrap();
+        assert!(process.missing.contains("/work/config.h"));
+        assert!(process.creates.contains("/etc/hosts"));
+        assert!(process.reads.contains("/etc/hosts"));
+        assert!(process.deletes.contains("/tmp/x"));
+        assert!(process.deletes.contains("/work/a.tmp"));
+        assert!(process.creates.contains("/work/a.out"));
+    }
+
+    #[test]
+    fn test_existing_files_are_written_not_created() {
+        let mut audit = FileAudit::default();
+        audit.record(1, "stat", &[quoted("log")], [0; 3], 0, live);
+        openat(
+            &mut audit,
+            1,
+            -100,
+            "log",
+            libc::O_WRONLY | libc::O_CREAT,
+            3,
+        );
+
+        let process = audit.processes().next().unwrap();
+        assert!(process.stats.contains("/work/log"));
+        assert!(process.writes.contains("/work/log"));
+        assert!(process.creates.is_empty());
+    }
+
+    #[test]
+    fn test_children_inherit_cwd_and_show_in_tree() {
+        let mut audit = FileAudit::default();
+        audit.record(1, "chdir", &[quoted("build")], [0; 3], 0, live);
+        audit.record(1, "clone", &[], [0; 3], 2, live);
+        audit.record(2, "execve", &[quoted("/usr/bin/cc")], [0; 3], 0, |_, _| {
+            None
+        });
+        openat(&mut audit, 2, -100, "main.c", libc::O_RDONLY, 3);
+
+        let child = audit.processes().find(|p| p.pid == 2).unwrap();
+        assert_eq!(child.parent, Some(1));
+        assert!(child.reads.contains("/work/build/main.c"));
+
+        let text = audit.to_text();
+        assert!(text.contains("[pid 1] /usr/bin/make"));
+        assert!(text.contains("  [pid 2] /usr/bin/cc\n    exec     /usr/bin/cc"));
+
+        let json: serde_json::Value = serde_json::from_str(&audit.to_json().unwrap()).unwrap();
+        assert_eq!(json["processes"][1]["reads"][0], "/work/build/main.c");
+    }
+
+    #[test]
+    fn test_unknown_cwd_keeps_relative_paths() {
+        let mut audit = FileAudit::default();
+        audit.record(
+            1,
+            "openat",
+            &[hex(-100), quoted("rel.txt"), hex(0)],
+            [0xffffff9c, 0, 0],
+            3,
+            |_, _| None,
+        );
+        audit.set_exe(1, "cp");
+        let process = audit.processes().next().unwrap();
+        assert!(process.reads.contains("rel.txt"));
+        assert_eq!(process.exe.as_deref(), Some("cp"));
+    }
+
+    #[test]
+    fn test_config_from_cli() {
+        assert!(FileAuditConfig::from_cli(false, None).is_none());
+        let config = FileAuditConfig::from_cli(false, Some("files.json")).unwrap();
+        assert!(!config.text);
+        assert_eq!(config.json_path, Some(PathBuf::from("files.json")));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("/a/./b/../c//d"), "/a/c/d");
+        assert_eq!(normalize("/.."), "/");
+        assert_eq!(normalize("out/./a/../b"), "out/b");
+        assert_eq!(normalize("../x/../y"), "../y");
+    }
+}
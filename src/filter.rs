@@ -224,6 +224,18 @@ fn parse_regex_pattern(input: &str) -> Result<Option<Regex>> {
     }
 }
 
+/// Syscall classes accepted in -e trace= expressions
+pub const SYSCALL_CLASSES: &[&str] = &["file", "network", "process", "memory"];
+
+/// Class a syscall belongs to, if any
+/// Sprint 60: Groups seccomp profile rules
+pub fn syscall_class(name: &str) -> Option<&'static str> {
+    SYSCALL_CLASSES
+        .iter()
+        .copied()
+        .find(|class| expand_syscall_class(class).iter().any(|s| s == name))
+}
+
 /// Expand a syscall class or return a single syscall name
 /// Sprint 15: Extracted to reduce complexity
 fn expand_syscall_class(name: &str) -> Vec<String> {
@@ -250,12 +262,18 @@ fn expand_syscall_class(name: &str) -> Vec<String> {
             "socket",
             "connect",
             "accept",
+            "accept4",
             "bind",
             "listen",
             "send",
             "recv",
             "sendto",
             "recvfrom",
+            "sendmsg",
+            "recvmsg",
+            "shutdown",
+            "getsockname",
+            "getpeername",
             "setsockopt",
             "getsockopt",
         ]
@@ -480,6 +498,7 @@ mod tests {
         let syscalls = expand_syscall_class("network");
         assert!(syscalls.contains(&"socket".to_string()));
         assert!(syscalls.contains(&"connect".to_string()));
+        assert!(syscalls.contains(&"accept4".to_string()));
     }
 
     #[test]
@@ -605,4 +624,13 @@ mod tests {
         assert!(!filter.should_trace("openat")); // Excluded by regex
         assert!(!filter.should_trace("newfstatat")); // Excluded by regex
     }
+
+    #[test]
+    fn test_syscall_class() {
+        assert_eq!(syscall_class("openat"), Some("file"));
+        assert_eq!(syscall_class("socket"), Some("network"));
+        assert_eq!(syscall_class("exit_group"), Some("process"));
+        assert_eq!(syscall_class("mmap"), Some("memory"));
+        assert_eq!(syscall_class("futex"), None);
+    }
 }
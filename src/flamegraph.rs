@@ -0,0 +1,901 @@
+//! Flamegraph generation from unwound syscall stacks
+//!
+//! Sprint 50: Every traced syscall contributes one sample whose stack is the
+//! full unwound call stack (root first) with the syscall name as the leaf
+//! frame. Samples are aggregated into Brendan Gregg's folded-stack format and
+//! can be rendered to a standalone, interactive SVG without external tools.
+//!
+//! # Folded format
+//!
+//! ```text
+//! main;app::run;std::io::Write::write_all;write 42
+//! ```
+//!
+//! Each line is a `;`-separated stack followed by a space and the sample
+//! weight (syscall count or microseconds spent in the syscall).
+
+use crate::cli::{FlamegraphStyle, FlamegraphWeight};
+use crate::stack_unwind::ResolvedFrame;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// SVG image width in pixels
+const IMAGE_WIDTH: f64 = 1200.0;
+/// Height of a single frame in pixels
+const FRAME_HEIGHT: f64 = 16.0;
+/// Font size for frame labels
+const FONT_SIZE: f64 = 12.0;
+/// Average glyph width relative to the font size (Verdana)
+const FONT_WIDTH: f64 = 0.59;
+/// Horizontal padding
+const X_PAD: f64 = 10.0;
+/// Space reserved above the frames (title, buttons)
+const TOP_PAD: f64 = FONT_SIZE * 3.0;
+/// Space reserved below the frames (details line)
+const BOTTOM_PAD: f64 = FONT_SIZE * 2.0 + 10.0;
+/// Frames narrower than this are not drawn
+const MIN_FRAME_WIDTH: f64 = 0.1;
+
+/// Flamegraph output configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlamegraphConfig {
+    /// SVG output path
+    pub svg_path: Option<PathBuf>,
+    /// Folded stacks output path
+    pub folded_path: Option<PathBuf>,
+    /// Sample weight
+    pub weight: FlamegraphWeight,
+    /// Orientation of the SVG
+    pub style: FlamegraphStyle,
+    /// Only record blocking syscalls (off-CPU flamegraph)
+    pub off_cpu: bool,
+}
+
+impl FlamegraphConfig {
+    /// Build a config from CLI arguments
+    ///
+    /// Returns `None` when no flamegraph output was requested.
+    pub fn from_cli(
+        svg_path: Option<&str>,
+        folded_path: Option<&str>,
+        weight: FlamegraphWeight,
+        style: FlamegraphStyle,
+        off_cpu: bool,
+    ) -> Option<Self> {
+        if svg_path.is_none() && folded_path.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            svg_path: svg_path.map(PathBuf::from),
+            folded_path: folded_path.map(PathBuf::from),
+            weight,
+            style,
+            off_cpu,
+        })
+    }
+
+    /// Weight actually used for samples
+    ///
+    /// Off-CPU flamegraphs are always weighted by blocked time.
+    pub fn effective_weight(&self) -> FlamegraphWeight {
+        if self.off_cpu {
+            FlamegraphWeight::Time
+        } else {
+            self.weight
+        }
+    }
+}
+
+/// Aggregated stacks in folded form
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldedStacks {
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+impl FoldedStacks {
+    /// Create an empty set of stacks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sample for a root-first stack
+    ///
+    /// Zero-weight samples are ignored.
+    pub fn add(&mut self, frames: &[String], weight: u64) {
+        if weight == 0 || frames.is_empty() {
+            return;
+        }
+        let key: Vec<String> = frames.iter().map(|f| sanitize_frame(f)).collect();
+        *self.stacks.entry(key).or_insert(0) += weight;
+    }
+
+    /// Parse folded stacks ("a;b;c 12" per line)
+    ///
+    /// Blank and malformed lines are skipped.
+    pub fn from_folded(text: &str) -> Self {
+        let mut folded = Self::new();
+        for line in text.lines() {
+            let Some((stack, weight)) = line.trim().rsplit_once(' ') else {
+                continue;
+            };
+            let Ok(weight) = weight.parse::<u64>() else {
+                continue;
+            };
+            let frames: Vec<String> = stack.split(';').map(|f| f.to_string()).collect();
+            folded.add(&frames, weight);
+        }
+        folded
+    }
+
+    /// Number of distinct stacks
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    /// Whether no samples were recorded
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Total weight of all samples
+    pub fn total(&self) -> u64 {
+        self.stacks.values().sum()
+    }
+
+    /// Iterate over (root-first stack, weight)
+    pub fn iter(&self) -> impl Iterator<Item = (&[String], u64)> {
+        self.stacks.iter().map(|(k, v)| (k.as_slice(), *v))
+    }
+
+    /// Write stacks in folded format, sorted by stack
+    pub fn write_folded<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        for (stack, weight) in &self.stacks {
+            writeln!(writer, "{} {}", stack.join(";"), weight)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace characters that would break the folded format
+fn sanitize_frame(frame: &str) -> String {
+    frame.trim().replace([';', '\n', '\r'], ":")
+}
+
+/// Convert an innermost-first resolved stack to root-first frame labels
+///
+/// Consecutive unresolved frames in the same module are collapsed, and the
+/// syscall name is appended as the leaf frame.
+pub fn stack_labels(frames: &[ResolvedFrame], syscall: &str) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::with_capacity(frames.len() + 1);
+    for frame in frames.iter().rev() {
+        let label = frame.label();
+        let unresolved = frame.function.is_none();
+        if unresolved && labels.last() == Some(&label) {
+            continue;
+        }
+        labels.push(label);
+    }
+    labels.push(syscall.to_string());
+    labels
+}
+
+/// Collects syscall stacks during tracing and writes flamegraph files
+#[derive(Debug)]
+pub struct FlamegraphCollector {
+    config: FlamegraphConfig,
+    stacks: FoldedStacks,
+}
+
+impl FlamegraphCollector {
+    /// Create a collector for the given configuration
+    pub fn new(config: FlamegraphConfig) -> Self {
+        Self {
+            config,
+            stacks: FoldedStacks::new(),
+        }
+    }
+
+    /// Record one syscall with its innermost-first stack
+    pub fn record(&mut self, syscall: &str, frames: &[ResolvedFrame], duration_us: u64) {
+        if self.config.off_cpu && !crate::syscalls::is_blocking_syscall(syscall) {
+            return;
+        }
+
+        let weight = match self.config.effective_weight() {
+            FlamegraphWeight::Count => 1,
+            // Sub-microsecond syscalls still count as one unit of time
+            FlamegraphWeight::Time => duration_us.max(1),
+        };
+
+        self.stacks.add(&stack_labels(frames, syscall), weight);
+    }
+
+    /// Recorded stacks
+    pub fn stacks(&self) -> &FoldedStacks {
+        &self.stacks
+    }
+
+    /// Render the recorded stacks as an SVG document
+    pub fn to_svg(&self) -> String {
+        let (title, palette) = if self.config.off_cpu {
+            ("Off-CPU Syscall Flame Graph", Palette::OffCpu)
+        } else {
+            ("Syscall Flame Graph", Palette::Hot)
+        };
+        let title = match self.config.style {
+            FlamegraphStyle::Flame => title.to_string(),
+            FlamegraphStyle::Icicle => title.replace("Flame Graph", "Icicle Graph"),
+        };
+
+        render_svg(
+            &self.stacks,
+            &SvgOptions {
+                title,
+                unit: unit_name(self.config.effective_weight()).to_string(),
+                style: self.config.style,
+                palette,
+            },
+        )
+    }
+
+    /// Write the configured SVG and folded files
+    pub fn write_outputs(&self) -> Result<()> {
+        if let Some(path) = &self.config.folded_path {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            self.stacks
+                .write_folded(std::io::BufWriter::new(file))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        if let Some(path) = &self.config.svg_path {
+            std::fs::write(path, self.to_svg())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Output paths that will be written, for status messages
+    pub fn output_paths(&self) -> Vec<&PathBuf> {
+        self.config
+            .svg_path
+            .iter()
+            .chain(self.config.folded_path.iter())
+            .collect()
+    }
+}
+
+fn unit_name(weight: FlamegraphWeight) -> &'static str {
+    match weight {
+        FlamegraphWeight::Count => "syscalls",
+        FlamegraphWeight::Time => "μs",
+    }
+}
+
+/// Color palette for frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    /// Classic red/orange/yellow (on-CPU call stacks)
+    Hot,
+    /// Blue tones (off-CPU / blocked time)
+    OffCpu,
+}
+
+/// SVG rendering options
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Title shown at the top of the image
+    pub title: String,
+    /// Unit of sample weights ("syscalls", "μs")
+    pub unit: String,
+    /// Flame (root at bottom) or icicle (root at top)
+    pub style: FlamegraphStyle,
+    /// Frame color palette
+    pub palette: Palette,
+}
+
+/// Node of the merged call tree
+#[derive(Debug, Default)]
+struct Node {
+    value: u64,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|child| child.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn build_tree(stacks: &FoldedStacks) -> Node {
+    let mut root = Node::default();
+    for (stack, weight) in stacks.iter() {
+        root.value += weight;
+        let mut node = &mut root;
+        for frame in stack {
+            node = node.children.entry(frame.clone()).or_default();
+            node.value += weight;
+        }
+    }
+    root
+}
+
+/// Deterministic 0..1 values derived from a frame name
+fn name_hash(name: &str) -> (f64, f64, f64) {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in name.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    let v1 = (hash & 0xffff) as f64 / 65535.0;
+    let v2 = ((hash >> 16) & 0xffff) as f64 / 65535.0;
+    let v3 = ((hash >> 32) & 0xffff) as f64 / 65535.0;
+    (v1, v2, v3)
+}
+
+fn frame_color(name: &str, is_leaf: bool, palette: Palette) -> String {
+    let (v1, v2, v3) = name_hash(name);
+    let (r, g, b) = if is_leaf {
+        // Syscall frames: aqua, like kernel frames in perf flamegraphs
+        (50.0 + 60.0 * v1, 165.0 + 55.0 * v1, 165.0 + 55.0 * v1)
+    } else {
+        match palette {
+            Palette::Hot => (205.0 + 50.0 * v3, 230.0 * v1, 55.0 * v2),
+            Palette::OffCpu => (80.0 + 60.0 * v1, 80.0 + 60.0 * v1, 190.0 + 55.0 * v2),
+        }
+    };
+    format!("rgb({},{},{})", r as u8, g as u8, b as u8)
+}
+
+/// Escape text for XML content and attributes
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Truncate a label to fit a frame of the given pixel width
+fn fit_label(name: &str, width: f64) -> String {
+    let max_chars = (width / (FONT_SIZE * FONT_WIDTH)) as usize;
+    if max_chars < 3 {
+        return String::new();
+    }
+    if name.chars().count() <= max_chars {
+        name.to_string()
+    } else {
+        let truncated: String = name.chars().take(max_chars - 2).collect();
+        format!("{}..", truncated)
+    }
+}
+
+struct Layout<'a> {
+    options: &'a SvgOptions,
+    total: u64,
+    image_height: f64,
+    out: String,
+}
+
+impl Layout<'_> {
+    fn frame_y(&self, depth: usize) -> f64 {
+        match self.options.style {
+            FlamegraphStyle::Flame => {
+                self.image_height - BOTTOM_PAD - (depth as f64 + 1.0) * FRAME_HEIGHT
+            }
+            FlamegraphStyle::Icicle => TOP_PAD + depth as f64 * FRAME_HEIGHT,
+        }
+    }
+
+    fn emit(&mut self, name: &str, node: &Node, offset: u64, depth: usize) {
+        let scale = (IMAGE_WIDTH - 2.0 * X_PAD) / self.total as f64;
+        let width = node.value as f64 * scale;
+        if width < MIN_FRAME_WIDTH {
+            return;
+        }
+
+        let x = X_PAD + offset as f64 * scale;
+        let y = self.frame_y(depth);
+        let pct = node.value as f64 * 100.0 / self.total as f64;
+        let fill = frame_color(name, node.children.is_empty(), self.options.palette);
+        let escaped = xml_escape(name);
+
+        let _ = writeln!(
+            self.out,
+            r#"<g class="func" data-x="{}" data-w="{}" data-d="{}"><title>{} ({} {}, {:.2}%)</title><rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="{}" rx="2" ry="2"/><text x="{:.1}" y="{:.1}">{}</text></g>"#,
+            offset,
+            node.value,
+            depth,
+            escaped,
+            node.value,
+            xml_escape(&self.options.unit),
+            pct,
+            x,
+            y,
+            width,
+            FRAME_HEIGHT - 1.0,
+            fill,
+            x + 3.0,
+            y + FRAME_HEIGHT - 4.5,
+            xml_escape(&fit_label(name, width - 3.0)),
+        );
+
+        let mut child_offset = offset;
+        for (child_name, child) in &node.children {
+            self.emit(child_name, child, child_offset, depth + 1);
+            child_offset += child.value;
+        }
+    }
+}
+
+/// Render folded stacks as a self-contained interactive SVG
+///
+/// The document embeds a small script for click-to-zoom, search (Ctrl+F)
+/// and hover details, so it can be opened directly in any browser.
+pub fn render_svg(stacks: &FoldedStacks, options: &SvgOptions) -> String {
+    let root = build_tree(stacks);
+    let depth = root.depth() + 1;
+    let image_height = TOP_PAD + BOTTOM_PAD + depth.max(1) as f64 * FRAME_HEIGHT;
+
+    let mut layout = Layout {
+        options,
+        total: root.value,
+        image_height,
+        out: String::new(),
+    };
+
+    let _ = writeln!(layout.out, r#"<?xml version="1.0" standalone="no"?>"#);
+    let _ = writeln!(
+        layout.out,
+        r#"<svg version="1.1" width="{w}" height="{h}" viewBox="0 0 {w} {h}" xmlns="http://www.w3.org/2000/svg" data-total="{total}" data-pad="{pad}" data-font="{font}" onload="init(evt)">"#,
+        w = IMAGE_WIDTH,
+        h = image_height,
+        total = root.value,
+        pad = X_PAD,
+        font = FONT_SIZE,
+    );
+    let _ = writeln!(
+        layout.out,
+        "<style>text {{ font-family: Verdana, sans-serif; font-size: {}px; fill: rgb(0,0,0); }} \
+         g.func:hover rect {{ stroke: rgb(0,0,0); stroke-width: 0.5; cursor: pointer; }} \
+         g.parent rect {{ opacity: 0.5; }} \
+         #title {{ font-size: 17px; text-anchor: middle; }} \
+         .button {{ cursor: pointer; fill: rgb(60,60,60); }} \
+         .hidden {{ display: none; }}</style>",
+        FONT_SIZE
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<rect x="0" y="0" width="{}" height="{}" fill="rgb(250,250,240)"/>"#,
+        IMAGE_WIDTH, image_height
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<text id="title" x="{:.1}" y="{:.1}">{}</text>"#,
+        IMAGE_WIDTH / 2.0,
+        FONT_SIZE * 2.0,
+        xml_escape(&options.title)
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<text id="unzoom" class="button hidden" x="{:.1}" y="{:.1}">Reset Zoom</text>"#,
+        X_PAD,
+        FONT_SIZE * 2.0
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<text id="search" class="button" x="{:.1}" y="{:.1}" text-anchor="end">Search</text>"#,
+        IMAGE_WIDTH - X_PAD,
+        FONT_SIZE * 2.0
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<text id="matched" x="{:.1}" y="{:.1}" text-anchor="end"></text>"#,
+        IMAGE_WIDTH - X_PAD,
+        image_height - FONT_SIZE
+    );
+    let _ = writeln!(
+        layout.out,
+        r#"<text id="details" x="{:.1}" y="{:.1}"> </text>"#,
+        X_PAD,
+        image_height - FONT_SIZE
+    );
+
+    if root.value == 0 {
+        let _ = writeln!(
+            layout.out,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">No stacks recorded</text>"#,
+            IMAGE_WIDTH / 2.0,
+            image_height / 2.0
+        );
+    } else {
+        let _ = writeln!(layout.out, r#"<g id="frames">"#);
+        layout.emit("all", &root, 0, 0);
+        let _ = writeln!(layout.out, "</g>");
+    }
+
+    let _ = writeln!(layout.out, "<script><![CDATA[{}]]></script>", SVG_SCRIPT);
+    let _ = writeln!(layout.out, "</svg>");
+    layout.out
+}
+
+/// Interactivity for the generated SVG (zoom, search, details)
+const SVG_SCRIPT: &str = r#"
+var svg, frames, details, matched, unzoomBtn, total, pad, fontSize, width;
+function init(evt) {
+  svg = document.documentElement;
+  frames = Array.prototype.slice.call(document.querySelectorAll("g.func"));
+  details = document.getElementById("details");
+  matched = document.getElementById("matched");
+  unzoomBtn = document.getElementById("unzoom");
+  total = +svg.getAttribute("data-total");
+  pad = +svg.getAttribute("data-pad");
+  fontSize = +svg.getAttribute("data-font");
+  width = +svg.getAttribute("width");
+  frames.forEach(function (g) {
+    g.addEventListener("click", function () { zoom(g); });
+    g.addEventListener("mouseover", function () { details.textContent = title(g); });
+    g.addEventListener("mouseout", function () { details.textContent = " "; });
+  });
+  unzoomBtn.addEventListener("click", function () { layout(0, total, 0); unzoomBtn.classList.add("hidden"); });
+  document.getElementById("search").addEventListener("click", search);
+  window.addEventListener("keydown", function (e) {
+    if ((e.ctrlKey || e.metaKey) && e.key === "f") { e.preventDefault(); search(); }
+    if (e.key === "Escape") { clearSearch(); }
+  });
+}
+function title(g) { return g.querySelector("title").textContent; }
+function frameName(g) { return title(g).replace(/ \([^()]*\)$/, ""); }
+function fit(g, w) {
+  var name = frameName(g), chars = Math.floor(w / (fontSize * 0.59)), t = g.querySelector("text");
+  if (chars < 3) { t.textContent = ""; return; }
+  t.textContent = name.length <= chars ? name : name.substring(0, chars - 2) + "..";
+}
+function layout(zx, zw, zd) {
+  var scale = (width - 2 * pad) / zw;
+  frames.forEach(function (g) {
+    var x = +g.getAttribute("data-x"), w = +g.getAttribute("data-w"), d = +g.getAttribute("data-d");
+    var show, nx, nw;
+    if (d < zd) { show = x <= zx && x + w >= zx + zw; nx = 0; nw = zw; }
+    else { show = x >= zx && x + w <= zx + zw; nx = x - zx; nw = w; }
+    g.classList.toggle("parent", show && d < zd);
+    g.style.display = show ? "" : "none";
+    if (!show) { return; }
+    var px = pad + nx * scale, pw = nw * scale, r = g.querySelector("rect");
+    r.setAttribute("x", px.toFixed(1));
+    r.setAttribute("width", pw.toFixed(1));
+    g.querySelector("text").setAttribute("x", (px + 3).toFixed(1));
+    fit(g, pw - 3);
+  });
+}
+function zoom(g) {
+  layout(+g.getAttribute("data-x"), +g.getAttribute("data-w"), +g.getAttribute("data-d"));
+  unzoomBtn.classList.remove("hidden");
+}
+function clearSearch() {
+  frames.forEach(function (g) {
+    var r = g.querySelector("rect");
+    if (r.hasAttribute("data-fill")) { r.setAttribute("fill", r.getAttribute("data-fill")); r.removeAttribute("data-fill"); }
+  });
+  matched.textContent = "";
+}
+function search() {
+  var term = prompt("Search frames (regular expression):", "");
+  if (term === null) { return; }
+  clearSearch();
+  if (term === "") { return; }
+  var re;
+  try { re = new RegExp(term); } catch (err) { matched.textContent = "Invalid regex"; return; }
+  var spans = [];
+  frames.forEach(function (g) {
+    if (!re.test(frameName(g))) { return; }
+    var r = g.querySelector("rect");
+    r.setAttribute("data-fill", r.getAttribute("fill"));
+    r.setAttribute("fill", "rgb(230,0,230)");
+    spans.push([+g.getAttribute("data-x"), +g.getAttribute("data-w")]);
+  });
+  spans.sort(function (a, b) { return a[0] - b[0] || b[1] - a[1]; });
+  var covered = 0, end = -1;
+  spans.forEach(function (s) {
+    if (s[0] >= end) { covered += s[1]; end = s[0] + s[1]; }
+    else if (s[0] + s[1] > end) { covered += s[0] + s[1] - end; end = s[0] + s[1]; }
+  });
+  matched.textContent = "Matched: " + (100 * covered / total).toFixed(1) + "%";
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(function: Option<&str>, module: Option<&str>) -> ResolvedFrame {
+        ResolvedFrame {
+            address: 0x1000,
+            function: function.map(|s| s.to_string()),
+            file: None,
+            line: None,
+            module: module.map(|s| s.to_string()),
+            inlined: false,
+        }
+    }
+
+    fn strings(frames: &[&str]) -> Vec<String> {
+        frames.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn config(off_cpu: bool, weight: FlamegraphWeight) -> FlamegraphConfig {
+        FlamegraphConfig {
+            svg_path: None,
+            folded_path: None,
+            weight,
+            style: FlamegraphStyle::Flame,
+            off_cpu,
+        }
+    }
+
+    #[test]
+    fn test_folded_stacks_aggregate() {
+        let mut stacks = FoldedStacks::new();
+        stacks.add(&strings(&["main", "run", "write"]), 1);
+        stacks.add(&strings(&["main", "run", "write"]), 2);
+        stacks.add(&strings(&["main", "read"]), 5);
+
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(stacks.total(), 8);
+
+        let mut out = Vec::new();
+        stacks.write_folded(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, "main;read 5\nmain;run;write 3\n");
+    }
+
+    #[test]
+    fn test_folded_stacks_ignore_zero_weight() {
+        let mut stacks = FoldedStacks::new();
+        stacks.add(&strings(&["main", "write"]), 0);
+        stacks.add(&[], 3);
+        assert!(stacks.is_empty());
+    }
+
+    #[test]
+    fn test_folded_stacks_sanitize_semicolons() {
+        let mut stacks = FoldedStacks::new();
+        stacks.add(&strings(&["core::array::<[u8; 4]>::map", "write"]), 1);
+
+        let mut out = Vec::new();
+        stacks.write_folded(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "core::array::<[u8: 4]>::map;write 1\n"
+        );
+    }
+
+    #[test]
+    fn test_folded_stacks_roundtrip() {
+        let text = "main;<T as Trait>::call;write 7\nmain;read 2\n\nbad line\n";
+        let stacks = FoldedStacks::from_folded(text);
+        assert_eq!(stacks.len(), 2);
+        assert_eq!(stacks.total(), 9);
+
+        let mut out = Vec::new();
+        stacks.write_folded(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "main;<T as Trait>::call;write 7\nmain;read 2\n"
+        );
+    }
+
+    #[test]
+    fn test_stack_labels_root_first_with_syscall_leaf() {
+        // Innermost first, as produced by the unwinder
+        let frames = vec![
+            frame(None, Some("libc.so.6")),
+            frame(None, Some("libc.so.6")),
+            frame(Some("app::write_output"), Some("app")),
+            frame(Some("app::main"), Some("app")),
+        ];
+        let labels = stack_labels(&frames, "write");
+        assert_eq!(
+            labels,
+            strings(&["app::main", "app::write_output", "[libc.so.6]", "write"])
+        );
+    }
+
+    #[test]
+    fn test_collector_count_weight() {
+        let mut collector = FlamegraphCollector::new(config(false, FlamegraphWeight::Count));
+        let frames = vec![frame(Some("main"), None)];
+        collector.record("write", &frames, 500);
+        collector.record("write", &frames, 10);
+        collector.record("mmap", &frames, 10);
+
+        assert_eq!(collector.stacks().total(), 3);
+        assert_eq!(collector.stacks().len(), 2);
+    }
+
+    #[test]
+    fn test_collector_time_weight() {
+        let mut collector = FlamegraphCollector::new(config(false, FlamegraphWeight::Time));
+        let frames = vec![frame(Some("main"), None)];
+        collector.record("write", &frames, 500);
+        collector.record("getpid", &frames, 0);
+
+        // Zero-duration syscalls still contribute one microsecond
+        assert_eq!(collector.stacks().total(), 501);
+    }
+
+    #[test]
+    fn test_collector_off_cpu_filters_non_blocking() {
+        let mut collector = FlamegraphCollector::new(config(true, FlamegraphWeight::Count));
+        let frames = vec![frame(Some("main"), None)];
+        collector.record("futex", &frames, 2000);
+        collector.record("mmap", &frames, 30);
+
+        // Off-CPU mode forces time weighting and drops CPU-bound syscalls
+        assert_eq!(collector.stacks().total(), 2000);
+        assert_eq!(collector.stacks().len(), 1);
+    }
+
+    #[test]
+    fn test_config_from_cli() {
+        assert!(FlamegraphConfig::from_cli(
+            None,
+            None,
+            FlamegraphWeight::Count,
+            FlamegraphStyle::Flame,
+            false
+        )
+        .is_none());
+
+        let config = FlamegraphConfig::from_cli(
+            Some("out.svg"),
+            None,
+            FlamegraphWeight::Count,
+            FlamegraphStyle::Icicle,
+            true,
+        )
+        .unwrap();
+        assert_eq!(config.svg_path, Some(PathBuf::from("out.svg")));
+        assert_eq!(config.effective_weight(), FlamegraphWeight::Time);
+    }
+
+    #[test]
+    fn test_render_svg_contains_frames() {
+        let stacks = FoldedStacks::from_folded("main;run;write 3\nmain;read 1\n");
+        let svg = render_svg(
+            &stacks,
+            &SvgOptions {
+                title: "Syscall Flame Graph".to_string(),
+                unit: "syscalls".to_string(),
+                style: FlamegraphStyle::Flame,
+                palette: Palette::Hot,
+            },
+        );
+
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains("<title>all (4 syscalls, 100.00%)</title>"));
+        assert!(svg.contains("<title>write (3 syscalls, 75.00%)</title>"));
+        assert!(svg.contains("<title>read (1 syscalls, 25.00%)</title>"));
+        assert!(svg.contains("function zoom"));
+        assert_eq!(svg.matches("class=\"func\"").count(), 5);
+    }
+
+    #[test]
+    fn test_render_svg_escapes_names() {
+        let stacks = FoldedStacks::from_folded("<T as Trait>::f&g;write 1\n");
+        let svg = render_svg(
+            &stacks,
+            &SvgOptions {
+                title: "t".to_string(),
+                unit: "syscalls".to_string(),
+                style: FlamegraphStyle::Flame,
+                palette: Palette::Hot,
+            },
+        );
+        assert!(svg.contains("&lt;T as Trait&gt;::f&amp;g"));
+        assert!(!svg.contains("<T as Trait>"));
+    }
+
+    #[test]
+    fn test_render_svg_icicle_puts_root_on_top() {
+        let stacks = FoldedStacks::from_folded("main;write 1\n");
+        let render = |style| {
+            render_svg(
+                &stacks,
+                &SvgOptions {
+                    title: "t".to_string(),
+                    unit: "syscalls".to_string(),
+                    style,
+                    palette: Palette::OffCpu,
+                },
+            )
+        };
+
+        let root_y = |svg: &str| -> f64 {
+            let start = svg.find("data-d=\"0\"").unwrap();
+            let rect = &svg[start..];
+            let y = rect.find(" y=\"").unwrap() + 4;
+            let end = rect[y..].find('"').unwrap();
+            rect[y..y + end].parse().unwrap()
+        };
+
+        let flame = render(FlamegraphStyle::Flame);
+        let icicle = render(FlamegraphStyle::Icicle);
+        assert!(root_y(&icicle) < root_y(&flame));
+    }
+
+    #[test]
+    fn test_render_svg_empty() {
+        let svg = render_svg(
+            &FoldedStacks::new(),
+            &SvgOptions {
+                title: "t".to_string(),
+                unit: "syscalls".to_string(),
+                style: FlamegraphStyle::Flame,
+                palette: Palette::Hot,
+            },
+        );
+        assert!(svg.contains("No stacks recorded"));
+        assert!(!svg.contains("class=\"func\""));
+    }
+
+    #[test]
+    fn test_fit_label() {
+        assert_eq!(fit_label("main", 100.0), "main");
+        assert_eq!(fit_label("a_very_long_function_name", 60.0), "a_very..");
+        assert_eq!(fit_label("main", 10.0), "");
+    }
+
+    #[test]
+    fn test_frame_color_deterministic() {
+        assert_eq!(
+            frame_color("main", false, Palette::Hot),
+            frame_color("main", false, Palette::Hot)
+        );
+        assert_ne!(
+            frame_color("main", false, Palette::Hot),
+            frame_color("main", false, Palette::OffCpu)
+        );
+    }
+
+    #[test]
+    fn test_collector_write_outputs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let svg_path = dir.path().join("out.svg");
+        let folded_path = dir.path().join("out.folded");
+
+        let mut collector = FlamegraphCollector::new(FlamegraphConfig {
+            svg_path: Some(svg_path.clone()),
+            folded_path: Some(folded_path.clone()),
+            weight: FlamegraphWeight::Count,
+            style: FlamegraphStyle::Icicle,
+            off_cpu: false,
+        });
+        collector.record("write", &[frame(Some("main"), None)], 1);
+        collector.write_outputs().unwrap();
+
+        let folded = std::fs::read_to_string(folded_path).unwrap();
+        assert_eq!(folded, "main;write 1\n");
+        let svg = std::fs::read_to_string(svg_path).unwrap();
+        assert!(svg.contains("Syscall Icicle Graph"));
+        assert_eq!(collector.output_paths().len(), 2);
+    }
+}
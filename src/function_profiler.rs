@@ -37,6 +37,13 @@ const IO_SYSCALLS: &[&str] = &[
 /// Threshold for slow I/O operations (1ms = 1000 microseconds)
 const SLOW_IO_THRESHOLD_US: u64 = 1000;
 
+/// Check whether a syscall counts as slow I/O (I/O syscall taking >1ms)
+///
+/// Sprint 51: Shared with the pprof exporter's slow-I/O sample type.
+pub fn is_slow_io(syscall_name: &str, duration_us: u64) -> bool {
+    IO_SYSCALLS.contains(&syscall_name) && duration_us > SLOW_IO_THRESHOLD_US
+}
+
 /// Statistics for a single function
 #[derive(Debug, Clone, Default)]
 pub struct FunctionStats {
@@ -91,7 +98,7 @@ impl FunctionProfiler {
             entry.io_syscalls += 1;
 
             // Track slow I/O operations (>1ms)
-            if duration_us > SLOW_IO_THRESHOLD_US {
+            if is_slow_io(syscall_name, duration_us) {
                 entry.slow_io_count += 1;
             }
         }
@@ -275,6 +282,13 @@ impl FunctionProfiler {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_slow_io() {
+        assert!(is_slow_io("read", 1500));
+        assert!(!is_slow_io("read", 1000));
+        assert!(!is_slow_io("futex", 50_000)); // Not an I/O syscall
+    }
+
     #[test]
     fn test_function_profiler_new() {
         let profiler = FunctionProfiler::new();
@@ -0,0 +1,438 @@
+// Build hermeticity checking (Sprint 62)
+//
+// `renacer hermetic --allow-list inputs.toml -- cargo build` traces the build
+// with its whole process tree and checks every file it read or wrote, and
+// every socket it opened, against the declared inputs, toolchain and outputs.
+// The rules themselves are a `HermeticAssertion` evaluated by the
+// `AssertionEngine`; this module collects the accesses and resolves the
+// allow-list paths.
+
+use crate::assertion_types::{
+    AssertionResult, HermeticAssertion, HermeticViolation, HermeticViolationKind,
+};
+use crate::file_audit::FileAudit;
+use crate::trace_file::SyscallRecord;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Pseudo-files that are never build inputs or outputs
+const PSEUDO_PATHS: &[&str] = &[
+    "/dev/null",
+    "/dev/zero",
+    "/dev/full",
+    "/dev/random",
+    "/dev/urandom",
+    "/dev/tty",
+    "/dev/pts",
+    "/proc",
+    "/sys",
+];
+
+/// Load an allow-list file (the fields of a hermetic assertion, at top level)
+pub fn load_allow_list(path: impl AsRef<Path>) -> Result<HermeticAssertion> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read allow-list {}", path.display()))?;
+    toml::from_str(&content)
+        .with_context(|| format!("Failed to parse allow-list {}", path.display()))
+}
+
+/// A socket outside AF_UNIX
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkAccess {
+    pub pid: i32,
+    /// `socket` or `connect`
+    pub syscall: String,
+    pub family: i32,
+}
+
+/// File and network activity of a traced build
+#[derive(Debug, Default)]
+pub struct BuildAccesses {
+    files: FileAudit,
+    network: Vec<NetworkAccess>,
+    /// Socket family of each open (pid, fd)
+    sockets: HashMap<(i32, i32), i32>,
+}
+
+impl BuildAccesses {
+    /// Collect the accesses of one recorded run
+    ///
+    /// `program` is the traced command, whose own exec precedes the recording.
+    pub fn from_run(records: &[SyscallRecord], program: Option<&str>) -> Self {
+        let mut accesses = Self::default();
+        if let (Some(first), Some(program)) = (records.first(), program) {
+            accesses.files.set_exe(first.pid, program);
+        }
+        for record in records {
+            accesses.record(record);
+        }
+        accesses
+    }
+
+    /// Record a completed syscall
+    pub fn record(&mut self, record: &SyscallRecord) {
+        // Recorded runs carry no /proc state: cwd and fds come from the trace
+        self.files.record(
+            record.pid,
+            &record.name,
+            &record.args,
+            record.raw_args,
+            record.result,
+            |_, _| None,
+        );
+
+        let fd = record.raw_args[0] as i32;
+        match record.name.as_str() {
+            "socket" if record.result >= 0 => {
+                let family = record.raw_args[0] as i32;
+                self.sockets
+                    .insert((record.pid, record.result as i32), family);
+                if family != libc::AF_UNIX {
+                    self.push_network(record.pid, "socket", family);
+                }
+            }
+            "connect" => {
+                if let Some(&family) = self.sockets.get(&(record.pid, fd)) {
+                    if family != libc::AF_UNIX {
+                        self.push_network(record.pid, "connect", family);
+                    }
+                }
+            }
+            "close" => {
+                self.sockets.remove(&(record.pid, fd));
+            }
+            _ => {}
+        }
+    }
+
+    fn push_network(&mut self, pid: i32, syscall: &str, family: i32) {
+        self.network.push(NetworkAccess {
+            pid,
+            syscall: syscall.to_string(),
+            family,
+        });
+    }
+
+    pub fn files(&self) -> &FileAudit {
+        &self.files
+    }
+
+    pub fn network(&self) -> &[NetworkAccess] {
+        &self.network
+    }
+}
+
+/// A [`HermeticAssertion`] with its paths resolved to absolute prefixes
+#[derive(Debug, Clone)]
+pub struct HermeticRules {
+    cwd: PathBuf,
+    inputs: Vec<PathBuf>,
+    toolchain: Vec<PathBuf>,
+    outputs: Vec<PathBuf>,
+    /// Environment variable and its directory
+    env_dirs: Vec<(String, PathBuf)>,
+    pub allow_network: bool,
+}
+
+impl HermeticRules {
+    /// Resolve an assertion for a build run in `cwd`
+    ///
+    /// Entries naming an unset variable are dropped.
+    pub fn new(
+        assertion: &HermeticAssertion,
+        cwd: &Path,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let resolve_all = |entries: &[String]| -> Vec<PathBuf> {
+            entries
+                .iter()
+                .filter_map(|entry| expand(entry, &env))
+                .map(|path| absolute(cwd, &path))
+                .collect()
+        };
+        let env_dirs = assertion
+            .env_vars
+            .iter()
+            .filter_map(|var| {
+                let dir = env(var).filter(|dir| !dir.is_empty())?;
+                let dir = absolute(cwd, &dir);
+                // A variable pointing at the root says nothing about the environment
+                (dir != Path::new("/")).then(|| (var.clone(), dir))
+            })
+            .collect();
+
+        Self {
+            cwd: cwd.to_path_buf(),
+            inputs: resolve_all(&assertion.inputs),
+            toolchain: resolve_all(&assertion.toolchain),
+            outputs: resolve_all(&assertion.outputs),
+            env_dirs,
+            allow_network: assertion.allow_network,
+        }
+    }
+
+    /// Check a file the build read or executed
+    pub fn check_input(&self, pid: i32, path: &str) -> Option<HermeticViolation> {
+        let path = absolute(&self.cwd, path);
+        // Directories above declared paths are opened while walking to them
+        let declared = self
+            .declared()
+            .any(|prefix| path.starts_with(prefix) || prefix.starts_with(&path));
+        if declared || is_pseudo(&path) {
+            return None;
+        }
+
+        let detail = path.display().to_string();
+        let violation = match self.env_dirs.iter().find(|(_, dir)| path.starts_with(dir)) {
+            Some((var, _)) => HermeticViolation {
+                kind: HermeticViolationKind::EnvironmentInput,
+                pid,
+                detail: format!("{} (under ${})", detail, var),
+            },
+            None => HermeticViolation {
+                kind: HermeticViolationKind::UndeclaredInput,
+                pid,
+                detail,
+            },
+        };
+        Some(violation)
+    }
+
+    /// Check a file the build wrote, created or deleted
+    pub fn check_output(&self, pid: i32, path: &str) -> Option<HermeticViolation> {
+        let path = absolute(&self.cwd, path);
+        if is_pseudo(&path) || self.outputs.iter().any(|prefix| path.starts_with(prefix)) {
+            return None;
+        }
+        Some(HermeticViolation {
+            kind: HermeticViolationKind::UndeclaredOutput,
+            pid,
+            detail: path.display().to_string(),
+        })
+    }
+
+    fn declared(&self) -> impl Iterator<Item = &PathBuf> {
+        self.inputs
+            .iter()
+            .chain(&self.toolchain)
+            .chain(&self.outputs)
+    }
+}
+
+/// Text report of a hermeticity check
+pub fn format_report(
+    accesses: &BuildAccesses,
+    violations: &[HermeticViolation],
+    result: &AssertionResult,
+) -> String {
+    let (mut read, mut written) = (0, 0);
+    for process in accesses.files().processes() {
+        read += process.reads.len() + process.executed.len();
+        written += process.writes.len() + process.creates.len() + process.deletes.len();
+    }
+
+    let mut out = String::from("\n=== Hermeticity Check ===\n");
+    out.push_str(&format!(
+        "Processes: {}, files read: {}, files written: {}, sockets: {}\n",
+        accesses.files().processes().count(),
+        read,
+        written,
+        accesses.network().len()
+    ));
+    for violation in violations {
+        out.push_str(&format!(
+            "  ✗ {:<18} {} (pid {})\n",
+            violation.kind, violation.detail, violation.pid
+        ));
+    }
+    let status = if result.passed { "PASS" } else { "FAIL" };
+    out.push_str(&format!("{}: {}\n", status, result.message));
+    out
+}
+
+fn is_pseudo(path: &Path) -> bool {
+    PSEUDO_PATHS.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Expand a leading `~` or `$VAR`
+fn expand(entry: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if entry == "~" || entry.starts_with("~/") {
+        return Some(format!("{}{}", env("HOME")?, &entry[1..]));
+    }
+    if let Some(rest) = entry.strip_prefix('$') {
+        let end = rest.find('/').unwrap_or(rest.len());
+        return Some(format!("{}{}", env(&rest[..end])?, &rest[end..]));
+    }
+    Some(entry.to_string())
+}
+
+/// Absolute, lexically normalized form of `path`
+fn absolute(cwd: &Path, path: &str) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in cwd.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::family_name;
+
+    fn record(
+        pid: i32,
+        name: &str,
+        args: &[&str],
+        raw_args: [u64; 3],
+        result: i64,
+    ) -> SyscallRecord {
+        SyscallRecord {
+            pid,
+            timestamp_us: 0,
+            duration_us: 0,
+            number: 0,
+            name: name.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            raw_args,
+            result,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: Vec::new(),
+            wait_object: None,
+            stderr: None,
+        }
+    }
+
+    fn env(var: &str) -> Option<String> {
+        match var {
+            "HOME" => Some("/home/dev".to_string()),
+            "TOOLS" => Some("/opt/tools".to_string()),
+            _ => None,
+        }
+    }
+
+    fn rules() -> HermeticRules {
+        let assertion = HermeticAssertion {
+            inputs: vec!["src".to_string(), "Cargo.toml".to_string()],
+            toolchain: vec![
+                "/usr".to_string(),
+                "~/.rustup".to_string(),
+                "$TOOLS/bin".to_string(),
+                "$UNSET/lib".to_string(),
+            ],
+            outputs: vec!["target".to_string()],
+            ..HermeticAssertion::default()
+        };
+        HermeticRules::new(&assertion, Path::new("/home/dev/project"), env)
+    }
+
+    #[test]
+    fn test_declared_inputs_pass() {
+        let rules = rules();
+        for path in [
+            "src/main.rs",
+            "/home/dev/project/Cargo.toml",
+            "../project/src/lib.rs",
+            "/usr/lib/libc.so.6",
+            "/home/dev/.rustup/toolchains/stable/bin/rustc",
+            "/opt/tools/bin/cc",
+            "target/debug/build.rs",
+            "/dev/null",
+            "/proc/self/maps",
+            "/home/dev",
+        ] {
+            assert_eq!(rules.check_input(1, path), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_undeclared_and_environment_inputs() {
+        let rules = rules();
+        let passwd = rules.check_input(7, "/etc/passwd").unwrap();
+        assert_eq!(passwd.kind, HermeticViolationKind::UndeclaredInput);
+        assert_eq!(passwd.pid, 7);
+        assert_eq!(passwd.detail, "/etc/passwd");
+
+        let gitconfig = rules.check_input(7, "../.gitconfig").unwrap();
+        assert_eq!(gitconfig.kind, HermeticViolationKind::EnvironmentInput);
+        assert_eq!(gitconfig.detail, "/home/dev/.gitconfig (under $HOME)");
+
+        // Cargo.toml is declared, Cargo.toml.orig is not
+        assert!(rules.check_input(7, "Cargo.toml.orig").is_some());
+    }
+
+    #[test]
+    fn test_outputs_only_under_declared_dirs() {
+        let rules = rules();
+        assert_eq!(rules.check_output(1, "target/debug/app"), None);
+        assert_eq!(rules.check_output(1, "/dev/null"), None);
+
+        let stray = rules.check_output(1, "/tmp/cache").unwrap();
+        assert_eq!(stray.kind, HermeticViolationKind::UndeclaredOutput);
+        // Inputs are not writable
+        assert!(rules.check_output(1, "src/generated.rs").is_some());
+    }
+
+    #[test]
+    fn test_build_accesses_track_files_and_sockets() {
+        let unix = libc::AF_UNIX as u64;
+        let inet = libc::AF_INET as u64;
+        let accesses = BuildAccesses::from_run(
+            &[
+                record(
+                    10,
+                    "openat",
+                    &["AT_FDCWD", "\"src/main.rs\"", "O_RDONLY"],
+                    [0, 0, 0],
+                    3,
+                ),
+                record(10, "socket", &[], [unix, 1, 0], 4),
+                record(10, "connect", &[], [4, 0, 0], 0),
+                record(10, "socket", &[], [inet, 1, 0], 5),
+                record(10, "connect", &[], [5, 0, 0], 0),
+            ],
+            Some("cargo"),
+        );
+
+        let process = accesses.files().processes().next().unwrap();
+        assert_eq!(process.exe.as_deref(), Some("cargo"));
+        assert!(process.reads.contains("src/main.rs"));
+
+        let network: Vec<_> = accesses
+            .network()
+            .iter()
+            .map(|n| (n.syscall.as_str(), family_name(n.family)))
+            .collect();
+        assert_eq!(
+            network,
+            vec![
+                ("socket", "AF_INET".to_string()),
+                ("connect", "AF_INET".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_allow_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("inputs.toml");
+        std::fs::write(&path, "inputs = [\"src\"]\nallow_network = true\n").unwrap();
+        let assertion = load_allow_list(&path).unwrap();
+        assert_eq!(assertion.inputs, vec!["src"]);
+        assert!(assertion.allow_network);
+        assert_eq!(assertion.env_vars, vec!["HOME"]);
+
+        std::fs::write(&path, "inputs = \"src\"\n").unwrap();
+        assert!(load_allow_list(&path).is_err());
+    }
+}
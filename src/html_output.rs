@@ -2,7 +2,14 @@
 //!
 //! Sprint 22: Rich visual reports with styled tables and embedded CSS
 
+use crate::network::Connection;
 use crate::stats::StatsTracker;
+use crate::time_attribution::HotspotReport;
+
+/// Stacked bar colors, cycled over clusters
+const CLUSTER_COLORS: [&str; 6] = [
+    "#4a90d9", "#5cb85c", "#f0ad4e", "#9b59b6", "#1abc9c", "#95a5a6",
+];
 
 /// HTML record for a single syscall event
 #[derive(Debug, Clone)]
@@ -20,6 +27,10 @@ pub struct HtmlOutput {
     syscalls: Vec<HtmlSyscall>,
     include_timing: bool,
     include_source: bool,
+    connections: Vec<Connection>, // Sprint 63
+    /// End of the trace, for the lifetime of sockets still open
+    trace_end_us: u64,
+    time_attribution: Option<HotspotReport>, // Sprint 64
 }
 
 impl HtmlOutput {
@@ -29,9 +40,23 @@ impl HtmlOutput {
             syscalls: Vec::new(),
             include_timing,
             include_source,
+            connections: Vec::new(),
+            trace_end_us: 0,
+            time_attribution: None,
         }
     }
 
+    /// Add the network connection table to the report (Sprint 63)
+    pub fn set_connections(&mut self, connections: Vec<Connection>, trace_end_us: u64) {
+        self.connections = connections;
+        self.trace_end_us = trace_end_us;
+    }
+
+    /// Add the per-cluster time attribution to the report (Sprint 64)
+    pub fn set_time_attribution(&mut self, report: HotspotReport) {
+        self.time_attribution = Some(report);
+    }
+
     /// Add a syscall to the output
     pub fn add_syscall(&mut self, syscall: HtmlSyscall) {
         self.syscalls.push(syscall);
@@ -110,6 +135,28 @@ impl HtmlOutput {
         .stats-table th {
             background-color: #5cb85c;
         }
+        .cluster-bar {
+            display: flex;
+            height: 28px;
+            margin-bottom: 20px;
+            background-color: white;
+            box-shadow: 0 1px 3px rgba(0,0,0,0.1);
+        }
+        .cluster-segment {
+            color: white;
+            font-size: 0.8em;
+            line-height: 28px;
+            overflow: hidden;
+            white-space: nowrap;
+            text-align: center;
+        }
+        .cluster-segment.unexpected {
+            background-color: #cc0000 !important;
+        }
+        .hotspot-unexpected {
+            color: #cc0000;
+            font-weight: bold;
+        }
         .footer {
             margin-top: 20px;
             font-size: 0.8em;
@@ -221,6 +268,16 @@ impl HtmlOutput {
             html.push_str(&self.render_statistics(tracker));
         }
 
+        // Time attribution section (if --hotspots)
+        if let Some(report) = &self.time_attribution {
+            html.push_str(&Self::render_time_attribution(report));
+        }
+
+        // Network connections section (if any sockets were traced)
+        if !self.connections.is_empty() {
+            html.push_str(&self.render_connections());
+        }
+
         // Footer
         html.push_str("    <div class=\"footer\">\n");
         html.push_str("        Generated by Renacer - System Call Tracer\n");
@@ -277,6 +334,98 @@ impl HtmlOutput {
 
         html
     }
+
+    /// Render time per cluster as a stacked bar and table (Sprint 64)
+    fn render_time_attribution(report: &HotspotReport) -> String {
+        let mut html = String::new();
+
+        html.push_str("    <h2>Time Attribution</h2>\n");
+        html.push_str(&format!(
+            "    <p>Wall-clock {:.6}s, {:.6}s in syscalls (clusters: {})</p>\n",
+            report.wall_time.as_secs_f64(),
+            report.syscall_time.as_secs_f64(),
+            Self::escape_html(&report.source)
+        ));
+
+        let unexpected: Vec<&str> = report.unexpected().map(|h| h.cluster.as_str()).collect();
+        html.push_str("    <div class=\"cluster-bar\">\n");
+        for (i, attribution) in report.attributions.iter().enumerate() {
+            let class = if unexpected.contains(&attribution.cluster.as_str()) {
+                "cluster-segment unexpected"
+            } else {
+                "cluster-segment"
+            };
+            let name = Self::escape_html(&attribution.cluster);
+            html.push_str(&format!(
+                "        <div class=\"{}\" style=\"width: {:.2}%; background-color: {}\" title=\"{}: {:.2}%\">{}</div>\n",
+                class,
+                attribution.percentage,
+                CLUSTER_COLORS[i % CLUSTER_COLORS.len()],
+                name,
+                attribution.percentage,
+                name
+            ));
+        }
+        html.push_str("    </div>\n");
+
+        html.push_str("    <table class=\"stats-table\">\n");
+        html.push_str("        <tr><th>% of syscall time</th><th>seconds</th><th>usecs/call</th><th>calls</th><th>cluster</th><th>hotspot</th></tr>\n");
+        for attribution in &report.attributions {
+            let hotspot = report
+                .hotspots
+                .iter()
+                .find(|h| h.cluster == attribution.cluster);
+            let hotspot = match hotspot {
+                Some(h) if !h.is_expected => format!(
+                    "<span class=\"hotspot-unexpected\">unexpected</span>: {}",
+                    Self::escape_html(&h.explanation)
+                ),
+                Some(h) => Self::escape_html(&h.explanation),
+                None => String::new(),
+            };
+            html.push_str(&format!(
+                "        <tr><td>{:.2}</td><td>{:.6}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                attribution.percentage,
+                attribution.total_time.as_secs_f64(),
+                attribution.avg_per_call.as_micros(),
+                attribution.call_count,
+                Self::escape_html(&attribution.cluster),
+                hotspot
+            ));
+        }
+        html.push_str("    </table>\n");
+
+        html
+    }
+
+    /// Render the connection table as HTML (Sprint 63)
+    fn render_connections(&self) -> String {
+        let mut html = String::new();
+
+        html.push_str("    <h2>Network Connections</h2>\n");
+        html.push_str("    <table class=\"stats-table\">\n");
+        html.push_str("        <tr><th>pid</th><th>fd</th><th>protocol</th><th>local</th><th>remote</th><th>bytes sent</th><th>bytes received</th><th>lifetime (s)</th></tr>\n");
+
+        for conn in &self.connections {
+            let lifetime = conn.lifetime_us(self.trace_end_us) as f64 / 1_000_000.0;
+            html.push_str(&format!(
+                "        <tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.6}{}</td></tr>\n",
+                conn.pid,
+                conn.fd,
+                Self::escape_html(&conn.protocol),
+                Self::escape_html(conn.local.as_deref().unwrap_or("-")),
+                Self::escape_html(conn.remote.as_deref().unwrap_or("-")),
+                conn.bytes_sent,
+                conn.bytes_received,
+                lifetime,
+                if conn.closed_us.is_none() { " (open)" } else { "" }
+            ));
+        }
+
+        html.push_str("    </table>\n");
+
+        html
+    }
 }
 
 #[cfg(test)]
@@ -419,4 +568,50 @@ mod tests {
         assert!(header.contains("Duration"));
         assert!(header.contains("Source"));
     }
+
+    #[test]
+    fn test_html_output_connections_section() {
+        let mut output = HtmlOutput::new(false, false);
+        assert!(!output.to_html(None).contains("Network Connections"));
+
+        let mut table = crate::network::ConnectionTable::new();
+        let args = [
+            "3".to_string(),
+            "{10.0.0.1:443}".to_string(),
+            "0x10".to_string(),
+        ];
+        table.record(9, "socket", &[], [libc::AF_INET as u64, 1, 0], 3, 0);
+        table.record(9, "connect", &args, [3, 0, 16], 0, 10);
+        table.record(9, "sendto", &[], [3, 0, 7], 7, 20);
+        output.set_connections(table.connections().to_vec(), 2_000_000);
+
+        let html = output.to_html(None);
+        assert!(html.contains("<h2>Network Connections</h2>"));
+        assert!(html.contains("<td>tcp</td><td>-</td><td>10.0.0.1:443</td><td>7</td><td>0</td>"));
+        assert!(html.contains("2.000000 (open)"));
+    }
+
+    #[test]
+    fn test_html_output_time_attribution_bar() {
+        let mut output = HtmlOutput::new(false, false);
+        assert!(!output.to_html(None).contains("Time Attribution"));
+
+        let registry = crate::cluster::ClusterRegistry::default_transpiler_clusters().unwrap();
+        let mut tracker =
+            crate::time_attribution::HotspotTracker::new(crate::time_attribution::HotspotConfig {
+                registry,
+                source: "built-in".to_string(),
+            });
+        tracker.record(1, "read", &[], [0; 3], 10, 0, 7_500, |_| None);
+        tracker.record(1, "connect", &[], [0; 3], 0, 8_000, 2_500, |_| None);
+        output.set_time_attribution(tracker.finish(0));
+
+        let html = output.to_html(None);
+        assert!(html.contains("<h2>Time Attribution</h2>"));
+        assert!(html.contains(
+            "<div class=\"cluster-segment\" style=\"width: 75.00%; background-color: #4a90d9\" title=\"FileIO: 75.00%\">FileIO</div>"
+        ));
+        assert!(html.contains("<div class=\"cluster-segment unexpected\" style=\"width: 25.00%"));
+        assert!(html.contains("<span class=\"hotspot-unexpected\">unexpected</span>"));
+    }
 }
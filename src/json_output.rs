@@ -123,6 +123,43 @@ pub struct JsonAutoencoderAnomaly {
     pub feature_contributions: Option<Vec<JsonFeatureImportance>>,
 }
 
+/// Time attribution per syscall cluster (Sprint 64)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonHotspotAnalysis {
+    /// Where the cluster pack was loaded from
+    pub clusters_source: String,
+    /// From the first traced syscall to the end of the last
+    pub wall_time_us: u64,
+    /// Time spent inside syscalls
+    pub syscall_time_us: u64,
+    /// Clusters by total time, descending
+    pub clusters: Vec<JsonClusterTime>,
+    /// Clusters above 5% of syscall time
+    pub hotspots: Vec<JsonHotspot>,
+}
+
+/// Time spent in one cluster
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonClusterTime {
+    pub cluster: String,
+    /// Percentage of syscall time
+    pub percentage: f64,
+    pub time_us: u64,
+    pub calls: usize,
+    pub avg_us: u64,
+}
+
+/// A cluster taking more than 5% of syscall time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonHotspot {
+    pub cluster: String,
+    pub percentage: f64,
+    pub time_us: u64,
+    /// Whether the cluster is expected for this kind of program
+    pub expected: bool,
+    pub explanation: String,
+}
+
 /// Summary statistics for the trace
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonSummary {
@@ -155,6 +192,9 @@ pub struct JsonOutput {
     /// Autoencoder anomaly detection (if --dl-anomaly enabled) (Sprint 23)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub autoencoder_analysis: Option<JsonAutoencoderAnalysis>,
+    /// Time attribution and hotspots (if --hotspots enabled) (Sprint 64)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotspots: Option<JsonHotspotAnalysis>,
 }
 
 impl JsonOutput {
@@ -172,6 +212,7 @@ impl JsonOutput {
             ml_analysis: None,
             isolation_forest_analysis: None,
             autoencoder_analysis: None,
+            hotspots: None,
         }
     }
 
@@ -295,6 +336,40 @@ impl JsonOutput {
         });
     }
 
+    /// Set time attribution and hotspots (Sprint 64)
+    pub fn set_hotspot_analysis(&mut self, report: &crate::time_attribution::HotspotReport) {
+        let clusters = report
+            .attributions
+            .iter()
+            .map(|a| JsonClusterTime {
+                cluster: a.cluster.clone(),
+                percentage: a.percentage,
+                time_us: a.total_time.as_micros() as u64,
+                calls: a.call_count,
+                avg_us: a.avg_per_call.as_micros() as u64,
+            })
+            .collect();
+        let hotspots = report
+            .hotspots
+            .iter()
+            .map(|h| JsonHotspot {
+                cluster: h.cluster.clone(),
+                percentage: h.percentage,
+                time_us: h.time.as_micros() as u64,
+                expected: h.is_expected,
+                explanation: h.explanation.clone(),
+            })
+            .collect();
+
+        self.hotspots = Some(JsonHotspotAnalysis {
+            clusters_source: report.source.clone(),
+            wall_time_us: report.wall_time.as_micros() as u64,
+            syscall_time_us: report.syscall_time.as_micros() as u64,
+            clusters,
+            hotspots,
+        });
+    }
+
     /// Serialize to JSON string
     pub fn to_json(&self) -> anyhow::Result<String> {
         Ok(serde_json::to_string_pretty(self)?)
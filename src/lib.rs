@@ -11,10 +11,13 @@ pub mod anomaly;
 pub mod anti_patterns; // Sprint 41: Anti-pattern detection (God Process, Tight Loop, PCIe)
 pub mod assertion_dsl; // Sprint 44: renacer.toml parser for build-time assertions
 pub mod assertion_engine; // Sprint 44: Assertion evaluation engine (Toyota Way: Andon)
+pub mod assertion_expr; // Sprint 68: Expression language for custom assertions
 pub mod assertion_types; // Sprint 44: Build-time trace assertion types (Toyota Way: Andon)
 pub mod autoencoder;
+pub mod bench; // Sprint 57: Multi-run baselines for regression detection
 pub mod causal_graph; // Sprint 41: Causal graph construction for critical path analysis
 pub mod chaos;
+pub mod check; // Sprint 65: renacer check runs renacer.toml assertions against a traced run
 pub mod cli;
 pub mod cluster; // Single-Shot Compile Tooling: TOML-based syscall clustering (Section 6.1)
 pub mod critical_path; // Sprint 41: Critical path analysis (longest path in DAG)
@@ -25,32 +28,48 @@ pub mod decision_trace;
 pub mod depyler_ingest; // Sprint 49: Depyler decision trace ingestion (Ticket #18)
 pub mod dwarf;
 pub mod experiment_span; // REN-001: Experiment span types for entrenar integration
+pub mod file_audit; // Sprint 61: Files read, written, created and deleted per process
 pub mod filter;
+pub mod flamegraph; // Sprint 50: Folded stacks and interactive SVG flamegraphs
 pub mod function_profiler;
 pub mod gpu_tracer; // Sprint 37: GPU kernel-level tracing for wgpu
+pub mod hermetic; // Sprint 62: Build hermeticity checks over traced file and network access
 pub mod hpu;
 pub mod html_output;
 pub mod isolation_forest;
 pub mod json_output;
 pub mod lamport_clock; // Sprint 40: Lamport logical clocks for causal ordering (Toyota Way: Poka-Yoke)
 pub mod lazy_span; // Sprint 36: Lazy span creation for performance
+pub mod locks; // Sprint 73: Lock contention from futex waits grouped by address
+pub mod memory; // Sprint 67: Address-space growth and RSS timelines for memory_usage assertions
 pub mod ml_anomaly;
 pub mod ml_pipeline; // Sprint 48: Enhanced ML pipeline (StandardScaler, DBSCAN, LOF, PCA)
 pub mod model_persistence; // Sprint 48: .apr model persistence (Toyota Way: Muda elimination)
+pub mod network; // Sprint 63: Socket argument decoding and per-process connection table
+pub mod offcpu; // Sprint 52: Off-CPU (blocking time) analysis
 pub mod otlp_exporter;
+pub mod pprof; // Sprint 51: pprof profile.proto export
+pub mod proc_maps; // Sprint 50: /proc/PID/maps parsing for symbol resolution
+pub mod process_tree; // Sprint 71: Process tree spans and critical path of multi-process runs
 pub mod profiling;
 pub mod regression; // Single-Shot Compile Tooling: Statistical regression detection (Section 6.4)
 pub mod ring_buffer; // Sprint 40: Lock-free ring buffer for span export (Toyota Way: Heijunka)
 pub mod rle_compression; // Sprint 41: Run-length encoding for tight loop compression (Toyota Way: Muda)
+pub mod seccomp; // Sprint 60: Seccomp allow-lists from recorded traces
 pub mod semantic_equivalence; // Sprint 40: Semantic Equivalence (Specification Section 6.3)
 pub mod sequence; // Single-Shot Compile Tooling: N-gram sequence mining (Section 6.1.1)
+pub mod sourcemap_v3; // Sprint 54: Source Map v3 (VLQ) decoding
 pub mod span_pool; // Sprint 36: Memory pool for span allocations
 pub mod span_record; // Sprint 40: Parquet-compatible span schema
 pub mod stack_unwind;
 pub mod stats;
+pub mod syscall_anti_patterns; // Sprint 74: Everyday I/O mistakes with recommendations and call sites
 pub mod syscalls;
+pub mod testing; // Sprint 70: Cargo test harness for renacer.toml assertions
 pub mod time_attribution; // Single-Shot Compile Tooling: Time-weighted attribution (Section 6.2)
 pub mod trace_context; // Sprint 33: W3C Trace Context propagation
+pub mod trace_diff; // Sprint 56: Cluster, sequence and regression comparison of traces
+pub mod trace_file; // Sprint 55: Binary trace recording for record/replay
 pub mod tracer;
 pub mod transpiler_map;
 pub mod trueno_db_storage; // Sprint 40: Trueno-DB Parquet storage for golden thread traces
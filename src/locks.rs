@@ -0,0 +1,726 @@
+//! Lock contention analysis from futex syscalls
+//!
+//! Sprint 73: Uncontended pthread mutexes, condition variables and Rust
+//! `Mutex`es never enter the kernel, so every `futex` wait is a thread that
+//! had to sleep on a lock word. Waits are grouped by futex word, which like
+//! the kernel's futex keys is the process and address (forked processes
+//! reuse the same addresses for different locks) unless the word is a shared
+//! futex in `MAP_SHARED` memory. Each lock is named after the static symbol
+//! holding the word (`counter_lock`) when the ELF symbol table has one, or
+//! after its mapping (`[heap]`, `libc.so.6`).
+//!
+//! Each contended lock gets a wait-time histogram, the threads that waited
+//! on it and the threads that woke them, and the call stacks that were
+//! trying to acquire it.
+
+use crate::dwarf::DwarfContext;
+use crate::flamegraph::stack_labels;
+use crate::offcpu::{format_us, BlockerStats};
+use crate::proc_maps::{find_mapping, MemoryMapping};
+use crate::stack_unwind::ResolvedFrame;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Default number of locks (and stacks per lock) reported
+pub const DEFAULT_TOP_LOCKS: usize = 10;
+
+// futex(2) op flags
+const FUTEX_CMD_MASK: u64 = 0x7f;
+const FUTEX_PRIVATE_FLAG: u64 = 128;
+const FUTEX_CLOCK_REALTIME: u64 = 256;
+
+const EAGAIN: i64 = 11;
+const ETIMEDOUT: i64 = 110;
+
+/// Width of the histogram bars
+const BAR_WIDTH: u64 = 40;
+
+/// A `futex(2)` operation, without the private and clock flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FutexOp {
+    Wait,
+    Wake,
+    Fd,
+    Requeue,
+    CmpRequeue,
+    WakeOp,
+    LockPi,
+    UnlockPi,
+    TrylockPi,
+    WaitBitset,
+    WakeBitset,
+    WaitRequeuePi,
+    CmpRequeuePi,
+    LockPi2,
+}
+
+impl FutexOp {
+    /// Decode the `futex_op` argument
+    pub fn from_raw(op: u64) -> Option<Self> {
+        let op = match op & FUTEX_CMD_MASK {
+            0 => FutexOp::Wait,
+            1 => FutexOp::Wake,
+            2 => FutexOp::Fd,
+            3 => FutexOp::Requeue,
+            4 => FutexOp::CmpRequeue,
+            5 => FutexOp::WakeOp,
+            6 => FutexOp::LockPi,
+            7 => FutexOp::UnlockPi,
+            8 => FutexOp::TrylockPi,
+            9 => FutexOp::WaitBitset,
+            10 => FutexOp::WakeBitset,
+            11 => FutexOp::WaitRequeuePi,
+            12 => FutexOp::CmpRequeuePi,
+            13 => FutexOp::LockPi2,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Constant name, as in `<linux/futex.h>`
+    pub fn name(self) -> &'static str {
+        match self {
+            FutexOp::Wait => "FUTEX_WAIT",
+            FutexOp::Wake => "FUTEX_WAKE",
+            FutexOp::Fd => "FUTEX_FD",
+            FutexOp::Requeue => "FUTEX_REQUEUE",
+            FutexOp::CmpRequeue => "FUTEX_CMP_REQUEUE",
+            FutexOp::WakeOp => "FUTEX_WAKE_OP",
+            FutexOp::LockPi => "FUTEX_LOCK_PI",
+            FutexOp::UnlockPi => "FUTEX_UNLOCK_PI",
+            FutexOp::TrylockPi => "FUTEX_TRYLOCK_PI",
+            FutexOp::WaitBitset => "FUTEX_WAIT_BITSET",
+            FutexOp::WakeBitset => "FUTEX_WAKE_BITSET",
+            FutexOp::WaitRequeuePi => "FUTEX_WAIT_REQUEUE_PI",
+            FutexOp::CmpRequeuePi => "FUTEX_CMP_REQUEUE_PI",
+            FutexOp::LockPi2 => "FUTEX_LOCK_PI2",
+        }
+    }
+
+    /// Whether the caller sleeps until the word is woken (or acquired)
+    pub fn is_wait(self) -> bool {
+        matches!(
+            self,
+            FutexOp::Wait
+                | FutexOp::WaitBitset
+                | FutexOp::LockPi
+                | FutexOp::LockPi2
+                | FutexOp::WaitRequeuePi
+        )
+    }
+
+    /// Whether the call wakes threads sleeping on the word
+    pub fn is_wake(self) -> bool {
+        matches!(
+            self,
+            FutexOp::Wake
+                | FutexOp::WakeBitset
+                | FutexOp::WakeOp
+                | FutexOp::Requeue
+                | FutexOp::CmpRequeue
+                | FutexOp::CmpRequeuePi
+                | FutexOp::UnlockPi
+        )
+    }
+}
+
+/// Format a `futex_op` argument: `FUTEX_WAIT_BITSET_PRIVATE|FUTEX_CLOCK_REALTIME`
+pub fn format_futex_op(op: u64) -> String {
+    let Some(cmd) = FutexOp::from_raw(op) else {
+        return format!("{:#x}", op);
+    };
+    let mut name = cmd.name().to_string();
+    if op & FUTEX_PRIVATE_FLAG != 0 {
+        name.push_str("_PRIVATE");
+    }
+    if op & FUTEX_CLOCK_REALTIME != 0 {
+        name.push_str("|FUTEX_CLOCK_REALTIME");
+    }
+    name
+}
+
+/// Decoded `futex(uaddr, futex_op, val)` arguments
+pub fn format_futex_args(raw_args: [u64; 6]) -> Vec<String> {
+    vec![
+        format!("{:#x}", raw_args[0]),
+        format_futex_op(raw_args[1]),
+        (raw_args[2] as i32).to_string(),
+    ]
+}
+
+/// Name of the lock at a runtime address
+///
+/// A word inside a sized data symbol of the traced binary is named after
+/// it (`pool+0x40` for a lock inside a struct); otherwise the mapping it
+/// lives in names it (`[heap]`, `libc.so.6`). Anonymous memory (thread
+/// stacks, allocator arenas) has no name.
+pub fn lock_symbol(
+    addr: u64,
+    dwarf: Option<&DwarfContext>,
+    maps: &[MemoryMapping],
+) -> Option<String> {
+    let symbol = dwarf
+        .and_then(|ctx| ctx.symbol_at(addr))
+        .filter(|(symbol, _)| !symbol.is_function && symbol.size > 0);
+    if let Some((symbol, offset)) = symbol {
+        return Some(if offset == 0 {
+            symbol.name.clone()
+        } else {
+            format!("{}+{:#x}", symbol.name, offset)
+        });
+    }
+    find_mapping(maps, addr)
+        .and_then(MemoryMapping::module_name)
+        .map(str::to_string)
+}
+
+/// Lock contention configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockConfig {
+    /// Number of locks, and acquiring stacks per lock, reported
+    pub top: usize,
+}
+
+impl LockConfig {
+    /// Build a config from CLI arguments; `None` when not requested
+    pub fn from_cli(enabled: bool, top: usize) -> Option<Self> {
+        enabled.then_some(Self { top })
+    }
+}
+
+/// Wait times in power-of-two microsecond buckets
+///
+/// Bucket 0 holds sub-microsecond waits; bucket `i` holds
+/// `[2^(i-1), 2^i)` microseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaitHistogram {
+    buckets: Vec<u64>,
+}
+
+impl WaitHistogram {
+    /// Count one wait
+    pub fn add(&mut self, duration_us: u64) {
+        let bucket = (u64::BITS - duration_us.leading_zeros()) as usize;
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, 0);
+        }
+        self.buckets[bucket] += 1;
+    }
+
+    /// Non-empty buckets as `(low_us, high_us, count)`, shortest first
+    pub fn buckets(&self) -> Vec<(u64, u64, u64)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(bucket, &count)| {
+                let low = if bucket == 0 { 0 } else { 1 << (bucket - 1) };
+                (low, 1 << bucket, count)
+            })
+            .collect()
+    }
+
+    /// Text histogram, one row per bucket from the shortest to the longest wait
+    fn render(&self, indent: &str) -> String {
+        let Some(first) = self.buckets.iter().position(|&count| count > 0) else {
+            return String::new();
+        };
+        let last = self
+            .buckets
+            .iter()
+            .rposition(|&count| count > 0)
+            .unwrap_or(first);
+        let max = self.buckets.iter().copied().max().unwrap_or(1);
+
+        let mut out = String::new();
+        for bucket in first..=last {
+            let count = self.buckets[bucket];
+            let low = if bucket == 0 { 0 } else { 1 << (bucket - 1) };
+            let range = format!("[{}, {})", format_us(low), format_us(1 << bucket));
+            let bar = "@".repeat(count.saturating_mul(BAR_WIDTH).div_ceil(max) as usize);
+            out.push_str(&format!("{}{:>22} {:>8} |{}\n", indent, range, count, bar));
+        }
+        out
+    }
+}
+
+/// A futex word, as the kernel tells them apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LockKey {
+    /// Process (thread group) whose address space holds the word; `None`
+    /// for a word that several processes can wait on
+    pub tgid: Option<i32>,
+    pub addr: u64,
+}
+
+impl LockKey {
+    /// The word a `futex(addr, futex_op)` call from process `tgid` is on
+    ///
+    /// Only a shared futex (no `FUTEX_PRIVATE_FLAG`) in shared memory is the
+    /// same word in every process; `pthread_join` and other shared futexes
+    /// in private memory are still per process.
+    pub fn new(tgid: i32, addr: u64, futex_op: u64, shared_memory: bool) -> Self {
+        let shared = futex_op & FUTEX_PRIVATE_FLAG == 0 && shared_memory;
+        Self {
+            tgid: (!shared).then_some(tgid),
+            addr,
+        }
+    }
+}
+
+impl fmt::Display for LockKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tgid {
+            Some(tgid) => write!(f, "{:#x}, pid {}", self.addr, tgid),
+            None => write!(f, "{:#x}, shared", self.addr),
+        }
+    }
+}
+
+/// Contention on one futex word
+#[derive(Debug, Clone, Default)]
+pub struct LockStats {
+    /// All waits on the word
+    pub waits: BlockerStats,
+    /// Distribution of wait times
+    pub histogram: WaitHistogram,
+    /// Waits that timed out (timed condition waits and lock attempts)
+    pub timeouts: u64,
+    /// Waits that returned at once because the word had changed (`EAGAIN`)
+    pub retries: u64,
+    /// Waits per waiting thread
+    pub waiters: BTreeMap<i32, BlockerStats>,
+    /// Threads woken, per waking thread
+    pub wakers: BTreeMap<i32, u64>,
+    /// Waits per root-first acquiring stack (leaf = futex op)
+    pub stacks: HashMap<Vec<String>, BlockerStats>,
+}
+
+/// Collects futex waits and wakes per futex word during tracing
+#[derive(Debug)]
+pub struct LockAnalyzer {
+    config: LockConfig,
+    locks: HashMap<LockKey, LockStats>,
+    /// Lock names, resolved once per word in the process that first used it
+    names: HashMap<LockKey, Option<String>>,
+    /// Thread group of each thread seen
+    thread_groups: HashMap<i32, i32>,
+}
+
+impl LockAnalyzer {
+    /// Create an analyzer for the given configuration
+    pub fn new(config: LockConfig) -> Self {
+        Self {
+            config,
+            locks: HashMap::new(),
+            names: HashMap::new(),
+            thread_groups: HashMap::new(),
+        }
+    }
+
+    /// Thread group of `tid`, looked up with `lookup` the first time
+    ///
+    /// Threads that cannot be looked up are taken to be processes of their own.
+    pub fn thread_group(&mut self, tid: i32, lookup: impl FnOnce(i32) -> Option<i32>) -> i32 {
+        *self
+            .thread_groups
+            .entry(tid)
+            .or_insert_with(|| lookup(tid).unwrap_or(tid))
+    }
+
+    /// Put `thread` in the thread group of `parent`, which cloned it
+    ///
+    /// Replayed traces have no /proc to look thread groups up in.
+    pub fn thread_spawned(&mut self, parent: i32, thread: i32) {
+        let group = self.thread_group(parent, |_| None);
+        self.thread_groups.insert(thread, group);
+    }
+
+    /// Name the lock `key` with `resolve` unless it was already named
+    ///
+    /// Called at syscall entry, while the tracee's symbols and maps are at hand.
+    pub fn resolve(&mut self, key: LockKey, resolve: impl FnOnce(u64) -> Option<String>) {
+        self.names.entry(key).or_insert_with(|| resolve(key.addr));
+    }
+
+    /// Name of the lock `key`, if resolved
+    pub fn name(&self, key: LockKey) -> Option<&str> {
+        self.names.get(&key)?.as_deref()
+    }
+
+    /// Record one completed `futex(key.addr, futex_op)` call by thread `tid`
+    ///
+    /// `frames` is the call stack at entry.
+    pub fn record(
+        &mut self,
+        tid: i32,
+        key: LockKey,
+        futex_op: u64,
+        result: i64,
+        frames: &[ResolvedFrame],
+        duration_us: u64,
+    ) {
+        let Some(op) = FutexOp::from_raw(futex_op) else {
+            return;
+        };
+
+        if op.is_wait() {
+            let lock = self.locks.entry(key).or_default();
+            lock.waits.add(duration_us);
+            lock.histogram.add(duration_us);
+            match -result {
+                ETIMEDOUT => lock.timeouts += 1,
+                EAGAIN => lock.retries += 1,
+                _ => {}
+            }
+            lock.waiters.entry(tid).or_default().add(duration_us);
+            lock.stacks
+                .entry(stack_labels(frames, op.name()))
+                .or_default()
+                .add(duration_us);
+        } else if op.is_wake() {
+            // FUTEX_UNLOCK_PI returns 0 and hands the lock to the top waiter;
+            // the other wakes return how many threads they woke
+            let woken = match op {
+                FutexOp::UnlockPi if result == 0 => 1,
+                _ => result.max(0) as u64,
+            };
+            if woken > 0 {
+                *self
+                    .locks
+                    .entry(key)
+                    .or_default()
+                    .wakers
+                    .entry(tid)
+                    .or_default() += woken;
+            }
+        }
+    }
+
+    /// Locks that had waits, longest total wait first
+    pub fn contended(&self) -> Vec<(LockKey, &LockStats)> {
+        let mut locks: Vec<(LockKey, &LockStats)> = self
+            .locks
+            .iter()
+            .filter(|(_, lock)| lock.waits.count > 0)
+            .map(|(&key, lock)| (key, lock))
+            .collect();
+        locks.sort_by(|a, b| {
+            b.1.waits
+                .total_us
+                .cmp(&a.1.waits.total_us)
+                .then(a.0.cmp(&b.0))
+        });
+        locks
+    }
+
+    /// Format the contended locks report
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        out.push_str("\n=== Lock Contention (futex waits by address) ===\n");
+
+        let locks = self.contended();
+        if locks.is_empty() {
+            out.push_str("\nNo contended locks recorded.\n");
+            return out;
+        }
+
+        for (key, lock) in locks.into_iter().take(self.config.top) {
+            let name = match self.name(key) {
+                Some(name) => format!("{} ({})", name, key),
+                None => format!("({})", key),
+            };
+            out.push_str(&format!(
+                "\nLock {}: {} waits, {} waited, max {}",
+                name,
+                lock.waits.count,
+                format_us(lock.waits.total_us),
+                format_us(lock.waits.max_us)
+            ));
+            if lock.timeouts > 0 {
+                out.push_str(&format!(", {} timed out", lock.timeouts));
+            }
+            if lock.retries > 0 {
+                out.push_str(&format!(", {} retried", lock.retries));
+            }
+            out.push('\n');
+
+            out.push_str(&format!("  {:>22} {:>8}\n", "wait time", "count"));
+            out.push_str(&lock.histogram.render("  "));
+
+            let waiters: Vec<String> = lock
+                .waiters
+                .iter()
+                .map(|(tid, stats)| {
+                    format!(
+                        "{} ({} waits, {})",
+                        tid,
+                        stats.count,
+                        format_us(stats.total_us)
+                    )
+                })
+                .collect();
+            out.push_str(&format!("  Waiters: {}\n", waiters.join(", ")));
+            if lock.wakers.is_empty() {
+                out.push_str("  Wakers: none traced\n");
+            } else {
+                let wakers: Vec<String> = lock
+                    .wakers
+                    .iter()
+                    .map(|(tid, woken)| format!("{} (woke {})", tid, woken))
+                    .collect();
+                out.push_str(&format!("  Wakers: {}\n", wakers.join(", ")));
+            }
+
+            out.push_str("  Acquiring stacks:\n");
+            let mut stacks: Vec<_> = lock.stacks.iter().collect();
+            stacks.sort_by(|a, b| b.1.total_us.cmp(&a.1.total_us).then(a.0.cmp(b.0)));
+            for (stack, stats) in stacks.into_iter().take(self.config.top) {
+                out.push_str(&format!(
+                    "    {:>12} {:>8} calls  {}\n",
+                    format_us(stats.total_us),
+                    stats.count,
+                    stack.join(" → ")
+                ));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WAIT_PRIVATE: u64 = 128;
+    const WAKE_PRIVATE: u64 = 129;
+
+    fn frame(function: &str) -> ResolvedFrame {
+        ResolvedFrame {
+            address: 0x1000,
+            function: Some(function.to_string()),
+            file: None,
+            line: None,
+            module: None,
+            inlined: false,
+        }
+    }
+
+    /// Futex word in private memory
+    fn key(tgid: i32, addr: u64, futex_op: u64) -> LockKey {
+        LockKey::new(tgid, addr, futex_op, false)
+    }
+
+    fn analyzer() -> LockAnalyzer {
+        LockAnalyzer::new(LockConfig {
+            top: DEFAULT_TOP_LOCKS,
+        })
+    }
+
+    #[test]
+    fn test_decode_futex_ops() {
+        assert_eq!(format_futex_op(WAIT_PRIVATE), "FUTEX_WAIT_PRIVATE");
+        assert_eq!(format_futex_op(1), "FUTEX_WAKE");
+        assert_eq!(
+            format_futex_op(9 | FUTEX_PRIVATE_FLAG | FUTEX_CLOCK_REALTIME),
+            "FUTEX_WAIT_BITSET_PRIVATE|FUTEX_CLOCK_REALTIME"
+        );
+        assert_eq!(format_futex_op(6), "FUTEX_LOCK_PI");
+        assert_eq!(format_futex_op(0x7f), "0x7f");
+        assert_eq!(
+            format_futex_args([0x5000, WAKE_PRIVATE, 0x7fffffff, 0, 0, 0]),
+            vec!["0x5000", "FUTEX_WAKE_PRIVATE", "2147483647"]
+        );
+
+        assert!(FutexOp::from_raw(WAIT_PRIVATE).unwrap().is_wait());
+        assert!(FutexOp::from_raw(4).unwrap().is_wake());
+        let trylock = FutexOp::from_raw(8).unwrap();
+        assert!(!trylock.is_wait() && !trylock.is_wake());
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let mut histogram = WaitHistogram::default();
+        for us in [0, 1, 3, 3, 1000] {
+            histogram.add(us);
+        }
+        assert_eq!(
+            histogram.buckets(),
+            vec![(0, 1, 1), (1, 2, 1), (2, 4, 2), (512, 1024, 1)]
+        );
+
+        let text = histogram.render("");
+        assert_eq!(text.lines().count(), 11, "empty buckets between are shown");
+        assert!(text.contains("[2μs, 4μs)        2 |@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@"));
+        assert!(text.contains("[512μs, 1.024ms)        1 |@@@@@@@@@@@@@@@@@@@@\n"));
+    }
+
+    #[test]
+    fn test_waits_grouped_by_address() {
+        let mut analyzer = analyzer();
+        let stack = [frame("pthread_mutex_lock"), frame("worker")];
+        analyzer.record(
+            101,
+            key(100, 0x5000, WAIT_PRIVATE),
+            WAIT_PRIVATE,
+            0,
+            &stack,
+            300,
+        );
+        analyzer.record(
+            102,
+            key(100, 0x5000, WAIT_PRIVATE),
+            WAIT_PRIVATE,
+            0,
+            &stack,
+            500,
+        );
+        analyzer.record(
+            102,
+            key(100, 0x5000, WAIT_PRIVATE),
+            WAIT_PRIVATE,
+            -EAGAIN,
+            &stack,
+            1,
+        );
+        analyzer.record(100, key(100, 0x5000, WAKE_PRIVATE), WAKE_PRIVATE, 1, &[], 2);
+        analyzer.record(101, key(100, 0x5000, WAKE_PRIVATE), WAKE_PRIVATE, 1, &[], 2);
+        let timed_wait = 9 | WAIT_PRIVATE;
+        let timed_out = -ETIMEDOUT;
+        analyzer.record(
+            100,
+            key(100, 0x6000, timed_wait),
+            timed_wait,
+            timed_out,
+            &[],
+            9_000,
+        );
+        // Wakes nobody waited for are not contention
+        analyzer.record(100, key(100, 0x7000, WAKE_PRIVATE), WAKE_PRIVATE, 0, &[], 1);
+
+        let locks = analyzer.contended();
+        let addrs: Vec<u64> = locks.iter().map(|(key, _)| key.addr).collect();
+        assert_eq!(addrs, vec![0x6000, 0x5000]);
+
+        let mutex = locks[1].1;
+        assert_eq!(mutex.waits.count, 3);
+        assert_eq!(mutex.waits.total_us, 801);
+        assert_eq!(mutex.retries, 1);
+        assert_eq!(mutex.waiters[&102].count, 2);
+        assert_eq!(mutex.wakers, BTreeMap::from([(100, 1), (101, 1)]));
+        let stacks: Vec<&Vec<String>> = mutex.stacks.keys().collect();
+        assert_eq!(
+            stacks,
+            vec![&vec![
+                "worker".to_string(),
+                "pthread_mutex_lock".to_string(),
+                "FUTEX_WAIT".to_string()
+            ]]
+        );
+        assert_eq!(locks[0].1.timeouts, 1);
+    }
+
+    #[test]
+    fn test_locks_are_per_process() {
+        let mut analyzer = analyzer();
+        // A forked child's mutex sits at the same address as its parent's
+        analyzer.record(10, key(10, 0x5000, WAIT_PRIVATE), WAIT_PRIVATE, 0, &[], 100);
+        analyzer.record(11, key(10, 0x5000, WAIT_PRIVATE), WAIT_PRIVATE, 0, &[], 100);
+        analyzer.record(20, key(20, 0x5000, WAIT_PRIVATE), WAIT_PRIVATE, 0, &[], 50);
+        analyzer.record(12, key(10, 0x5000, WAKE_PRIVATE), WAKE_PRIVATE, 1, &[], 1);
+        // So do the thread ids pthread_join waits on with shared futexes
+        analyzer.record(10, key(10, 0x7000, 0), 0, 0, &[], 20);
+        analyzer.record(20, key(20, 0x7000, 0), 0, 0, &[], 20);
+        // A process-shared mutex in MAP_SHARED memory is one lock for everybody
+        let shared = LockKey::new(10, 0x9000, 0, true);
+        assert_eq!(shared, LockKey::new(20, 0x9000, 0, true));
+        assert_eq!(LockKey::new(10, 0x9000, WAIT_PRIVATE, true).tgid, Some(10));
+        analyzer.record(10, shared, 0, 0, &[], 10);
+        analyzer.record(20, shared, 0, 0, &[], 10);
+
+        let locks = analyzer.contended();
+        let keys: Vec<String> = locks.iter().map(|(key, _)| key.to_string()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "0x5000, pid 10",
+                "0x5000, pid 20",
+                "0x9000, shared",
+                "0x7000, pid 10",
+                "0x7000, pid 20"
+            ]
+        );
+        assert_eq!(locks[0].1.waits.count, 2);
+        assert_eq!(locks[0].1.wakers, BTreeMap::from([(12, 1)]));
+        assert!(locks[1].1.wakers.is_empty());
+        assert_eq!(locks[2].1.waits.count, 2);
+    }
+
+    #[test]
+    fn test_thread_groups() {
+        let mut analyzer = analyzer();
+        assert_eq!(analyzer.thread_group(11, |_| Some(10)), 10);
+        assert_eq!(analyzer.thread_group(11, |_| panic!("looked up once")), 10);
+        assert_eq!(analyzer.thread_group(30, |_| None), 30);
+        analyzer.thread_spawned(11, 12);
+        analyzer.thread_spawned(30, 31);
+        assert_eq!(analyzer.thread_group(12, |_| None), 10);
+        assert_eq!(analyzer.thread_group(31, |_| None), 30);
+    }
+
+    #[test]
+    fn test_unlock_pi_hands_over_the_lock() {
+        let mut analyzer = analyzer();
+        analyzer.record(1, key(1, 0x10, 6), 6, 0, &[], 40);
+        analyzer.record(2, key(1, 0x10, 7), 7, 0, &[], 1);
+        assert_eq!(analyzer.contended()[0].1.wakers[&2], 1);
+    }
+
+    #[test]
+    fn test_lock_names() {
+        let maps = crate::proc_maps::parse_maps(
+            "7f0000000000-7f0000100000 rw-p 00000000 00:00 0 [heap]\n\
+             7f1000000000-7f1000001000 rw-p 00000000 00:00 0\n",
+        );
+        assert_eq!(
+            lock_symbol(0x7f0000000040, None, &maps),
+            Some("[heap]".to_string())
+        );
+        assert_eq!(lock_symbol(0x7f1000000040, None, &maps), None);
+        assert_eq!(lock_symbol(0x10, None, &maps), None);
+
+        let mut analyzer = analyzer();
+        let word = |tgid, addr| key(tgid, addr, WAIT_PRIVATE);
+        analyzer.resolve(word(1, 0x10), |_| Some("counter_lock".to_string()));
+        analyzer.resolve(word(1, 0x10), |_| panic!("resolved once"));
+        analyzer.resolve(word(2, 0x10), |_| Some("other_lock".to_string()));
+        analyzer.resolve(word(1, 0x20), |_| None);
+        assert_eq!(analyzer.name(word(1, 0x10)), Some("counter_lock"));
+        assert_eq!(analyzer.name(word(2, 0x10)), Some("other_lock"));
+        assert_eq!(analyzer.name(word(1, 0x20)), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut analyzer = analyzer();
+        assert!(analyzer.report().contains("No contended locks recorded"));
+
+        let lock = key(100, 0x5000, WAIT_PRIVATE);
+        analyzer.resolve(lock, |_| Some("counter_lock".to_string()));
+        analyzer.record(101, lock, WAIT_PRIVATE, 0, &[frame("worker")], 2_500);
+        analyzer.record(100, key(100, 0x5000, WAKE_PRIVATE), WAKE_PRIVATE, 1, &[], 3);
+        let report = analyzer.report();
+        assert!(report
+            .contains("Lock counter_lock (0x5000, pid 100): 1 waits, 2.500ms waited, max 2.500ms"));
+        assert!(report.contains("[2.048ms, 4.096ms)        1 |@@@@"));
+        assert!(report.contains("Waiters: 101 (1 waits, 2.500ms)"));
+        assert!(report.contains("Wakers: 100 (woke 1)"));
+        assert!(report.contains("2.500ms        1 calls  worker → FUTEX_WAIT"));
+    }
+
+    #[test]
+    fn test_config_from_cli() {
+        assert_eq!(LockConfig::from_cli(false, 10), None);
+        assert_eq!(LockConfig::from_cli(true, 3).unwrap().top, 3);
+    }
+}
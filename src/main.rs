@@ -1,8 +1,271 @@
-use anyhow::Result;
-use clap::Parser;
-use renacer::{chaos::ChaosConfig, cli::Cli, filter, tracer, transpiler_map};
+use anyhow::{Context, Result};
+use renacer::{
+    assertion_dsl::AssertionConfig,
+    assertion_engine::AssertionEngine,
+    bench,
+    chaos::ChaosConfig,
+    check::{CheckReport, EXIT_CHECK_ERROR},
+    cli::{Cli, Commands, OutputFormat, SeccompFormat},
+    cluster::{ClusterRegistry, ClusterSummaryConfig},
+    file_audit::FileAuditConfig,
+    filter,
+    flamegraph::FlamegraphConfig,
+    hermetic::{self, BuildAccesses, HermeticRules},
+    locks::LockConfig,
+    model_persistence::{self, PersistenceOptions},
+    offcpu::OffCpuConfig,
+    regression::RegressionConfig,
+    seccomp::SeccompProfile,
+    sequence::SequenceModel,
+    time_attribution::HotspotConfig,
+    trace_diff::{self, DiffConfig, TraceSet},
+    tracer, transpiler_map,
+};
 use tracing_subscriber::EnvFilter;
 
+/// Sprint 56: Compare two recorded trace sets, returning the process exit code
+fn run_diff(
+    baseline: &str,
+    current: &str,
+    clusters: Option<&str>,
+    ngram_size: usize,
+    significance_level: f64,
+    format: &OutputFormat,
+) -> Result<i32> {
+    let (registry, _) = ClusterRegistry::resolve(clusters)?;
+    let regression = RegressionConfig {
+        significance_level,
+        ..RegressionConfig::default()
+    };
+    regression.validate().map_err(anyhow::Error::msg)?;
+    let config = DiffConfig {
+        ngram_size,
+        regression,
+        ..DiffConfig::default()
+    };
+
+    let diff = trace_diff::compare(
+        &TraceSet::load(baseline)?,
+        &TraceSet::load(current)?,
+        &registry,
+        &config,
+    )?;
+    match format {
+        OutputFormat::Text => print!("{}", diff.to_text()),
+        OutputFormat::Json => println!("{}", diff.to_json()?),
+        other => anyhow::bail!(
+            "renacer diff supports text and json output, not {:?}",
+            other
+        ),
+    }
+    Ok(diff.exit_code())
+}
+
+/// Sprint 59: Train a sequence model from traced runs or recorded traces
+fn run_learn(
+    output: &str,
+    runs: usize,
+    traces: &[String],
+    window: usize,
+    command: &[String],
+    follow_forks: bool,
+) -> Result<()> {
+    if window == 0 {
+        anyhow::bail!("--window must be at least 1");
+    }
+    let sets = if traces.is_empty() {
+        if command.is_empty() {
+            anyhow::bail!(
+                "Must specify a command or --trace. Usage: renacer learn -o MODEL -- COMMAND [ARGS...]"
+            );
+        }
+        if runs == 0 {
+            anyhow::bail!("--runs must be at least 1");
+        }
+        vec![bench::record_runs(command, runs, 0, follow_forks, false)?]
+    } else {
+        traces
+            .iter()
+            .map(TraceSet::load)
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let traced = sets
+        .iter()
+        .flat_map(|set| set.commands.iter())
+        .find(|cmd| !cmd.is_empty())
+        .map(Vec::as_slice)
+        .unwrap_or(command);
+    let program = traced.first().map(|p| program_name(p)).unwrap_or_default();
+
+    let mut model = SequenceModel::new(program, window);
+    for set in &sets {
+        for threads in set.thread_sequences() {
+            model.train_run(&threads);
+        }
+    }
+    if model.is_empty() {
+        anyhow::bail!("No complete {}-syscall windows to learn from", window);
+    }
+
+    let options = PersistenceOptions::new()
+        .with_name(format!("{} syscall sequences", model.program))
+        .with_description("renacer learn");
+    model_persistence::save_sequence_model(&model, output, options)?;
+    eprintln!(
+        "[renacer: learned {} sequences of {} syscalls from {} runs of {}]",
+        model.len(),
+        model.window,
+        model.runs,
+        model.program
+    );
+    eprintln!("[renacer: sequence model saved to {}]", output);
+    Ok(())
+}
+
+/// Sprint 60: Write a seccomp allow-list for the syscalls in recorded traces
+fn run_seccomp_profile(
+    traces: &[String],
+    emit: SeccompFormat,
+    output: Option<&str>,
+    deny_error_only: bool,
+) -> Result<()> {
+    let sets = traces
+        .iter()
+        .map(TraceSet::load)
+        .collect::<Result<Vec<_>>>()?;
+    let profile = SeccompProfile {
+        deny_error_only,
+        ..SeccompProfile::from_traces(&sets)
+    };
+    if profile.syscalls.is_empty() {
+        anyhow::bail!("No syscalls in the given traces");
+    }
+
+    let rendered = profile.render(emit)?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, &rendered).with_context(|| format!("Failed to write {}", path))?;
+            eprintln!("[renacer: seccomp profile saved to {}]", path);
+        }
+        None => print!("{}", rendered),
+    }
+    eprint!("{}", profile.summary());
+    Ok(())
+}
+
+/// Sprint 62: Check a build's file and network accesses against an allow-list
+///
+/// Returns the process exit code: 1 when the build is not hermetic, whatever
+/// the build's own exit status.
+fn run_hermetic(allow_list: &str, trace: Option<&str>, command: &[String]) -> Result<i32> {
+    let assertion = hermetic::load_allow_list(allow_list)?;
+    let mut exit_code = None;
+    let set = match trace {
+        Some(path) => TraceSet::load(path)?,
+        None => {
+            if command.is_empty() {
+                anyhow::bail!(
+                    "Must specify a command or --trace. Usage: renacer hermetic --allow-list FILE -- COMMAND [ARGS...]"
+                );
+            }
+            // Builds spawn compilers and scripts, so the whole process tree is
+            // traced. The build's output is shown and a failed build still
+            // gets a report.
+            let (set, code) = bench::record_run(command, true, false, true)?;
+            exit_code = Some(code);
+            set
+        }
+    };
+    let records = set.runs.first().map(Vec::as_slice).unwrap_or_default();
+    let program = set.commands.first().and_then(|cmd| cmd.first());
+    let accesses = BuildAccesses::from_run(records, program.map(String::as_str));
+
+    let cwd = std::env::current_dir().context("Failed to read the working directory")?;
+    let rules = HermeticRules::new(&assertion, &cwd, |var| std::env::var(var).ok());
+    let engine = AssertionEngine::new();
+    let violations = engine.hermetic_violations(&rules, &accesses);
+    let result = engine.evaluate_hermetic("hermetic", &violations);
+    print!(
+        "{}",
+        hermetic::format_report(&accesses, &violations, &result)
+    );
+    if let Some(code) = exit_code {
+        println!("Build exited with status {}", code);
+    }
+    Ok(if result.passed { 0 } else { 1 })
+}
+
+/// Sprint 65: Evaluate renacer.toml assertions against a traced run
+///
+/// Returns the process exit code: 1 when an assertion fails. The traced
+/// command's own exit status is reported, not returned.
+fn run_check(
+    config_path: &str,
+    trace: Option<&str>,
+    junit: Option<&str>,
+    command: &[String],
+    follow_forks: bool,
+    stacks: bool,
+) -> Result<i32> {
+    let config = AssertionConfig::from_file(config_path)?;
+    let mut exit_code = None;
+    let set = match trace {
+        Some(path) => TraceSet::load(path)?,
+        None => {
+            if command.is_empty() {
+                anyhow::bail!(
+                    "Must specify a command or --trace. Usage: renacer check [--config renacer.toml] -- COMMAND [ARGS...]"
+                );
+            }
+            let (set, code) = bench::record_run(command, follow_forks, stacks, false)?;
+            exit_code = Some(code);
+            set
+        }
+    };
+    let records = set.runs.first().map(Vec::as_slice).unwrap_or_default();
+    let rss_samples = set.memory.first().map(Vec::as_slice).unwrap_or_default();
+    let command = set.commands.first().map(Vec::as_slice).unwrap_or_default();
+
+    let cwd = std::env::current_dir().context("Failed to read the working directory")?;
+    let report = CheckReport::evaluate(
+        config_path,
+        &config,
+        records,
+        rss_samples,
+        command,
+        &cwd,
+        |var| std::env::var(var).ok(),
+    );
+    print!("{}", report.to_text());
+    if let Some(code) = exit_code {
+        println!("Command exited with status {}", code);
+    }
+    if let Some(path) = junit {
+        std::fs::write(path, report.to_junit())
+            .with_context(|| format!("Failed to write {}", path))?;
+        eprintln!("[renacer: JUnit report written to {}]", path);
+    }
+    Ok(if report.has_failures() { 1 } else { 0 })
+}
+
+/// Exit code of `check` and `hermetic`, keeping errors apart from failed
+/// assertions
+fn check_exit_code(result: Result<i32>) -> i32 {
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {:?}", e);
+        EXIT_CHECK_ERROR
+    })
+}
+
+/// File name of a program path, used to match sequence models to commands
+fn program_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
 /// Initialize tracing subscriber for debug output
 fn init_tracing(debug: bool) {
     if debug {
@@ -51,19 +314,23 @@ fn print_stack_trace_mappings(map: &transpiler_map::TranspilerMap, show_context:
         println!();
     }
 
-    if !map.mappings.is_empty() {
+    if !map.ranges.is_empty() {
         if show_context {
             println!("Line Mappings (Rust -> {}):", map.source_language());
             println!("─────────────────────────────────────────");
         }
-        for mapping in &map.mappings {
+        // Sprint 53: v2 maps span several file pairs and column ranges
+        for mapping in &map.ranges {
+            let files = map.file_pair(mapping);
+            // Sprint 54: v3 maps only name the original identifier
+            let function = if mapping.rust_function.is_empty() {
+                &mapping.source_function
+            } else {
+                &mapping.rust_function
+            };
             println!(
                 "{} ({}:{}) -> {}:{}",
-                mapping.rust_function,
-                map.generated_file().display(),
-                mapping.rust_line,
-                map.source_file().display(),
-                mapping.python_line
+                function, files.generated_file, mapping.rust, files.source_file, mapping.source
             );
         }
         if show_context {
@@ -85,19 +352,20 @@ fn print_error_correlation_mappings(map: &transpiler_map::TranspilerMap, show_co
         println!();
     }
 
-    if !map.mappings.is_empty() {
+    if !map.ranges.is_empty() {
         if show_context {
-            println!("Available Line Mappings ({} entries):", map.mappings.len());
+            println!("Available Line Mappings ({} entries):", map.ranges.len());
             println!("─────────────────────────────────────────");
         }
-        for mapping in &map.mappings {
+        for mapping in &map.ranges {
+            let files = map.file_pair(mapping);
             println!(
                 "  {}:{} -> {}:{} ({})",
-                map.generated_file().display(),
-                mapping.rust_line,
-                map.source_file().display(),
-                mapping.python_line,
-                mapping.python_function
+                files.generated_file,
+                mapping.rust,
+                files.source_file,
+                mapping.source,
+                mapping.source_function
             );
         }
         if show_context {
@@ -130,9 +398,11 @@ fn run_tracer(
     Ok(())
 }
 
-fn main() -> Result<()> {
-    let args = Cli::parse();
-
+/// Build the tracer configuration from the tracing flags
+///
+/// `traced` is the command about to be traced, if any, for checking that a
+/// sequence model was learned from the same program.
+fn tracer_config(args: Cli, traced: Option<&[String]>) -> Result<tracer::TracerConfig> {
     // Validate ml_clusters range (must be >= 2)
     if args.ml_clusters < 2 {
         anyhow::bail!(
@@ -188,14 +458,81 @@ fn main() -> Result<()> {
         eprintln!("⚠️  Chaos mode enabled: {}", chaos.status_line());
     }
 
-    // Create tracer configuration
-    let config = tracer::TracerConfig {
+    // Sprint 50: Flamegraph output configuration
+    // Sprint 52: Off-CPU analysis
+    let off_cpu = OffCpuConfig::from_cli(
+        args.off_cpu,
+        args.off_cpu_folded.as_deref(),
+        args.off_cpu_top,
+    );
+
+    let flamegraph = FlamegraphConfig::from_cli(
+        args.flamegraph.as_deref(),
+        args.flamegraph_folded.as_deref(),
+        args.flamegraph_weight,
+        args.flamegraph_style,
+        args.flamegraph_off_cpu,
+    );
+
+    // Sprint 58: Per-cluster summary (-c --by-cluster)
+    let clusters = if args.by_cluster {
+        let (registry, source) = ClusterRegistry::resolve(args.clusters.as_deref())?;
+        let baseline = match &args.cluster_baseline {
+            Some(path) => Some(bench::Baseline::load(path)?.mean_counts()),
+            None => None,
+        };
+        Some(ClusterSummaryConfig {
+            registry,
+            source,
+            baseline,
+        })
+    } else {
+        None
+    };
+
+    // Sprint 59: Check live syscall windows against a learned model
+    let sequence_model = match &args.sequence_model {
+        Some(path) => {
+            let model = model_persistence::load_sequence_model(path)?;
+            eprintln!(
+                "[renacer: sequence model for {} ({} sequences of {} syscalls)]",
+                model.program,
+                model.len(),
+                model.window
+            );
+            if let Some(program) = traced.and_then(|cmd| cmd.first()) {
+                if program_name(program) != model.program {
+                    eprintln!(
+                        "[renacer: Warning: sequence model was learned from {}, not {}]",
+                        model.program,
+                        program_name(program)
+                    );
+                }
+            }
+            Some(model)
+        }
+        None => None,
+    };
+
+    // Sprint 64: Time attribution uses the same cluster pack as --by-cluster
+    let hotspots = if args.hotspots {
+        let (registry, source) = ClusterRegistry::resolve(args.clusters.as_deref())?;
+        Some(HotspotConfig { registry, source })
+    } else {
+        None
+    };
+
+    // Sprint 61: File access audit
+    let file_audit = FileAuditConfig::from_cli(args.file_audit, args.file_audit_json.as_deref());
+
+    Ok(tracer::TracerConfig {
         enable_source: args.source,
         filter,
         statistics_mode: args.statistics,
         timing_mode: args.timing,
         output_format: args.format,
-        follow_forks: args.follow_forks,
+        // Sprint 71/73: --critical-path and --locks imply -f
+        follow_forks: args.follow_forks || args.critical_path || args.locks,
         profile_self: args.profile_self,
         function_time: args.function_time,
         stats_extended: args.stats_extended,       // Sprint 19
@@ -221,10 +558,142 @@ fn main() -> Result<()> {
         otlp_service_name: args.otlp_service_name, // Sprint 30
         trace_parent: args.trace_parent,           // Sprint 33
         chaos_config,                              // Sprint 47
-    };
+        flamegraph,                                // Sprint 50
+        pprof: args.pprof.clone(),                 // Sprint 51
+        off_cpu,                                   // Sprint 52
+        record: None,                              // Sprint 55
+        record_stacks: false,                      // Sprint 55
+        clusters,                                  // Sprint 58
+        sequence_model,                            // Sprint 59
+        file_audit,                                // Sprint 61
+        connections: args.connections,             // Sprint 63
+        hotspots,                                  // Sprint 64
+        critical_path: args.critical_path,         // Sprint 71
+        locks: LockConfig::from_cli(args.locks, args.locks_top), // Sprint 73
+        anti_patterns: args.anti_patterns,         // Sprint 74
+    })
+}
 
-    // Either attach to PID or trace command (mutually exclusive)
-    run_tracer(args.pid, args.command, config)?;
+fn main() -> Result<()> {
+    let mut args = Cli::parse_args();
+    let pid = args.pid;
+
+    // Sprint 55-65: record/replay, diff, bench, learn, seccomp-profile, hermetic and check subcommands
+    match args.subcommand.take() {
+        Some(Commands::Record {
+            output,
+            stacks,
+            command,
+        }) => {
+            let config = tracer::TracerConfig {
+                record: Some(output),
+                record_stacks: stacks,
+                ..tracer_config(args, command.as_deref())?
+            };
+            run_tracer(pid, command, config)?;
+        }
+        Some(Commands::Replay { file }) => {
+            tracer::replay_trace(&file, tracer_config(args, None)?)?;
+        }
+        Some(Commands::Diff {
+            baseline,
+            current,
+            ngram,
+            significance,
+            clusters,
+            format,
+        }) => {
+            let code = run_diff(
+                &baseline,
+                &current,
+                clusters.as_deref(),
+                ngram,
+                significance,
+                &format,
+            )?;
+            std::process::exit(code);
+        }
+        Some(Commands::Bench {
+            runs,
+            warmup,
+            output,
+            compare_baseline,
+            significance,
+            follow_forks,
+            command,
+        }) => {
+            let options = bench::BenchOptions {
+                runs,
+                warmup,
+                output,
+                compare_baseline,
+                follow_forks,
+                regression: RegressionConfig {
+                    significance_level: significance,
+                    ..RegressionConfig::default()
+                },
+            };
+            let code = bench::run_bench(&command.unwrap_or_default(), &options)?;
+            std::process::exit(code);
+        }
+        Some(Commands::Learn {
+            output,
+            runs,
+            traces,
+            window,
+            follow_forks,
+            command,
+        }) => {
+            run_learn(
+                &output,
+                runs,
+                &traces,
+                window,
+                &command.unwrap_or_default(),
+                follow_forks,
+            )?;
+        }
+        Some(Commands::SeccompProfile {
+            traces,
+            emit,
+            output,
+            deny_error_only,
+        }) => {
+            run_seccomp_profile(&traces, emit, output.as_deref(), deny_error_only)?;
+        }
+        Some(Commands::Hermetic {
+            allow_list,
+            trace,
+            command,
+        }) => {
+            let code = run_hermetic(&allow_list, trace.as_deref(), &command.unwrap_or_default());
+            std::process::exit(check_exit_code(code));
+        }
+        Some(Commands::Check {
+            config,
+            trace,
+            junit,
+            follow_forks,
+            stacks,
+            command,
+        }) => {
+            let code = run_check(
+                &config,
+                trace.as_deref(),
+                junit.as_deref(),
+                &command.unwrap_or_default(),
+                follow_forks,
+                stacks,
+            );
+            std::process::exit(check_exit_code(code));
+        }
+        // Either attach to PID or trace command (mutually exclusive)
+        None => {
+            let command = args.command.take();
+            let config = tracer_config(args, command.as_deref())?;
+            run_tracer(pid, command, config)?;
+        }
+    }
 
     Ok(())
 }
@@ -0,0 +1,428 @@
+//! Memory timelines for `memory_usage` assertions (Sprint 67)
+//!
+//! Two ways to measure how much memory a traced run used:
+//!
+//! - **Address space**: replay the decoded `mmap`, `munmap`, `mremap` and
+//!   `brk` calls of each process into a set of mapped regions. Threads share
+//!   their process's regions, overlapping and `MAP_FIXED` mappings are only
+//!   counted once, and `execve` starts a fresh address space.
+//! - **RSS**: `renacer record` samples each process's resident set from
+//!   `/proc/<pid>/smaps_rollup` (or `VmRSS` in `/proc/<pid>/status`) at most
+//!   every [`RSS_SAMPLE_INTERVAL_US`].
+//!
+//! Either way the result is a [`MemoryTimeline`] of the total over all
+//! processes, with its peak.
+
+use crate::process_tree::shares_memory;
+use crate::unified_trace::UnifiedTrace;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Minimum time between two RSS samples of the same process
+pub const RSS_SAMPLE_INTERVAL_US: u64 = 10_000;
+
+const PAGE_SIZE: u64 = 4096;
+
+/// Sparkline levels, lowest first
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Resident set size of one process at one point of a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySample {
+    /// Microseconds since the start of the recording
+    pub timestamp_us: u64,
+    /// Process (thread group) the sample belongs to
+    pub pid: i32,
+    /// Resident bytes (0 once the process has exited)
+    pub bytes: u64,
+}
+
+/// Read the resident set of `pid`, returning its thread group and size in bytes
+pub fn read_rss(pid: i32) -> Option<(i32, u64)> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let tgid = status_field(&status, "Tgid:").map_or(pid, |tgid| tgid as i32);
+    let rss = std::fs::read_to_string(format!("/proc/{}/smaps_rollup", pid))
+        .ok()
+        .and_then(|rollup| status_field(&rollup, "Rss:"))
+        .or_else(|| status_field(&status, "VmRSS:"))?;
+    Some((tgid, rss * 1024))
+}
+
+/// Value of a `Name:   123 kB` line in a /proc file
+fn status_field(text: &str, name: &str) -> Option<u64> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(name))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|value| value.parse().ok())
+}
+
+/// Total memory over time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryTimeline {
+    /// (nanoseconds since the start of the run, bytes), in time order
+    pub points: Vec<(u64, u64)>,
+}
+
+impl MemoryTimeline {
+    /// Sum of the latest RSS sample of every process
+    pub fn from_rss_samples(samples: &[MemorySample]) -> Self {
+        let mut latest: HashMap<i32, u64> = HashMap::new();
+        let mut points = Vec::with_capacity(samples.len());
+        for sample in samples {
+            latest.insert(sample.pid, sample.bytes);
+            points.push((sample.timestamp_us * 1000, latest.values().sum()));
+        }
+        Self { points }
+    }
+
+    /// Address-space growth of all processes, from their memory syscalls
+    ///
+    /// Threads (children spawned with `CLONE_VM`) replay into their
+    /// spawner's address space rather than one of their own.
+    pub fn from_address_space(trace: &UnifiedTrace) -> Self {
+        let mut spaces: Vec<AddressSpace> = Vec::new();
+        // Thread → index into `spaces`
+        let mut space_of: HashMap<i32, usize> = HashMap::new();
+        let mut points = Vec::new();
+        for span in &trace.syscall_spans {
+            let pid = span.pid.unwrap_or(trace.process_span.pid);
+            let arg = |i: usize| span.args.get(i).and_then(|(_, value)| parse_number(value));
+            let result = span.return_value;
+            let index = *space_of.entry(pid).or_insert_with(|| {
+                spaces.push(AddressSpace::default());
+                spaces.len() - 1
+            });
+            let space = &mut spaces[index];
+            match &*span.name {
+                "clone" | "clone3" | "fork" | "vfork" if result > 0 => {
+                    if shares_memory(&span.name, arg(0).unwrap_or(0)) {
+                        space_of.insert(result as i32, index);
+                    }
+                    continue;
+                }
+                "mmap" if result >= 0 => {
+                    if let Some(length) = arg(1) {
+                        space.map(result as u64, length);
+                    }
+                }
+                "munmap" if result == 0 => {
+                    if let (Some(addr), Some(length)) = (arg(0), arg(1)) {
+                        space.unmap(addr, length);
+                    }
+                }
+                "mremap" if result >= 0 => {
+                    if let (Some(addr), Some(old), Some(new)) = (arg(0), arg(1), arg(2)) {
+                        space.unmap(addr, old);
+                        space.map(result as u64, new);
+                    }
+                }
+                "brk" if result > 0 => space.set_break(result as u64),
+                // The new program gets a fresh address space; a vfork
+                // child leaves its parent's one behind
+                "execve" if result == 0 => {
+                    if space_of.iter().any(|(&p, &i)| i == index && p != pid) {
+                        spaces.push(AddressSpace::default());
+                        space_of.insert(pid, spaces.len() - 1);
+                    } else {
+                        *space = AddressSpace::default();
+                    }
+                }
+                _ => continue,
+            }
+            points.push((span.timestamp_nanos, spaces.iter().map(|s| s.bytes()).sum()));
+        }
+        Self { points }
+    }
+
+    /// Highest total and when it was reached (nanoseconds)
+    pub fn peak(&self) -> Option<(u64, u64)> {
+        self.points
+            .iter()
+            .copied()
+            .reduce(|peak, point| if point.1 > peak.1 { point } else { peak })
+    }
+
+    /// The timeline as `width` sparkline characters, scaled to the peak
+    ///
+    /// Each character shows the highest total within its slice of time.
+    pub fn sparkline(&self, width: usize) -> String {
+        let (Some(&(start, _)), Some(&(end, _)), Some((_, peak))) =
+            (self.points.first(), self.points.last(), self.peak())
+        else {
+            return String::new();
+        };
+        if peak == 0 || width == 0 {
+            return String::new();
+        }
+
+        let span = (end - start).max(1);
+        let mut buckets: Vec<Option<u64>> = vec![None; width];
+        for &(timestamp, bytes) in &self.points {
+            let i = (((timestamp - start) as u128 * width as u128) / span as u128) as usize;
+            let bucket = &mut buckets[i.min(width - 1)];
+            *bucket = Some(bucket.map_or(bytes, |b| b.max(bytes)));
+        }
+
+        let mut level = 0;
+        buckets
+            .into_iter()
+            .map(|bucket| {
+                // Slices without samples keep the previous level
+                if let Some(bytes) = bucket {
+                    level = ((bytes as u128 * (SPARKS.len() - 1) as u128) / peak as u128) as usize;
+                }
+                SPARKS[level]
+            })
+            .collect()
+    }
+}
+
+/// Mapped regions and heap of one process
+#[derive(Debug, Default)]
+struct AddressSpace {
+    /// Non-overlapping regions, start → end
+    regions: BTreeMap<u64, u64>,
+    mapped: u64,
+    /// Initial and current program break
+    heap: Option<(u64, u64)>,
+}
+
+impl AddressSpace {
+    fn bytes(&self) -> u64 {
+        self.mapped
+            + self
+                .heap
+                .map_or(0, |(base, current)| current.saturating_sub(base))
+    }
+
+    fn map(&mut self, addr: u64, length: u64) {
+        let (mut start, mut end) = (addr, addr.saturating_add(page_align(length)));
+        let overlapping: Vec<(u64, u64)> = self
+            .regions
+            .range(..=end)
+            .rev()
+            .take_while(|&(_, &e)| e >= start)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in overlapping {
+            self.regions.remove(&s);
+            self.mapped -= e - s;
+            start = start.min(s);
+            end = end.max(e);
+        }
+        self.regions.insert(start, end);
+        self.mapped += end - start;
+    }
+
+    fn unmap(&mut self, addr: u64, length: u64) {
+        let (start, end) = (addr, addr.saturating_add(page_align(length)));
+        let overlapping: Vec<(u64, u64)> = self
+            .regions
+            .range(..end)
+            .rev()
+            .take_while(|&(_, &e)| e > start)
+            .map(|(&s, &e)| (s, e))
+            .collect();
+        for (s, e) in overlapping {
+            self.regions.remove(&s);
+            self.mapped -= e - s;
+            for (keep_start, keep_end) in [(s, start), (end, e)] {
+                if keep_start < keep_end {
+                    self.regions.insert(keep_start, keep_end);
+                    self.mapped += keep_end - keep_start;
+                }
+            }
+        }
+    }
+
+    /// The first break seen (usually from `brk(NULL)`) is the heap's base
+    fn set_break(&mut self, brk: u64) {
+        let base = self.heap.map_or(brk, |(base, _)| base);
+        self.heap = Some((base, brk));
+    }
+}
+
+fn page_align(length: u64) -> u64 {
+    length.div_ceil(PAGE_SIZE) * PAGE_SIZE
+}
+
+/// Decoded argument as a number (`0x1000` or `4096`)
+fn parse_number(value: &str) -> Option<u64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified_trace::SyscallSpan;
+    use std::borrow::Cow;
+
+    fn add(trace: &mut UnifiedTrace, pid: i32, name: &'static str, args: &[&str], result: i64) {
+        let timestamp = trace.syscall_spans.len() as u64 * 1000;
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| (Cow::Owned(format!("arg{}", i)), arg.to_string()))
+            .collect();
+        let span = SyscallSpan::new(
+            trace.process_span.span_id,
+            Cow::Borrowed(name),
+            args,
+            result,
+            timestamp,
+            10,
+            None,
+            &trace.clock,
+        )
+        .with_pid(pid);
+        trace.add_syscall(span);
+    }
+
+    #[test]
+    fn test_address_space_growth() {
+        let mut trace = UnifiedTrace::new(1, "app".to_string());
+        add(&mut trace, 1, "brk", &["0x0"], 0x10000);
+        add(&mut trace, 1, "brk", &["0x31000"], 0x31000);
+        // 1 MiB anonymous mapping, then a MAP_FIXED remap inside it
+        add(
+            &mut trace,
+            1,
+            "mmap",
+            &["0x0", "0x100000", "0x3"],
+            0x7000_0000,
+        );
+        add(
+            &mut trace,
+            1,
+            "mmap",
+            &["0x70001000", "0x2000", "0x1"],
+            0x7000_1000,
+        );
+        // Grown to 2 MiB and moved, then half of it unmapped
+        add(
+            &mut trace,
+            1,
+            "mremap",
+            &["0x70000000", "0x100000", "0x200000"],
+            0x7100_0000,
+        );
+        add(&mut trace, 1, "munmap", &["0x71000000", "0x100000"], 0);
+        add(&mut trace, 1, "read", &["0x3"], 5);
+
+        let timeline = MemoryTimeline::from_address_space(&trace);
+        let heap = 0x21000;
+        let bytes: Vec<u64> = timeline.points.iter().map(|&(_, b)| b).collect();
+        assert_eq!(
+            bytes,
+            vec![
+                0,
+                heap,
+                heap + 0x100000,
+                heap + 0x100000,
+                heap + 0x200000,
+                heap + 0x100000
+            ]
+        );
+        assert_eq!(timeline.peak(), Some((4000, heap + 0x200000)));
+    }
+
+    #[test]
+    fn test_processes_and_exec() {
+        let mut trace = UnifiedTrace::new(1, "sh".to_string());
+        add(
+            &mut trace,
+            1,
+            "mmap",
+            &["0x0", "0x1001", "0x3"],
+            0x1000_0000,
+        );
+        add(
+            &mut trace,
+            2,
+            "mmap",
+            &["0x0", "0x4000", "0x3"],
+            0x1000_0000,
+        );
+        add(&mut trace, 2, "execve", &["\"/bin/cc\""], 0);
+        add(&mut trace, 2, "munmap", &["0x10000000", "0x4000"], 0);
+
+        let timeline = MemoryTimeline::from_address_space(&trace);
+        let bytes: Vec<u64> = timeline.points.iter().map(|&(_, b)| b).collect();
+        // Lengths are page-aligned; exec drops the child's mappings
+        assert_eq!(bytes, vec![0x2000, 0x6000, 0x2000, 0x2000]);
+    }
+
+    #[test]
+    fn test_threads_share_an_address_space() {
+        let mut trace = UnifiedTrace::new(1, "app".to_string());
+        add(
+            &mut trace,
+            1,
+            "mmap",
+            &["0x0", "0x100000", "0x3"],
+            0x7000_0000,
+        );
+        // pthread_create's flags include CLONE_VM; fork's do not
+        add(&mut trace, 1, "clone", &["0x3d0f00", "0x0", "0x0"], 2);
+        add(&mut trace, 1, "clone", &["0x1200011", "0x0", "0x0"], 3);
+        // The thread maps over its process's region, then unmaps it
+        add(
+            &mut trace,
+            2,
+            "mmap",
+            &["0x70000000", "0x1000", "0x3"],
+            0x7000_0000,
+        );
+        add(&mut trace, 2, "munmap", &["0x70000000", "0x100000"], 0);
+        // The forked child maps and unmaps in an address space of its own
+        add(
+            &mut trace,
+            3,
+            "mmap",
+            &["0x0", "0x2000", "0x3"],
+            0x7000_0000,
+        );
+        add(&mut trace, 3, "munmap", &["0x70000000", "0x100000"], 0);
+
+        let timeline = MemoryTimeline::from_address_space(&trace);
+        let bytes: Vec<u64> = timeline.points.iter().map(|&(_, b)| b).collect();
+        assert_eq!(bytes, vec![0x100000, 0x100000, 0, 0x2000, 0]);
+    }
+
+    #[test]
+    fn test_rss_timeline_and_sparkline() {
+        let sample = |timestamp_us, pid, bytes| MemorySample {
+            timestamp_us,
+            pid,
+            bytes,
+        };
+        let timeline = MemoryTimeline::from_rss_samples(&[
+            sample(0, 1, 100),
+            sample(10, 2, 700),
+            sample(20, 1, 300),
+            sample(40, 2, 0),
+        ]);
+        assert_eq!(
+            timeline.points,
+            vec![(0, 100), (10_000, 800), (20_000, 1000), (40_000, 300)]
+        );
+        assert_eq!(timeline.peak(), Some((20_000, 1000)));
+        assert_eq!(timeline.sparkline(5), "▁▆██▃");
+        assert_eq!(MemoryTimeline::default().sparkline(5), "");
+    }
+
+    #[test]
+    fn test_proc_fields() {
+        let status = "Name:\tcc\nTgid:\t41\nVmRSS:\t    2048 kB\n";
+        assert_eq!(status_field(status, "Tgid:"), Some(41));
+        assert_eq!(status_field(status, "VmRSS:"), Some(2048));
+        assert_eq!(status_field(status, "Rss:"), None);
+
+        let (tgid, rss) = read_rss(std::process::id() as i32).unwrap();
+        assert_eq!(tgid, std::process::id() as i32);
+        assert!(rss > 0);
+    }
+}
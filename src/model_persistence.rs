@@ -253,6 +253,47 @@ pub fn load_isolation_forest_model(
         .map_err(|e| ModelPersistenceError::LoadError(e.to_string()))
 }
 
+/// Save a syscall sequence model to .apr format (Sprint 59)
+pub fn save_sequence_model(
+    model: &crate::sequence::SequenceModel,
+    path: impl AsRef<Path>,
+    options: PersistenceOptions,
+) -> Result<()> {
+    use aprender::format::{save, Compression, ModelType, SaveOptions};
+
+    let compression = if options.compress {
+        Compression::ZstdDefault
+    } else {
+        Compression::None
+    };
+
+    let mut save_options = SaveOptions::new().with_compression(compression);
+
+    if let Some(name) = options.name {
+        save_options = save_options.with_name(name);
+    }
+    if let Some(desc) = options.description {
+        save_options = save_options.with_description(desc);
+    }
+
+    save(model, ModelType::NgramLm, path.as_ref(), save_options)
+        .map_err(|e| ModelPersistenceError::SaveError(e.to_string()))
+}
+
+/// Load a syscall sequence model from .apr format (Sprint 59)
+pub fn load_sequence_model(path: impl AsRef<Path>) -> Result<crate::sequence::SequenceModel> {
+    use aprender::format::{load, ModelType};
+
+    if !path.as_ref().exists() {
+        return Err(ModelPersistenceError::FileNotFound(
+            path.as_ref().display().to_string(),
+        ));
+    }
+
+    load::<crate::sequence::SequenceModel>(path.as_ref(), ModelType::NgramLm)
+        .map_err(|e| ModelPersistenceError::LoadError(e.to_string()))
+}
+
 /// Check if a model file exists and is valid
 pub fn validate_model_file(path: impl AsRef<Path>) -> Result<ModelMetadata> {
     // Try to load as KMeans first
@@ -265,6 +306,11 @@ pub fn validate_model_file(path: impl AsRef<Path>) -> Result<ModelMetadata> {
         return Ok(model.metadata);
     }
 
+    // Try syscall sequence model
+    if let Ok(model) = load_sequence_model(path.as_ref()) {
+        return Ok(model.metadata);
+    }
+
     Err(ModelPersistenceError::InvalidFormat(
         "Could not determine model type".to_string(),
     ))
@@ -445,6 +491,31 @@ mod tests {
         assert_eq!(loaded.tree_data, model.tree_data);
     }
 
+    #[test]
+    fn test_save_and_load_sequence_model() {
+        let temp_dir = TempDir::new().unwrap();
+        let model_path = temp_dir.path().join("test_sequences.apr");
+
+        let mut model = crate::sequence::SequenceModel::new("cat", 3);
+        model.train_run(&[vec![
+            "openat".to_string(),
+            "read".to_string(),
+            "write".to_string(),
+            "close".to_string(),
+        ]]);
+
+        let options = PersistenceOptions::new().with_name("test-sequences");
+        save_sequence_model(&model, &model_path, options).expect("Failed to save");
+
+        let loaded = load_sequence_model(&model_path).expect("Failed to load");
+        assert_eq!(loaded.program, "cat");
+        assert_eq!(loaded.sequences, model.sequences);
+        assert_eq!(loaded.metadata.training_samples, 2);
+
+        let metadata = validate_model_file(&model_path).expect("Should validate");
+        assert_eq!(metadata.training_samples, 2);
+    }
+
     #[test]
     fn test_model_status_line() {
         let metadata = ModelMetadata::new(1234);
@@ -0,0 +1,725 @@
+// Network syscall decoding and connection tracking (Sprint 63)
+//
+// Socket domains, types and protocols are printed by name and `sockaddr`
+// arguments as endpoints (`127.0.0.1:80`, `[::1]:443`, `unix:/run/x.sock`),
+// wrapped in braces so decoded addresses can be told from raw pointers.
+// Addresses the kernel writes (accept, recvfrom, getsockname) are decoded at
+// syscall exit.
+//
+// The connection table follows each socket fd from creation to close,
+// with its endpoints and the bytes moved through it.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::time::Instant;
+
+/// Largest sockaddr (`sizeof(struct sockaddr_storage)`)
+const SOCKADDR_MAX: usize = 128;
+
+/// Syscalls whose fds a child process shares with its parent
+const SPAWN_SYSCALLS: &[&str] = &["clone", "clone3", "fork", "vfork"];
+
+const SEND_SYSCALLS: &[&str] = &["write", "writev", "send", "sendto", "sendmsg"];
+const RECV_SYSCALLS: &[&str] = &["read", "readv", "recv", "recvfrom", "recvmsg"];
+
+/// Index of the sockaddr argument and of its length
+///
+/// The length is a value for addresses passed in, and a pointer to a
+/// `socklen_t` for addresses the kernel fills in.
+fn address_args(syscall: &str) -> Option<(usize, usize)> {
+    match syscall {
+        "connect" | "bind" | "accept" | "accept4" | "getsockname" | "getpeername" => Some((1, 2)),
+        "sendto" | "recvfrom" => Some((4, 5)),
+        _ => None,
+    }
+}
+
+/// Whether the kernel writes the syscall's address, so it is decoded at exit
+pub fn address_at_exit(syscall: &str) -> bool {
+    matches!(
+        syscall,
+        "accept" | "accept4" | "getsockname" | "getpeername" | "recvfrom"
+    )
+}
+
+/// Whether the syscall has arguments decoded by [`format_args`]
+pub fn is_network_syscall(syscall: &str) -> bool {
+    syscall == "socket" || address_args(syscall).is_some()
+}
+
+/// A decoded socket address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketAddress {
+    Inet(SocketAddrV4),
+    Inet6(SocketAddrV6),
+    /// Filesystem path, `@name` for the abstract namespace, empty when unnamed
+    Unix(String),
+    Netlink {
+        pid: u32,
+        groups: u32,
+    },
+    Other(i32),
+}
+
+impl fmt::Display for SocketAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SocketAddress::Inet(addr) => write!(f, "{}", addr),
+            SocketAddress::Inet6(addr) => write!(f, "{}", addr),
+            SocketAddress::Unix(path) if path.is_empty() => write!(f, "unix:(unnamed)"),
+            SocketAddress::Unix(path) => write!(f, "unix:{}", path),
+            SocketAddress::Netlink { pid, groups } => {
+                write!(f, "netlink:pid={},groups={:#x}", pid, groups)
+            }
+            SocketAddress::Other(family) => write!(f, "{}", family_name(*family)),
+        }
+    }
+}
+
+/// Decode a `struct sockaddr` of the given length
+pub fn decode_sockaddr(bytes: &[u8]) -> Option<SocketAddress> {
+    let family = i32::from(u16::from_ne_bytes([*bytes.first()?, *bytes.get(1)?]));
+    let port = |bytes: &[u8]| u16::from_be_bytes([bytes[2], bytes[3]]);
+    let u32_at = |at: usize| -> Option<u32> {
+        Some(u32::from_ne_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    };
+
+    let address = match family {
+        libc::AF_INET => {
+            let ip: [u8; 4] = bytes.get(4..8)?.try_into().ok()?;
+            SocketAddress::Inet(SocketAddrV4::new(Ipv4Addr::from(ip), port(bytes)))
+        }
+        libc::AF_INET6 => {
+            let ip: [u8; 16] = bytes.get(8..24)?.try_into().ok()?;
+            let scope = u32_at(24).unwrap_or(0);
+            SocketAddress::Inet6(SocketAddrV6::new(
+                Ipv6Addr::from(ip),
+                port(bytes),
+                u32_at(4)?,
+                scope,
+            ))
+        }
+        libc::AF_UNIX => {
+            let path = &bytes[2..];
+            match path.first() {
+                None => SocketAddress::Unix(String::new()),
+                // Abstract names are not NUL-terminated; their length is the address length
+                Some(0) => SocketAddress::Unix(format!(
+                    "@{}",
+                    String::from_utf8_lossy(&path[1..]).trim_end_matches('\0')
+                )),
+                Some(_) => {
+                    let end = path.iter().position(|&b| b == 0).unwrap_or(path.len());
+                    SocketAddress::Unix(String::from_utf8_lossy(&path[..end]).into_owned())
+                }
+            }
+        }
+        libc::AF_NETLINK => SocketAddress::Netlink {
+            pid: u32_at(4)?,
+            groups: u32_at(8)?,
+        },
+        other => SocketAddress::Other(other),
+    };
+    Some(address)
+}
+
+/// Decoded address argument, as written by [`format_args`]
+pub fn address_arg(arg: &str) -> Option<&str> {
+    arg.strip_prefix('{')?.strip_suffix('}')
+}
+
+/// Decode the arguments of a network syscall, or None for other syscalls
+///
+/// `raw_args` are the six argument registers and `read` reads tracee memory.
+/// Addresses the kernel fills in are decoded once the syscall `completed`
+/// successfully; undecodable addresses stay raw pointers.
+pub fn format_args(
+    syscall: &str,
+    raw_args: [u64; 6],
+    completed: Option<i64>,
+    read: impl Fn(u64, usize) -> Option<Vec<u8>>,
+) -> Option<Vec<String>> {
+    if syscall == "socket" {
+        let [domain, kind, protocol, ..] = raw_args;
+        return Some(vec![
+            family_name(domain as i32),
+            socket_type_name(kind as i32),
+            protocol_name(domain as i32, protocol as i32),
+        ]);
+    }
+
+    let (addr_index, len_index) = address_args(syscall)?;
+    let count = match syscall {
+        "sendto" | "recvfrom" => 6,
+        "accept4" => 4,
+        _ => 3,
+    };
+    let mut args: Vec<String> = raw_args[..count]
+        .iter()
+        .map(|arg| format!("{:#x}", arg))
+        .collect();
+    args[0] = (raw_args[0] as i32).to_string();
+
+    let addr = raw_args[addr_index];
+    let len = if address_at_exit(syscall) {
+        match completed {
+            Some(result) if result >= 0 && raw_args[len_index] != 0 => read(raw_args[len_index], 4)
+                .and_then(|len| Some(u32::from_ne_bytes(len.get(..4)?.try_into().ok()?)))
+                .unwrap_or(0)
+                as usize,
+            _ => 0,
+        }
+    } else {
+        raw_args[len_index] as usize
+    };
+    if addr != 0 && len > 0 {
+        let decoded = read(addr, len.min(SOCKADDR_MAX)).and_then(|bytes| decode_sockaddr(&bytes));
+        if let Some(decoded) = decoded {
+            args[addr_index] = format!("{{{}}}", decoded);
+        }
+    }
+    Some(args)
+}
+
+/// Printable name of a socket address family
+pub fn family_name(family: i32) -> String {
+    match family {
+        libc::AF_UNSPEC => "AF_UNSPEC".to_string(),
+        libc::AF_UNIX => "AF_UNIX".to_string(),
+        libc::AF_INET => "AF_INET".to_string(),
+        libc::AF_INET6 => "AF_INET6".to_string(),
+        libc::AF_NETLINK => "AF_NETLINK".to_string(),
+        libc::AF_PACKET => "AF_PACKET".to_string(),
+        other => format!("family {}", other),
+    }
+}
+
+/// Socket type with its SOCK_NONBLOCK/SOCK_CLOEXEC flags
+fn socket_type_name(kind: i32) -> String {
+    let base = match kind & 0xf {
+        libc::SOCK_STREAM => "SOCK_STREAM".to_string(),
+        libc::SOCK_DGRAM => "SOCK_DGRAM".to_string(),
+        libc::SOCK_RAW => "SOCK_RAW".to_string(),
+        libc::SOCK_SEQPACKET => "SOCK_SEQPACKET".to_string(),
+        other => other.to_string(),
+    };
+    let mut name = base;
+    if kind & libc::SOCK_NONBLOCK != 0 {
+        name.push_str("|SOCK_NONBLOCK");
+    }
+    if kind & libc::SOCK_CLOEXEC != 0 {
+        name.push_str("|SOCK_CLOEXEC");
+    }
+    name
+}
+
+fn protocol_name(domain: i32, protocol: i32) -> String {
+    if domain != libc::AF_INET && domain != libc::AF_INET6 {
+        return protocol.to_string();
+    }
+    match protocol {
+        libc::IPPROTO_TCP => "IPPROTO_TCP".to_string(),
+        libc::IPPROTO_UDP => "IPPROTO_UDP".to_string(),
+        libc::IPPROTO_ICMP => "IPPROTO_ICMP".to_string(),
+        libc::IPPROTO_ICMPV6 => "IPPROTO_ICMPV6".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Short protocol label of a socket (tcp, udp6, unix, ...)
+/// Address family of a formatted [`SocketAddress`]
+fn text_family(address: &str) -> i32 {
+    if address.starts_with("unix:") {
+        libc::AF_UNIX
+    } else if address.starts_with("netlink:") {
+        libc::AF_NETLINK
+    } else {
+        match address.parse::<std::net::SocketAddr>() {
+            Ok(std::net::SocketAddr::V4(_)) => libc::AF_INET,
+            Ok(std::net::SocketAddr::V6(_)) => libc::AF_INET6,
+            Err(_) => libc::AF_UNSPEC,
+        }
+    }
+}
+
+fn protocol_label(family: i32, kind: i32) -> String {
+    let suffix = if family == libc::AF_INET6 { "6" } else { "" };
+    match (family, kind & 0xf) {
+        (libc::AF_INET | libc::AF_INET6, libc::SOCK_STREAM) => format!("tcp{}", suffix),
+        (libc::AF_INET | libc::AF_INET6, libc::SOCK_DGRAM) => format!("udp{}", suffix),
+        (libc::AF_INET | libc::AF_INET6, _) => format!("raw{}", suffix),
+        (libc::AF_UNIX, libc::SOCK_DGRAM) => "unix-dgram".to_string(),
+        (libc::AF_UNIX, _) => "unix".to_string(),
+        (libc::AF_NETLINK, _) => "netlink".to_string(),
+        (other, _) => family_name(other),
+    }
+}
+
+/// One socket and its traffic
+#[derive(Debug, Clone, Serialize)]
+pub struct Connection {
+    /// Process that created (or accepted) the socket
+    pub pid: i32,
+    pub fd: i32,
+    /// tcp, udp6, unix, ...
+    pub protocol: String,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    /// Microseconds since the start of the trace
+    pub opened_us: u64,
+    /// None while the socket is still open
+    pub closed_us: Option<u64>,
+    #[serde(skip)]
+    family: i32,
+    #[serde(skip)]
+    kind: i32,
+    /// (pid, fd) pairs still referring to the socket
+    #[serde(skip)]
+    refs: usize,
+}
+
+impl Connection {
+    /// Lifetime in microseconds, up to `now_us` for sockets still open
+    pub fn lifetime_us(&self, now_us: u64) -> u64 {
+        self.closed_us
+            .unwrap_or(now_us)
+            .saturating_sub(self.opened_us)
+    }
+}
+
+/// Sockets of a traced process tree, by (pid, fd)
+#[derive(Debug)]
+pub struct ConnectionTable {
+    connections: Vec<Connection>,
+    open: HashMap<(i32, i32), usize>,
+    started: Instant,
+    last_us: u64,
+}
+
+impl Default for ConnectionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectionTable {
+    pub fn new() -> Self {
+        Self {
+            connections: Vec::new(),
+            open: HashMap::new(),
+            started: Instant::now(),
+            last_us: 0,
+        }
+    }
+
+    /// Microseconds since the table was created (timestamps for live tracing)
+    pub fn elapsed_us(&self) -> u64 {
+        self.started.elapsed().as_micros() as u64
+    }
+
+    /// Record a completed syscall
+    ///
+    /// `args` are the decoded arguments (see [`format_args`]).
+    pub fn record(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        args: &[String],
+        raw_args: [u64; 3],
+        result: i64,
+        timestamp_us: u64,
+    ) {
+        self.last_us = self.last_us.max(timestamp_us);
+        // A non-blocking connect still names its peer
+        let in_progress = syscall == "connect" && result == -(libc::EINPROGRESS as i64);
+        if result < 0 && !in_progress {
+            return;
+        }
+        let fd = raw_args[0] as i32;
+        let address = || {
+            let (index, _) = address_args(syscall)?;
+            address_arg(args.get(index)?).map(str::to_string)
+        };
+
+        if SPAWN_SYSCALLS.contains(&syscall) {
+            if result > 0 {
+                self.spawn(pid, result as i32);
+            }
+            return;
+        }
+        match syscall {
+            "socket" => {
+                self.open_connection(
+                    pid,
+                    result as i32,
+                    raw_args[0] as i32,
+                    raw_args[1] as i32,
+                    None,
+                    timestamp_us,
+                );
+            }
+            "accept" | "accept4" => {
+                let remote = address();
+                // Without the listener (e.g. its clone was filtered out), go by the peer
+                let listener = self.get(pid, fd).cloned();
+                let (family, kind, local) = listener
+                    .map(|l| (l.family, l.kind, l.local))
+                    .unwrap_or_else(|| {
+                        let family = remote.as_deref().map_or(libc::AF_UNSPEC, text_family);
+                        (family, libc::SOCK_STREAM, None)
+                    });
+                let index =
+                    self.open_connection(pid, result as i32, family, kind, local, timestamp_us);
+                self.connections[index].remote = remote;
+            }
+            "bind" | "getsockname" => {
+                if let (Some(local), Some(conn)) = (address(), self.get_mut(pid, fd)) {
+                    conn.local = Some(local);
+                }
+            }
+            "connect" | "getpeername" => {
+                if let (Some(remote), Some(conn)) = (address(), self.get_mut(pid, fd)) {
+                    conn.remote = Some(remote);
+                }
+            }
+            "close" => self.close(pid, fd, timestamp_us),
+            "dup" | "dup2" | "dup3" => self.duplicate(pid, fd, pid, result as i32),
+            "fcntl" if [libc::F_DUPFD, libc::F_DUPFD_CLOEXEC].contains(&(raw_args[1] as i32)) => {
+                self.duplicate(pid, fd, pid, result as i32)
+            }
+            _ => {}
+        }
+
+        let sent = SEND_SYSCALLS.contains(&syscall);
+        if sent || RECV_SYSCALLS.contains(&syscall) {
+            let peer = address();
+            if let Some(conn) = self.get_mut(pid, fd) {
+                // Datagram sockets name their peer per message
+                if conn.remote.is_none() {
+                    conn.remote = peer;
+                }
+                if sent {
+                    conn.bytes_sent += result as u64;
+                } else {
+                    conn.bytes_received += result as u64;
+                }
+            }
+        }
+    }
+
+    fn open_connection(
+        &mut self,
+        pid: i32,
+        fd: i32,
+        family: i32,
+        kind: i32,
+        local: Option<String>,
+        timestamp_us: u64,
+    ) -> usize {
+        // A reused fd number means the old socket was closed without us seeing it
+        self.close(pid, fd, timestamp_us);
+        self.connections.push(Connection {
+            pid,
+            fd,
+            protocol: protocol_label(family, kind),
+            local,
+            remote: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            opened_us: timestamp_us,
+            closed_us: None,
+            family,
+            kind,
+            refs: 1,
+        });
+        let index = self.connections.len() - 1;
+        self.open.insert((pid, fd), index);
+        index
+    }
+
+    fn close(&mut self, pid: i32, fd: i32, timestamp_us: u64) {
+        if let Some(index) = self.open.remove(&(pid, fd)) {
+            let conn = &mut self.connections[index];
+            conn.refs -= 1;
+            if conn.refs == 0 {
+                conn.closed_us = Some(timestamp_us);
+            }
+        }
+    }
+
+    fn duplicate(&mut self, from_pid: i32, from_fd: i32, to_pid: i32, to_fd: i32) {
+        if let Some(&index) = self.open.get(&(from_pid, from_fd)) {
+            if let Some(old) = self.open.insert((to_pid, to_fd), index) {
+                self.connections[old].refs -= 1;
+            }
+            self.connections[index].refs += 1;
+        }
+    }
+
+    /// A child shares the sockets open in its parent
+    fn spawn(&mut self, parent: i32, child: i32) {
+        let inherited: Vec<i32> = self
+            .open
+            .keys()
+            .filter(|(pid, _)| *pid == parent)
+            .map(|&(_, fd)| fd)
+            .collect();
+        for fd in inherited {
+            self.duplicate(parent, fd, child, fd);
+        }
+    }
+
+    fn get(&self, pid: i32, fd: i32) -> Option<&Connection> {
+        self.open
+            .get(&(pid, fd))
+            .map(|&index| &self.connections[index])
+    }
+
+    fn get_mut(&mut self, pid: i32, fd: i32) -> Option<&mut Connection> {
+        let index = *self.open.get(&(pid, fd))?;
+        self.connections.get_mut(index)
+    }
+
+    /// Sockets in creation order
+    pub fn connections(&self) -> &[Connection] {
+        &self.connections
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.connections.is_empty()
+    }
+
+    /// Time of the last recorded syscall, the lifetime end of open sockets
+    pub fn last_us(&self) -> u64 {
+        self.last_us
+    }
+
+    /// Text table of all sockets
+    pub fn report(&self) -> String {
+        let mut out = String::from("\n=== Network Connections ===\n");
+        out.push_str(&format!(
+            "{:>7} {:>4}  {:<10} {:<24} {:<24} {:>10} {:>10} {:>10}\n",
+            "PID", "FD", "PROTO", "LOCAL", "REMOTE", "SENT", "RECEIVED", "LIFETIME"
+        ));
+        for conn in &self.connections {
+            let lifetime = format!(
+                "{:.3}s{}",
+                conn.lifetime_us(self.last_us) as f64 / 1_000_000.0,
+                if conn.closed_us.is_none() { "+" } else { "" }
+            );
+            out.push_str(&format!(
+                "{:>7} {:>4}  {:<10} {:<24} {:<24} {:>10} {:>10} {:>10}\n",
+                conn.pid,
+                conn.fd,
+                conn.protocol,
+                conn.local.as_deref().unwrap_or("-"),
+                conn.remote.as_deref().unwrap_or("-"),
+                conn.bytes_sent,
+                conn.bytes_received,
+                lifetime
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sockaddr_in(ip: [u8; 4], port: u16) -> Vec<u8> {
+        let mut bytes = (libc::AF_INET as u16).to_ne_bytes().to_vec();
+        bytes.extend_from_slice(&port.to_be_bytes());
+        bytes.extend_from_slice(&ip);
+        bytes.extend_from_slice(&[0; 8]);
+        bytes
+    }
+
+    fn sockaddr_un(path: &[u8]) -> Vec<u8> {
+        let mut bytes = (libc::AF_UNIX as u16).to_ne_bytes().to_vec();
+        bytes.extend_from_slice(path);
+        bytes
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_decode_inet_addresses() {
+        let v4 = decode_sockaddr(&sockaddr_in([127, 0, 0, 1], 8080)).unwrap();
+        assert_eq!(v4.to_string(), "127.0.0.1:8080");
+
+        let mut v6 = (libc::AF_INET6 as u16).to_ne_bytes().to_vec();
+        v6.extend_from_slice(&443u16.to_be_bytes());
+        v6.extend_from_slice(&[0; 4]);
+        v6.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        v6.extend_from_slice(&[0; 4]);
+        assert_eq!(decode_sockaddr(&v6).unwrap().to_string(), "[::1]:443");
+
+        // Truncated addresses are not decoded
+        assert_eq!(decode_sockaddr(&sockaddr_in([1, 2, 3, 4], 1)[..6]), None);
+    }
+
+    #[test]
+    fn test_decode_unix_and_netlink_addresses() {
+        let path = decode_sockaddr(&sockaddr_un(b"/run/app.sock\0\0\0")).unwrap();
+        assert_eq!(path.to_string(), "unix:/run/app.sock");
+        let abstract_name = decode_sockaddr(&sockaddr_un(b"\0bus")).unwrap();
+        assert_eq!(abstract_name.to_string(), "unix:@bus");
+        assert_eq!(
+            decode_sockaddr(&sockaddr_un(b"")).unwrap().to_string(),
+            "unix:(unnamed)"
+        );
+
+        let mut netlink = (libc::AF_NETLINK as u16).to_ne_bytes().to_vec();
+        netlink.extend_from_slice(&[0; 2]);
+        netlink.extend_from_slice(&42u32.to_ne_bytes());
+        netlink.extend_from_slice(&1u32.to_ne_bytes());
+        assert_eq!(
+            decode_sockaddr(&netlink).unwrap().to_string(),
+            "netlink:pid=42,groups=0x1"
+        );
+    }
+
+    #[test]
+    fn test_format_socket_and_connect_args() {
+        let socket = format_args(
+            "socket",
+            [
+                libc::AF_INET as u64,
+                (libc::SOCK_STREAM | libc::SOCK_CLOEXEC) as u64,
+                libc::IPPROTO_TCP as u64,
+                0,
+                0,
+                0,
+            ],
+            None,
+            |_, _| None,
+        );
+        assert_eq!(
+            socket.unwrap(),
+            strings(&["AF_INET", "SOCK_STREAM|SOCK_CLOEXEC", "IPPROTO_TCP"])
+        );
+
+        let addr = sockaddr_in([10, 0, 0, 1], 53);
+        let connect = format_args("connect", [3, 0x1000, 16, 0, 0, 0], None, |ptr, len| {
+            assert_eq!((ptr, len), (0x1000, 16));
+            Some(addr.clone())
+        });
+        assert_eq!(connect.unwrap(), strings(&["3", "{10.0.0.1:53}", "0x10"]));
+
+        assert_eq!(format_args("read", [0; 6], None, |_, _| None), None);
+    }
+
+    #[test]
+    fn test_returned_addresses_decoded_at_exit() {
+        let addr = sockaddr_in([192, 168, 1, 9], 40000);
+        let read = |ptr: u64, _len: usize| match ptr {
+            0x2000 => Some(16u32.to_ne_bytes().to_vec()),
+            0x1000 => Some(addr.clone()),
+            _ => None,
+        };
+        let raw = [3, 0x1000, 0x2000, 0, 0, 0];
+
+        let entry = format_args("accept4", raw, None, read).unwrap();
+        assert_eq!(entry[1], "0x1000");
+        let failed = format_args("accept4", raw, Some(-11), read).unwrap();
+        assert_eq!(failed[1], "0x1000");
+        let exit = format_args("accept4", raw, Some(4), read).unwrap();
+        assert_eq!(
+            exit,
+            strings(&["3", "{192.168.1.9:40000}", "0x2000", "0x0"])
+        );
+    }
+
+    #[test]
+    fn test_connection_table_tracks_lifetime_and_bytes() {
+        let mut table = ConnectionTable::new();
+        let inet = libc::AF_INET as u64;
+        let stream = libc::SOCK_STREAM as u64;
+        table.record(7, "socket", &[], [inet, stream, 0], 3, 100);
+        table.record(
+            7,
+            "connect",
+            &strings(&["3", "{10.0.0.1:80}", "0x10"]),
+            [3, 0, 16],
+            0,
+            150,
+        );
+        table.record(7, "write", &[], [3, 0, 64], 64, 200);
+        table.record(7, "read", &[], [3, 0, 4096], 1000, 300);
+        table.record(7, "read", &[], [5, 0, 4096], 10, 310); // not a socket
+        table.record(7, "close", &[], [3, 0, 0], 0, 1_100);
+
+        let conn = &table.connections()[0];
+        assert_eq!(conn.protocol, "tcp");
+        assert_eq!(conn.remote.as_deref(), Some("10.0.0.1:80"));
+        assert_eq!((conn.bytes_sent, conn.bytes_received), (64, 1000));
+        assert_eq!(conn.lifetime_us(0), 1_000);
+
+        let report = table.report();
+        assert!(report.contains("=== Network Connections ==="));
+        assert!(report.contains("10.0.0.1:80"));
+        assert!(report.contains("0.001s"));
+    }
+
+    #[test]
+    fn test_accepted_connections_share_across_threads() {
+        let mut table = ConnectionTable::new();
+        let unix = libc::AF_UNIX as u64;
+        table.record(1, "socket", &[], [unix, libc::SOCK_STREAM as u64, 0], 3, 0);
+        table.record(
+            1,
+            "bind",
+            &strings(&["3", "{unix:/tmp/s}", "0x9"]),
+            [3, 0, 9],
+            0,
+            1,
+        );
+        table.record(1, "clone", &[], [0, 0, 0], 2, 2);
+        table.record(
+            2,
+            "accept4",
+            &strings(&["3", "{unix:(unnamed)}", "0x0", "0x0"]),
+            [3, 0, 0],
+            4,
+            3,
+        );
+        table.record(
+            2,
+            "sendto",
+            &strings(&["4", "0x0", "0x5", "0x0", "0x0", "0x0"]),
+            [4, 0, 5],
+            5,
+            4,
+        );
+        // The parent never saw fd 4; only the thread's close ends the socket
+        table.record(1, "close", &[], [4, 0, 0], -9, 5);
+        table.record(2, "close", &[], [4, 0, 0], 0, 6);
+
+        let accepted = &table.connections()[1];
+        assert_eq!(accepted.pid, 2);
+        assert_eq!(accepted.local.as_deref(), Some("unix:/tmp/s"));
+        assert_eq!(accepted.remote.as_deref(), Some("unix:(unnamed)"));
+        assert_eq!(accepted.bytes_sent, 5);
+        assert_eq!(accepted.closed_us, Some(6));
+        // The listener is still open in both processes
+        assert_eq!(table.connections()[0].closed_us, None);
+
+        // An unknown listener falls back to the peer's address family
+        table.record(
+            9,
+            "accept",
+            &strings(&["3", "{[::1]:5000}", "0x0"]),
+            [3, 0, 0],
+            4,
+            7,
+        );
+        assert_eq!(table.connections()[2].protocol, "tcp6");
+    }
+}
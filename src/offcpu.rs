@@ -0,0 +1,618 @@
+//! Off-CPU (blocking time) analysis
+//!
+//! Sprint 52: Answers "where are we blocked?". Time spent in blocking
+//! syscalls (futex waits, poll/epoll, reads on pipes and sockets, sleeps...)
+//! is grouped per thread by full call stack and by the object waited on
+//! (file descriptor path or futex address). Non-blocking syscalls are only
+//! counted so blocked time can be compared against on-CPU syscall time.
+//! Reads, writes and syncs of regular files are disk I/O rather than waits
+//! for readiness, and count as on-CPU syscall time too.
+
+use crate::flamegraph::{stack_labels, FoldedStacks};
+use crate::stack_unwind::ResolvedFrame;
+use anyhow::{Context, Result};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Default number of blockers reported per thread
+pub const DEFAULT_TOP_BLOCKERS: usize = 10;
+
+// futex(2) operations that put the caller to sleep
+const FUTEX_CMD_MASK: u64 = 0x7f;
+const FUTEX_WAIT: u64 = 0;
+const FUTEX_LOCK_PI: u64 = 6;
+const FUTEX_WAIT_BITSET: u64 = 9;
+const FUTEX_WAIT_REQUEUE_PI: u64 = 11;
+const FUTEX_LOCK_PI2: u64 = 13;
+
+/// Syscalls whose first argument is the file descriptor being waited on
+const FD_WAIT_SYSCALLS: &[&str] = &[
+    "read",
+    "readv",
+    "pread64",
+    "preadv",
+    "write",
+    "writev",
+    "pwrite64",
+    "recvfrom",
+    "recvmsg",
+    "recvmmsg",
+    "sendto",
+    "sendmsg",
+    "accept",
+    "accept4",
+    "connect",
+    "epoll_wait",
+    "epoll_pwait",
+    "flock",
+    "fsync",
+    "fdatasync",
+    "syncfs",
+    "io_uring_enter",
+];
+
+/// Fd syscalls that only block when the fd is a pipe, socket, terminal or
+/// similar; on regular files they are disk I/O
+const FILE_IO_SYSCALLS: &[&str] = &[
+    "read",
+    "readv",
+    "pread64",
+    "preadv",
+    "write",
+    "writev",
+    "pwrite64",
+    "fsync",
+    "fdatasync",
+    "syncfs",
+];
+
+/// What a blocking syscall was waiting on
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum WaitObject {
+    /// File descriptor, with its `/proc/PID/fd` target when available
+    Fd { fd: i32, path: Option<String> },
+    /// Futex word address
+    Futex { addr: u64 },
+    /// Timed sleep (nanosleep, clock_nanosleep)
+    Sleep,
+    /// Multiplexed wait on several descriptors (poll, select)
+    Poll,
+    /// Child process state change (wait4, waitid)
+    Child,
+    /// Signal delivery (pause, rt_sigsuspend, rt_sigtimedwait)
+    Signal,
+    /// Any other blocking syscall
+    Other,
+}
+
+impl fmt::Display for WaitObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaitObject::Fd {
+                fd,
+                path: Some(path),
+            } => write!(f, "fd {} ({})", fd, path),
+            WaitObject::Fd { fd, path: None } => write!(f, "fd {}", fd),
+            WaitObject::Futex { addr } => write!(f, "futex {:#x}", addr),
+            WaitObject::Sleep => write!(f, "sleep"),
+            WaitObject::Poll => write!(f, "poll set"),
+            WaitObject::Child => write!(f, "child process"),
+            WaitObject::Signal => write!(f, "signal"),
+            WaitObject::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Classify a syscall by what it blocks on
+///
+/// Returns `None` for syscalls that do not block, including futex
+/// operations that never sleep (e.g. `FUTEX_WAKE`). `resolve_fd` maps a
+/// descriptor number to its path.
+pub fn classify_wait<F>(syscall: &str, arg1: u64, arg2: u64, resolve_fd: F) -> Option<WaitObject>
+where
+    F: FnOnce(i32) -> Option<String>,
+{
+    if !crate::syscalls::is_blocking_syscall(syscall) {
+        return None;
+    }
+
+    let object = match syscall {
+        "futex" => {
+            let cmd = arg2 & FUTEX_CMD_MASK;
+            if !matches!(
+                cmd,
+                FUTEX_WAIT
+                    | FUTEX_LOCK_PI
+                    | FUTEX_WAIT_BITSET
+                    | FUTEX_WAIT_REQUEUE_PI
+                    | FUTEX_LOCK_PI2
+            ) {
+                return None;
+            }
+            WaitObject::Futex { addr: arg1 }
+        }
+        "nanosleep" | "clock_nanosleep" => WaitObject::Sleep,
+        "poll" | "ppoll" | "select" | "pselect6" => WaitObject::Poll,
+        "wait4" | "waitid" => WaitObject::Child,
+        "pause" | "rt_sigsuspend" | "rt_sigtimedwait" => WaitObject::Signal,
+        name if FD_WAIT_SYSCALLS.contains(&name) => {
+            let fd = arg1 as i32;
+            WaitObject::Fd {
+                fd,
+                path: resolve_fd(fd),
+            }
+        }
+        _ => WaitObject::Other,
+    };
+    Some(object)
+}
+
+/// Check whether `syscall` waiting on `wait` is time spent blocked
+///
+/// Reads, writes and syncs of fds resolving to a regular file (any path
+/// outside `/dev`) are not; pipes, sockets, `anon_inode:` fds, devices
+/// such as terminals, and fds that could not be resolved are.
+pub fn is_off_cpu_wait(syscall: &str, wait: &WaitObject) -> bool {
+    match wait {
+        WaitObject::Fd {
+            path: Some(path), ..
+        } if FILE_IO_SYSCALLS.contains(&syscall) => {
+            !path.starts_with('/') || path.starts_with("/dev/")
+        }
+        _ => true,
+    }
+}
+
+/// Resolve a descriptor of process `pid` via `/proc/PID/fd`
+///
+/// Pipes and sockets resolve to names like `pipe:[1234]` and `socket:[5678]`.
+pub fn fd_path(pid: Pid, fd: i32) -> Option<String> {
+    if fd < 0 {
+        return None;
+    }
+    std::fs::read_link(format!("/proc/{}/fd/{}", pid, fd))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Off-CPU analysis configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffCpuConfig {
+    /// Number of blockers reported per thread
+    pub top: usize,
+    /// Folded stacks output path (blocked microseconds per stack)
+    pub folded_path: Option<PathBuf>,
+}
+
+impl OffCpuConfig {
+    /// Build a config from CLI arguments
+    ///
+    /// Returns `None` when off-CPU analysis was not requested. Requesting
+    /// folded output implies the analysis.
+    pub fn from_cli(enabled: bool, folded_path: Option<&str>, top: usize) -> Option<Self> {
+        if !enabled && folded_path.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            top,
+            folded_path: folded_path.map(PathBuf::from),
+        })
+    }
+}
+
+/// Accumulated blocked time for one group
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockerStats {
+    /// Number of blocking calls
+    pub count: u64,
+    /// Total blocked time in microseconds
+    pub total_us: u64,
+    /// Longest single wait in microseconds
+    pub max_us: u64,
+}
+
+impl BlockerStats {
+    pub(crate) fn add(&mut self, duration_us: u64) {
+        self.count += 1;
+        self.total_us += duration_us;
+        self.max_us = self.max_us.max(duration_us);
+    }
+}
+
+/// Off-CPU data for a single thread
+#[derive(Debug, Clone, Default)]
+pub struct ThreadOffCpu {
+    /// Blocked time per root-first stack (leaf = syscall)
+    pub by_stack: HashMap<Vec<String>, BlockerStats>,
+    /// Blocked time per wait object
+    pub by_object: HashMap<WaitObject, BlockerStats>,
+    /// Totals over all blocking syscalls
+    pub blocked: BlockerStats,
+    /// Totals over non-blocking syscalls
+    pub on_cpu: BlockerStats,
+}
+
+impl ThreadOffCpu {
+    /// Top `n` stacks by blocked time
+    pub fn top_stacks(&self, n: usize) -> Vec<(&Vec<String>, &BlockerStats)> {
+        top_n(&self.by_stack, n)
+    }
+
+    /// Top `n` wait objects by blocked time
+    pub fn top_objects(&self, n: usize) -> Vec<(&WaitObject, &BlockerStats)> {
+        top_n(&self.by_object, n)
+    }
+}
+
+fn top_n<K: Ord>(map: &HashMap<K, BlockerStats>, n: usize) -> Vec<(&K, &BlockerStats)> {
+    let mut sorted: Vec<_> = map.iter().collect();
+    sorted.sort_by(|a, b| b.1.total_us.cmp(&a.1.total_us).then(a.0.cmp(b.0)));
+    sorted.truncate(n);
+    sorted
+}
+
+/// Collects blocking syscalls per thread during tracing
+#[derive(Debug)]
+pub struct OffCpuAnalyzer {
+    config: OffCpuConfig,
+    threads: BTreeMap<i32, ThreadOffCpu>,
+    folded: FoldedStacks,
+}
+
+impl OffCpuAnalyzer {
+    /// Create an analyzer for the given configuration
+    pub fn new(config: OffCpuConfig) -> Self {
+        Self {
+            config,
+            threads: BTreeMap::new(),
+            folded: FoldedStacks::new(),
+        }
+    }
+
+    /// Record one completed syscall
+    ///
+    /// `wait` is the result of [`classify_wait`] at syscall entry; syscalls
+    /// without a wait object, or with one that [`is_off_cpu_wait`] rejects,
+    /// are counted as on-CPU.
+    pub fn record(
+        &mut self,
+        tid: i32,
+        syscall: &str,
+        wait: Option<&WaitObject>,
+        frames: &[ResolvedFrame],
+        duration_us: u64,
+    ) {
+        let thread = self.threads.entry(tid).or_default();
+        let Some(wait) = wait.filter(|wait| is_off_cpu_wait(syscall, wait)) else {
+            thread.on_cpu.add(duration_us);
+            return;
+        };
+
+        let stack = stack_labels(frames, syscall);
+        thread.blocked.add(duration_us);
+        thread
+            .by_object
+            .entry(wait.clone())
+            .or_default()
+            .add(duration_us);
+
+        let mut folded = stack.clone();
+        folded.push(format!("[{}]", wait));
+        // Sub-microsecond waits still count as one unit of time
+        self.folded.add(&folded, duration_us.max(1));
+
+        thread.by_stack.entry(stack).or_default().add(duration_us);
+    }
+
+    /// Per-thread results, keyed by thread id
+    pub fn threads(&self) -> &BTreeMap<i32, ThreadOffCpu> {
+        &self.threads
+    }
+
+    /// Blocked stacks in folded form (leaf = wait object)
+    pub fn folded(&self) -> &FoldedStacks {
+        &self.folded
+    }
+
+    /// Format the per-thread top blockers report
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        out.push_str("\n=== Off-CPU Analysis (time blocked in syscalls) ===\n");
+
+        if self.threads.values().all(|t| t.blocked.count == 0) {
+            out.push_str("\nNo blocking syscalls recorded.\n");
+            return out;
+        }
+
+        for (tid, thread) in &self.threads {
+            if thread.blocked.count == 0 {
+                continue;
+            }
+            out.push_str(&format!(
+                "\nThread {}: blocked {} in {} calls, on-CPU syscalls {} in {} calls\n",
+                tid,
+                format_us(thread.blocked.total_us),
+                thread.blocked.count,
+                format_us(thread.on_cpu.total_us),
+                thread.on_cpu.count
+            ));
+
+            out.push_str("  Top wait objects:\n");
+            for (object, stats) in thread.top_objects(self.config.top) {
+                out.push_str(&format!(
+                    "    {:>12} {:>8} calls  max {:>10}  {}\n",
+                    format_us(stats.total_us),
+                    stats.count,
+                    format_us(stats.max_us),
+                    object
+                ));
+            }
+
+            out.push_str("  Top blocking stacks:\n");
+            for (stack, stats) in thread.top_stacks(self.config.top) {
+                out.push_str(&format!(
+                    "    {:>12} {:>8} calls  {}\n",
+                    format_us(stats.total_us),
+                    stats.count,
+                    stack.join(" → ")
+                ));
+            }
+        }
+        out
+    }
+
+    /// Write folded stacks if configured; returns the path written
+    pub fn write_folded(&self) -> Result<Option<&PathBuf>> {
+        let Some(path) = &self.config.folded_path else {
+            return Ok(None);
+        };
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        self.folded
+            .write_folded(std::io::BufWriter::new(file))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Some(path))
+    }
+}
+
+pub(crate) fn format_us(us: u64) -> String {
+    if us >= 1_000_000 {
+        format!("{:.3}s", us as f64 / 1_000_000.0)
+    } else if us >= 1_000 {
+        format!("{:.3}ms", us as f64 / 1_000.0)
+    } else {
+        format!("{}μs", us)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(function: &str) -> ResolvedFrame {
+        ResolvedFrame {
+            address: 0x1000,
+            function: Some(function.to_string()),
+            file: None,
+            line: None,
+            module: None,
+            inlined: false,
+        }
+    }
+
+    fn config() -> OffCpuConfig {
+        OffCpuConfig {
+            top: DEFAULT_TOP_BLOCKERS,
+            folded_path: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_fd_wait() {
+        let wait = classify_wait("read", 3, 0, |fd| Some(format!("pipe:[{}]", fd)));
+        assert_eq!(
+            wait,
+            Some(WaitObject::Fd {
+                fd: 3,
+                path: Some("pipe:[3]".to_string())
+            })
+        );
+        assert_eq!(wait.unwrap().to_string(), "fd 3 (pipe:[3])");
+    }
+
+    #[test]
+    fn test_classify_futex() {
+        // FUTEX_WAIT_PRIVATE = FUTEX_WAIT | FUTEX_PRIVATE_FLAG (128)
+        let wait = classify_wait("futex", 0x7ffd1000, 128, |_| None);
+        assert_eq!(wait, Some(WaitObject::Futex { addr: 0x7ffd1000 }));
+        assert_eq!(wait.unwrap().to_string(), "futex 0x7ffd1000");
+
+        // FUTEX_WAKE_PRIVATE never blocks
+        assert_eq!(classify_wait("futex", 0x7ffd1000, 129, |_| None), None);
+    }
+
+    #[test]
+    fn test_classify_other_kinds() {
+        assert_eq!(
+            classify_wait("clock_nanosleep", 0, 0, |_| None),
+            Some(WaitObject::Sleep)
+        );
+        assert_eq!(
+            classify_wait("poll", 0, 0, |_| None),
+            Some(WaitObject::Poll)
+        );
+        assert_eq!(
+            classify_wait("wait4", 0, 0, |_| None),
+            Some(WaitObject::Child)
+        );
+        assert_eq!(
+            classify_wait("rt_sigsuspend", 0, 0, |_| None),
+            Some(WaitObject::Signal)
+        );
+        assert_eq!(
+            classify_wait("semop", 0, 0, |_| None),
+            Some(WaitObject::Other)
+        );
+    }
+
+    #[test]
+    fn test_classify_non_blocking() {
+        assert_eq!(classify_wait("mmap", 0, 0, |_| None), None);
+        assert_eq!(classify_wait("getpid", 0, 0, |_| None), None);
+    }
+
+    #[test]
+    fn test_fd_path_self() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let fd = std::os::unix::io::AsRawFd::as_raw_fd(file.as_file());
+        let path = fd_path(Pid::this(), fd).unwrap();
+        assert_eq!(PathBuf::from(path), file.path().canonicalize().unwrap());
+        assert_eq!(fd_path(Pid::this(), -1), None);
+    }
+
+    #[test]
+    fn test_config_from_cli() {
+        assert_eq!(OffCpuConfig::from_cli(false, None, 10), None);
+        assert_eq!(OffCpuConfig::from_cli(true, None, 5).unwrap().top, 5);
+        let config = OffCpuConfig::from_cli(false, Some("off.folded"), 10).unwrap();
+        assert_eq!(config.folded_path, Some(PathBuf::from("off.folded")));
+    }
+
+    #[test]
+    fn test_record_groups_by_stack_and_object() {
+        let mut analyzer = OffCpuAnalyzer::new(config());
+        let pipe = WaitObject::Fd {
+            fd: 3,
+            path: Some("pipe:[42]".to_string()),
+        };
+        let stack = vec![frame("read_input"), frame("main")];
+
+        analyzer.record(100, "read", Some(&pipe), &stack, 500);
+        analyzer.record(100, "read", Some(&pipe), &stack, 1500);
+        analyzer.record(
+            100,
+            "futex",
+            Some(&WaitObject::Futex { addr: 0x10 }),
+            &[],
+            50,
+        );
+        analyzer.record(100, "mmap", None, &stack, 7);
+        analyzer.record(101, "nanosleep", Some(&WaitObject::Sleep), &[], 10_000);
+
+        let threads = analyzer.threads();
+        assert_eq!(threads.len(), 2);
+
+        let main = &threads[&100];
+        assert_eq!(main.blocked.count, 3);
+        assert_eq!(main.blocked.total_us, 2050);
+        assert_eq!(main.on_cpu.count, 1);
+        assert_eq!(main.on_cpu.total_us, 7);
+
+        let objects = main.top_objects(10);
+        assert_eq!(objects[0].0, &pipe);
+        assert_eq!(objects[0].1.count, 2);
+        assert_eq!(objects[0].1.max_us, 1500);
+
+        let stacks = main.top_stacks(1);
+        assert_eq!(stacks.len(), 1);
+        assert_eq!(
+            stacks[0].0,
+            &vec![
+                "main".to_string(),
+                "read_input".to_string(),
+                "read".to_string()
+            ]
+        );
+
+        assert_eq!(threads[&101].blocked.total_us, 10_000);
+    }
+
+    #[test]
+    fn test_regular_file_io_is_not_off_cpu() {
+        let fd = |path: &str| WaitObject::Fd {
+            fd: 3,
+            path: Some(path.to_string()),
+        };
+        let file = fd("/home/user/data.csv");
+        let mut analyzer = OffCpuAnalyzer::new(config());
+        analyzer.record(1, "read", Some(&file), &[frame("main")], 800);
+        analyzer.record(1, "fsync", Some(&file), &[frame("main")], 200);
+
+        let thread = &analyzer.threads()[&1];
+        assert_eq!(thread.blocked.count, 0);
+        assert_eq!(thread.on_cpu.total_us, 1000);
+        assert!(analyzer.folded().is_empty());
+
+        assert!(!is_off_cpu_wait("pwrite64", &file));
+        // Locks on regular files do block
+        assert!(is_off_cpu_wait("flock", &file));
+        assert!(is_off_cpu_wait("read", &fd("/dev/pts/0")));
+        assert!(is_off_cpu_wait("write", &fd("socket:[7]")));
+        assert!(is_off_cpu_wait("read", &fd("anon_inode:[eventfd]")));
+        assert!(is_off_cpu_wait(
+            "read",
+            &WaitObject::Fd { fd: 3, path: None }
+        ));
+    }
+
+    #[test]
+    fn test_folded_leaf_is_wait_object() {
+        let mut analyzer = OffCpuAnalyzer::new(config());
+        analyzer.record(
+            1,
+            "nanosleep",
+            Some(&WaitObject::Sleep),
+            &[frame("main")],
+            20,
+        );
+        analyzer.record(1, "getpid", None, &[frame("main")], 1);
+
+        let mut out = Vec::new();
+        analyzer.folded().write_folded(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "main;nanosleep;[sleep] 20\n"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let mut analyzer = OffCpuAnalyzer::new(config());
+        assert!(analyzer.report().contains("No blocking syscalls recorded"));
+
+        analyzer.record(
+            7,
+            "futex",
+            Some(&WaitObject::Futex { addr: 0xbeef }),
+            &[frame("lock")],
+            2500,
+        );
+        let report = analyzer.report();
+        assert!(report.contains("Thread 7: blocked 2.500ms in 1 calls"));
+        assert!(report.contains("futex 0xbeef"));
+        assert!(report.contains("lock → futex"));
+    }
+
+    #[test]
+    fn test_write_folded() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("off.folded");
+        let mut analyzer = OffCpuAnalyzer::new(OffCpuConfig {
+            top: 1,
+            folded_path: Some(path.clone()),
+        });
+        analyzer.record(1, "poll", Some(&WaitObject::Poll), &[], 3);
+
+        assert_eq!(analyzer.write_folded().unwrap(), Some(&path));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "poll;[poll set] 3\n"
+        );
+
+        assert_eq!(OffCpuAnalyzer::new(config()).write_folded().unwrap(), None);
+    }
+}
@@ -0,0 +1,803 @@
+//! pprof profile export (gzip-compressed `profile.proto`)
+//!
+//! Sprint 51: Writes function-level syscall profiles in the format used by
+//! `go tool pprof`, speedscope and other pprof-compatible viewers.
+//!
+//! Each sample is one unwound syscall stack (leaf = the syscall itself) with
+//! three values:
+//!
+//! | sample type    | unit        |
+//! |----------------|-------------|
+//! | `syscalls`     | count       |
+//! | `syscall_time` | nanoseconds |
+//! | `slow_io`      | count       |
+//!
+//! Locations, functions and mappings come from the DWARF/ELF resolution done
+//! by [`crate::stack_unwind::resolve_frames`] and `/proc/PID/maps`. Mappings
+//! and locations belong to the process they were seen in, and samples carry
+//! a `pid` label, so processes of a `-f` trace that load the same address
+//! with different code are kept apart.
+
+use crate::proc_maps::MemoryMapping;
+use crate::stack_unwind::ResolvedFrame;
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use prost::Message;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// ---------------------------------------------------------------------------
+// profile.proto messages (github.com/google/pprof/proto/profile.proto)
+// ---------------------------------------------------------------------------
+
+/// Top-level pprof profile
+#[derive(Clone, PartialEq, Message)]
+pub struct Profile {
+    #[prost(message, repeated, tag = "1")]
+    pub sample_type: Vec<ValueType>,
+    #[prost(message, repeated, tag = "2")]
+    pub sample: Vec<Sample>,
+    #[prost(message, repeated, tag = "3")]
+    pub mapping: Vec<Mapping>,
+    #[prost(message, repeated, tag = "4")]
+    pub location: Vec<Location>,
+    #[prost(message, repeated, tag = "5")]
+    pub function: Vec<Function>,
+    #[prost(string, repeated, tag = "6")]
+    pub string_table: Vec<String>,
+    #[prost(int64, tag = "7")]
+    pub drop_frames: i64,
+    #[prost(int64, tag = "8")]
+    pub keep_frames: i64,
+    #[prost(int64, tag = "9")]
+    pub time_nanos: i64,
+    #[prost(int64, tag = "10")]
+    pub duration_nanos: i64,
+    #[prost(message, optional, tag = "11")]
+    pub period_type: Option<ValueType>,
+    #[prost(int64, tag = "12")]
+    pub period: i64,
+    #[prost(int64, repeated, tag = "13")]
+    pub comment: Vec<i64>,
+    #[prost(int64, tag = "14")]
+    pub default_sample_type: i64,
+}
+
+/// Sample value type (indices into the string table)
+#[derive(Clone, PartialEq, Message)]
+pub struct ValueType {
+    #[prost(int64, tag = "1")]
+    pub r#type: i64,
+    #[prost(int64, tag = "2")]
+    pub unit: i64,
+}
+
+/// A single aggregated sample
+#[derive(Clone, PartialEq, Message)]
+pub struct Sample {
+    /// Location ids, leaf first
+    #[prost(uint64, repeated, tag = "1")]
+    pub location_id: Vec<u64>,
+    #[prost(int64, repeated, tag = "2")]
+    pub value: Vec<i64>,
+    #[prost(message, repeated, tag = "3")]
+    pub label: Vec<Label>,
+}
+
+/// Sample label
+#[derive(Clone, PartialEq, Message)]
+pub struct Label {
+    #[prost(int64, tag = "1")]
+    pub key: i64,
+    #[prost(int64, tag = "2")]
+    pub str: i64,
+    #[prost(int64, tag = "3")]
+    pub num: i64,
+    #[prost(int64, tag = "4")]
+    pub num_unit: i64,
+}
+
+/// Mapped binary or shared library
+#[derive(Clone, PartialEq, Message)]
+pub struct Mapping {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(uint64, tag = "2")]
+    pub memory_start: u64,
+    #[prost(uint64, tag = "3")]
+    pub memory_limit: u64,
+    #[prost(uint64, tag = "4")]
+    pub file_offset: u64,
+    #[prost(int64, tag = "5")]
+    pub filename: i64,
+    #[prost(int64, tag = "6")]
+    pub build_id: i64,
+    #[prost(bool, tag = "7")]
+    pub has_functions: bool,
+    #[prost(bool, tag = "8")]
+    pub has_filenames: bool,
+    #[prost(bool, tag = "9")]
+    pub has_line_numbers: bool,
+    #[prost(bool, tag = "10")]
+    pub has_inline_frames: bool,
+}
+
+/// Program counter location (one per unwound frame address)
+#[derive(Clone, PartialEq, Message)]
+pub struct Location {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(uint64, tag = "2")]
+    pub mapping_id: u64,
+    #[prost(uint64, tag = "3")]
+    pub address: u64,
+    /// Inline chain, innermost first
+    #[prost(message, repeated, tag = "4")]
+    pub line: Vec<Line>,
+    #[prost(bool, tag = "5")]
+    pub is_folded: bool,
+}
+
+/// Source line within a location
+#[derive(Clone, PartialEq, Message)]
+pub struct Line {
+    #[prost(uint64, tag = "1")]
+    pub function_id: u64,
+    #[prost(int64, tag = "2")]
+    pub line: i64,
+    #[prost(int64, tag = "3")]
+    pub column: i64,
+}
+
+/// Function metadata
+#[derive(Clone, PartialEq, Message)]
+pub struct Function {
+    #[prost(uint64, tag = "1")]
+    pub id: u64,
+    #[prost(int64, tag = "2")]
+    pub name: i64,
+    #[prost(int64, tag = "3")]
+    pub system_name: i64,
+    #[prost(int64, tag = "4")]
+    pub filename: i64,
+    #[prost(int64, tag = "5")]
+    pub start_line: i64,
+}
+
+// ---------------------------------------------------------------------------
+// Profile builder
+// ---------------------------------------------------------------------------
+
+/// Number of values per sample (count, time, slow I/O)
+const SAMPLE_VALUES: usize = 3;
+
+/// Identity of a location: process and address plus its inline chain
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LocationKey {
+    pid: i32,
+    address: u64,
+    functions: Vec<(String, Option<String>, Option<u32>)>,
+}
+
+/// Incrementally builds a pprof [`Profile`] from syscall stacks
+#[derive(Debug)]
+pub struct PprofBuilder {
+    strings: Vec<String>,
+    string_ids: HashMap<String, i64>,
+    functions: Vec<Function>,
+    function_ids: HashMap<(String, String), u64>,
+    locations: Vec<Location>,
+    location_ids: HashMap<LocationKey, u64>,
+    syscall_locations: HashMap<String, u64>,
+    mappings: Vec<Mapping>,
+    /// Process of each mapping, indexed like `mappings`
+    mapping_pids: Vec<i32>,
+    samples: HashMap<(i32, Vec<u64>), [i64; SAMPLE_VALUES]>,
+    sample_syscalls: HashMap<(i32, Vec<u64>), String>,
+    start_time: SystemTime,
+    start_instant: Instant,
+}
+
+impl Default for PprofBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PprofBuilder {
+    /// Create an empty builder; the profile start time is now
+    pub fn new() -> Self {
+        let mut builder = Self {
+            strings: Vec::new(),
+            string_ids: HashMap::new(),
+            functions: Vec::new(),
+            function_ids: HashMap::new(),
+            locations: Vec::new(),
+            location_ids: HashMap::new(),
+            syscall_locations: HashMap::new(),
+            mappings: Vec::new(),
+            mapping_pids: Vec::new(),
+            samples: HashMap::new(),
+            sample_syscalls: HashMap::new(),
+            start_time: SystemTime::now(),
+            start_instant: Instant::now(),
+        };
+        // string_table[0] must be the empty string
+        builder.string("");
+        builder
+    }
+
+    fn string(&mut self, s: &str) -> i64 {
+        if let Some(&id) = self.string_ids.get(s) {
+            return id;
+        }
+        let id = self.strings.len() as i64;
+        self.strings.push(s.to_string());
+        self.string_ids.insert(s.to_string(), id);
+        id
+    }
+
+    fn function(&mut self, name: &str, file: &str, system_name: &str) -> u64 {
+        let key = (name.to_string(), file.to_string());
+        if let Some(&id) = self.function_ids.get(&key) {
+            return id;
+        }
+        let id = self.functions.len() as u64 + 1;
+        let function = Function {
+            id,
+            name: self.string(name),
+            system_name: self.string(system_name),
+            filename: self.string(file),
+            start_line: 0,
+        };
+        self.functions.push(function);
+        self.function_ids.insert(key, id);
+        id
+    }
+
+    /// Register a memory mapping of process `pid`, returning its id
+    /// (deduplicated per process)
+    pub fn add_mapping(&mut self, pid: i32, mapping: &MemoryMapping) -> u64 {
+        if let Some(existing) = self
+            .process_mappings(pid)
+            .find(|m| m.memory_start == mapping.start && m.memory_limit == mapping.end)
+        {
+            return existing.id;
+        }
+        let id = self.mappings.len() as u64 + 1;
+        let filename = self.string(&mapping.path);
+        self.mappings.push(Mapping {
+            id,
+            memory_start: mapping.start,
+            memory_limit: mapping.end,
+            file_offset: mapping.offset,
+            filename,
+            ..Default::default()
+        });
+        self.mapping_pids.push(pid);
+        id
+    }
+
+    fn process_mappings(&self, pid: i32) -> impl Iterator<Item = &Mapping> {
+        self.mappings
+            .iter()
+            .zip(&self.mapping_pids)
+            .filter(move |(_, &owner)| owner == pid)
+            .map(|(mapping, _)| mapping)
+    }
+
+    fn mapping_for(&self, pid: i32, address: u64) -> Option<&Mapping> {
+        self.process_mappings(pid)
+            .find(|m| address >= m.memory_start && address < m.memory_limit)
+    }
+
+    /// Group an innermost-first frame list into inline chains
+    fn inline_groups(frames: &[ResolvedFrame]) -> Vec<&[ResolvedFrame]> {
+        let mut groups = Vec::new();
+        let mut start = 0;
+        for (i, frame) in frames.iter().enumerate() {
+            if !frame.inlined {
+                groups.push(&frames[start..=i]);
+                start = i + 1;
+            }
+        }
+        if start < frames.len() {
+            groups.push(&frames[start..]);
+        }
+        groups
+    }
+
+    fn location(&mut self, pid: i32, group: &[ResolvedFrame]) -> u64 {
+        let address = group[0].address;
+        let key = LocationKey {
+            pid,
+            address,
+            functions: group
+                .iter()
+                .filter_map(|f| {
+                    f.function
+                        .clone()
+                        .map(|name| (name, f.file.clone(), f.line))
+                })
+                .collect(),
+        };
+        if let Some(&id) = self.location_ids.get(&key) {
+            return id;
+        }
+
+        let mut lines = Vec::with_capacity(key.functions.len());
+        for (name, file, line) in &key.functions {
+            let function_id = self.function(name, file.as_deref().unwrap_or(""), name);
+            lines.push(Line {
+                function_id,
+                line: line.map(i64::from).unwrap_or(0),
+                column: 0,
+            });
+        }
+
+        let resolved = !lines.is_empty();
+        let has_files = key.functions.iter().any(|(_, file, _)| file.is_some());
+        let mapping_id = match self
+            .mapping_for(pid, address)
+            .map(|m| m.id)
+            .and_then(|id| self.mappings.get_mut(id as usize - 1))
+        {
+            Some(mapping) => {
+                mapping.has_functions |= resolved;
+                mapping.has_filenames |= has_files;
+                mapping.has_line_numbers |= has_files;
+                mapping.has_inline_frames |= lines.len() > 1;
+                mapping.id
+            }
+            None => 0,
+        };
+
+        let id = self.locations.len() as u64 + 1;
+        self.locations.push(Location {
+            id,
+            mapping_id,
+            address,
+            line: lines,
+            is_folded: false,
+        });
+        self.location_ids.insert(key, id);
+        id
+    }
+
+    /// Synthetic leaf location representing the syscall itself
+    fn syscall_location(&mut self, syscall: &str) -> u64 {
+        if let Some(&id) = self.syscall_locations.get(syscall) {
+            return id;
+        }
+        let function_id = self.function(syscall, "[kernel]", &format!("syscall:{}", syscall));
+        let id = self.locations.len() as u64 + 1;
+        self.locations.push(Location {
+            id,
+            mapping_id: 0,
+            address: 0,
+            line: vec![Line {
+                function_id,
+                line: 0,
+                column: 0,
+            }],
+            is_folded: false,
+        });
+        self.syscall_locations.insert(syscall.to_string(), id);
+        id
+    }
+
+    /// Whether an address of process `pid` is covered by a registered mapping
+    pub fn has_mapping_for(&self, pid: i32, address: u64) -> bool {
+        self.mapping_for(pid, address).is_some()
+    }
+
+    /// Record one syscall of process `pid` with its innermost-first stack
+    pub fn add_sample(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        frames: &[ResolvedFrame],
+        duration_us: u64,
+    ) {
+        let mut location_ids = vec![self.syscall_location(syscall)];
+        for group in Self::inline_groups(frames) {
+            location_ids.push(self.location(pid, group));
+        }
+
+        let slow_io = crate::function_profiler::is_slow_io(syscall, duration_us);
+        let key = (pid, location_ids);
+        let values = self
+            .samples
+            .entry(key.clone())
+            .or_insert([0; SAMPLE_VALUES]);
+        values[0] += 1;
+        values[1] += duration_us.saturating_mul(1000) as i64;
+        values[2] += i64::from(slow_io);
+
+        self.sample_syscalls
+            .entry(key)
+            .or_insert_with(|| syscall.to_string());
+    }
+
+    /// Number of distinct samples (unique stacks)
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Build the final profile
+    pub fn build(mut self) -> Profile {
+        let count = self.string("count");
+        let nanoseconds = self.string("nanoseconds");
+        let syscalls = self.string("syscalls");
+        let syscall_time = self.string("syscall_time");
+        let slow_io = self.string("slow_io");
+        let syscall_key = self.string("syscall");
+        let pid_key = self.string("pid");
+        let comment = self.string("renacer syscall profile");
+
+        let mut samples: Vec<((i32, Vec<u64>), [i64; SAMPLE_VALUES])> =
+            std::mem::take(&mut self.samples).into_iter().collect();
+        samples.sort();
+
+        let sample = samples
+            .into_iter()
+            .map(|(key, values)| {
+                let name = self.sample_syscalls.get(&key).cloned();
+                let (pid, location_id) = key;
+                let label = name
+                    .map(|name| Label {
+                        key: syscall_key,
+                        str: self.string(&name),
+                        num: 0,
+                        num_unit: 0,
+                    })
+                    .into_iter()
+                    .chain(std::iter::once(Label {
+                        key: pid_key,
+                        str: 0,
+                        num: i64::from(pid),
+                        num_unit: 0,
+                    }))
+                    .collect();
+                Sample {
+                    location_id,
+                    value: values.to_vec(),
+                    label,
+                }
+            })
+            .collect();
+
+        let time_nanos = self
+            .start_time
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as i64)
+            .unwrap_or(0);
+
+        Profile {
+            sample_type: vec![
+                ValueType {
+                    r#type: syscalls,
+                    unit: count,
+                },
+                ValueType {
+                    r#type: syscall_time,
+                    unit: nanoseconds,
+                },
+                ValueType {
+                    r#type: slow_io,
+                    unit: count,
+                },
+            ],
+            sample,
+            mapping: self.mappings,
+            location: self.locations,
+            function: self.functions,
+            string_table: self.strings,
+            drop_frames: 0,
+            keep_frames: 0,
+            time_nanos,
+            duration_nanos: self.start_instant.elapsed().as_nanos() as i64,
+            period_type: Some(ValueType {
+                r#type: syscalls,
+                unit: count,
+            }),
+            period: 1,
+            comment: vec![comment],
+            default_sample_type: syscall_time,
+        }
+    }
+}
+
+/// Encode a profile as gzip-compressed protobuf
+pub fn encode_gzip(profile: &Profile) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&profile.encode_to_vec())
+        .context("Failed to compress pprof profile")?;
+    encoder.finish().context("Failed to compress pprof profile")
+}
+
+/// Collects syscall stacks during tracing and writes a pprof file
+#[derive(Debug)]
+pub struct PprofCollector {
+    path: PathBuf,
+    builder: PprofBuilder,
+}
+
+impl PprofCollector {
+    /// Create a collector writing to `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            builder: PprofBuilder::new(),
+        }
+    }
+
+    /// Output path
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Record a syscall from process `pid`
+    ///
+    /// Mappings are read from `/proc/PID/maps` when a frame address is not
+    /// yet covered (libraries are loaded lazily).
+    pub fn record(
+        &mut self,
+        pid: nix::unistd::Pid,
+        syscall: &str,
+        frames: &[ResolvedFrame],
+        duration_us: u64,
+    ) {
+        let raw = pid.as_raw();
+        if frames
+            .iter()
+            .any(|frame| !self.builder.has_mapping_for(raw, frame.address))
+        {
+            if let Ok(maps) = crate::proc_maps::read_maps(pid) {
+                self.add_mappings(raw, &maps);
+            }
+        }
+        self.builder.add_sample(raw, syscall, frames, duration_us);
+    }
+
+    /// Add the executable mappings of process `pid`
+    ///
+    /// Sprint 55: Replayed traces carry their mappings instead of `/proc`.
+    pub fn add_mappings(&mut self, pid: i32, maps: &[MemoryMapping]) {
+        for mapping in maps.iter().filter(|m| m.is_executable()) {
+            self.builder.add_mapping(pid, mapping);
+        }
+    }
+
+    /// Number of unique stacks recorded so far
+    pub fn sample_count(&self) -> usize {
+        self.builder.sample_count()
+    }
+
+    /// Build the profile and write it to the output path
+    pub fn write(self) -> Result<()> {
+        let bytes = encode_gzip(&self.builder.build())?;
+        std::fs::write(&self.path, bytes)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn frame(address: u64, function: Option<&str>, inlined: bool) -> ResolvedFrame {
+        ResolvedFrame {
+            address,
+            function: function.map(|s| s.to_string()),
+            file: function.map(|_| "src/main.rs".to_string()),
+            line: function.map(|_| 10),
+            module: Some("app".to_string()),
+            inlined,
+        }
+    }
+
+    fn mapping() -> MemoryMapping {
+        MemoryMapping {
+            start: 0x1000,
+            end: 0x9000,
+            perms: "r-xp".to_string(),
+            offset: 0,
+            path: "/usr/bin/app".to_string(),
+        }
+    }
+
+    fn string(profile: &Profile, idx: i64) -> &str {
+        &profile.string_table[idx as usize]
+    }
+
+    #[test]
+    fn test_profile_sample_types() {
+        let profile = PprofBuilder::new().build();
+        assert_eq!(profile.string_table[0], "");
+
+        let types: Vec<(&str, &str)> = profile
+            .sample_type
+            .iter()
+            .map(|vt| (string(&profile, vt.r#type), string(&profile, vt.unit)))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("syscalls", "count"),
+                ("syscall_time", "nanoseconds"),
+                ("slow_io", "count")
+            ]
+        );
+        assert_eq!(
+            string(&profile, profile.default_sample_type),
+            "syscall_time"
+        );
+        assert_eq!(profile.period, 1);
+    }
+
+    #[test]
+    fn test_samples_aggregate_identical_stacks() {
+        let mut builder = PprofBuilder::new();
+        builder.add_mapping(1, &mapping());
+        let stack = vec![
+            frame(0x1100, Some("app::write"), false),
+            frame(0x1200, Some("main"), false),
+        ];
+        builder.add_sample(1, "write", &stack, 2000);
+        builder.add_sample(1, "write", &stack, 10);
+        builder.add_sample(1, "read", &stack, 5);
+
+        let profile = builder.build();
+        assert_eq!(profile.sample.len(), 2);
+
+        let write = profile
+            .sample
+            .iter()
+            .find(|s| s.value[0] == 2)
+            .expect("aggregated write sample");
+        assert_eq!(write.value, vec![2, 2_010_000, 1]);
+        assert_eq!(write.location_id.len(), 3);
+        assert_eq!(string(&profile, write.label[0].key), "syscall");
+        assert_eq!(string(&profile, write.label[0].str), "write");
+
+        // Leaf location is the syscall itself
+        let leaf = &profile.location[(write.location_id[0] - 1) as usize];
+        let leaf_fn = &profile.function[(leaf.line[0].function_id - 1) as usize];
+        assert_eq!(string(&profile, leaf_fn.name), "write");
+    }
+
+    #[test]
+    fn test_inline_frames_share_location() {
+        let mut builder = PprofBuilder::new();
+        builder.add_mapping(1, &mapping());
+        let stack = vec![
+            frame(0x1100, Some("inner_inlined"), true),
+            frame(0x1100, Some("outer"), false),
+            frame(0x1200, Some("main"), false),
+        ];
+        builder.add_sample(1, "write", &stack, 1);
+
+        let profile = builder.build();
+        // syscall leaf + inline chain + main
+        assert_eq!(profile.location.len(), 3);
+        let chain = profile
+            .location
+            .iter()
+            .find(|l| l.address == 0x1100)
+            .unwrap();
+        assert_eq!(chain.line.len(), 2);
+        let inner = &profile.function[(chain.line[0].function_id - 1) as usize];
+        assert_eq!(string(&profile, inner.name), "inner_inlined");
+        assert_eq!(chain.line[0].line, 10);
+
+        assert_eq!(profile.mapping.len(), 1);
+        assert!(profile.mapping[0].has_functions);
+        assert!(profile.mapping[0].has_inline_frames);
+        assert_eq!(chain.mapping_id, profile.mapping[0].id);
+    }
+
+    #[test]
+    fn test_unresolved_frames_keep_address() {
+        let mut builder = PprofBuilder::new();
+        builder.add_mapping(1, &mapping());
+        builder.add_sample(1, "read", &[frame(0x1300, None, false)], 1);
+
+        let profile = builder.build();
+        let loc = profile
+            .location
+            .iter()
+            .find(|l| l.address == 0x1300)
+            .unwrap();
+        assert!(loc.line.is_empty());
+        assert_eq!(loc.mapping_id, 1);
+        assert!(!profile.mapping[0].has_functions);
+    }
+
+    #[test]
+    fn test_mappings_deduplicated() {
+        let mut builder = PprofBuilder::new();
+        assert_eq!(builder.add_mapping(1, &mapping()), 1);
+        assert_eq!(builder.add_mapping(1, &mapping()), 1);
+        assert!(builder.has_mapping_for(1, 0x1000));
+        assert!(!builder.has_mapping_for(1, 0x9000));
+        assert!(!builder.has_mapping_for(2, 0x1000));
+    }
+
+    #[test]
+    fn test_mappings_per_process() {
+        let mut builder = PprofBuilder::new();
+        let library = MemoryMapping {
+            path: "/usr/lib/libfoo.so".to_string(),
+            ..mapping()
+        };
+        assert_eq!(builder.add_mapping(1, &mapping()), 1);
+        assert_eq!(builder.add_mapping(2, &library), 2);
+
+        // The same address is different code in each process
+        builder.add_sample(1, "write", &[frame(0x1100, Some("main"), false)], 1);
+        builder.add_sample(2, "write", &[frame(0x1100, Some("foo"), false)], 1);
+        builder.add_sample(2, "write", &[frame(0x1100, None, false)], 1);
+
+        let profile = builder.build();
+        let mapping_of = |pid: i64| {
+            let sample = profile
+                .sample
+                .iter()
+                .find(|s| s.label[1].num == pid)
+                .unwrap();
+            let location = &profile.location[(sample.location_id[1] - 1) as usize];
+            string(
+                &profile,
+                profile.mapping[location.mapping_id as usize - 1].filename,
+            )
+        };
+        assert_eq!(mapping_of(1), "/usr/bin/app");
+        assert_eq!(mapping_of(2), "/usr/lib/libfoo.so");
+        assert_eq!(string(&profile, profile.sample[0].label[1].key), "pid");
+        assert_eq!(profile.sample.len(), 3);
+    }
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let mut builder = PprofBuilder::new();
+        builder.add_sample(1, "write", &[frame(0x1100, Some("main"), false)], 42);
+        let profile = builder.build();
+
+        let bytes = encode_gzip(&profile).unwrap();
+        assert_eq!(&bytes[..2], &[0x1f, 0x8b]); // gzip magic
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(&bytes[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        let decoded = Profile::decode(&decoded[..]).unwrap();
+        assert_eq!(decoded, profile);
+    }
+
+    #[test]
+    fn test_collector_writes_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("profile.pb.gz");
+
+        let mut collector = PprofCollector::new(&path);
+        collector.record(nix::unistd::Pid::this(), "write", &[], 5);
+        assert_eq!(collector.sample_count(), 1);
+        collector.write().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let mut decoded = Vec::new();
+        GzDecoder::new(&bytes[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        let profile = Profile::decode(&decoded[..]).unwrap();
+        assert_eq!(profile.sample.len(), 1);
+        assert_eq!(profile.sample[0].value, vec![1, 5000, 0]);
+    }
+}
@@ -0,0 +1,208 @@
+//! Memory map parsing for traced processes
+//!
+//! Sprint 50: Parse `/proc/PID/maps` so unwound return addresses can be
+//! attributed to the binary or shared library they belong to, and so PIE
+//! executables can be relocated before DWARF lookups.
+
+use anyhow::{Context, Result};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A single mapping from `/proc/PID/maps`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryMapping {
+    /// Start address (inclusive)
+    pub start: u64,
+    /// End address (exclusive)
+    pub end: u64,
+    /// Permission string (e.g. "r-xp")
+    pub perms: String,
+    /// Offset into the mapped file
+    pub offset: u64,
+    /// Backing file path or pseudo-name ("[heap]", "[stack]"), empty if anonymous
+    pub path: String,
+}
+
+impl MemoryMapping {
+    /// Whether the address falls inside this mapping
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.start && addr < self.end
+    }
+
+    /// Whether the mapping is executable
+    pub fn is_executable(&self) -> bool {
+        self.perms.as_bytes().get(2) == Some(&b'x')
+    }
+
+    /// Whether the mapping is shared with other processes (`MAP_SHARED`)
+    pub fn is_shared(&self) -> bool {
+        self.perms.as_bytes().get(3) == Some(&b's')
+    }
+
+    /// Short module name (file name of the backing path)
+    pub fn module_name(&self) -> Option<&str> {
+        if self.path.is_empty() {
+            return None;
+        }
+        if self.path.starts_with('[') {
+            return Some(&self.path);
+        }
+        Path::new(&self.path)
+            .file_name()
+            .and_then(|name| name.to_str())
+    }
+}
+
+/// Parse the contents of a `/proc/PID/maps` file
+///
+/// Malformed lines are skipped.
+pub fn parse_maps(contents: &str) -> Vec<MemoryMapping> {
+    contents.lines().filter_map(parse_maps_line).collect()
+}
+
+fn parse_maps_line(line: &str) -> Option<MemoryMapping> {
+    // Format: start-end perms offset dev inode [path]
+    let mut fields = line.split_whitespace();
+    let range = fields.next()?;
+    let perms = fields.next()?;
+    let offset = fields.next()?;
+    let _dev = fields.next()?;
+    let _inode = fields.next()?;
+    let path = fields.collect::<Vec<_>>().join(" ");
+
+    let (start, end) = range.split_once('-')?;
+
+    Some(MemoryMapping {
+        start: u64::from_str_radix(start, 16).ok()?,
+        end: u64::from_str_radix(end, 16).ok()?,
+        perms: perms.to_string(),
+        offset: u64::from_str_radix(offset, 16).ok()?,
+        path,
+    })
+}
+
+/// Read and parse the memory maps of a process
+pub fn read_maps(pid: Pid) -> Result<Vec<MemoryMapping>> {
+    let path = format!("/proc/{}/maps", pid);
+    let contents =
+        std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))?;
+    Ok(parse_maps(&contents))
+}
+
+/// Thread group (process) of thread `tid`, from `/proc/<tid>/status`
+pub fn thread_group(tid: i32) -> Option<i32> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", tid)).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Tgid:"))
+        .and_then(|tgid| tgid.trim().parse().ok())
+}
+
+/// Parent process of `pid`, from `/proc/<pid>/status`
+pub fn parent_pid(pid: i32) -> Option<i32> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("PPid:"))
+        .and_then(|ppid| ppid.trim().parse().ok())
+}
+
+/// Find the mapping that contains an address
+pub fn find_mapping(maps: &[MemoryMapping], addr: u64) -> Option<&MemoryMapping> {
+    maps.iter().find(|mapping| mapping.contains(addr))
+}
+
+/// Lowest address at which a file is mapped
+///
+/// For a PIE executable this is the runtime base that DWARF addresses are
+/// relative to (once the image base is subtracted).
+pub fn base_address(maps: &[MemoryMapping], path: &Path) -> Option<u64> {
+    let path = path.to_str()?;
+    maps.iter()
+        .filter(|mapping| mapping.path == path)
+        .map(|mapping| mapping.start.saturating_sub(mapping.offset))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_MAPS: &str = "\
+55d4c8a00000-55d4c8a02000 r--p 00000000 08:01 1234 /usr/bin/app
+55d4c8a02000-55d4c8a08000 r-xp 00002000 08:01 1234 /usr/bin/app
+55d4c9e00000-55d4c9e21000 rw-p 00000000 00:00 0 [heap]
+7f1e2a000000-7f1e2a028000 r--p 00000000 08:01 5678 /usr/lib/x86_64-linux-gnu/libc.so.6
+7f1e2a028000-7f1e2a1bd000 r-xp 00028000 08:01 5678 /usr/lib/x86_64-linux-gnu/libc.so.6
+7f1e2a300000-7f1e2a301000 rw-p 00000000 00:00 0
+7ffc1b000000-7ffc1b021000 rw-p 00000000 00:00 0 [stack]
+";
+
+    #[test]
+    fn test_parse_maps() {
+        let maps = parse_maps(SAMPLE_MAPS);
+        assert_eq!(maps.len(), 7);
+        assert_eq!(maps[0].start, 0x55d4c8a00000);
+        assert_eq!(maps[1].offset, 0x2000);
+        assert_eq!(maps[1].path, "/usr/bin/app");
+        assert!(maps[1].is_executable());
+        assert!(!maps[0].is_executable());
+        assert_eq!(maps[5].path, "");
+    }
+
+    #[test]
+    fn test_parse_maps_skips_malformed_lines() {
+        let maps = parse_maps("garbage\nzz-yy r--p 0 0 0\n");
+        assert!(maps.is_empty());
+    }
+
+    #[test]
+    fn test_find_mapping_and_module_name() {
+        let maps = parse_maps(SAMPLE_MAPS);
+        let libc = find_mapping(&maps, 0x7f1e2a030000).unwrap();
+        assert_eq!(libc.module_name(), Some("libc.so.6"));
+
+        let heap = find_mapping(&maps, 0x55d4c9e00010).unwrap();
+        assert_eq!(heap.module_name(), Some("[heap]"));
+
+        let anon = find_mapping(&maps, 0x7f1e2a300000).unwrap();
+        assert_eq!(anon.module_name(), None);
+
+        assert!(find_mapping(&maps, 0x1000).is_none());
+    }
+
+    #[test]
+    fn test_base_address() {
+        let maps = parse_maps(SAMPLE_MAPS);
+        assert_eq!(
+            base_address(&maps, Path::new("/usr/bin/app")),
+            Some(0x55d4c8a00000)
+        );
+        assert_eq!(
+            base_address(&maps, Path::new("/usr/lib/x86_64-linux-gnu/libc.so.6")),
+            Some(0x7f1e2a000000)
+        );
+        assert_eq!(base_address(&maps, Path::new("/missing")), None);
+    }
+
+    #[test]
+    fn test_read_maps_self() {
+        let maps = read_maps(Pid::this()).unwrap();
+        assert!(maps.iter().any(|m| m.is_executable()));
+    }
+
+    #[test]
+    fn test_thread_group() {
+        let pid = std::process::id() as i32;
+        let thread = std::thread::spawn(|| thread_group(nix::unistd::gettid().as_raw()));
+        assert_eq!(thread_group(pid), Some(pid));
+        assert_eq!(thread.join().unwrap(), Some(pid));
+    }
+
+    #[test]
+    fn test_parent_pid() {
+        let pid = std::process::id() as i32;
+        assert_eq!(parent_pid(pid), Some(nix::unistd::getppid().as_raw()));
+    }
+}
@@ -0,0 +1,1197 @@
+//! Process tree spans and `--critical-path` (Sprint 71)
+//!
+//! The tracer records every traced process and its completed syscalls. At
+//! the end they become [`SpanRecord`]s for [`CausalGraph`]:
+//!
+//! ```text
+//! make (process)
+//! ├─ openat → read → clone ─┐
+//! │                          └─ sh (process) → execve → ... → exit
+//! └─ ... → wait4 ←───────────────────────────────── join ─┘
+//! ```
+//!
+//! - A process span is the child of the `clone`/`fork`/`vfork` that
+//!   returned its pid; the traced program is the root.
+//! - Syscall spans follow each other in program order.
+//! - A `wait4` that reaped a child gets an extra edge from the child's last
+//!   syscall. If the parent slept in an earlier syscall while the child
+//!   finished (a shell's `wait` sleeps in `rt_sigsuspend`), the edge goes to
+//!   that syscall instead.
+//! - Sprint 72: A blocking syscall gets a wait-for edge from the syscall of
+//!   another process or thread that woke it: a `read`/`recv*` from the
+//!   `write`/`send*` on the same pipe or the other end of a socketpair, a
+//!   `futex` wait from the `futex` wake on the same word. The waker must
+//!   have started while the sleeper was blocked. Span logical clocks follow
+//!   all edges (Lamport), not just program order.
+//!
+//! Span durations telescope: a span covers the time from the end of its
+//! latest predecessor to its own end, i.e. the user-space work before the
+//! syscall plus the syscall itself. A `wait4` that joined a child only
+//! covers the time after the child finished. No time is counted twice
+//! along a chain, so [`find_critical_path`] returns at most the wall-clock
+//! time, and its path is the chain of steps that made the run that long.
+
+use crate::causal_graph::CausalGraph;
+use crate::critical_path::find_critical_path;
+use crate::lamport_clock::LamportClock;
+use crate::offcpu::WaitObject;
+use crate::span_record::{SpanKind, SpanRecord, StatusCode};
+use anyhow::Result;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Syscalls that return the pid of a new process or thread
+const SPAWN_SYSCALLS: &[&str] = &["clone", "clone3", "fork", "vfork"];
+
+/// Syscalls that return the pid of a reaped child
+const WAIT_SYSCALLS: &[&str] = &["wait4", "waitpid"];
+
+/// Syscalls that sleep until data arrives on their fd
+const RECEIVE_SYSCALLS: &[&str] = &["read", "readv", "recvfrom", "recvmsg", "recvmmsg"];
+
+/// Syscalls that wake a reader on the other end of their fd
+const SEND_SYSCALLS: &[&str] = &["write", "writev", "sendto", "sendmsg", "sendmmsg"];
+
+// futex(2) operations that wake sleepers on `uaddr`
+const FUTEX_CMD_MASK: u64 = 0x7f;
+const FUTEX_WAKE_OPS: &[u64] = &[1, 3, 4, 5, 7, 10]; // WAKE, (CMP_)REQUEUE, WAKE_OP, UNLOCK_PI, WAKE_BITSET
+
+/// `clone` flag: the child shares the parent's address space
+const CLONE_VM: u64 = 0x100;
+
+/// Trace id shared by all spans of a run
+const TRACE_ID: [u8; 16] = *b"renacer-procTree";
+
+/// Something one syscall waits on and another signals (Sprint 72)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// A pipe or socket, by its `/proc/PID/fd` target (`pipe:[1234]`)
+    Fd(String),
+    /// A futex word in one address space
+    Futex { space: usize, addr: u64 },
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Fd(path) => write!(f, "{}", path),
+            Channel::Futex { addr, .. } => write!(f, "futex {:#x}", addr),
+        }
+    }
+}
+
+/// A completed syscall for [`ProcessTreeTracker::record_syscall`]
+#[derive(Debug, Clone)]
+pub struct SyscallEvent<'a> {
+    pub pid: i32,
+    pub name: &'a str,
+    pub result: i64,
+    pub timestamp_nanos: u64,
+    pub duration_nanos: u64,
+    /// Decoded arguments (the fd pair of `pipe`/`socketpair`)
+    pub args: &'a [String],
+    /// First three raw argument registers (`clone` flags, futex word and op)
+    pub raw_args: [u64; 3],
+    /// What the syscall blocked on, resolved at entry
+    pub wait_object: Option<&'a WaitObject>,
+}
+
+impl<'a> SyscallEvent<'a> {
+    pub fn new(
+        pid: i32,
+        name: &'a str,
+        result: i64,
+        timestamp_nanos: u64,
+        duration_nanos: u64,
+    ) -> Self {
+        Self {
+            pid,
+            name,
+            result,
+            timestamp_nanos,
+            duration_nanos,
+            args: &[],
+            raw_args: [0; 3],
+            wait_object: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Process {
+    pid: i32,
+    /// Executable name; empty until known
+    program: String,
+    /// Index of the syscall that returned this pid
+    spawned_by: Option<usize>,
+    /// Indices into [`ProcessTreeTracker::syscalls`], in program order
+    syscalls: Vec<usize>,
+    /// Process whose address space this one runs in (futex words are per space)
+    space: usize,
+}
+
+#[derive(Debug)]
+struct Syscall {
+    process: usize,
+    name: String,
+    result: i64,
+    timestamp_nanos: u64,
+    duration_nanos: u64,
+    /// Reaped processes that finished while this syscall was blocked
+    joins: Vec<usize>,
+    /// Channel this syscall slept on until another thread signalled it
+    waits_on: Option<Channel>,
+    /// Channel this syscall signalled
+    wakes: Option<Channel>,
+}
+
+impl Syscall {
+    fn end_nanos(&self) -> u64 {
+        self.timestamp_nanos + self.duration_nanos
+    }
+}
+
+/// A syscall woken by a syscall of another process or thread
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Wake {
+    waker: usize,
+    sleeper: usize,
+}
+
+/// How an edge into a span came about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    /// Program order, or a process starting at its spawning syscall
+    Program,
+    /// A reaped child's last syscall
+    Join,
+    /// A syscall of another process or thread woke this one
+    Wake,
+}
+
+/// Span times and logical clocks, indexed like [`ProcessTreeTracker::spans`]
+#[derive(Debug)]
+struct Timeline {
+    ready: Vec<u64>,
+    done: Vec<u64>,
+    logical: Vec<u64>,
+    /// Wake edges that were kept (none may close a cycle)
+    wakes: Vec<Wake>,
+}
+
+/// Records processes and syscalls of a run for the critical path
+#[derive(Debug)]
+pub struct ProcessTreeTracker {
+    started: Instant,
+    processes: Vec<Process>,
+    /// Live pid → index into `processes` (pids are reused after a `wait4`)
+    by_pid: HashMap<i32, usize>,
+    syscalls: Vec<Syscall>,
+    /// Socketpair ends, both ways (`socket:[1]` ↔ `socket:[2]`)
+    peers: HashMap<String, String>,
+}
+
+impl Default for ProcessTreeTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessTreeTracker {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            processes: Vec::new(),
+            by_pid: HashMap::new(),
+            syscalls: Vec::new(),
+            peers: HashMap::new(),
+        }
+    }
+
+    /// Open the root process span for the traced program
+    pub fn start(&mut self, pid: i32, program: &str) {
+        *self = Self::new();
+        let root = self.process(pid);
+        self.processes[root].program = executable_name(program);
+    }
+
+    /// Nanoseconds since [`start`](Self::start) (timestamps for live tracing)
+    pub fn elapsed_nanos(&self) -> u64 {
+        self.started.elapsed().as_nanos() as u64
+    }
+
+    /// A successful execve/execveat renames the process and replaces its
+    /// address space
+    pub fn exec(&mut self, pid: i32, program: &str) {
+        let process = self.process(pid);
+        self.processes[process].program = executable_name(program);
+        self.processes[process].space = process;
+    }
+
+    /// Add a completed syscall of `pid` known only by name and result
+    pub fn record(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        result: i64,
+        timestamp_nanos: u64,
+        duration_nanos: u64,
+    ) {
+        self.record_syscall(&SyscallEvent::new(
+            pid,
+            syscall,
+            result,
+            timestamp_nanos,
+            duration_nanos,
+        ));
+    }
+
+    /// Add a completed syscall
+    ///
+    /// Spawning syscalls link the new pid to this syscall; a `wait4` that
+    /// reaped a child joins the child back in. Pipe, socket and futex
+    /// syscalls are kept for the wait-for edges.
+    pub fn record_syscall(&mut self, call: &SyscallEvent) {
+        let process = self.process(call.pid);
+        let index = self.syscalls.len();
+        let mut joins = Vec::new();
+        if call.result > 0 {
+            let target = call.result as i32;
+            if SPAWN_SYSCALLS.contains(&call.name) {
+                let shares_memory = shares_memory(call.name, call.raw_args[0]);
+                self.spawned(target, process, index, shares_memory);
+            } else if WAIT_SYSCALLS.contains(&call.name) {
+                if let Some(child) = self.by_pid.remove(&target) {
+                    match self.waiter(process, child) {
+                        Some(waiter) => self.syscalls[waiter].joins.push(child),
+                        None => joins.push(child),
+                    }
+                }
+            }
+        }
+        if call.name == "socketpair" && call.result == 0 {
+            if let Some([(_, Some(first)), (_, Some(second))]) =
+                call.args.get(3).and_then(|arg| parse_fd_pair(arg))
+            {
+                self.peers.insert(first.clone(), second.clone());
+                self.peers.insert(second, first);
+            }
+        }
+        let (waits_on, wakes) = self.channels(process, call);
+
+        self.syscalls.push(Syscall {
+            process,
+            name: call.name.to_string(),
+            result: call.result,
+            timestamp_nanos: call.timestamp_nanos,
+            duration_nanos: call.duration_nanos,
+            joins,
+            waits_on,
+            wakes,
+        });
+        self.processes[process].syscalls.push(index);
+    }
+
+    /// The channel a syscall slept on, and the channel it signalled
+    fn channels(&self, process: usize, call: &SyscallEvent) -> (Option<Channel>, Option<Channel>) {
+        let space = self.processes[process].space;
+        match call.wait_object {
+            Some(WaitObject::Fd {
+                path: Some(path), ..
+            }) if call.result > 0 && is_ipc(path) => {
+                if RECEIVE_SYSCALLS.contains(&call.name) {
+                    return (Some(Channel::Fd(path.clone())), None);
+                }
+                if SEND_SYSCALLS.contains(&call.name) {
+                    // A pipe has one inode; a socketpair end wakes its peer
+                    let target = match self.peers.get(path) {
+                        Some(peer) => Some(peer.clone()),
+                        None => path.starts_with("pipe:").then(|| path.clone()),
+                    };
+                    return (None, target.map(Channel::Fd));
+                }
+                (None, None)
+            }
+            Some(WaitObject::Futex { addr }) if call.result == 0 => {
+                (Some(Channel::Futex { space, addr: *addr }), None)
+            }
+            _ if call.name == "futex"
+                && call.result > 0
+                && FUTEX_WAKE_OPS.contains(&(call.raw_args[1] & FUTEX_CMD_MASK)) =>
+            {
+                let addr = call.raw_args[0];
+                (None, Some(Channel::Futex { space, addr }))
+            }
+            _ => (None, None),
+        }
+    }
+
+    /// The earlier syscall of `process` that was blocked when `child` finished
+    ///
+    /// A shell's `wait` sleeps in `rt_sigsuspend` until SIGCHLD and only
+    /// then reaps the child, so the child's exit is what ended that syscall.
+    fn waiter(&self, process: usize, child: usize) -> Option<usize> {
+        let child_end = self.syscalls[*self.processes[child].syscalls.last()?].end_nanos();
+        for &syscall in self.processes[process].syscalls.iter().rev() {
+            let candidate = &self.syscalls[syscall];
+            if candidate.end_nanos() < child_end {
+                break;
+            }
+            if candidate.timestamp_nanos <= child_end {
+                return Some(syscall);
+            }
+        }
+        None
+    }
+
+    /// Index of the live process `pid`, added on first sight
+    fn process(&mut self, pid: i32) -> usize {
+        let processes = &mut self.processes;
+        *self.by_pid.entry(pid).or_insert_with(|| {
+            processes.push(Process {
+                pid,
+                program: String::new(),
+                spawned_by: None,
+                syscalls: Vec::new(),
+                space: processes.len(),
+            });
+            processes.len() - 1
+        })
+    }
+
+    /// Link `pid` to the syscall that created it
+    ///
+    /// The child often runs before the parent's `clone` returns, so it may
+    /// already have been seen.
+    fn spawned(&mut self, pid: i32, parent: usize, syscall: usize, shares_memory: bool) {
+        let child = match self.by_pid.get(&pid) {
+            Some(&child) if child != 0 && self.processes[child].spawned_by.is_none() => child,
+            _ => {
+                self.by_pid.remove(&pid);
+                self.process(pid)
+            }
+        };
+        self.processes[child].spawned_by = Some(syscall);
+        if self.processes[child].program.is_empty() {
+            self.processes[child].program = self.processes[parent].program.clone();
+        }
+        if shares_memory {
+            self.processes[child].space = self.processes[parent].space;
+        }
+    }
+
+    /// Spans of the run: processes first, then syscalls in completion order
+    ///
+    /// Span ids are the big-endian index into this list, plus one.
+    pub fn spans(&self) -> Vec<SpanRecord> {
+        self.spans_of(&self.timeline())
+    }
+
+    fn spans_of(&self, timeline: &Timeline) -> Vec<SpanRecord> {
+        let mut spans = Vec::with_capacity(self.processes.len() + self.syscalls.len());
+
+        for (index, process) in self.processes.iter().enumerate() {
+            let mut attributes = HashMap::new();
+            attributes.insert("process.pid".to_string(), process.pid.to_string());
+            spans.push(SpanRecord::new(
+                TRACE_ID,
+                self.process_span_id(index),
+                process.spawned_by.map(|s| self.syscall_span_id(s)),
+                self.program(index),
+                SpanKind::Internal,
+                timeline.ready[index],
+                timeline.done[index],
+                timeline.logical[index],
+                StatusCode::Ok,
+                String::new(),
+                attributes,
+                HashMap::new(),
+                process.pid as u32,
+                process.pid as u64,
+            ));
+        }
+
+        for (index, syscall) in self.syscalls.iter().enumerate() {
+            let process = &self.processes[syscall.process];
+            let parent = match self.previous(index) {
+                Some(previous) => self.syscall_span_id(previous),
+                None => self.process_span_id(syscall.process),
+            };
+            let node = self.processes.len() + index;
+
+            let mut attributes = HashMap::new();
+            attributes.insert("syscall.result".to_string(), syscall.result.to_string());
+            if let Some(channel) = syscall.waits_on.as_ref().or(syscall.wakes.as_ref()) {
+                attributes.insert("syscall.channel".to_string(), channel.to_string());
+            }
+            spans.push(SpanRecord::new(
+                TRACE_ID,
+                self.syscall_span_id(index),
+                Some(parent),
+                syscall.name.clone(),
+                SpanKind::Internal,
+                timeline.ready[node],
+                timeline.done[node],
+                timeline.logical[node],
+                if syscall.result < 0 {
+                    StatusCode::Error
+                } else {
+                    StatusCode::Ok
+                },
+                String::new(),
+                attributes,
+                HashMap::new(),
+                process.pid as u32,
+                process.pid as u64,
+            ));
+        }
+        spans
+    }
+
+    /// Causal graph of [`spans`](Self::spans) with the `wait4` join and
+    /// wait-for edges
+    pub fn graph(&self) -> Result<CausalGraph> {
+        self.graph_of(&self.timeline())
+    }
+
+    fn graph_of(&self, timeline: &Timeline) -> Result<CausalGraph> {
+        let mut graph = CausalGraph::from_spans(&self.spans_of(timeline))?;
+        for (index, syscall) in self.syscalls.iter().enumerate() {
+            for &child in &syscall.joins {
+                let last = match self.processes[child].syscalls.last() {
+                    Some(&last) => self.syscall_span_id(last),
+                    None => self.process_span_id(child),
+                };
+                graph.add_edge(&last, &self.syscall_span_id(index))?;
+            }
+        }
+        for wake in &timeline.wakes {
+            graph.add_edge(
+                &self.syscall_span_id(wake.waker),
+                &self.syscall_span_id(wake.sleeper),
+            )?;
+        }
+        Ok(graph)
+    }
+
+    /// Find the longest dependency chain and group it into per-process steps
+    pub fn finish(self) -> Result<CriticalPathReport> {
+        let timeline = self.timeline();
+        let graph = self.graph_of(&timeline)?;
+        let path = find_critical_path(&graph)?;
+
+        let mut steps: Vec<CriticalPathStep> = Vec::new();
+        let mut previous: Option<usize> = None;
+        for node in &path.path {
+            let Some(span) = graph.get_span(*node) else {
+                continue;
+            };
+            let index = u64::from_be_bytes(span.span_id) as usize - 1;
+            let (process, syscall) = match index.checked_sub(self.processes.len()) {
+                Some(s) => (self.syscalls[s].process, Some(s)),
+                None => (index, None),
+            };
+            let time = Duration::from_nanos(span.duration_nanos);
+
+            if steps.last().map(|step| step.process) != Some(process) {
+                steps.push(CriticalPathStep {
+                    process,
+                    pid: self.processes[process].pid,
+                    program: self.program(process),
+                    time: Duration::ZERO,
+                    syscalls: 0,
+                    longest: None,
+                    via: previous.and_then(|from| self.via(from, index, &timeline)),
+                });
+            }
+            let step = steps.last_mut().expect("step was just pushed");
+            step.time += time;
+            if let Some(s) = syscall {
+                step.syscalls += 1;
+                if step
+                    .longest
+                    .as_ref()
+                    .is_none_or(|(_, longest)| time > *longest)
+                {
+                    step.longest = Some((self.syscalls[s].name.clone(), time));
+                }
+            }
+            previous = Some(index);
+        }
+
+        let start = self.syscalls.iter().map(|s| s.timestamp_nanos).min();
+        let end = timeline.done.iter().copied().max();
+        Ok(CriticalPathReport {
+            wall_time: Duration::from_nanos(end.unwrap_or(0) - start.unwrap_or(0)),
+            length: Duration::from_nanos(path.total_duration),
+            processes: self.processes.len(),
+            steps,
+        })
+    }
+
+    /// How the critical path got from span `from` to span `to` of another process
+    fn via(&self, from: usize, to: usize, timeline: &Timeline) -> Option<String> {
+        let processes = self.processes.len();
+        let (Some(from), Some(to)) = (from.checked_sub(processes), to.checked_sub(processes))
+        else {
+            // Into a process span: the spawning syscall
+            return self.processes[to]
+                .spawned_by
+                .map(|s| self.syscalls[s].name.clone());
+        };
+        if self.syscalls[to]
+            .joins
+            .contains(&self.syscalls[from].process)
+        {
+            return Some("exit".to_string());
+        }
+        timeline
+            .wakes
+            .iter()
+            .find(|wake| wake.waker == from && wake.sleeper == to)
+            .and_then(|_| self.syscalls[to].waits_on.as_ref())
+            .map(Channel::to_string)
+    }
+
+    fn program(&self, process: usize) -> String {
+        let process = &self.processes[process];
+        if process.program.is_empty() {
+            format!("pid:{}", process.pid)
+        } else {
+            process.program.clone()
+        }
+    }
+
+    fn process_span_id(&self, process: usize) -> [u8; 8] {
+        (process as u64 + 1).to_be_bytes()
+    }
+
+    fn syscall_span_id(&self, syscall: usize) -> [u8; 8] {
+        ((self.processes.len() + syscall) as u64 + 1).to_be_bytes()
+    }
+
+    /// The previous syscall of the same process
+    fn previous(&self, syscall: usize) -> Option<usize> {
+        let syscalls = &self.processes[self.syscalls[syscall].process].syscalls;
+        let position = syscalls.partition_point(|&s| s < syscall);
+        position.checked_sub(1).map(|p| syscalls[p])
+    }
+
+    /// Sleepers paired with the syscall that woke them
+    ///
+    /// The waker is the first signal on the sleeper's channel from another
+    /// process or thread that started while the sleeper was blocked.
+    fn wakes(&self) -> Vec<Wake> {
+        let mut signals: HashMap<&Channel, Vec<usize>> = HashMap::new();
+        for (index, syscall) in self.syscalls.iter().enumerate() {
+            if let Some(channel) = &syscall.wakes {
+                signals.entry(channel).or_default().push(index);
+            }
+        }
+        for wakers in signals.values_mut() {
+            wakers.sort_by_key(|&w| self.syscalls[w].timestamp_nanos);
+        }
+
+        let mut wakes = Vec::new();
+        for (sleeper, syscall) in self.syscalls.iter().enumerate() {
+            let Some(wakers) = syscall.waits_on.as_ref().and_then(|c| signals.get(c)) else {
+                continue;
+            };
+            let first = wakers
+                .partition_point(|&w| self.syscalls[w].timestamp_nanos < syscall.timestamp_nanos);
+            let waker = wakers[first..]
+                .iter()
+                .take_while(|&&w| self.syscalls[w].timestamp_nanos <= syscall.end_nanos())
+                .find(|&&w| self.syscalls[w].process != syscall.process);
+            if let Some(&waker) = waker {
+                wakes.push(Wake { waker, sleeper });
+            }
+        }
+        wakes
+    }
+
+    /// Predecessors of every span, indexed like [`spans`](Self::spans)
+    fn predecessors(&self, wakes: &[Wake]) -> Vec<Vec<(usize, Edge)>> {
+        let processes = self.processes.len();
+        let mut predecessors = vec![Vec::new(); processes + self.syscalls.len()];
+        for (index, process) in self.processes.iter().enumerate() {
+            if let Some(spawn) = process.spawned_by {
+                predecessors[index].push((processes + spawn, Edge::Program));
+            }
+        }
+        for (index, syscall) in self.syscalls.iter().enumerate() {
+            let node = processes + index;
+            let previous = match self.previous(index) {
+                Some(previous) => processes + previous,
+                None => syscall.process,
+            };
+            predecessors[node].push((previous, Edge::Program));
+            for &child in &syscall.joins {
+                let last = match self.processes[child].syscalls.last() {
+                    Some(&last) => processes + last,
+                    None => child,
+                };
+                predecessors[node].push((last, Edge::Join));
+            }
+        }
+        for wake in wakes {
+            predecessors[processes + wake.sleeper].push((processes + wake.waker, Edge::Wake));
+        }
+        predecessors
+    }
+
+    /// Span times and logical clocks
+    ///
+    /// A span is ready when its predecessors are done, and done at its end
+    /// or when it became ready if that is later (a child's first syscall
+    /// can end before `clone` returns; a reader can return before the
+    /// writer that woke it). Each process keeps a Lamport clock that syncs
+    /// with the clocks of spans it joins or is woken by.
+    fn timeline(&self) -> Timeline {
+        let processes = self.processes.len();
+        let mut wakes = self.wakes();
+        let (order, predecessors) = loop {
+            let predecessors = self.predecessors(&wakes);
+            match topological_order(&predecessors) {
+                Ok(order) => break (order, predecessors),
+                // Timestamps overlap enough to close a cycle: drop the
+                // wake edges into the spans caught in it
+                Err(stuck) => wakes.retain(|wake| !stuck.contains(&(processes + wake.sleeper))),
+            }
+        };
+
+        let nodes = predecessors.len();
+        let mut ready = vec![0; nodes];
+        let mut done = vec![0; nodes];
+        let mut logical = vec![0; nodes];
+        let clocks: Vec<LamportClock> = (0..processes).map(|_| LamportClock::new()).collect();
+        for node in order {
+            let (process, end) = match node.checked_sub(processes) {
+                Some(s) => (self.syscalls[s].process, self.syscalls[s].end_nanos()),
+                // Processes without a traced parent start at their first syscall
+                None => {
+                    let first = self.processes[node].syscalls.first();
+                    (node, first.map_or(0, |&s| self.syscalls[s].timestamp_nanos))
+                }
+            };
+            let clock = &clocks[process];
+            let mut at = None;
+            for &(predecessor, edge) in &predecessors[node] {
+                at = at.max(Some(done[predecessor]));
+                if edge != Edge::Program || predecessor >= processes && node < processes {
+                    clock.sync(logical[predecessor]);
+                }
+            }
+            ready[node] = at.unwrap_or(end);
+            done[node] = if node < processes {
+                ready[node]
+            } else {
+                end.max(ready[node])
+            };
+            logical[node] = clock.tick() + 1;
+        }
+
+        Timeline {
+            ready,
+            done,
+            logical,
+            wakes,
+        }
+    }
+}
+
+/// Kahn's algorithm; `Err` holds the nodes left in cycles
+fn topological_order(predecessors: &[Vec<(usize, Edge)>]) -> Result<Vec<usize>, Vec<usize>> {
+    let mut successors = vec![Vec::new(); predecessors.len()];
+    let mut pending: Vec<usize> = predecessors.iter().map(Vec::len).collect();
+    for (node, preds) in predecessors.iter().enumerate() {
+        for &(predecessor, _) in preds {
+            successors[predecessor].push(node);
+        }
+    }
+
+    let mut queue: VecDeque<usize> = (0..predecessors.len())
+        .filter(|&n| pending[n] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(predecessors.len());
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        for &successor in &successors[node] {
+            pending[successor] -= 1;
+            if pending[successor] == 0 {
+                queue.push_back(successor);
+            }
+        }
+    }
+
+    if order.len() == predecessors.len() {
+        Ok(order)
+    } else {
+        Err((0..predecessors.len())
+            .filter(|&n| pending[n] > 0)
+            .collect())
+    }
+}
+
+/// Whether an fd target is a pipe or socket
+/// Whether the child of a spawning syscall shares its parent's address
+/// space (`flags` is `clone`'s first argument)
+pub(crate) fn shares_memory(syscall: &str, flags: u64) -> bool {
+    match syscall {
+        "clone" => flags & CLONE_VM != 0,
+        // clone3 takes its flags in a struct: glibc uses it for
+        // threads and posix_spawn, which both share memory
+        "clone3" | "vfork" => true,
+        _ => false,
+    }
+}
+
+fn is_ipc(path: &str) -> bool {
+    path.starts_with("pipe:") || path.starts_with("socket:")
+}
+
+/// Argument holding the `int[2]` the kernel fills in (Sprint 72)
+pub fn fd_pair_arg(syscall: &str) -> Option<usize> {
+    match syscall {
+        "pipe" | "pipe2" => Some(0),
+        "socketpair" => Some(3),
+        _ => None,
+    }
+}
+
+/// Format a filled-in fd pair with what each fd refers to:
+/// `[3<pipe:[1234]>, 4<pipe:[1234]>]`
+pub fn format_fd_pair(fds: [i32; 2], resolve: impl Fn(i32) -> Option<String>) -> String {
+    let [first, second] = fds.map(|fd| match resolve(fd) {
+        Some(path) => format!("{}<{}>", fd, path),
+        None => fd.to_string(),
+    });
+    format!("[{}, {}]", first, second)
+}
+
+/// Parse [`format_fd_pair`] output back into fds and their targets
+fn parse_fd_pair(arg: &str) -> Option<[(i32, Option<String>); 2]> {
+    let inner = arg.strip_prefix('[')?.strip_suffix(']')?;
+    let (first, second) = inner.split_once(", ")?;
+    let parse = |part: &str| -> Option<(i32, Option<String>)> {
+        match part.split_once('<') {
+            Some((fd, path)) => Some((fd.parse().ok()?, Some(path.strip_suffix('>')?.to_string()))),
+            None => Some((part.parse().ok()?, None)),
+        }
+    };
+    Some([parse(first)?, parse(second)?])
+}
+
+/// Basename of an executable path (`/usr/bin/cc` → `cc`)
+fn executable_name(program: &str) -> String {
+    let program = program.trim_matches('"');
+    program.rsplit('/').next().unwrap_or(program).to_string()
+}
+
+/// Consecutive critical-path spans of one process
+#[derive(Debug, Clone)]
+pub struct CriticalPathStep {
+    process: usize,
+    pub pid: i32,
+    pub program: String,
+    /// Time this step adds to the critical path
+    pub time: Duration,
+    /// Syscalls of the step on the path
+    pub syscalls: usize,
+    /// The span that added the most time, and how much
+    pub longest: Option<(String, Duration)>,
+    /// How the path entered this process from the previous step: the
+    /// spawning syscall, `exit` of a reaped child, or the pipe, socket or
+    /// futex that woke it
+    pub via: Option<String>,
+}
+
+/// Longest dependency chain through a traced run
+#[derive(Debug, Clone)]
+pub struct CriticalPathReport {
+    /// From the first traced syscall to the end of the last
+    ///
+    /// A child's syscalls that end before its `clone` returns in the parent
+    /// count as ending then.
+    pub wall_time: Duration,
+    /// Length of the critical path
+    pub length: Duration,
+    /// Processes and threads traced
+    pub processes: usize,
+    /// The path, one step per process visit
+    pub steps: Vec<CriticalPathStep>,
+}
+
+impl CriticalPathReport {
+    /// Text report, in the layout of the `-c` summary
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "\nCritical path: {:.6}s of {:.6}s wall-clock, {} process{} traced\n",
+            self.length.as_secs_f64(),
+            self.wall_time.as_secs_f64(),
+            self.processes,
+            if self.processes == 1 { "" } else { "es" }
+        );
+        if self.steps.is_empty() {
+            out.push_str("(no syscalls recorded)\n");
+            return out;
+        }
+
+        let separator = "------ ----------- --------- ------------------------------\n";
+        out.push_str("% path     seconds  syscalls step\n");
+        out.push_str(separator);
+        for step in &self.steps {
+            let share = if self.length.is_zero() {
+                0.0
+            } else {
+                step.time.as_secs_f64() * 100.0 / self.length.as_secs_f64()
+            };
+            let longest = step
+                .longest
+                .as_ref()
+                .map(|(name, time)| format!(", longest {} {:.6}s", name, time.as_secs_f64()))
+                .unwrap_or_default();
+            let via = step
+                .via
+                .as_ref()
+                .map(|via| format!(", via {}", via))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "{:6.2} {:>11.6} {:>9} {} (pid {}){}{}\n",
+                share,
+                step.time.as_secs_f64(),
+                step.syscalls,
+                step.program,
+                step.pid,
+                longest,
+                via
+            ));
+        }
+        out.push_str(separator);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// make (pid 10) forks sh (pid 11), which forks cc (pid 12), then waits
+    fn build() -> ProcessTreeTracker {
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(10, "/usr/bin/make");
+        tracker.record(10, "openat", 3, 0, 1_000);
+        tracker.record(11, "getpid", 11, 2_000, 100);
+        tracker.record(10, "clone", 11, 1_500, 1_000);
+        tracker.exec(11, "/bin/sh");
+        tracker.record(11, "vfork", 12, 3_000, 500);
+        tracker.record(12, "read", 10, 3_500, 50_000);
+        tracker.record(11, "wait4", 12, 4_000, 50_500);
+        tracker.record(10, "read", 0, 3_000, 500);
+        tracker.record(10, "wait4", 11, 4_000, 76_000);
+        tracker
+    }
+
+    #[test]
+    fn test_spans_link_processes_and_syscalls() {
+        let tracker = build();
+        let spans = tracker.spans();
+        assert_eq!(spans.len(), 3 + 8);
+
+        let name = |id: Option<[u8; 8]>| {
+            let id = id.expect("span has a parent");
+            spans
+                .iter()
+                .find(|s| s.span_id == id)
+                .unwrap()
+                .span_name
+                .as_str()
+        };
+        let find = |pid: u32, span_name: &str| {
+            spans
+                .iter()
+                .find(|s| s.process_id == pid && s.span_name == span_name)
+                .unwrap()
+        };
+
+        assert!(spans[0].is_root());
+        assert_eq!(spans[0].span_name, "make");
+        assert_eq!(name(spans[1].parent_span_id), "clone");
+        assert_eq!(spans[1].span_name, "sh");
+        assert_eq!(spans[2].span_name, "sh", "inherited until exec");
+        assert_eq!(name(spans[2].parent_span_id), "vfork");
+        assert_eq!(name(find(10, "openat").parent_span_id), "make");
+        assert_eq!(name(find(10, "read").parent_span_id), "clone");
+        assert_eq!(name(find(11, "getpid").parent_span_id), "sh");
+
+        // The getpid ran before clone returned: it adds nothing, and vfork
+        // counts from when clone returned
+        assert_eq!(find(11, "getpid").duration_nanos, 0);
+        assert_eq!(find(11, "vfork").start_time_nanos, 2_500);
+        // make's wait4 only waits from the end of sh's wait4
+        assert_eq!(find(10, "wait4").start_time_nanos, 54_500);
+        assert_eq!(find(10, "wait4").duration_nanos, 25_500);
+    }
+
+    #[test]
+    fn test_wait4_joins_the_child() {
+        let graph = build().graph().unwrap();
+        assert!(graph.is_dag().unwrap());
+        assert_eq!(graph.roots().len(), 1);
+        assert_eq!(graph.edge_count(), 3 + 8 - 1 + 2);
+    }
+
+    #[test]
+    fn test_critical_path_goes_through_the_slowest_child() {
+        let report = build().finish().unwrap();
+        assert_eq!(report.wall_time, Duration::from_nanos(80_000));
+        assert_eq!(report.length, Duration::from_nanos(80_000));
+        assert_eq!(report.processes, 3);
+
+        let steps: Vec<(&str, i32)> = report
+            .steps
+            .iter()
+            .map(|s| (s.program.as_str(), s.pid))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("make", 10),
+                ("sh", 11),
+                ("sh", 12),
+                ("sh", 11),
+                ("make", 10)
+            ]
+        );
+        assert_eq!(report.steps[2].time, Duration::from_nanos(50_000));
+        assert_eq!(
+            report.steps[2].longest,
+            Some(("read".to_string(), Duration::from_nanos(50_000)))
+        );
+
+        let text = report.to_text();
+        assert!(
+            text.contains("Critical path: 0.000080s of 0.000080s wall-clock, 3 processes traced")
+        );
+        assert!(text.contains(" 62.50    0.000050         1 sh (pid 12), longest read 0.000050s"));
+    }
+
+    #[test]
+    fn test_child_exit_wakes_rt_sigsuspend() {
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "sh");
+        tracker.record(1, "clone", 2, 0, 10);
+        tracker.record(2, "pselect6", 0, 20, 1_000);
+        tracker.record(1, "rt_sigsuspend", -4, 100, 1_200);
+        tracker.record(1, "wait4", 2, 1_400, 10);
+
+        let spans = tracker.spans();
+        let sigsuspend = spans
+            .iter()
+            .find(|s| s.span_name == "rt_sigsuspend")
+            .unwrap();
+        assert_eq!(sigsuspend.start_time_nanos, 1_020);
+
+        let report = tracker.finish().unwrap();
+        let pids: Vec<i32> = report.steps.iter().map(|s| s.pid).collect();
+        assert_eq!(pids, vec![1, 2, 1]);
+        assert_eq!(report.steps[1].time, Duration::from_nanos(1_010));
+        assert_eq!(report.length, report.wall_time);
+    }
+
+    #[test]
+    fn test_reused_pid_is_a_new_process() {
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "init");
+        tracker.record(1, "fork", 2, 0, 10);
+        tracker.record(2, "exit_group", 0, 20, 0);
+        tracker.record(1, "wait4", 2, 10, 30);
+        tracker.record(1, "fork", 2, 50, 10);
+        tracker.record(2, "getppid", 1, 70, 10);
+
+        let spans = tracker.spans();
+        let processes = spans
+            .iter()
+            .filter(|s| s.span_name == "init" && s.process_id == 2);
+        assert_eq!(processes.count(), 2);
+        assert!(tracker.graph().unwrap().is_dag().unwrap());
+    }
+
+    fn fd(path: &str) -> WaitObject {
+        WaitObject::Fd {
+            fd: 3,
+            path: Some(path.to_string()),
+        }
+    }
+
+    /// Parent (pid 1) blocks reading a pipe until the child (pid 2) writes
+    fn pipe_run() -> ProcessTreeTracker {
+        let pipe = fd("pipe:[100]");
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "producer");
+        tracker.record(1, "fork", 2, 0, 10);
+        tracker.record(2, "nanosleep", 0, 20, 5_000);
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&pipe),
+            ..SyscallEvent::new(2, "write", 3, 5_020, 10)
+        });
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&pipe),
+            ..SyscallEvent::new(1, "read", 3, 30, 5_000)
+        });
+        tracker.record(1, "fsync", 0, 5_040, 2_000);
+        tracker
+    }
+
+    #[test]
+    fn test_pipe_write_wakes_the_reader() {
+        let tracker = pipe_run();
+        let spans = tracker.spans();
+        let find = |name: &str| spans.iter().find(|s| s.span_name == name).unwrap();
+        assert_eq!(
+            find("read").parse_attributes()["syscall.channel"],
+            "pipe:[100]"
+        );
+        assert!(find("write").logical_clock < find("read").logical_clock);
+        assert_eq!(tracker.graph().unwrap().edge_count(), 2 + 5 - 1 + 1);
+
+        let report = tracker.finish().unwrap();
+        let via: Vec<Option<&str>> = report.steps.iter().map(|s| s.via.as_deref()).collect();
+        assert_eq!(via, vec![None, Some("fork"), Some("pipe:[100]")]);
+        assert_eq!(report.steps[1].time, Duration::from_nanos(5_020));
+        assert!(report
+            .to_text()
+            .contains("longest fsync 0.000002s, via pipe:[100]"));
+    }
+
+    #[test]
+    fn test_write_outside_the_read_does_not_wake_it() {
+        let pipe = fd("pipe:[100]");
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "sh");
+        tracker.record(1, "fork", 2, 0, 10);
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&pipe),
+            ..SyscallEvent::new(2, "write", 3, 20, 10)
+        });
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&pipe),
+            ..SyscallEvent::new(1, "read", 3, 100, 10)
+        });
+        assert_eq!(tracker.graph().unwrap().edge_count(), 2 + 3 - 1);
+    }
+
+    #[test]
+    fn test_futex_wake_in_the_same_address_space() {
+        let word = WaitObject::Futex { addr: 0x1000 };
+        let wake = |tracker: &mut ProcessTreeTracker, pid| {
+            tracker.record_syscall(&SyscallEvent {
+                raw_args: [0x1000, 0x81, 1], // FUTEX_WAKE_PRIVATE
+                ..SyscallEvent::new(pid, "futex", 1, 500, 10)
+            });
+        };
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "server");
+        tracker.record_syscall(&SyscallEvent {
+            raw_args: [0x3d0f00, 0, 0], // CLONE_VM | CLONE_THREAD | ...
+            ..SyscallEvent::new(1, "clone", 2, 0, 10)
+        });
+        tracker.record(1, "fork", 3, 20, 10);
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&word),
+            ..SyscallEvent::new(1, "futex", 0, 40, 1_000)
+        });
+        wake(&mut tracker, 3);
+        wake(&mut tracker, 2);
+
+        let report = tracker.finish().unwrap();
+        let pids: Vec<i32> = report.steps.iter().map(|s| s.pid).collect();
+        assert_eq!(
+            pids,
+            vec![1, 2, 1],
+            "the forked process has its own futex words"
+        );
+        assert_eq!(report.steps[2].via.as_deref(), Some("futex 0x1000"));
+    }
+
+    #[test]
+    fn test_socketpair_end_wakes_its_peer() {
+        let pair = [
+            "0x1".to_string(),
+            "0x1".to_string(),
+            "0x0".to_string(),
+            format_fd_pair([3, 4], |fd| Some(format!("socket:[{}]", fd * 100))),
+        ];
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "client");
+        tracker.record_syscall(&SyscallEvent {
+            args: &pair,
+            ..SyscallEvent::new(1, "socketpair", 0, 0, 10)
+        });
+        tracker.record(1, "fork", 2, 20, 10);
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&fd("socket:[300]")),
+            ..SyscallEvent::new(2, "sendto", 5, 1_000, 10)
+        });
+        tracker.record_syscall(&SyscallEvent {
+            wait_object: Some(&fd("socket:[400]")),
+            ..SyscallEvent::new(1, "recvfrom", 5, 40, 1_000)
+        });
+
+        let report = tracker.finish().unwrap();
+        assert_eq!(
+            report.steps.last().unwrap().via.as_deref(),
+            Some("socket:[400]")
+        );
+    }
+
+    #[test]
+    fn test_fd_pair_round_trip() {
+        let text = format_fd_pair([3, 4], |fd| (fd == 3).then(|| "pipe:[7]".to_string()));
+        assert_eq!(text, "[3<pipe:[7]>, 4]");
+        assert_eq!(
+            parse_fd_pair(&text),
+            Some([(3, Some("pipe:[7]".to_string())), (4, None)])
+        );
+        assert_eq!(parse_fd_pair("0x7ffd0000"), None);
+        assert_eq!(fd_pair_arg("pipe2"), Some(0));
+        assert_eq!(fd_pair_arg("socketpair"), Some(3));
+        assert_eq!(fd_pair_arg("read"), None);
+    }
+
+    #[test]
+    fn test_overlapping_wakes_do_not_form_a_cycle() {
+        // Each read returns before the other side's write ends
+        let (a, b) = (fd("pipe:[1]"), fd("pipe:[2]"));
+        let mut tracker = ProcessTreeTracker::new();
+        tracker.start(1, "ping");
+        tracker.record(1, "fork", 2, 0, 10);
+        let calls = [
+            (1, "read", &a, 20),
+            (2, "write", &a, 40),
+            (2, "read", &b, 50),
+            (1, "write", &b, 60),
+        ];
+        for (pid, name, pipe, ts) in calls {
+            tracker.record_syscall(&SyscallEvent {
+                wait_object: Some(pipe),
+                ..SyscallEvent::new(pid, name, 1, ts, 100)
+            });
+        }
+        assert!(tracker.graph().unwrap().is_dag().unwrap());
+        assert!(tracker.finish().is_ok());
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let text = ProcessTreeTracker::new().finish().unwrap().to_text();
+        assert!(text.contains("(no syscalls recorded)"));
+    }
+
+    #[test]
+    fn test_executable_name() {
+        assert_eq!(executable_name("\"/usr/bin/cc\""), "cc");
+        assert_eq!(executable_name("make"), "make");
+    }
+}
@@ -0,0 +1,477 @@
+//! Seccomp allow-lists from recorded traces
+//!
+//! Sprint 60: `renacer seccomp-profile TRACE...` turns the syscalls observed in
+//! one or more recorded runs into a seccomp allow-list, emitted as an OCI /
+//! Docker JSON profile, a libseccomp C function or a `seccompiler` Rust
+//! function. Everything not observed fails with `EPERM`.
+//!
+//! Arguments that select a whole family of operations (socket domains, ioctl
+//! requests, fcntl commands, ...) are narrowed to the values that were seen.
+//! Syscalls that only ever failed are reported: they usually come from
+//! feature probes and error paths, and can often be denied.
+//!
+//! Rules are grouped by the `-e trace=` syscall classes
+//! ([`crate::filter::syscall_class`]).
+
+use crate::cli::SeccompFormat;
+use crate::filter::{syscall_class, SYSCALL_CLASSES};
+use crate::trace_diff::TraceSet;
+use anyhow::Result;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+/// Group for syscalls outside the `-e trace=` classes
+const OTHER_CLASS: &str = "other";
+
+/// Argument narrowed to observed values: (syscall, argument index, argument name)
+const NARROWED_ARGS: &[(&str, usize, &str)] = &[
+    ("socket", 0, "domain"),
+    ("socketpair", 0, "domain"),
+    ("ioctl", 1, "request"),
+    ("fcntl", 1, "cmd"),
+    ("prctl", 0, "option"),
+    ("arch_prctl", 0, "code"),
+];
+
+/// Argument index and name narrowed for a syscall, if any
+fn narrowed_arg(syscall: &str) -> Option<(usize, &'static str)> {
+    NARROWED_ARGS
+        .iter()
+        .find(|(name, _, _)| *name == syscall)
+        .map(|&(_, index, arg)| (index, arg))
+}
+
+/// seccompiler comparison width for an observed argument value
+///
+/// Dword compares the low 32 bits only, which is all an `int` argument
+/// carries; wider values (ioctl requests, sign-extended negatives) need the
+/// whole register.
+fn cmp_arg_len(value: u64) -> &'static str {
+    if value > u64::from(u32::MAX) {
+        "Qword"
+    } else {
+        "Dword"
+    }
+}
+
+/// Observed use of one syscall
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyscallUsage {
+    pub name: String,
+    pub number: i64,
+    pub calls: u64,
+    pub errors: u64,
+    /// Errno values of failed calls
+    pub errnos: BTreeSet<i32>,
+    /// Observed values of the narrowed argument (see [`NARROWED_ARGS`])
+    pub arg_values: BTreeSet<u64>,
+}
+
+impl SyscallUsage {
+    /// Whether every call failed
+    pub fn error_only(&self) -> bool {
+        self.errors == self.calls
+    }
+
+    /// Narrowed argument index and observed values
+    pub fn narrowed(&self) -> Option<(usize, &'static str, &BTreeSet<u64>)> {
+        narrowed_arg(&self.name).map(|(index, arg)| (index, arg, &self.arg_values))
+    }
+
+    /// The syscall's `-e trace=` class, or "other"
+    pub fn class(&self) -> &'static str {
+        syscall_class(&self.name).unwrap_or(OTHER_CLASS)
+    }
+}
+
+/// Syscalls observed across a set of runs
+#[derive(Debug, Clone, Default)]
+pub struct SeccompProfile {
+    /// Number of runs the profile was built from
+    pub runs: usize,
+    /// Observed syscalls by number
+    pub syscalls: BTreeMap<i64, SyscallUsage>,
+    /// Leave syscalls that only ever failed out of the allow-list
+    pub deny_error_only: bool,
+}
+
+impl SeccompProfile {
+    /// Collect the syscalls of every run
+    pub fn from_traces(sets: &[TraceSet]) -> Self {
+        let mut profile = Self::default();
+        for set in sets {
+            profile.runs += set.runs.len();
+            for record in set.runs.iter().flatten() {
+                let usage = profile
+                    .syscalls
+                    .entry(record.number)
+                    .or_insert_with(|| SyscallUsage {
+                        name: record.name.clone(),
+                        number: record.number,
+                        calls: 0,
+                        errors: 0,
+                        errnos: BTreeSet::new(),
+                        arg_values: BTreeSet::new(),
+                    });
+                usage.calls += 1;
+                if (-4095..0).contains(&record.result) {
+                    usage.errors += 1;
+                    usage.errnos.insert(-record.result as i32);
+                }
+                if let Some((index, _)) = narrowed_arg(&record.name) {
+                    usage.arg_values.insert(record.raw_args[index]);
+                }
+            }
+        }
+        profile
+    }
+
+    /// Syscalls that only ever failed
+    pub fn error_only(&self) -> impl Iterator<Item = &SyscallUsage> {
+        self.syscalls.values().filter(|usage| usage.error_only())
+    }
+
+    /// Allowed syscalls grouped by class, in class order then by name
+    fn allowed_by_class(&self) -> Vec<(&'static str, Vec<&SyscallUsage>)> {
+        let mut groups: Vec<(&'static str, Vec<&SyscallUsage>)> = SYSCALL_CLASSES
+            .iter()
+            .chain(std::iter::once(&OTHER_CLASS))
+            .map(|&class| (class, Vec::new()))
+            .collect();
+        for usage in self.syscalls.values() {
+            if self.deny_error_only && usage.error_only() {
+                continue;
+            }
+            if let Some((_, members)) = groups.iter_mut().find(|(c, _)| *c == usage.class()) {
+                members.push(usage);
+            }
+        }
+        for (_, members) in &mut groups {
+            members.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        groups.retain(|(_, members)| !members.is_empty());
+        groups
+    }
+
+    /// Render the profile in the requested format
+    pub fn render(&self, format: SeccompFormat) -> Result<String> {
+        match format {
+            SeccompFormat::Oci => self.to_oci_json(),
+            SeccompFormat::C => Ok(self.to_c()),
+            SeccompFormat::Rust => Ok(self.to_rust()),
+        }
+    }
+
+    /// OCI runtime / Docker seccomp profile (JSON)
+    ///
+    /// Syscalls the tracer could not name have no OCI name and are left out.
+    pub fn to_oci_json(&self) -> Result<String> {
+        let mut rules = Vec::new();
+        for (class, members) in self.allowed_by_class() {
+            let names: Vec<&str> = members
+                .iter()
+                .filter(|usage| usage.narrowed().is_none() && usage.name != "unknown")
+                .map(|usage| usage.name.as_str())
+                .collect();
+            if !names.is_empty() {
+                rules.push(json!({
+                    "names": names,
+                    "action": "SCMP_ACT_ALLOW",
+                    "comment": class,
+                }));
+            }
+
+            // Rules AND their argument conditions, so one rule per value
+            for usage in &members {
+                let Some((index, arg, values)) = usage.narrowed() else {
+                    continue;
+                };
+                for value in values {
+                    rules.push(json!({
+                        "names": [usage.name],
+                        "action": "SCMP_ACT_ALLOW",
+                        "args": [{ "index": index, "value": value, "op": "SCMP_CMP_EQ" }],
+                        "comment": format!("{}: observed {}", class, arg),
+                    }));
+                }
+            }
+        }
+
+        let profile = json!({
+            "defaultAction": "SCMP_ACT_ERRNO",
+            "defaultErrnoRet": libc::EPERM,
+            "architectures": ["SCMP_ARCH_X86_64"],
+            "syscalls": rules,
+        });
+        Ok(serde_json::to_string_pretty(&profile)?)
+    }
+
+    /// libseccomp C function installing the filter
+    pub fn to_c(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "/* Generated by renacer seccomp-profile from {} runs */",
+            self.runs
+        );
+        out.push_str("#include <errno.h>\n#include <seccomp.h>\n\n");
+        out.push_str("int install_seccomp_filter(void)\n{\n");
+        out.push_str("    scmp_filter_ctx ctx = seccomp_init(SCMP_ACT_ERRNO(EPERM));\n");
+        out.push_str("    int rc;\n\n    if (ctx == NULL)\n        return -ENOMEM;\n");
+
+        for (class, members) in self.allowed_by_class() {
+            let _ = writeln!(out, "\n    /* {} */", class);
+            for usage in members {
+                let syscall = if usage.name == "unknown" {
+                    usage.number.to_string()
+                } else {
+                    format!("SCMP_SYS({})", usage.name)
+                };
+                let conditions: Vec<String> = match usage.narrowed() {
+                    Some((index, _, values)) => values
+                        .iter()
+                        .map(|value| format!("1, SCMP_A{}(SCMP_CMP_EQ, {:#x})", index, value))
+                        .collect(),
+                    None => vec!["0".to_string()],
+                };
+                for condition in conditions {
+                    let _ = writeln!(
+                        out,
+                        "    if ((rc = seccomp_rule_add(ctx, SCMP_ACT_ALLOW, {}, {})) < 0)\n        goto out;",
+                        syscall, condition
+                    );
+                }
+            }
+        }
+
+        out.push_str(
+            "\n    rc = seccomp_load(ctx);\nout:\n    seccomp_release(ctx);\n    return rc;\n}\n",
+        );
+        out
+    }
+
+    /// `seccompiler` Rust function installing the filter
+    pub fn to_rust(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "// Generated by renacer seccomp-profile from {} runs",
+            self.runs
+        );
+        out.push_str(
+            "use seccompiler::{\n    BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,\n    SeccompRule, TargetArch,\n};\n\n",
+        );
+        out.push_str(
+            "pub fn install_seccomp_filter() -> Result<(), Box<dyn std::error::Error>> {\n",
+        );
+        out.push_str("    let rules = vec![\n");
+
+        for (class, members) in self.allowed_by_class() {
+            let _ = writeln!(out, "        // {}", class);
+            for usage in members {
+                let syscall = if usage.name == "unknown" {
+                    usage.number.to_string()
+                } else {
+                    format!("libc::SYS_{}", usage.name)
+                };
+                match usage.narrowed() {
+                    Some((index, _, values)) => {
+                        let _ = writeln!(
+                            out,
+                            "        (\n            {},\n            vec![",
+                            syscall
+                        );
+                        for &value in values {
+                            let _ = writeln!(
+                                out,
+                                "                SeccompRule::new(vec![SeccompCondition::new(\n                    {},\n                    SeccompCmpArgLen::{},\n                    SeccompCmpOp::Eq,\n                    {:#x},\n                )?])?,",
+                                index,
+                                cmp_arg_len(value),
+                                value
+                            );
+                        }
+                        out.push_str("            ],\n        ),\n");
+                    }
+                    None => {
+                        let _ = writeln!(out, "        ({}, vec![]),", syscall);
+                    }
+                }
+            }
+        }
+
+        out.push_str("    ];\n\n");
+        out.push_str(
+            "    let filter = SeccompFilter::new(\n        rules.into_iter().collect(),\n        SeccompAction::Errno(libc::EPERM as u32),\n        SeccompAction::Allow,\n        TargetArch::x86_64,\n    )?;\n",
+        );
+        out.push_str("    let program: BpfProgram = filter.try_into()?;\n");
+        out.push_str("    seccompiler::apply_filter(&program)?;\n    Ok(())\n}\n");
+        out
+    }
+
+    /// Human-readable summary of the profile
+    pub fn summary(&self) -> String {
+        let allowed: usize = self
+            .allowed_by_class()
+            .iter()
+            .map(|(_, members)| members.len())
+            .sum();
+        let mut out = format!(
+            "Seccomp profile: {} syscalls allowed from {} runs\n",
+            allowed, self.runs
+        );
+
+        for usage in self.syscalls.values() {
+            if let Some((_, arg, values)) = usage.narrowed() {
+                let values: Vec<String> = values.iter().map(|v| format!("{:#x}", v)).collect();
+                let _ = writeln!(
+                    out,
+                    "  {} narrowed to {} {}",
+                    usage.name,
+                    arg,
+                    values.join(", ")
+                );
+            }
+        }
+
+        let error_only: Vec<_> = self.error_only().collect();
+        if !error_only.is_empty() {
+            let _ = writeln!(
+                out,
+                "Syscalls seen only on error paths{}:",
+                if self.deny_error_only {
+                    " (denied)"
+                } else {
+                    ""
+                }
+            );
+            for usage in error_only {
+                let errnos: Vec<String> = usage
+                    .errnos
+                    .iter()
+                    .map(|&errno| format!("{:?}", nix::errno::Errno::from_raw(errno)))
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "  {:<20} {:>6} calls, {}",
+                    usage.name,
+                    usage.calls,
+                    errnos.join("/")
+                );
+            }
+        }
+        if self.syscalls.values().any(|usage| usage.name == "unknown") {
+            out.push_str("Note: unnamed syscalls are left out of the OCI profile\n");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace_file::SyscallRecord;
+
+    fn record(name: &str, number: i64, raw_args: [u64; 3], result: i64) -> SyscallRecord {
+        SyscallRecord {
+            pid: 1,
+            timestamp_us: 0,
+            duration_us: 1,
+            number,
+            name: name.to_string(),
+            args: Vec::new(),
+            raw_args,
+            result,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: Vec::new(),
+            wait_object: None,
+            stderr: None,
+        }
+    }
+
+    fn profile() -> SeccompProfile {
+        let run = vec![
+            record("openat", 257, [0xffffff9c, 0, 0], 3),
+            record("read", 0, [3, 0, 64], 64),
+            record("socket", 41, [1, 1, 0], 4),
+            record("socket", 41, [2, 1, 0], 5),
+            record("ioctl", 16, [1, 0x5401, 0], -25),
+            record("futex", 202, [0, 0, 0], 0),
+        ];
+        SeccompProfile::from_traces(&[TraceSet::from_runs("t.rnc", vec![run.clone(), run])])
+    }
+
+    #[test]
+    fn test_usage_and_narrowing() {
+        let profile = profile();
+        assert_eq!(profile.runs, 2);
+        let socket = &profile.syscalls[&41];
+        assert_eq!(socket.calls, 4);
+        assert_eq!(socket.class(), "network");
+        let (index, arg, values) = socket.narrowed().unwrap();
+        assert_eq!((index, arg), (0, "domain"));
+        assert_eq!(values.iter().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert!(profile.syscalls[&0].narrowed().is_none());
+    }
+
+    #[test]
+    fn test_error_only_syscalls() {
+        let profile = profile();
+        let error_only: Vec<_> = profile.error_only().map(|u| u.name.as_str()).collect();
+        assert_eq!(error_only, vec!["ioctl"]);
+        assert!(profile.summary().contains("ENOTTY"));
+    }
+
+    #[test]
+    fn test_oci_profile() {
+        let json: serde_json::Value =
+            serde_json::from_str(&profile().to_oci_json().unwrap()).unwrap();
+        assert_eq!(json["defaultAction"], "SCMP_ACT_ERRNO");
+        let rules = json["syscalls"].as_array().unwrap();
+        assert_eq!(rules[0]["comment"], "file");
+        assert_eq!(rules[0]["names"], json!(["openat", "read"]));
+
+        let sockets: Vec<_> = rules
+            .iter()
+            .filter(|rule| rule["names"] == json!(["socket"]))
+            .collect();
+        assert_eq!(sockets.len(), 2);
+        assert_eq!(sockets[1]["args"][0]["value"], 2);
+    }
+
+    #[test]
+    fn test_deny_error_only() {
+        let mut profile = profile();
+        assert!(profile
+            .to_c()
+            .contains("SCMP_SYS(ioctl), 1, SCMP_A1(SCMP_CMP_EQ, 0x5401)"));
+
+        profile.deny_error_only = true;
+        assert!(!profile.to_c().contains("ioctl"));
+        assert!(!profile.to_rust().contains("SYS_ioctl"));
+        assert!(profile.summary().contains("error paths (denied)"));
+    }
+
+    #[test]
+    fn test_rust_filter() {
+        let rust = profile().to_rust();
+        assert!(rust.contains("(libc::SYS_read, vec![]),"));
+        assert!(rust.contains("// other\n        (libc::SYS_futex, vec![]),"));
+        assert!(rust.contains("SeccompCmpArgLen::Dword"));
+        assert!(!rust.contains("SeccompCmpArgLen::Qword"));
+    }
+
+    #[test]
+    fn test_rust_filter_compares_wide_values_in_full() {
+        let run = vec![
+            record("ioctl", 16, [3, 0x5401, 0], 0),
+            record("ioctl", 16, [3, 0xc020_4d00_0000_0001, 0], 0),
+        ];
+        let profile = SeccompProfile::from_traces(&[TraceSet::from_runs("t.rnc", vec![run])]);
+        let rust = profile.to_rust();
+        assert!(rust.contains("SeccompCmpArgLen::Dword,\n                    SeccompCmpOp::Eq,\n                    0x5401,"));
+        assert!(rust.contains("SeccompCmpArgLen::Qword,\n                    SeccompCmpOp::Eq,\n                    0xc0204d0000000001,"));
+    }
+}
@@ -13,9 +13,11 @@
 // SEQUENCES disrupted, not just counts changed.
 
 mod anomaly;
+mod model;
 mod ngram;
 
 pub use anomaly::{detect_sequence_anomalies, AnomalyType, SequenceAnomaly};
+pub use model::{SequenceMismatch, SequenceModel, SequenceMonitor, DEFAULT_WINDOW_SIZE};
 pub use ngram::{extract_ngrams, ngram_coverage, top_ngrams, NGram, NGramMap};
 
 #[cfg(test)]
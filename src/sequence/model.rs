@@ -0,0 +1,280 @@
+// Learned model of normal syscall sequences (Sprint 59)
+//
+// Forrest et al.'s "sense of self": the set of fixed-size syscall windows a
+// program produces in normal runs. During a later run, any window outside the
+// set is a deviation from normal behavior, whatever its frequency.
+//
+// Windows are taken per thread, so interleaving between threads never forms
+// a sequence. The model is persisted like the ML models (`.apr`, see
+// `model_persistence`).
+
+use crate::model_persistence::ModelMetadata;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// Default window size (trigrams, as for n-gram comparison)
+pub const DEFAULT_WINDOW_SIZE: usize = 3;
+
+/// Unknown sequences listed in the report
+const MAX_REPORTED_SEQUENCES: usize = 10;
+
+/// Allowed syscall windows of one program
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceModel {
+    /// Program the model was trained on
+    pub program: String,
+    /// Window size (syscalls per sequence)
+    pub window: usize,
+    /// Number of training runs
+    pub runs: usize,
+    /// Known windows and how often they occurred in training
+    pub sequences: BTreeMap<Vec<String>, u64>,
+    pub metadata: ModelMetadata,
+}
+
+impl SequenceModel {
+    /// Create an empty model
+    pub fn new(program: impl Into<String>, window: usize) -> Self {
+        Self {
+            program: program.into(),
+            window,
+            runs: 0,
+            sequences: BTreeMap::new(),
+            metadata: ModelMetadata::new(0)
+                .with_hyperparameter("window", window.to_string())
+                .with_description("Normal syscall sequences"),
+        }
+    }
+
+    /// Learn the windows of one run, given each thread's syscall names in order
+    pub fn train_run(&mut self, threads: &[Vec<String>]) {
+        for syscalls in threads {
+            for window in syscalls.windows(self.window) {
+                *self.sequences.entry(window.to_vec()).or_insert(0) += 1;
+                self.metadata.training_samples += 1;
+            }
+        }
+        self.runs += 1;
+    }
+
+    /// Whether a window was seen in training
+    pub fn contains(&self, window: &[String]) -> bool {
+        self.sequences.contains_key(window)
+    }
+
+    /// Number of distinct known windows
+    pub fn len(&self) -> usize {
+        self.sequences.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+}
+
+/// A live window that is not part of the model
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceMismatch {
+    pub pid: i32,
+    pub window: Vec<String>,
+    /// Where the last syscall of the window was made, if known
+    pub call_site: Option<String>,
+    /// First time this window was seen in the current run
+    pub first_occurrence: bool,
+}
+
+/// Occurrences of one unknown window
+#[derive(Debug, Clone)]
+struct UnknownSequence {
+    count: u64,
+    call_site: Option<String>,
+}
+
+/// Checks live syscalls against a [`SequenceModel`]
+#[derive(Debug)]
+pub struct SequenceMonitor {
+    model: SequenceModel,
+    /// Last `window` syscalls of each thread
+    recent: HashMap<i32, VecDeque<String>>,
+    checked: u64,
+    mismatches: u64,
+    unknown: HashMap<Vec<String>, UnknownSequence>,
+}
+
+impl SequenceMonitor {
+    pub fn new(model: SequenceModel) -> Self {
+        Self {
+            model,
+            recent: HashMap::new(),
+            checked: 0,
+            mismatches: 0,
+            unknown: HashMap::new(),
+        }
+    }
+
+    pub fn model(&self) -> &SequenceModel {
+        &self.model
+    }
+
+    /// Add a syscall to its thread's window and check the window
+    ///
+    /// `call_site` is only evaluated for mismatching windows.
+    pub fn observe(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        call_site: impl FnOnce() -> Option<String>,
+    ) -> Option<SequenceMismatch> {
+        let size = self.model.window;
+        let recent = self.recent.entry(pid).or_default();
+        recent.push_back(syscall.to_string());
+        if recent.len() > size {
+            recent.pop_front();
+        }
+        if recent.len() < size {
+            return None;
+        }
+
+        self.checked += 1;
+        let window: Vec<String> = recent.iter().cloned().collect();
+        if self.model.contains(&window) {
+            return None;
+        }
+
+        self.mismatches += 1;
+        let first_occurrence = !self.unknown.contains_key(&window);
+        let unknown = self
+            .unknown
+            .entry(window.clone())
+            .or_insert_with(|| UnknownSequence {
+                count: 0,
+                call_site: call_site(),
+            });
+        unknown.count += 1;
+
+        Some(SequenceMismatch {
+            pid,
+            window,
+            call_site: unknown.call_site.clone(),
+            first_occurrence,
+        })
+    }
+
+    /// Windows checked so far
+    pub fn checked(&self) -> u64 {
+        self.checked
+    }
+
+    /// Windows that were not in the model
+    pub fn mismatches(&self) -> u64 {
+        self.mismatches
+    }
+
+    /// Summary of windows outside the model
+    pub fn report(&self) -> String {
+        let mut out = String::from("\n=== Sequence Model Report ===\n");
+        out.push_str(&format!(
+            "Model: {} ({} sequences of {} syscalls, {} training runs)\n",
+            self.model.program,
+            self.model.len(),
+            self.model.window,
+            self.model.runs
+        ));
+        let rate = if self.checked > 0 {
+            self.mismatches as f64 / self.checked as f64 * 100.0
+        } else {
+            0.0
+        };
+        out.push_str(&format!(
+            "Windows checked: {}, outside model: {} ({:.2}%), unique: {}\n",
+            self.checked,
+            self.mismatches,
+            rate,
+            self.unknown.len()
+        ));
+
+        let mut unknown: Vec<_> = self.unknown.iter().collect();
+        unknown.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+        for (window, seen) in unknown.into_iter().take(MAX_REPORTED_SEQUENCES) {
+            out.push_str(&format!("  {:>6}× {}", seen.count, window.join(" → ")));
+            if let Some(site) = &seen.call_site {
+                out.push_str(&format!(" (at {})", site));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(syscalls: &[&str]) -> Vec<String> {
+        syscalls.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn trained() -> SequenceModel {
+        let mut model = SequenceModel::new("cat", 3);
+        model.train_run(&[names(&["openat", "read", "write", "read", "close"])]);
+        model
+    }
+
+    #[test]
+    fn test_train_run_collects_windows() {
+        let model = trained();
+        assert_eq!(model.runs, 1);
+        assert_eq!(model.len(), 3);
+        assert_eq!(model.metadata.training_samples, 3);
+        assert!(model.contains(&names(&["read", "write", "read"])));
+        assert!(!model.contains(&names(&["read", "close", "exit"])));
+    }
+
+    #[test]
+    fn test_threads_train_separately() {
+        let mut model = SequenceModel::new("app", 2);
+        model.train_run(&[names(&["read", "read"]), names(&["futex", "futex"])]);
+        assert_eq!(model.len(), 2);
+        assert!(!model.contains(&names(&["read", "futex"])));
+    }
+
+    #[test]
+    fn test_monitor_flags_unknown_windows_once() {
+        let mut monitor = SequenceMonitor::new(trained());
+        for syscall in ["openat", "read", "write"] {
+            assert!(monitor.observe(1, syscall, || None).is_none());
+        }
+
+        let mismatch = monitor
+            .observe(1, "socket", || Some("main.c:10".to_string()))
+            .unwrap();
+        assert_eq!(mismatch.window, names(&["read", "write", "socket"]));
+        assert_eq!(mismatch.call_site.as_deref(), Some("main.c:10"));
+        assert!(mismatch.first_occurrence);
+
+        // Same window again: still a mismatch, no longer the first
+        for syscall in ["read", "write"] {
+            monitor.observe(1, syscall, || None);
+        }
+        let again = monitor.observe(1, "socket", || None).unwrap();
+        assert!(!again.first_occurrence);
+        assert_eq!(again.call_site.as_deref(), Some("main.c:10"));
+
+        assert_eq!(monitor.mismatches(), 4);
+        assert!(monitor
+            .report()
+            .contains("read → write → socket (at main.c:10)"));
+    }
+
+    #[test]
+    fn test_monitor_keeps_windows_per_thread() {
+        let mut monitor = SequenceMonitor::new(trained());
+        // Interleaved threads each follow the model
+        for (a, b) in [("openat", "openat"), ("read", "read"), ("write", "write")] {
+            assert!(monitor.observe(1, a, || None).is_none());
+            assert!(monitor.observe(2, b, || None).is_none());
+        }
+        assert_eq!(monitor.checked(), 2);
+        assert_eq!(monitor.mismatches(), 0);
+    }
+}
@@ -0,0 +1,538 @@
+// Source Map v3 decoding (Sprint 54)
+//
+// Decodes standard JavaScript-style source maps ("version": 3) with
+// Base64 VLQ `mappings`, as emitted by TypeScript-style toolchains:
+//
+// ```json
+// {
+//   "version": 3,
+//   "file": "app.rs",
+//   "sourceRoot": "",
+//   "sources": ["app.ts"],
+//   "sourcesContent": ["function main() {\n  run();\n}\n"],
+//   "names": ["main", "run"],
+//   "mappings": "AAAAA,QAAQ;AACNC"
+// }
+// ```
+//
+// Index maps (`"sections"` with line/column offsets) are flattened. The
+// decoded segments become whole-segment ranges in the generated file, so
+// `TranspilerMap` serves them through the same interval-tree lookups as its
+// own v2 format.
+
+use crate::transpiler_map::{FilePair, RangeMapping, SourceRange};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Source map v3 document (regular or index map)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSourceMap {
+    version: u32,
+    #[serde(default)]
+    file: Option<String>,
+    #[serde(default)]
+    source_root: Option<String>,
+    #[serde(default)]
+    sources: Vec<Option<String>>,
+    #[serde(default)]
+    sources_content: Vec<Option<String>>,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    mappings: Option<String>,
+    #[serde(default)]
+    sections: Option<Vec<Section>>,
+}
+
+/// Index map section
+#[derive(Debug, Deserialize)]
+struct Section {
+    offset: Offset,
+    #[serde(default)]
+    map: Option<RawSourceMap>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+/// Section offset (0-based)
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct Offset {
+    line: usize,
+    column: usize,
+}
+
+/// Decoded v3 source map
+#[derive(Debug, Clone, Default)]
+pub struct DecodedSourceMap {
+    /// Generated file named by the map (`file`), if any
+    pub generated_file: Option<String>,
+    /// One pair per original source
+    pub files: Vec<FilePair>,
+    /// Mapped segments as ranges (1-based lines and columns)
+    pub ranges: Vec<RangeMapping>,
+}
+
+/// Whether a JSON document looks like a v3 source map
+pub fn is_v3(value: &serde_json::Value) -> bool {
+    value.get("version").and_then(serde_json::Value::as_u64) == Some(3)
+        && (value
+            .get("mappings")
+            .is_some_and(serde_json::Value::is_string)
+            || value
+                .get("sections")
+                .is_some_and(serde_json::Value::is_array))
+}
+
+/// Decode a v3 source map
+///
+/// `generated_file` names the generated file when the map has no `file`
+/// field (usually derived from the map's own path).
+pub fn decode(value: serde_json::Value, generated_file: Option<&str>) -> Result<DecodedSourceMap> {
+    let raw: RawSourceMap = serde_json::from_value(value).context("Invalid source map JSON")?;
+    let generated = raw
+        .file
+        .clone()
+        .filter(|f| !f.is_empty())
+        .or_else(|| generated_file.map(str::to_string));
+
+    let mut decoded = DecodedSourceMap {
+        generated_file: generated.clone(),
+        ..Default::default()
+    };
+    let mut decoder = Decoder {
+        generated: generated.unwrap_or_default(),
+        out: &mut decoded,
+        file_ids: HashMap::new(),
+    };
+    decoder.add_map(&raw, Offset { line: 0, column: 0 })?;
+    Ok(decoded)
+}
+
+struct Decoder<'a> {
+    generated: String,
+    out: &'a mut DecodedSourceMap,
+    file_ids: HashMap<String, usize>,
+}
+
+impl Decoder<'_> {
+    fn add_map(&mut self, map: &RawSourceMap, offset: Offset) -> Result<()> {
+        if map.version != 3 {
+            bail!(
+                "Unsupported source map version: {} (expected 3 in index map section)",
+                map.version
+            );
+        }
+
+        if let Some(sections) = &map.sections {
+            for section in sections {
+                let Some(inner) = &section.map else {
+                    bail!(
+                        "Invalid source map: index map section {} uses unsupported \"url\"",
+                        section.url.as_deref().unwrap_or("<missing>")
+                    );
+                };
+                let nested = Offset {
+                    line: offset.line + section.offset.line,
+                    column: if section.offset.line == 0 {
+                        offset.column + section.offset.column
+                    } else {
+                        section.offset.column
+                    },
+                };
+                self.add_map(inner, nested)?;
+            }
+            return Ok(());
+        }
+
+        let file_ids: Vec<Option<usize>> = map
+            .sources
+            .iter()
+            .map(|source| {
+                source
+                    .as_deref()
+                    .map(|s| self.file_id(&join_source_root(map.source_root.as_deref(), s)))
+            })
+            .collect();
+
+        let segments = decode_mappings(map.mappings.as_deref().unwrap_or(""))?;
+        for (i, segment) in segments.iter().enumerate() {
+            let Some(source) = segment.source else {
+                continue;
+            };
+            let Some(Some(file)) = file_ids.get(source.index) else {
+                bail!(
+                    "Invalid source map: mapping refers to source {} (only {} sources)",
+                    source.index,
+                    map.sources.len()
+                );
+            };
+
+            // A segment extends to the next segment on the same line
+            let end_column = segments
+                .get(i + 1)
+                .filter(|next| next.line == segment.line)
+                .map(|next| next.column);
+
+            let column_offset = if segment.line == 0 { offset.column } else { 0 };
+            let context = map
+                .sources_content
+                .get(source.index)
+                .and_then(Option::as_deref)
+                .and_then(|content| content.lines().nth(source.line))
+                .map(|line| line.trim().to_string())
+                .unwrap_or_default();
+            let name = source
+                .name
+                .and_then(|n| map.names.get(n))
+                .cloned()
+                .unwrap_or_default();
+
+            self.out.ranges.push(RangeMapping {
+                file: *file,
+                rust: SourceRange {
+                    line: offset.line + segment.line + 1,
+                    column: Some(column_offset + segment.column + 1),
+                    end_line: None,
+                    end_column: end_column.map(|c| column_offset + c),
+                },
+                source: SourceRange {
+                    line: source.line + 1,
+                    column: Some(source.column + 1),
+                    end_line: None,
+                    end_column: None,
+                },
+                rust_function: String::new(),
+                source_function: name,
+                source_context: context,
+            });
+        }
+        Ok(())
+    }
+
+    fn file_id(&mut self, source: &str) -> usize {
+        if let Some(&id) = self.file_ids.get(source) {
+            return id;
+        }
+        let id = self.out.files.len();
+        self.out.files.push(FilePair {
+            source_file: source.to_string(),
+            generated_file: self.generated.clone(),
+        });
+        self.file_ids.insert(source.to_string(), id);
+        id
+    }
+}
+
+fn join_source_root(root: Option<&str>, source: &str) -> String {
+    match root {
+        Some(root) if !root.is_empty() => {
+            format!("{}/{}", root.trim_end_matches('/'), source)
+        }
+        _ => source.to_string(),
+    }
+}
+
+/// Original position of a segment (0-based)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OriginalPosition {
+    /// Index into `sources`
+    pub index: usize,
+    /// Line in the original source
+    pub line: usize,
+    /// Column in the original source
+    pub column: usize,
+    /// Index into `names`
+    pub name: Option<usize>,
+}
+
+/// One decoded mapping segment (0-based generated position)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    /// Generated line
+    pub line: usize,
+    /// Generated column
+    pub column: usize,
+    /// Original position, absent for unmapped segments
+    pub source: Option<OriginalPosition>,
+}
+
+/// Decode the `mappings` string into absolute segments
+///
+/// Generated columns reset on each line; source index, line, column and
+/// name are relative to the previous segment across the whole string.
+pub fn decode_mappings(mappings: &str) -> Result<Vec<Segment>> {
+    let mut segments = Vec::new();
+    let (mut source, mut source_line, mut source_column, mut name) = (0i64, 0i64, 0i64, 0i64);
+
+    for (line, group) in mappings.split(';').enumerate() {
+        let mut column = 0i64;
+        for text in group.split(',').filter(|s| !s.is_empty()) {
+            let fields = decode_vlq(text)?;
+            column += fields[0];
+
+            let original = match fields.len() {
+                1 => None,
+                4 | 5 => {
+                    source += fields[1];
+                    source_line += fields[2];
+                    source_column += fields[3];
+                    let name_index = if fields.len() == 5 {
+                        name += fields[4];
+                        Some(to_index(name, text)?)
+                    } else {
+                        None
+                    };
+                    Some(OriginalPosition {
+                        index: to_index(source, text)?,
+                        line: to_index(source_line, text)?,
+                        column: to_index(source_column, text)?,
+                        name: name_index,
+                    })
+                }
+                n => bail!(
+                    "Invalid source map: segment \"{}\" has {} fields (expected 1, 4 or 5)",
+                    text,
+                    n
+                ),
+            };
+
+            segments.push(Segment {
+                line,
+                column: to_index(column, text)?,
+                source: original,
+            });
+        }
+    }
+    Ok(segments)
+}
+
+fn to_index(value: i64, segment: &str) -> Result<usize> {
+    usize::try_from(value)
+        .with_context(|| format!("Invalid source map: negative position in \"{}\"", segment))
+}
+
+/// Decode one segment of Base64 VLQ values
+pub fn decode_vlq(segment: &str) -> Result<Vec<i64>> {
+    let mut values = Vec::new();
+    let mut value: i64 = 0;
+    let mut shift = 0;
+
+    for byte in segment.bytes() {
+        let digit = base64_value(byte).with_context(|| {
+            format!(
+                "Invalid source map: bad VLQ character '{}' in \"{}\"",
+                byte as char, segment
+            )
+        })?;
+        if shift > 60 {
+            bail!("Invalid source map: VLQ value too large in \"{}\"", segment);
+        }
+
+        value |= i64::from(digit & 0b11111) << shift;
+        if digit & 0b100000 != 0 {
+            shift += 5;
+            continue;
+        }
+
+        // Lowest bit is the sign
+        let magnitude = value >> 1;
+        values.push(if value & 1 == 1 {
+            -magnitude
+        } else {
+            magnitude
+        });
+        value = 0;
+        shift = 0;
+    }
+
+    if shift != 0 {
+        bail!("Invalid source map: truncated VLQ value in \"{}\"", segment);
+    }
+    Ok(values)
+}
+
+fn base64_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_vlq() {
+        assert_eq!(decode_vlq("A").unwrap(), vec![0]);
+        assert_eq!(decode_vlq("C").unwrap(), vec![1]);
+        assert_eq!(decode_vlq("D").unwrap(), vec![-1]);
+        assert_eq!(decode_vlq("gB").unwrap(), vec![16]);
+        assert_eq!(decode_vlq("AAgBC").unwrap(), vec![0, 0, 16, 1]);
+        assert_eq!(decode_vlq("2HwcrxB").unwrap(), vec![123, 456, -789]);
+    }
+
+    #[test]
+    fn test_decode_vlq_errors() {
+        assert!(decode_vlq("A!").is_err());
+        // Continuation bit set on the last digit
+        assert!(decode_vlq("g").is_err());
+    }
+
+    #[test]
+    fn test_decode_mappings_relative_fields() {
+        // Line 0: col 0 -> src 0 0:0, col 8 -> src 0 0:8 name 0
+        // Line 1: unmapped col 2, col 4 -> src 1 1:10
+        let segments = decode_mappings("AAAA,QAAQA;E,ECCE").unwrap();
+        assert_eq!(segments.len(), 4);
+        assert_eq!(segments[1].column, 8);
+        assert_eq!(
+            segments[1].source,
+            Some(OriginalPosition {
+                index: 0,
+                line: 0,
+                column: 8,
+                name: Some(0)
+            })
+        );
+        assert_eq!((segments[2].line, segments[2].column), (1, 2));
+        assert_eq!(segments[2].source, None);
+        assert_eq!(
+            segments[3].source,
+            Some(OriginalPosition {
+                index: 1,
+                line: 1,
+                column: 10,
+                name: None
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_mappings_bad_field_count() {
+        assert!(decode_mappings("AA").is_err());
+        // Negative absolute position
+        assert!(decode_mappings("ADAA").is_err());
+    }
+
+    #[test]
+    fn test_is_v3() {
+        assert!(is_v3(&json!({"version": 3, "mappings": ""})));
+        assert!(is_v3(&json!({"version": 3, "sections": []})));
+        assert!(!is_v3(&json!({"version": 2, "mappings": []})));
+        assert!(!is_v3(&json!({"version": 3})));
+    }
+
+    #[test]
+    fn test_decode_map_ranges() {
+        let map = json!({
+            "version": 3,
+            "file": "app.rs",
+            "sourceRoot": "src/",
+            "sources": ["app.ts"],
+            "sourcesContent": ["function main() {\n  run();\n}\n"],
+            "names": ["main", "run"],
+            "mappings": "AAAAA,QAAQ;AACNC"
+        });
+        let decoded = decode(map, None).unwrap();
+
+        assert_eq!(decoded.generated_file.as_deref(), Some("app.rs"));
+        assert_eq!(
+            decoded.files,
+            vec![FilePair {
+                source_file: "src/app.ts".to_string(),
+                generated_file: "app.rs".to_string()
+            }]
+        );
+        assert_eq!(decoded.ranges.len(), 3);
+
+        let first = &decoded.ranges[0];
+        assert_eq!(first.rust.line, 1);
+        assert_eq!(first.rust.column, Some(1));
+        assert_eq!(first.rust.end_column, Some(8));
+        assert_eq!(first.source_function, "main");
+        assert_eq!(first.source_context, "function main() {");
+
+        // Last segment on a line runs to the end of the line
+        assert_eq!(decoded.ranges[1].rust.end_column, None);
+
+        let second_line = &decoded.ranges[2];
+        assert_eq!(second_line.rust.line, 2);
+        assert_eq!(second_line.source.line, 2);
+        assert_eq!(second_line.source.column, Some(3));
+        assert_eq!(second_line.source_function, "run");
+        assert_eq!(second_line.source_context, "run();");
+    }
+
+    #[test]
+    fn test_decode_index_map_offsets() {
+        let map = json!({
+            "version": 3,
+            "file": "bundle.rs",
+            "sections": [
+                {
+                    "offset": {"line": 0, "column": 0},
+                    "map": {"version": 3, "sources": ["a.ts"], "names": [], "mappings": "AAAA"}
+                },
+                {
+                    "offset": {"line": 10, "column": 4},
+                    "map": {"version": 3, "sources": ["b.ts", "a.ts"], "names": [], "mappings": "AAAA;ACAA"}
+                }
+            ]
+        });
+        let decoded = decode(map, None).unwrap();
+
+        let sources: Vec<&str> = decoded
+            .files
+            .iter()
+            .map(|f| f.source_file.as_str())
+            .collect();
+        assert_eq!(sources, vec!["a.ts", "b.ts"]);
+        assert!(decoded
+            .files
+            .iter()
+            .all(|f| f.generated_file == "bundle.rs"));
+
+        let b = &decoded.ranges[1];
+        assert_eq!(decoded.files[b.file].source_file, "b.ts");
+        assert_eq!((b.rust.line, b.rust.column), (11, Some(5)));
+
+        // Column offset only applies to the section's first line
+        let a = &decoded.ranges[2];
+        assert_eq!(decoded.files[a.file].source_file, "a.ts");
+        assert_eq!((a.rust.line, a.rust.column), (12, Some(1)));
+    }
+
+    #[test]
+    fn test_decode_rejects_url_sections_and_bad_sources() {
+        let map = json!({
+            "version": 3,
+            "sections": [{"offset": {"line": 0, "column": 0}, "url": "other.map"}]
+        });
+        assert!(decode(map, None)
+            .unwrap_err()
+            .to_string()
+            .contains("unsupported \"url\""));
+
+        let map = json!({"version": 3, "sources": ["a.ts"], "mappings": "ACAA"});
+        assert!(decode(map, None)
+            .unwrap_err()
+            .to_string()
+            .contains("refers to source 1"));
+    }
+
+    #[test]
+    fn test_generated_file_fallback() {
+        let map = json!({"version": 3, "sources": ["a.ts"], "mappings": "AAAA"});
+        let decoded = decode(map, Some("a.rs")).unwrap();
+        assert_eq!(decoded.generated_file.as_deref(), Some("a.rs"));
+        assert_eq!(decoded.files[0].generated_file, "a.rs");
+    }
+}
@@ -10,6 +10,7 @@ use anyhow::{Context, Result};
 use nix::sys::ptrace;
 use nix::sys::uio::{process_vm_readv, RemoteIoVec};
 use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
 use std::io::IoSliceMut;
 
 /// Maximum stack depth to unwind (prevent infinite loops)
@@ -25,6 +26,104 @@ pub struct StackFrame {
     pub rbp: u64,
 }
 
+/// A stack frame resolved to a function name and source location
+///
+/// Sprint 50: Used for flamegraph output, where every frame of the call stack
+/// (not just the first user function) is needed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResolvedFrame {
+    /// Instruction pointer of the frame
+    pub address: u64,
+    /// Demangled function name (if it could be resolved)
+    pub function: Option<String>,
+    /// Source file (if DWARF line info is available)
+    pub file: Option<String>,
+    /// Source line (if DWARF line info is available)
+    pub line: Option<u32>,
+    /// Binary or shared library containing the address
+    pub module: Option<String>,
+    /// True if this function was inlined into the next (outer) frame
+    ///
+    /// Sprint 51: pprof groups an inline chain into a single location.
+    pub inlined: bool,
+}
+
+impl ResolvedFrame {
+    /// Label used for this frame in folded stacks and flamegraphs
+    ///
+    /// Falls back to `[module]` (like perf) and finally `[unknown]`.
+    pub fn label(&self) -> String {
+        if let Some(function) = &self.function {
+            function.clone()
+        } else if let Some(module) = &self.module {
+            format!("[{}]", module)
+        } else {
+            "[unknown]".to_string()
+        }
+    }
+}
+
+/// Resolve raw stack frames to function names, innermost frame first
+///
+/// Inlined functions expand into several frames sharing the same address.
+/// Return addresses (all frames but the first) point after the call
+/// instruction, so they are looked up one byte earlier.
+pub fn resolve_frames(
+    frames: &[StackFrame],
+    dwarf_ctx: Option<&crate::dwarf::DwarfContext>,
+    maps: &[crate::proc_maps::MemoryMapping],
+) -> Vec<ResolvedFrame> {
+    let mut resolved = Vec::with_capacity(frames.len());
+
+    for (i, frame) in frames.iter().enumerate() {
+        let lookup_ip = if i == 0 {
+            frame.rip
+        } else {
+            frame.rip.saturating_sub(1)
+        };
+
+        let module = crate::proc_maps::find_mapping(maps, frame.rip)
+            .and_then(|mapping| mapping.module_name())
+            .map(|name| name.to_string());
+
+        let (names, location) = match dwarf_ctx {
+            Some(ctx) => (
+                ctx.function_names(lookup_ip),
+                ctx.lookup(lookup_ip).ok().flatten(),
+            ),
+            None => (Vec::new(), None),
+        };
+
+        if names.is_empty() {
+            resolved.push(ResolvedFrame {
+                address: frame.rip,
+                function: None,
+                file: None,
+                line: None,
+                module,
+                inlined: false,
+            });
+            continue;
+        }
+
+        // Source location belongs to the innermost (possibly inlined) frame
+        let outermost = names.len() - 1;
+        for (j, name) in names.into_iter().enumerate() {
+            let location = location.as_ref().filter(|_| j == 0);
+            resolved.push(ResolvedFrame {
+                address: frame.rip,
+                function: Some(name),
+                file: location.map(|loc| loc.file.clone()),
+                line: location.map(|loc| loc.line),
+                module: module.clone(),
+                inlined: j < outermost,
+            });
+        }
+    }
+
+    resolved
+}
+
 /// Unwind the stack of a traced process
 ///
 /// Returns a list of stack frames, with the first frame being the current
@@ -164,6 +263,49 @@ mod tests {
         assert_eq!(frame.rbp, 0xFFFFFFFFFFFFFFFF);
     }
 
+    #[test]
+    fn test_resolved_frame_label() {
+        let mut frame = ResolvedFrame {
+            address: 0x1000,
+            function: Some("app::main".to_string()),
+            file: None,
+            line: None,
+            module: Some("app".to_string()),
+            inlined: false,
+        };
+        assert_eq!(frame.label(), "app::main");
+
+        frame.function = None;
+        assert_eq!(frame.label(), "[app]");
+
+        frame.module = None;
+        assert_eq!(frame.label(), "[unknown]");
+    }
+
+    #[test]
+    fn test_resolve_frames_without_symbols() {
+        let frames = vec![
+            StackFrame {
+                rip: 0x7f00_0000_1000,
+                rbp: 0,
+            },
+            StackFrame {
+                rip: 0x5500_0000_2000,
+                rbp: 0,
+            },
+        ];
+        let maps = crate::proc_maps::parse_maps(
+            "7f0000000000-7f0000100000 r-xp 00000000 08:01 1 /usr/lib/libc.so.6\n",
+        );
+
+        let resolved = resolve_frames(&frames, None, &maps);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].label(), "[libc.so.6]");
+        assert_eq!(resolved[1].label(), "[unknown]");
+        assert_eq!(resolved[1].address, 0x5500_0000_2000);
+        assert!(resolved.iter().all(|frame| !frame.inlined));
+    }
+
     // Note: Testing unwind_stack() and read_u64_from_process() requires
     // a real traced process, which is covered by integration tests
     // (tests/sprint13_stack_unwinding_tests.rs)
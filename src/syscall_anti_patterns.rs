@@ -0,0 +1,735 @@
+//! Syscall-level anti-patterns (Sprint 74)
+//!
+//! [`crate::anti_patterns`] finds distributed-system smells in causal graphs
+//! and [`crate::analysis::anti_pattern`] finds architectural ones. This
+//! module finds the everyday I/O mistakes usually spotted by reading strace
+//! output, each with a recommendation and the call site responsible:
+//!
+//! - **Tiny writes**: many small `write`s to one fd (unbuffered output)
+//! - **Byte reads**: `read`s with a 1-byte buffer
+//! - **Repeated lookups**: `stat`/`openat` of the same path again and again
+//! - **Search path probing**: the same file name failing with `ENOENT` in
+//!   directory after directory (`PATH`, `LD_LIBRARY_PATH`, `sys.path`)
+//! - **Fsync loop**: `fsync`/`fdatasync` called repeatedly from one site
+//! - **Polling loop**: `nanosleep`/`sched_yield` repeated from one site
+//! - **Mmap churn**: `mmap` followed by `munmap` of the same region, repeatedly
+//!
+//! Calls are grouped per process (and fd or path where it matters); a
+//! group becomes a finding once it reaches its threshold.
+
+use crate::anti_patterns::Severity;
+use crate::offcpu::format_us;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+const ENOENT: i64 = 2;
+const ENOTDIR: i64 = 20;
+
+/// Syscalls that look a path up without reading it
+const LOOKUP_SYSCALLS: &[&str] = &[
+    "open",
+    "openat",
+    "openat2",
+    "stat",
+    "lstat",
+    "newfstatat",
+    "statx",
+    "access",
+    "faccessat",
+    "faccessat2",
+];
+
+/// Syscalls that probe for a file along a search path
+///
+/// stat and access are left out: the dynamic loader stats every hwcaps
+/// subdirectory on its way, which says nothing about the file it is after.
+const PROBE_SYSCALLS: &[&str] = &["open", "openat", "openat2", "execve"];
+
+/// Syscalls that write a buffer to one fd
+const WRITE_SYSCALLS: &[&str] = &["write", "pwrite64", "send", "sendto"];
+
+/// Syscalls that read into a buffer from one fd
+const READ_SYSCALLS: &[&str] = &["read", "pread64", "recv", "recvfrom"];
+
+const SYNC_SYSCALLS: &[&str] = &["fsync", "fdatasync"];
+
+const POLL_SYSCALLS: &[&str] = &["nanosleep", "clock_nanosleep", "sched_yield"];
+
+/// Detection thresholds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    /// Writes of at most this many bytes are tiny
+    pub tiny_write_bytes: u64,
+    /// Tiny writes to one fd from one site before they are reported
+    pub tiny_writes: u64,
+    /// 1-byte reads from one fd from one site
+    pub byte_reads: u64,
+    /// Lookups of one path
+    pub path_lookups: u64,
+    /// Directories a file name is probed in without success
+    pub probed_dirs: usize,
+    /// Syncs from one site
+    pub syncs: u64,
+    /// Sleeps or yields from one site
+    pub polls: u64,
+    /// Map/unmap cycles from one site
+    pub mmap_cycles: u64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            tiny_write_bytes: 64,
+            tiny_writes: 20,
+            byte_reads: 20,
+            path_lookups: 10,
+            probed_dirs: 5,
+            syncs: 10,
+            polls: 20,
+            mmap_cycles: 20,
+        }
+    }
+}
+
+/// Kind of syscall-level anti-pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SyscallAntiPattern {
+    TinyWrites,
+    ByteReads,
+    RepeatedLookups,
+    SearchPathProbing,
+    FsyncLoop,
+    PollingLoop,
+    MmapChurn,
+}
+
+impl SyscallAntiPattern {
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            SyscallAntiPattern::TinyWrites => "Unbuffered tiny writes",
+            SyscallAntiPattern::ByteReads => "1-byte reads",
+            SyscallAntiPattern::RepeatedLookups => "Repeated path lookups",
+            SyscallAntiPattern::SearchPathProbing => "Search path probing",
+            SyscallAntiPattern::FsyncLoop => "fsync in a loop",
+            SyscallAntiPattern::PollingLoop => "Polling loop",
+            SyscallAntiPattern::MmapChurn => "mmap/munmap churn",
+        }
+    }
+
+    /// What to do about it
+    pub fn recommendation(self) -> &'static str {
+        match self {
+            SyscallAntiPattern::TinyWrites => {
+                "Buffer output (BufWriter, setvbuf/fwrite) and write in blocks of 4 KiB or more, \
+                 or gather pieces with writev."
+            }
+            SyscallAntiPattern::ByteReads => {
+                "Read into a larger buffer (BufReader, fread) and scan it in memory."
+            }
+            SyscallAntiPattern::RepeatedLookups => {
+                "Cache the metadata or keep the file open instead of looking the path up again."
+            }
+            SyscallAntiPattern::SearchPathProbing => {
+                "Shorten the search path (PATH, LD_LIBRARY_PATH, PYTHONPATH), put the usual \
+                 location first, or use an absolute path."
+            }
+            SyscallAntiPattern::FsyncLoop => {
+                "Batch writes and sync once per transaction or at the end; only sync each \
+                 write where durability requires it."
+            }
+            SyscallAntiPattern::PollingLoop => {
+                "Block on the event instead (condition variable, channel, eventfd, epoll, \
+                 waitpid) of sleeping or yielding and checking again."
+            }
+            SyscallAntiPattern::MmapChurn => {
+                "Reuse buffers or a pool instead of mapping per use; for malloc-driven churn \
+                 raise M_MMAP_THRESHOLD / M_TRIM_THRESHOLD."
+            }
+        }
+    }
+}
+
+impl fmt::Display for SyscallAntiPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A completed syscall for [`SyscallPatternDetector::record`]
+#[derive(Debug, Clone, Default)]
+pub struct Call<'a> {
+    pub pid: i32,
+    pub name: &'a str,
+    /// Decoded arguments; path arguments are quoted
+    pub args: &'a [String],
+    /// First three raw argument registers
+    pub raw_args: [u64; 3],
+    pub result: i64,
+    pub duration_us: u64,
+    /// Target of the fd argument (`/dev/pts/0`, `pipe:[1234]`), when resolved
+    pub fd_path: Option<&'a str>,
+    /// `file:line (function)` or function that made the call
+    pub call_site: Option<String>,
+}
+
+impl Call<'_> {
+    /// First path argument, unquoted
+    fn path(&self) -> Option<&str> {
+        let index = *crate::file_audit::path_arg_indices(self.name).first()?;
+        crate::file_audit::unquote(self.args.get(index)?)
+    }
+
+    fn fd(&self) -> i32 {
+        self.raw_args[0] as i32
+    }
+}
+
+/// One reported anti-pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub pattern: SyscallAntiPattern,
+    pub severity: Severity,
+    pub pid: i32,
+    /// Where the calls were made (most frequent site for path patterns)
+    pub call_site: Option<String>,
+    /// Calls (or map/unmap cycles) involved
+    pub count: u64,
+    /// Time spent in those calls, in microseconds
+    pub total_us: u64,
+    /// What was found, e.g. `100 writes of at most 64 bytes to fd 3 (/dev/null)`
+    pub detail: String,
+}
+
+impl Finding {
+    /// Recommended fix
+    pub fn recommendation(&self) -> &'static str {
+        self.pattern.recommendation()
+    }
+}
+
+/// Calls grouped under one key
+#[derive(Debug, Clone, Default)]
+struct Group {
+    count: u64,
+    total_us: u64,
+    bytes: u64,
+    /// Calls per call site
+    sites: HashMap<Option<String>, u64>,
+    /// Fd target, for fd groups
+    target: Option<String>,
+}
+
+impl Group {
+    fn add(&mut self, call: &Call, bytes: u64) {
+        self.count += 1;
+        self.total_us += call.duration_us;
+        self.bytes += bytes;
+        *self.sites.entry(call.call_site.clone()).or_default() += 1;
+        if self.target.is_none() {
+            self.target = call.fd_path.map(str::to_string);
+        }
+    }
+
+    /// The site most calls came from
+    fn top_site(&self) -> Option<String> {
+        self.sites
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+            .and_then(|(site, _)| site.clone())
+    }
+}
+
+/// Failed lookups of one file name
+#[derive(Debug, Clone, Default)]
+struct Probe {
+    group: Group,
+    /// Directories the name was missing from
+    missing: BTreeSet<String>,
+    /// Where it was eventually found
+    found: Option<String>,
+}
+
+type SiteKey = (i32, &'static str, Option<String>);
+type FdKey = (i32, i32, Option<String>);
+
+/// Groups syscalls into anti-pattern candidates during tracing
+#[derive(Debug, Default)]
+pub struct SyscallPatternDetector {
+    thresholds: Thresholds,
+    tiny_writes: HashMap<FdKey, Group>,
+    byte_reads: HashMap<FdKey, Group>,
+    lookups: HashMap<(i32, String), Group>,
+    probes: HashMap<(i32, String), Probe>,
+    /// Syncs and polls, by syscall
+    loops: HashMap<SiteKey, Group>,
+    /// Live mappings per process: address → (length, site)
+    mappings: HashMap<(i32, u64), (u64, Option<String>)>,
+    mmap_cycles: HashMap<(i32, Option<String>), Group>,
+}
+
+impl SyscallPatternDetector {
+    pub fn new(thresholds: Thresholds) -> Self {
+        Self {
+            thresholds,
+            ..Self::default()
+        }
+    }
+
+    /// Record one completed syscall
+    pub fn record(&mut self, call: &Call) {
+        let name = call.name;
+        if WRITE_SYSCALLS.contains(&name) {
+            let size = call.raw_args[2];
+            if call.result >= 0 && size <= self.thresholds.tiny_write_bytes {
+                let key = (call.pid, call.fd(), call.call_site.clone());
+                self.tiny_writes.entry(key).or_default().add(call, size);
+            }
+        } else if READ_SYSCALLS.contains(&name) {
+            if call.raw_args[2] == 1 {
+                let key = (call.pid, call.fd(), call.call_site.clone());
+                self.byte_reads.entry(key).or_default().add(call, 1);
+            }
+        } else if let Some(&sync) = SYNC_SYSCALLS.iter().find(|&&s| s == name) {
+            let key = (call.pid, sync, call.call_site.clone());
+            self.loops.entry(key).or_default().add(call, 0);
+        } else if let Some(&poll) = POLL_SYSCALLS.iter().find(|&&s| s == name) {
+            let key = (call.pid, poll, call.call_site.clone());
+            self.loops.entry(key).or_default().add(call, 0);
+        } else if name == "mmap" && call.result > 0 {
+            let site = call.call_site.clone();
+            self.mappings
+                .insert((call.pid, call.result as u64), (call.raw_args[1], site));
+        } else if name == "munmap" && call.result == 0 {
+            let [addr, length, _] = call.raw_args;
+            if let Some((mapped, site)) = self.mappings.remove(&(call.pid, addr)) {
+                if mapped == length {
+                    let cycle = Call {
+                        call_site: site.clone(),
+                        ..call.clone()
+                    };
+                    let group = self.mmap_cycles.entry((call.pid, site)).or_default();
+                    group.add(&cycle, length);
+                }
+            }
+        }
+
+        if let Some(path) = call.path() {
+            if LOOKUP_SYSCALLS.contains(&name) {
+                let key = (call.pid, path.to_string());
+                self.lookups.entry(key).or_default().add(call, 0);
+            }
+            if PROBE_SYSCALLS.contains(&name) {
+                self.probe(call, path);
+            }
+        }
+    }
+
+    /// Track a lookup by file name across directories
+    fn probe(&mut self, call: &Call, path: &str) {
+        let Some((dir, file)) = path.rsplit_once('/') else {
+            return;
+        };
+        if file.is_empty() {
+            return;
+        }
+        let missing = matches!(-call.result, ENOENT | ENOTDIR);
+        if !missing && call.result < 0 {
+            return;
+        }
+        let key = (call.pid, file.to_string());
+        if missing {
+            let probe = self.probes.entry(key).or_default();
+            probe.group.add(call, 0);
+            probe
+                .missing
+                .insert(if dir.is_empty() { "/" } else { dir }.to_string());
+        } else if let Some(probe) = self.probes.get_mut(&key) {
+            probe.found.get_or_insert_with(|| path.to_string());
+        }
+    }
+
+    /// Anti-patterns found so far, most severe (then most costly) first
+    pub fn findings(&self) -> Vec<Finding> {
+        let t = &self.thresholds;
+        let mut findings = Vec::new();
+
+        for ((pid, fd, site), group) in &self.tiny_writes {
+            if group.count >= t.tiny_writes {
+                findings.push(finding(
+                    SyscallAntiPattern::TinyWrites,
+                    *pid,
+                    site.clone(),
+                    group,
+                    t.tiny_writes,
+                    format!(
+                        "{} writes of at most {} bytes ({} bytes in all) to {}",
+                        group.count,
+                        t.tiny_write_bytes,
+                        group.bytes,
+                        fd_label(*fd, group)
+                    ),
+                ));
+            }
+        }
+        for ((pid, fd, site), group) in &self.byte_reads {
+            if group.count >= t.byte_reads {
+                findings.push(finding(
+                    SyscallAntiPattern::ByteReads,
+                    *pid,
+                    site.clone(),
+                    group,
+                    t.byte_reads,
+                    format!(
+                        "{} reads of 1 byte from {}",
+                        group.count,
+                        fd_label(*fd, group)
+                    ),
+                ));
+            }
+        }
+        for ((pid, path), group) in &self.lookups {
+            if group.count >= t.path_lookups {
+                findings.push(finding(
+                    SyscallAntiPattern::RepeatedLookups,
+                    *pid,
+                    group.top_site(),
+                    group,
+                    t.path_lookups,
+                    format!("{} lookups of {}", group.count, path),
+                ));
+            }
+        }
+        for ((pid, file), probe) in &self.probes {
+            let dirs = probe.missing.len();
+            if dirs >= t.probed_dirs {
+                let outcome = match &probe.found {
+                    Some(path) => format!(", found at {}", path),
+                    None => ", never found".to_string(),
+                };
+                findings.push(finding(
+                    SyscallAntiPattern::SearchPathProbing,
+                    *pid,
+                    probe.group.top_site(),
+                    &probe.group,
+                    t.probed_dirs as u64,
+                    format!(
+                        "{} missing from {} directories ({} failed lookups){}",
+                        file, dirs, probe.group.count, outcome
+                    ),
+                ));
+            }
+        }
+        for ((pid, syscall, site), group) in &self.loops {
+            let (pattern, threshold) = if SYNC_SYSCALLS.contains(syscall) {
+                (SyscallAntiPattern::FsyncLoop, t.syncs)
+            } else {
+                (SyscallAntiPattern::PollingLoop, t.polls)
+            };
+            if group.count >= threshold {
+                findings.push(finding(
+                    pattern,
+                    *pid,
+                    site.clone(),
+                    group,
+                    threshold,
+                    format!("{} {} calls", group.count, syscall),
+                ));
+            }
+        }
+        for ((pid, site), group) in &self.mmap_cycles {
+            if group.count >= t.mmap_cycles {
+                findings.push(finding(
+                    SyscallAntiPattern::MmapChurn,
+                    *pid,
+                    site.clone(),
+                    group,
+                    t.mmap_cycles,
+                    format!(
+                        "{} regions mapped and unmapped again ({} bytes in all)",
+                        group.count, group.bytes
+                    ),
+                ));
+            }
+        }
+
+        findings.sort_by(|a, b| {
+            b.severity
+                .cmp(&a.severity)
+                .then(b.count.cmp(&a.count))
+                .then(a.pattern.cmp(&b.pattern))
+                .then(a.pid.cmp(&b.pid))
+                .then(a.detail.cmp(&b.detail))
+        });
+        findings
+    }
+
+    /// Text report of [`findings`](Self::findings)
+    pub fn report(&self) -> String {
+        let mut out = String::from("\n=== Syscall Anti-Patterns ===\n");
+        let findings = self.findings();
+        if findings.is_empty() {
+            out.push_str("\nNo syscall anti-patterns found.\n");
+            return out;
+        }
+
+        for finding in &findings {
+            out.push_str(&format!(
+                "\n[{:?}] {}: {} ({} in syscalls)\n",
+                finding.severity,
+                finding.pattern,
+                finding.detail,
+                format_us(finding.total_us)
+            ));
+            out.push_str(&format!(
+                "  pid {} at {}\n",
+                finding.pid,
+                finding.call_site.as_deref().unwrap_or("unknown call site")
+            ));
+            out.push_str(&format!("  Recommendation: {}\n", finding.recommendation()));
+        }
+        out
+    }
+}
+
+fn finding(
+    pattern: SyscallAntiPattern,
+    pid: i32,
+    call_site: Option<String>,
+    group: &Group,
+    threshold: u64,
+    detail: String,
+) -> Finding {
+    Finding {
+        pattern,
+        severity: severity(group.count, threshold),
+        pid,
+        call_site,
+        count: group.count,
+        total_us: group.total_us,
+        detail,
+    }
+}
+
+/// Severity from how far past its threshold a group is
+fn severity(count: u64, threshold: u64) -> Severity {
+    let threshold = threshold.max(1);
+    if count >= threshold * 50 {
+        Severity::High
+    } else if count >= threshold * 5 {
+        Severity::Medium
+    } else {
+        Severity::Low
+    }
+}
+
+fn fd_label(fd: i32, group: &Group) -> String {
+    match &group.target {
+        Some(target) => format!("fd {} ({})", fd, target),
+        None => format!("fd {}", fd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call<'a>(pid: i32, name: &'a str, raw_args: [u64; 3], result: i64) -> Call<'a> {
+        Call {
+            pid,
+            name,
+            raw_args,
+            result,
+            duration_us: 2,
+            call_site: Some("app.c:10 (main)".to_string()),
+            ..Call::default()
+        }
+    }
+
+    fn lookup<'a>(name: &'a str, args: &'a [String], result: i64) -> Call<'a> {
+        Call {
+            args,
+            ..call(1, name, [0; 3], result)
+        }
+    }
+
+    fn quoted(path: &str) -> String {
+        format!("\"{}\"", path)
+    }
+
+    fn patterns(detector: &SyscallPatternDetector) -> Vec<SyscallAntiPattern> {
+        detector.findings().iter().map(|f| f.pattern).collect()
+    }
+
+    #[test]
+    fn test_tiny_writes_per_fd_and_site() {
+        let mut detector = SyscallPatternDetector::default();
+        for _ in 0..19 {
+            detector.record(&Call {
+                fd_path: Some("/dev/null"),
+                ..call(1, "write", [3, 0x1000, 1], 1)
+            });
+        }
+        detector.record(&call(1, "write", [3, 0x1000, 4096], 4096));
+        detector.record(&call(1, "write", [4, 0x1000, 1], 1));
+        assert!(detector.findings().is_empty());
+
+        detector.record(&call(1, "write", [3, 0x1000, 2], 2));
+        let findings = detector.findings();
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].pattern, SyscallAntiPattern::TinyWrites);
+        assert_eq!(findings[0].severity, Severity::Low);
+        assert_eq!(findings[0].call_site.as_deref(), Some("app.c:10 (main)"));
+        assert_eq!(
+            findings[0].detail,
+            "20 writes of at most 64 bytes (21 bytes in all) to fd 3 (/dev/null)"
+        );
+    }
+
+    #[test]
+    fn test_byte_reads() {
+        let mut detector = SyscallPatternDetector::default();
+        for _ in 0..100 {
+            detector.record(&call(1, "read", [0, 0x1000, 1], 1));
+            detector.record(&call(1, "read", [5, 0x1000, 8192], 8192));
+        }
+        let findings = detector.findings();
+        assert_eq!(patterns(&detector), vec![SyscallAntiPattern::ByteReads]);
+        assert_eq!(findings[0].severity, Severity::Medium);
+        assert_eq!(findings[0].detail, "100 reads of 1 byte from fd 0");
+    }
+
+    #[test]
+    fn test_repeated_lookups_of_one_path() {
+        let mut detector = SyscallPatternDetector::default();
+        let config = ["0xffffff9c".to_string(), quoted("/etc/app.conf")];
+        let other = ["0xffffff9c".to_string(), quoted("/etc/hosts")];
+        for _ in 0..6 {
+            detector.record(&lookup("newfstatat", &config, 0));
+            detector.record(&lookup("openat", &config, 3));
+            detector.record(&lookup("openat", &other, 3));
+        }
+        let findings = detector.findings();
+        assert_eq!(
+            patterns(&detector),
+            vec![SyscallAntiPattern::RepeatedLookups]
+        );
+        assert_eq!(findings[0].detail, "12 lookups of /etc/app.conf");
+    }
+
+    #[test]
+    fn test_search_path_probing() {
+        let mut detector = SyscallPatternDetector::default();
+        let dirs = [
+            "/opt/a",
+            "/opt/b",
+            "/usr/local/lib",
+            "/lib/tls",
+            "/lib/x86_64",
+        ];
+        let paths: Vec<[String; 2]> = dirs
+            .iter()
+            .map(|dir| ["0x0".to_string(), quoted(&format!("{}/libfoo.so", dir))])
+            .collect();
+        for args in &paths {
+            detector.record(&lookup("openat", args, -ENOENT));
+        }
+        let found = ["0x0".to_string(), quoted("/usr/lib/libfoo.so")];
+        detector.record(&lookup("openat", &found, 3));
+
+        let findings = detector.findings();
+        assert_eq!(
+            patterns(&detector),
+            vec![SyscallAntiPattern::SearchPathProbing]
+        );
+        assert_eq!(
+            findings[0].detail,
+            "libfoo.so missing from 5 directories (5 failed lookups), found at /usr/lib/libfoo.so"
+        );
+
+        // execve along PATH counts too
+        let mut detector = SyscallPatternDetector::default();
+        let paths: Vec<[String; 1]> = dirs
+            .iter()
+            .map(|dir| [quoted(&format!("{}/python3", dir))])
+            .collect();
+        for args in &paths {
+            detector.record(&lookup("execve", args, -ENOENT));
+        }
+        assert!(detector.findings()[0].detail.ends_with(", never found"));
+    }
+
+    #[test]
+    fn test_fsync_and_polling_loops() {
+        let mut detector = SyscallPatternDetector::default();
+        for _ in 0..10 {
+            detector.record(&call(1, "write", [3, 0x1000, 4096], 4096));
+            detector.record(&call(1, "fsync", [3, 0, 0], 0));
+        }
+        for _ in 0..19 {
+            detector.record(&call(1, "nanosleep", [0x1000, 0, 0], 0));
+        }
+        assert_eq!(patterns(&detector), vec![SyscallAntiPattern::FsyncLoop]);
+
+        detector.record(&call(1, "nanosleep", [0x1000, 0, 0], 0));
+        let findings = detector.findings();
+        assert_eq!(
+            patterns(&detector),
+            vec![
+                SyscallAntiPattern::PollingLoop,
+                SyscallAntiPattern::FsyncLoop
+            ]
+        );
+        assert_eq!(findings[0].detail, "20 nanosleep calls");
+    }
+
+    #[test]
+    fn test_mmap_churn() {
+        let mut detector = SyscallPatternDetector::default();
+        for i in 0..25 {
+            let addr = 0x7f00_0000_0000 + i * 0x10_0000;
+            detector.record(&call(1, "mmap", [0, 0x10_0000, 3], addr as i64));
+            detector.record(&Call {
+                call_site: Some("free".to_string()),
+                ..call(1, "munmap", [addr, 0x10_0000, 0], 0)
+            });
+        }
+        // Long-lived and partially unmapped regions are not churn
+        detector.record(&call(1, "mmap", [0, 0x2000, 3], 0x5000));
+        detector.record(&call(1, "munmap", [0x5000, 0x1000, 0], 0));
+
+        let findings = detector.findings();
+        assert_eq!(patterns(&detector), vec![SyscallAntiPattern::MmapChurn]);
+        assert_eq!(
+            findings[0].call_site.as_deref(),
+            Some("app.c:10 (main)"),
+            "attributed to the mmap"
+        );
+        assert_eq!(findings[0].count, 25);
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(severity(20, 20), Severity::Low);
+        assert_eq!(severity(100, 20), Severity::Medium);
+        assert_eq!(severity(1000, 20), Severity::High);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut detector = SyscallPatternDetector::default();
+        assert!(detector
+            .report()
+            .contains("No syscall anti-patterns found."));
+
+        for _ in 0..1000 {
+            detector.record(&call(7, "sched_yield", [0; 3], 0));
+        }
+        let report = detector.report();
+        assert!(
+            report.contains("[High] Polling loop: 1000 sched_yield calls (2.000ms in syscalls)")
+        );
+        assert!(report.contains("  pid 7 at app.c:10 (main)\n"));
+        assert!(report.contains("  Recommendation: Block on the event instead"));
+    }
+}
@@ -1,6 +1,8 @@
-//! Syscall number to name mapping for x86_64
+//! Syscall number to name mapping for x86_64, and errno names
 //!
 //! Sprint 3-4: Full syscall coverage
+//! Sprint 50: Complete table from the kernel's syscall_64.tbl (blocking
+//! syscalls such as clock_nanosleep and epoll_wait were reported as unknown)
 
 /// Resolve syscall number to name for x86_64
 ///
@@ -71,6 +73,14 @@ pub fn syscall_name(num: i64) -> &'static str {
         61 => "wait4",
         62 => "kill",
         63 => "uname",
+        64 => "semget",
+        65 => "semop",
+        66 => "semctl",
+        67 => "shmdt",
+        68 => "msgget",
+        69 => "msgsnd",
+        70 => "msgrcv",
+        71 => "msgctl",
         72 => "fcntl",
         73 => "flock",
         74 => "fsync",
@@ -99,39 +109,486 @@ pub fn syscall_name(num: i64) -> &'static str {
         97 => "getrlimit",
         98 => "getrusage",
         99 => "sysinfo",
+        100 => "times",
+        101 => "ptrace",
         102 => "getuid",
+        103 => "syslog",
         104 => "getgid",
         105 => "setuid",
-        107 => "setgid",
-        108 => "geteuid",
-        109 => "getegid",
-        110 => "setpgid",
-        111 => "getppid",
-        112 => "getpgrp",
-        113 => "setsid",
+        106 => "setgid",
+        107 => "geteuid",
+        108 => "getegid",
+        109 => "setpgid",
+        110 => "getppid",
+        111 => "getpgrp",
+        112 => "setsid",
+        113 => "setreuid",
+        114 => "setregid",
+        115 => "getgroups",
+        116 => "setgroups",
+        117 => "setresuid",
+        118 => "getresuid",
+        119 => "setresgid",
+        120 => "getresgid",
+        121 => "getpgid",
+        122 => "setfsuid",
+        123 => "setfsgid",
+        124 => "getsid",
+        125 => "capget",
+        126 => "capset",
+        127 => "rt_sigpending",
+        128 => "rt_sigtimedwait",
+        129 => "rt_sigqueueinfo",
+        130 => "rt_sigsuspend",
         131 => "sigaltstack",
+        132 => "utime",
+        133 => "mknod",
+        134 => "uselib",
+        135 => "personality",
+        136 => "ustat",
+        137 => "statfs",
+        138 => "fstatfs",
+        139 => "sysfs",
+        140 => "getpriority",
+        141 => "setpriority",
+        142 => "sched_setparam",
+        143 => "sched_getparam",
+        144 => "sched_setscheduler",
+        145 => "sched_getscheduler",
+        146 => "sched_get_priority_max",
+        147 => "sched_get_priority_min",
+        148 => "sched_rr_get_interval",
+        149 => "mlock",
+        150 => "munlock",
+        151 => "mlockall",
+        152 => "munlockall",
+        153 => "vhangup",
+        154 => "modify_ldt",
+        155 => "pivot_root",
+        156 => "_sysctl",
         157 => "prctl",
         158 => "arch_prctl",
+        159 => "adjtimex",
+        160 => "setrlimit",
+        161 => "chroot",
+        162 => "sync",
+        163 => "acct",
+        164 => "settimeofday",
+        165 => "mount",
+        166 => "umount2",
+        167 => "swapon",
+        168 => "swapoff",
+        169 => "reboot",
+        170 => "sethostname",
+        171 => "setdomainname",
+        172 => "iopl",
+        173 => "ioperm",
+        174 => "create_module",
+        175 => "init_module",
+        176 => "delete_module",
+        177 => "get_kernel_syms",
+        178 => "query_module",
+        179 => "quotactl",
+        180 => "nfsservctl",
+        181 => "getpmsg",
+        182 => "putpmsg",
+        183 => "afs_syscall",
+        184 => "tuxcall",
+        185 => "security",
         186 => "gettid",
+        187 => "readahead",
+        188 => "setxattr",
+        189 => "lsetxattr",
+        190 => "fsetxattr",
+        191 => "getxattr",
+        192 => "lgetxattr",
+        193 => "fgetxattr",
+        194 => "listxattr",
+        195 => "llistxattr",
+        196 => "flistxattr",
+        197 => "removexattr",
+        198 => "lremovexattr",
+        199 => "fremovexattr",
+        200 => "tkill",
+        201 => "time",
         202 => "futex",
+        203 => "sched_setaffinity",
+        204 => "sched_getaffinity",
+        205 => "set_thread_area",
+        206 => "io_setup",
+        207 => "io_destroy",
+        208 => "io_getevents",
+        209 => "io_submit",
+        210 => "io_cancel",
+        211 => "get_thread_area",
+        212 => "lookup_dcookie",
+        213 => "epoll_create",
+        214 => "epoll_ctl_old",
+        215 => "epoll_wait_old",
+        216 => "remap_file_pages",
         217 => "getdents64",
         218 => "set_tid_address",
+        219 => "restart_syscall",
+        220 => "semtimedop",
+        221 => "fadvise64",
+        222 => "timer_create",
+        223 => "timer_settime",
+        224 => "timer_gettime",
+        225 => "timer_getoverrun",
+        226 => "timer_delete",
+        227 => "clock_settime",
         228 => "clock_gettime",
+        229 => "clock_getres",
+        230 => "clock_nanosleep",
         231 => "exit_group",
+        232 => "epoll_wait",
+        233 => "epoll_ctl",
+        234 => "tgkill",
+        235 => "utimes",
+        236 => "vserver",
+        237 => "mbind",
+        238 => "set_mempolicy",
+        239 => "get_mempolicy",
+        240 => "mq_open",
+        241 => "mq_unlink",
+        242 => "mq_timedsend",
+        243 => "mq_timedreceive",
+        244 => "mq_notify",
+        245 => "mq_getsetattr",
+        246 => "kexec_load",
+        247 => "waitid",
+        248 => "add_key",
+        249 => "request_key",
+        250 => "keyctl",
+        251 => "ioprio_set",
+        252 => "ioprio_get",
+        253 => "inotify_init",
+        254 => "inotify_add_watch",
+        255 => "inotify_rm_watch",
+        256 => "migrate_pages",
         257 => "openat",
+        258 => "mkdirat",
+        259 => "mknodat",
+        260 => "fchownat",
+        261 => "futimesat",
         262 => "newfstatat",
+        263 => "unlinkat",
+        264 => "renameat",
+        265 => "linkat",
+        266 => "symlinkat",
+        267 => "readlinkat",
+        268 => "fchmodat",
+        269 => "faccessat",
+        270 => "pselect6",
+        271 => "ppoll",
+        272 => "unshare",
         273 => "set_robust_list",
+        274 => "get_robust_list",
+        275 => "splice",
+        276 => "tee",
+        277 => "sync_file_range",
+        278 => "vmsplice",
+        279 => "move_pages",
+        280 => "utimensat",
+        281 => "epoll_pwait",
+        282 => "signalfd",
+        283 => "timerfd_create",
+        284 => "eventfd",
+        285 => "fallocate",
+        286 => "timerfd_settime",
+        287 => "timerfd_gettime",
+        288 => "accept4",
+        289 => "signalfd4",
+        290 => "eventfd2",
+        291 => "epoll_create1",
+        292 => "dup3",
+        293 => "pipe2",
+        294 => "inotify_init1",
+        295 => "preadv",
+        296 => "pwritev",
+        297 => "rt_tgsigqueueinfo",
+        298 => "perf_event_open",
+        299 => "recvmmsg",
+        300 => "fanotify_init",
+        301 => "fanotify_mark",
+        302 => "prlimit64",
+        303 => "name_to_handle_at",
+        304 => "open_by_handle_at",
+        305 => "clock_adjtime",
+        306 => "syncfs",
+        307 => "sendmmsg",
+        308 => "setns",
+        309 => "getcpu",
+        310 => "process_vm_readv",
+        311 => "process_vm_writev",
+        312 => "kcmp",
+        313 => "finit_module",
+        314 => "sched_setattr",
+        315 => "sched_getattr",
+        316 => "renameat2",
+        317 => "seccomp",
         318 => "getrandom",
+        319 => "memfd_create",
+        320 => "kexec_file_load",
+        321 => "bpf",
+        322 => "execveat",
+        323 => "userfaultfd",
+        324 => "membarrier",
+        325 => "mlock2",
+        326 => "copy_file_range",
+        327 => "preadv2",
+        328 => "pwritev2",
+        329 => "pkey_mprotect",
+        330 => "pkey_alloc",
+        331 => "pkey_free",
         332 => "statx",
+        333 => "io_pgetevents",
+        334 => "rseq",
+        424 => "pidfd_send_signal",
+        425 => "io_uring_setup",
+        426 => "io_uring_enter",
+        427 => "io_uring_register",
+        428 => "open_tree",
+        429 => "move_mount",
+        430 => "fsopen",
+        431 => "fsconfig",
+        432 => "fsmount",
+        433 => "fspick",
+        434 => "pidfd_open",
         435 => "clone3",
+        436 => "close_range",
+        437 => "openat2",
+        438 => "pidfd_getfd",
+        439 => "faccessat2",
+        440 => "process_madvise",
+        441 => "epoll_pwait2",
+        442 => "mount_setattr",
+        443 => "quotactl_fd",
+        444 => "landlock_create_ruleset",
+        445 => "landlock_add_rule",
+        446 => "landlock_restrict_self",
+        447 => "memfd_secret",
+        448 => "process_mrelease",
+        449 => "futex_waitv",
+        450 => "set_mempolicy_home_node",
         _ => "unknown",
     }
 }
 
+/// Resolve an errno name such as "ENOENT" to its number (Sprint 69)
+pub fn errno_number(name: &str) -> Option<i32> {
+    Some(match name {
+        "EPERM" => libc::EPERM,
+        "ENOENT" => libc::ENOENT,
+        "ESRCH" => libc::ESRCH,
+        "EINTR" => libc::EINTR,
+        "EIO" => libc::EIO,
+        "ENXIO" => libc::ENXIO,
+        "E2BIG" => libc::E2BIG,
+        "ENOEXEC" => libc::ENOEXEC,
+        "EBADF" => libc::EBADF,
+        "ECHILD" => libc::ECHILD,
+        "EAGAIN" => libc::EAGAIN,
+        "ENOMEM" => libc::ENOMEM,
+        "EACCES" => libc::EACCES,
+        "EFAULT" => libc::EFAULT,
+        "ENOTBLK" => libc::ENOTBLK,
+        "EBUSY" => libc::EBUSY,
+        "EEXIST" => libc::EEXIST,
+        "EXDEV" => libc::EXDEV,
+        "ENODEV" => libc::ENODEV,
+        "ENOTDIR" => libc::ENOTDIR,
+        "EISDIR" => libc::EISDIR,
+        "EINVAL" => libc::EINVAL,
+        "ENFILE" => libc::ENFILE,
+        "EMFILE" => libc::EMFILE,
+        "ENOTTY" => libc::ENOTTY,
+        "ETXTBSY" => libc::ETXTBSY,
+        "EFBIG" => libc::EFBIG,
+        "ENOSPC" => libc::ENOSPC,
+        "ESPIPE" => libc::ESPIPE,
+        "EROFS" => libc::EROFS,
+        "EMLINK" => libc::EMLINK,
+        "EPIPE" => libc::EPIPE,
+        "EDOM" => libc::EDOM,
+        "ERANGE" => libc::ERANGE,
+        "EDEADLK" => libc::EDEADLK,
+        "ENAMETOOLONG" => libc::ENAMETOOLONG,
+        "ENOLCK" => libc::ENOLCK,
+        "ENOSYS" => libc::ENOSYS,
+        "ENOTEMPTY" => libc::ENOTEMPTY,
+        "ELOOP" => libc::ELOOP,
+        "ENOMSG" => libc::ENOMSG,
+        "EIDRM" => libc::EIDRM,
+        "ECHRNG" => libc::ECHRNG,
+        "EL2NSYNC" => libc::EL2NSYNC,
+        "EL3HLT" => libc::EL3HLT,
+        "EL3RST" => libc::EL3RST,
+        "ELNRNG" => libc::ELNRNG,
+        "EUNATCH" => libc::EUNATCH,
+        "ENOCSI" => libc::ENOCSI,
+        "EL2HLT" => libc::EL2HLT,
+        "EBADE" => libc::EBADE,
+        "EBADR" => libc::EBADR,
+        "EXFULL" => libc::EXFULL,
+        "ENOANO" => libc::ENOANO,
+        "EBADRQC" => libc::EBADRQC,
+        "EBADSLT" => libc::EBADSLT,
+        "EBFONT" => libc::EBFONT,
+        "ENOSTR" => libc::ENOSTR,
+        "ENODATA" => libc::ENODATA,
+        "ETIME" => libc::ETIME,
+        "ENOSR" => libc::ENOSR,
+        "ENONET" => libc::ENONET,
+        "ENOPKG" => libc::ENOPKG,
+        "EREMOTE" => libc::EREMOTE,
+        "ENOLINK" => libc::ENOLINK,
+        "EADV" => libc::EADV,
+        "ESRMNT" => libc::ESRMNT,
+        "ECOMM" => libc::ECOMM,
+        "EPROTO" => libc::EPROTO,
+        "EMULTIHOP" => libc::EMULTIHOP,
+        "EDOTDOT" => libc::EDOTDOT,
+        "EBADMSG" => libc::EBADMSG,
+        "EOVERFLOW" => libc::EOVERFLOW,
+        "ENOTUNIQ" => libc::ENOTUNIQ,
+        "EBADFD" => libc::EBADFD,
+        "EREMCHG" => libc::EREMCHG,
+        "ELIBACC" => libc::ELIBACC,
+        "ELIBBAD" => libc::ELIBBAD,
+        "ELIBSCN" => libc::ELIBSCN,
+        "ELIBMAX" => libc::ELIBMAX,
+        "ELIBEXEC" => libc::ELIBEXEC,
+        "EILSEQ" => libc::EILSEQ,
+        "ERESTART" => libc::ERESTART,
+        "ESTRPIPE" => libc::ESTRPIPE,
+        "EUSERS" => libc::EUSERS,
+        "ENOTSOCK" => libc::ENOTSOCK,
+        "EDESTADDRREQ" => libc::EDESTADDRREQ,
+        "EMSGSIZE" => libc::EMSGSIZE,
+        "EPROTOTYPE" => libc::EPROTOTYPE,
+        "ENOPROTOOPT" => libc::ENOPROTOOPT,
+        "EPROTONOSUPPORT" => libc::EPROTONOSUPPORT,
+        "ESOCKTNOSUPPORT" => libc::ESOCKTNOSUPPORT,
+        "EOPNOTSUPP" => libc::EOPNOTSUPP,
+        "EPFNOSUPPORT" => libc::EPFNOSUPPORT,
+        "EAFNOSUPPORT" => libc::EAFNOSUPPORT,
+        "EADDRINUSE" => libc::EADDRINUSE,
+        "EADDRNOTAVAIL" => libc::EADDRNOTAVAIL,
+        "ENETDOWN" => libc::ENETDOWN,
+        "ENETUNREACH" => libc::ENETUNREACH,
+        "ENETRESET" => libc::ENETRESET,
+        "ECONNABORTED" => libc::ECONNABORTED,
+        "ECONNRESET" => libc::ECONNRESET,
+        "ENOBUFS" => libc::ENOBUFS,
+        "EISCONN" => libc::EISCONN,
+        "ENOTCONN" => libc::ENOTCONN,
+        "ESHUTDOWN" => libc::ESHUTDOWN,
+        "ETOOMANYREFS" => libc::ETOOMANYREFS,
+        "ETIMEDOUT" => libc::ETIMEDOUT,
+        "ECONNREFUSED" => libc::ECONNREFUSED,
+        "EHOSTDOWN" => libc::EHOSTDOWN,
+        "EHOSTUNREACH" => libc::EHOSTUNREACH,
+        "EALREADY" => libc::EALREADY,
+        "EINPROGRESS" => libc::EINPROGRESS,
+        "ESTALE" => libc::ESTALE,
+        "EUCLEAN" => libc::EUCLEAN,
+        "ENOTNAM" => libc::ENOTNAM,
+        "ENAVAIL" => libc::ENAVAIL,
+        "EISNAM" => libc::EISNAM,
+        "EREMOTEIO" => libc::EREMOTEIO,
+        "EDQUOT" => libc::EDQUOT,
+        "ENOMEDIUM" => libc::ENOMEDIUM,
+        "EMEDIUMTYPE" => libc::EMEDIUMTYPE,
+        "ECANCELED" => libc::ECANCELED,
+        "ENOKEY" => libc::ENOKEY,
+        "EKEYEXPIRED" => libc::EKEYEXPIRED,
+        "EKEYREVOKED" => libc::EKEYREVOKED,
+        "EKEYREJECTED" => libc::EKEYREJECTED,
+        "EOWNERDEAD" => libc::EOWNERDEAD,
+        "ENOTRECOVERABLE" => libc::ENOTRECOVERABLE,
+        "ERFKILL" => libc::ERFKILL,
+        "EHWPOISON" => libc::EHWPOISON,
+        // Aliases
+        "EWOULDBLOCK" => libc::EWOULDBLOCK,
+        "EDEADLOCK" => libc::EDEADLOCK,
+        "ENOTSUP" => libc::ENOTSUP,
+        _ => return None,
+    })
+}
+
+/// Syscalls that can put the calling thread to sleep waiting on an external event
+///
+/// Sprint 50: Used to build off-CPU flamegraphs. Reads and writes are included
+/// because they block on pipes, sockets and terminals; off-CPU analysis
+/// (`offcpu::is_off_cpu_wait`) leaves out those on regular files.
+const BLOCKING_SYSCALLS: &[&str] = &[
+    "read",
+    "readv",
+    "pread64",
+    "preadv",
+    "write",
+    "writev",
+    "pwrite64",
+    "recvfrom",
+    "recvmsg",
+    "recvmmsg",
+    "sendto",
+    "sendmsg",
+    "accept",
+    "accept4",
+    "connect",
+    "poll",
+    "ppoll",
+    "select",
+    "pselect6",
+    "epoll_wait",
+    "epoll_pwait",
+    "futex",
+    "nanosleep",
+    "clock_nanosleep",
+    "pause",
+    "wait4",
+    "waitid",
+    "rt_sigsuspend",
+    "rt_sigtimedwait",
+    "flock",
+    "fsync",
+    "fdatasync",
+    "sync",
+    "syncfs",
+    "msgrcv",
+    "msgsnd",
+    "semop",
+    "semtimedop",
+    "io_getevents",
+    "io_uring_enter",
+];
+
+/// Check whether a syscall may block (sleep off-CPU)
+pub fn is_blocking_syscall(name: &str) -> bool {
+    BLOCKING_SYSCALLS.contains(&name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_errno_numbers() {
+        assert_eq!(errno_number("ENOENT"), Some(2));
+        assert_eq!(errno_number("EWOULDBLOCK"), errno_number("EAGAIN"));
+        assert_eq!(errno_number("enoent"), None);
+        assert_eq!(errno_number("EFOO"), None);
+        // Every name nix prints resolves back to its number
+        for errno in 1..4096 {
+            let name = format!("{:?}", nix::errno::Errno::from_raw(errno));
+            if name != "UnknownErrno" {
+                assert_eq!(errno_number(&name), Some(errno), "{}", name);
+            }
+        }
+    }
+
     #[test]
     fn test_common_syscalls() {
         assert_eq!(syscall_name(0), "read");
@@ -146,6 +603,17 @@ mod tests {
         assert_eq!(syscall_name(9999), "unknown");
     }
 
+    #[test]
+    fn test_blocking_syscalls() {
+        assert!(is_blocking_syscall("futex"));
+        assert!(is_blocking_syscall("epoll_wait"));
+        assert!(is_blocking_syscall("read"));
+        assert!(is_blocking_syscall("nanosleep"));
+        assert!(!is_blocking_syscall("mmap"));
+        assert!(!is_blocking_syscall("getpid"));
+        assert!(!is_blocking_syscall("unknown"));
+    }
+
     #[test]
     fn test_all_known_syscalls() {
         // Test all known syscall numbers (comprehensive coverage)
@@ -190,9 +658,11 @@ mod tests {
             (102, "getuid"),
             (104, "getgid"),
             (105, "setuid"),
-            (107, "setgid"),
-            (108, "geteuid"),
-            (109, "getegid"),
+            (106, "setgid"),
+            (107, "geteuid"),
+            (108, "getegid"),
+            (230, "clock_nanosleep"),
+            (232, "epoll_wait"),
             (186, "gettid"),
             (228, "clock_gettime"),
             (231, "exit_group"),
@@ -0,0 +1,291 @@
+//! Cargo test harness for renacer.toml assertions (Sprint 70)
+//!
+//! Runs a command under `renacer record` from an integration test, builds
+//! the run's [`UnifiedTrace`] and evaluates an [`AssertionConfig`] against
+//! it, panicking with a diff of the failed assertions:
+//!
+//! ```no_run
+//! #[test]
+//! fn build_stays_within_budget() {
+//!     renacer::testing::trace_and_assert(&["make", "-C", "fixtures"], "renacer.toml");
+//! }
+//! ```
+//!
+//! Relative config paths are resolved from the working directory, which
+//! `cargo test` sets to the package root. The tracer is the `renacer`
+//! binary named by `$RENACER`, next to the test executable's directory
+//! (renacer's own tests) or on `PATH`, in that order.
+//!
+//! Tracing happens in a child process: a test harness runs tests on many
+//! threads, which does not mix with ptrace in the test process itself.
+
+use crate::assertion_dsl::AssertionConfig;
+use crate::assertion_types::{Assertion, AssertionResult};
+use crate::bench::record_once;
+use crate::check::{unified_trace, CheckReport};
+use crate::trace_diff::TraceSet;
+use crate::unified_trace::UnifiedTrace;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the renacer binary to trace with
+pub const RENACER_ENV: &str = "RENACER";
+
+/// Trace `command`, evaluate the assertions in `config` and panic if any
+/// with `fail_on_violation = true` fails
+///
+/// Returns the traced run for further checks.
+pub fn trace_and_assert<S: AsRef<str>>(command: &[S], config: impl AsRef<Path>) -> TracedRun {
+    let run = TraceHarness::new(command)
+        .run(config)
+        .unwrap_or_else(|e| panic!("renacer: {:#}", e));
+    run.assert_passed();
+    run
+}
+
+/// Options for tracing a command in a test
+#[derive(Debug, Clone)]
+pub struct TraceHarness {
+    command: Vec<String>,
+    follow_forks: bool,
+    stacks: bool,
+    renacer: Option<PathBuf>,
+}
+
+impl TraceHarness {
+    pub fn new<S: AsRef<str>>(command: &[S]) -> Self {
+        Self {
+            command: command.iter().map(|arg| arg.as_ref().to_string()).collect(),
+            follow_forks: false,
+            stacks: false,
+            renacer: None,
+        }
+    }
+
+    /// Also trace child processes (`renacer record -f`)
+    pub fn follow_forks(mut self, follow_forks: bool) -> Self {
+        self.follow_forks = follow_forks;
+        self
+    }
+
+    /// Unwind call stacks so failure messages name the call sites
+    /// (`renacer record --stacks`)
+    pub fn call_stacks(mut self, stacks: bool) -> Self {
+        self.stacks = stacks;
+        self
+    }
+
+    /// Trace with this renacer binary instead of looking one up
+    pub fn renacer_binary(mut self, path: impl Into<PathBuf>) -> Self {
+        self.renacer = Some(path.into());
+        self
+    }
+
+    /// Trace the command once and evaluate the assertions in `config`
+    ///
+    /// Fails if the config is invalid or the command cannot be traced or
+    /// exits unsuccessfully; assertion failures are in the result.
+    pub fn run(&self, config: impl AsRef<Path>) -> Result<TracedRun> {
+        let config_path = config.as_ref();
+        let config = AssertionConfig::from_file(config_path)?;
+        if self.command.is_empty() {
+            anyhow::bail!("No command to trace");
+        }
+
+        let renacer = match &self.renacer {
+            Some(path) => path.clone(),
+            None => find_renacer(),
+        };
+        let set = record(&renacer, &self.command, self.follow_forks, self.stacks)?;
+        let records = set.runs.first().map(Vec::as_slice).unwrap_or_default();
+        let rss_samples = set.memory.first().map(Vec::as_slice).unwrap_or_default();
+
+        let cwd = std::env::current_dir().context("Failed to read the working directory")?;
+        let report = CheckReport::evaluate(
+            &config_path.display().to_string(),
+            &config,
+            records,
+            rss_samples,
+            &self.command,
+            &cwd,
+            |var| std::env::var(var).ok(),
+        );
+        let mut trace = unified_trace(records, &self.command);
+        trace.rss_samples = rss_samples.to_vec();
+        Ok(TracedRun {
+            command: self.command.clone(),
+            trace,
+            report,
+        })
+    }
+}
+
+/// A traced command and its assertion results
+#[derive(Debug, Clone)]
+pub struct TracedRun {
+    pub command: Vec<String>,
+    pub trace: UnifiedTrace,
+    pub report: CheckReport,
+}
+
+impl TracedRun {
+    /// Results of assertions with `fail_on_violation = true` that failed
+    pub fn failures(&self) -> impl Iterator<Item = (&AssertionResult, &Assertion)> {
+        self.report
+            .results
+            .iter()
+            .zip(&self.report.assertions)
+            .filter(|(result, assertion)| !result.passed && assertion.fail_on_violation)
+    }
+
+    /// Expected and actual values of the failed assertions, diff style
+    pub fn failure_diff(&self) -> String {
+        let failures: Vec<_> = self.failures().collect();
+        let mut out = format!(
+            "{} of {} assertions in {} failed for `{}`:\n",
+            failures.len(),
+            self.report.assertions.len(),
+            self.report.source,
+            self.command.join(" ")
+        );
+        for (result, _) in failures {
+            out.push_str(&format!("\n  {}: {}\n", result.name, result.message));
+            if let (Some(actual), Some(expected)) = (&result.actual_value, &result.expected_value) {
+                out.push_str(&format!("  - expected: {}\n", expected));
+                out.push_str(&format!("  + actual:   {}\n", actual));
+            }
+        }
+        out
+    }
+
+    /// Panic with [`failure_diff`](Self::failure_diff) if an assertion failed
+    pub fn assert_passed(&self) {
+        if self.report.has_failures() {
+            panic!("{}", self.failure_diff());
+        }
+    }
+}
+
+/// `$RENACER`, `renacer` beside the directory of the test executable
+/// (`target/<profile>/deps/..`), or `renacer` on `PATH`
+fn find_renacer() -> PathBuf {
+    if let Some(path) = std::env::var_os(RENACER_ENV) {
+        return PathBuf::from(path);
+    }
+    let beside_tests = std::env::current_exe().ok().and_then(|exe| {
+        let path = exe.parent()?.parent()?.join("renacer");
+        path.is_file().then_some(path)
+    });
+    beside_tests.unwrap_or_else(|| PathBuf::from("renacer"))
+}
+
+/// Record one run into a private directory and load it
+fn record(
+    renacer: &Path,
+    command: &[String],
+    follow_forks: bool,
+    stacks: bool,
+) -> Result<TraceSet> {
+    let work_dir = tempfile::Builder::new()
+        .prefix("renacer-test-")
+        .tempdir()
+        .context("Failed to create a directory for the run")?;
+    let trace = work_dir.path().join("run.rnc");
+    record_once(renacer, command, follow_forks, stacks, false, &trace)
+        .with_context(|| format!("Failed to trace with {}", renacer.display()))
+        .and_then(|code| match code {
+            0 => TraceSet::load(&trace),
+            code => anyhow::bail!(
+                "`{}` failed under trace (exit status {})",
+                command.join(" "),
+                code
+            ),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace_file::SyscallRecord;
+
+    const CONFIG: &str = r#"
+        [[assertion]]
+        name = "max_syscalls"
+        type = "span_count"
+        max_spans = 1
+
+        [[assertion]]
+        name = "few_reads"
+        type = "syscall_count"
+        syscall_pattern = "read"
+        max_count = 0
+        fail_on_violation = false
+
+        [[assertion]]
+        name = "no_connect"
+        type = "forbidden_syscall"
+        syscall_pattern = "connect"
+    "#;
+
+    fn run(names: &[&str]) -> TracedRun {
+        let records: Vec<SyscallRecord> = names
+            .iter()
+            .map(|name| SyscallRecord {
+                pid: 7,
+                timestamp_us: 0,
+                duration_us: 1,
+                number: 0,
+                name: name.to_string(),
+                args: Vec::new(),
+                raw_args: [0; 3],
+                result: 0,
+                source: None,
+                function_name: None,
+                caller_name: None,
+                stack: Vec::new(),
+                wait_object: None,
+                stderr: None,
+            })
+            .collect();
+        let command = vec!["tool".to_string(), "--fast".to_string()];
+        let config = AssertionConfig::from_toml_str(CONFIG).unwrap();
+        let report = CheckReport::evaluate(
+            "renacer.toml",
+            &config,
+            &records,
+            &[],
+            &command,
+            Path::new("/work"),
+            |_| None,
+        );
+        TracedRun {
+            trace: unified_trace(&records, &command),
+            command,
+            report,
+        }
+    }
+
+    #[test]
+    fn test_failure_diff_lists_failed_assertions() {
+        let run = run(&["read", "connect"]);
+        assert_eq!(
+            run.failure_diff(),
+            "2 of 3 assertions in renacer.toml failed for `tool --fast`:\n\
+             \n  max_syscalls: Span count 2 exceeds maximum 1\n\
+             \x20 - expected: 1 spans\n\
+             \x20 + actual:   2 spans\n\
+             \n  no_connect: Forbidden syscall matching 'connect' called 1 time (connect x1). \
+             Top call sites: 1x tool: connect\n\
+             \x20 - expected: 0 calls\n\
+             \x20 + actual:   1 call\n"
+        );
+    }
+
+    #[test]
+    fn test_assert_passed() {
+        run(&["read"]).assert_passed();
+        let panic = std::panic::catch_unwind(|| run(&["connect", "close"]).assert_passed());
+        let message = panic.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.starts_with("2 of 3 assertions"));
+    }
+}
@@ -21,7 +21,7 @@ pub struct TimeAttribution {
     /// Number of calls in this cluster
     pub call_count: usize,
 
-    /// Percentage of total execution time
+    /// Percentage of total syscall time
     pub percentage: f64,
 
     /// Average time per call
@@ -68,6 +68,8 @@ impl fmt::Display for TimeAttribution {
 ///         timestamp_nanos: 0,
 ///         duration_nanos: 1000,
 ///         errno: None,
+///         pid: None,
+///         call_site: None,
 ///     },
 /// ];
 ///
@@ -78,22 +80,7 @@ pub fn calculate_time_attribution(
     spans: &[SyscallSpan],
     registry: &ClusterRegistry,
 ) -> Vec<TimeAttribution> {
-    if spans.is_empty() {
-        return Vec::new();
-    }
-
-    // Calculate total execution time
-    let total_time_nanos: u64 = spans.iter().map(|s| s.duration_nanos).sum();
-
-    if total_time_nanos == 0 {
-        return Vec::new(); // Avoid division by zero
-    }
-
-    // Aggregate time and count by cluster
-    let mut cluster_time: HashMap<String, u64> = HashMap::new();
-    let mut cluster_count: HashMap<String, usize> = HashMap::new();
-
-    for span in spans {
+    attribute_time(spans.iter().map(|span| {
         // Extract args as strings for classification
         let args: Vec<String> = span.args.iter().map(|(_, v)| v.clone()).collect();
 
@@ -101,11 +88,32 @@ pub fn calculate_time_attribution(
         let cluster_name = registry
             .classify_simple(&span.name, &args)
             .unwrap_or("Unclassified".to_string());
+        (cluster_name, span.duration_nanos)
+    }))
+}
+
+/// Aggregate already classified syscalls, given as (cluster, duration in
+/// nanoseconds), into time attributions sorted by total time (descending)
+///
+/// Sprint 64: `--hotspots` classifies with live fd tracking (`ClusterTracker`)
+/// so argument-filtered clusters match.
+pub fn attribute_time(calls: impl IntoIterator<Item = (String, u64)>) -> Vec<TimeAttribution> {
+    // Aggregate time and count by cluster
+    let mut cluster_time: HashMap<String, u64> = HashMap::new();
+    let mut cluster_count: HashMap<String, usize> = HashMap::new();
 
-        *cluster_time.entry(cluster_name.clone()).or_default() += span.duration_nanos;
+    for (cluster_name, duration_nanos) in calls {
+        *cluster_time.entry(cluster_name.clone()).or_default() += duration_nanos;
         *cluster_count.entry(cluster_name).or_default() += 1;
     }
 
+    // Calculate total execution time
+    let total_time_nanos: u64 = cluster_time.values().sum();
+
+    if total_time_nanos == 0 {
+        return Vec::new(); // Avoid division by zero
+    }
+
     // Calculate attributions
     let mut attributions: Vec<TimeAttribution> = cluster_time
         .into_iter()
@@ -146,6 +154,8 @@ mod tests {
             timestamp_nanos: 0,
             duration_nanos,
             errno: None,
+            pid: None,
+            call_site: None,
         }
     }
 
@@ -65,6 +65,8 @@ impl Hotspot {
 ///         timestamp_nanos: 0,
 ///         duration_nanos: 10000, // 10μs - dominates
 ///         errno: None,
+///         pid: None,
+///         call_site: None,
 ///     },
 /// ];
 ///
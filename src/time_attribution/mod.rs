@@ -11,9 +11,11 @@
 
 mod attribution;
 mod hotspot;
+mod tracker;
 
-pub use attribution::{calculate_time_attribution, TimeAttribution};
+pub use attribution::{attribute_time, calculate_time_attribution, TimeAttribution};
 pub use hotspot::{identify_hotspots, Hotspot};
+pub use tracker::{HotspotConfig, HotspotReport, HotspotTracker};
 
 #[cfg(test)]
 mod tests;
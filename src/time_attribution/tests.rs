@@ -25,6 +25,8 @@ fn make_span(
         timestamp_nanos: 0,
         duration_nanos,
         errno: None,
+        pid: None,
+        call_site: None,
     }
 }
 
@@ -0,0 +1,288 @@
+// Live time attribution for `--hotspots` (Sprint 64)
+//
+// The tracer records each completed syscall as a `SyscallSpan` of a
+// `UnifiedTrace`, classified as it happens by a `ClusterTracker` so that
+// `fd_path_pattern` and `arg_contains` filters see live fds and decoded
+// args. At the end the classified spans go through `attribute_time` and
+// `identify_hotspots`.
+
+use crate::cluster::{ClusterRegistry, ClusterSummaryConfig, ClusterTracker};
+use crate::time_attribution::{attribute_time, identify_hotspots};
+use crate::time_attribution::{Hotspot, TimeAttribution};
+use crate::unified_trace::{SyscallSpan, UnifiedTrace};
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
+/// Settings for the hotspot report
+#[derive(Debug, Clone)]
+pub struct HotspotConfig {
+    /// Cluster pack used for classification
+    pub registry: ClusterRegistry,
+    /// Where the cluster pack was loaded from (shown in the report header)
+    pub source: String,
+}
+
+/// Builds a [`UnifiedTrace`] from traced syscalls
+#[derive(Debug)]
+pub struct HotspotTracker {
+    config: HotspotConfig,
+    trace: UnifiedTrace,
+    started: Instant,
+    clusters: ClusterTracker,
+    /// Cluster of each syscall span, in span order
+    span_clusters: Vec<String>,
+}
+
+impl HotspotTracker {
+    pub fn new(config: HotspotConfig) -> Self {
+        let clusters = ClusterTracker::new(ClusterSummaryConfig {
+            registry: config.registry.clone(),
+            source: config.source.clone(),
+            baseline: None,
+        });
+        Self {
+            config,
+            trace: UnifiedTrace::new(0, String::new()),
+            started: Instant::now(),
+            clusters,
+            span_clusters: Vec::new(),
+        }
+    }
+
+    /// Open the root process span for the traced program
+    pub fn start(&mut self, pid: i32, program: String) {
+        self.trace = UnifiedTrace::new(pid, program);
+        self.started = Instant::now();
+    }
+
+    /// Nanoseconds since [`start`](Self::start) (timestamps for live tracing)
+    pub fn elapsed_nanos(&self) -> u64 {
+        self.started.elapsed().as_nanos() as u64
+    }
+
+    /// Classify a completed syscall and add it as a span of the process
+    ///
+    /// `resolve_fd` is asked for the path of fds the cluster tracker hasn't
+    /// seen, as in [`ClusterTracker::record`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn record(
+        &mut self,
+        pid: i32,
+        syscall: &str,
+        args: &[String],
+        raw_args: [u64; 3],
+        result: i64,
+        timestamp_nanos: u64,
+        duration_nanos: u64,
+        resolve_fd: impl Fn(i32) -> Option<String>,
+    ) {
+        let cluster = self.clusters.record(
+            pid,
+            syscall,
+            args,
+            raw_args,
+            result,
+            duration_nanos / 1000,
+            resolve_fd,
+        );
+        self.span_clusters.push(cluster.to_string());
+
+        let args = args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| (Cow::Owned(format!("arg{}", i)), arg.clone()))
+            .collect();
+        let span = SyscallSpan::new(
+            self.trace.process_span.span_id,
+            Cow::Owned(syscall.to_string()),
+            args,
+            result,
+            timestamp_nanos,
+            duration_nanos,
+            (result < 0).then(|| -result as i32),
+            &self.trace.clock,
+        );
+        self.trace.add_syscall(span);
+    }
+
+    pub fn trace(&self) -> &UnifiedTrace {
+        &self.trace
+    }
+
+    /// End the process span and attribute the recorded time
+    pub fn finish(mut self, exit_code: i32) -> HotspotReport {
+        self.trace.end_process(exit_code);
+        let spans = &self.trace.syscall_spans;
+
+        let start = spans.iter().map(|s| s.timestamp_nanos).min().unwrap_or(0);
+        let end = spans
+            .iter()
+            .map(|s| s.timestamp_nanos + s.duration_nanos)
+            .max()
+            .unwrap_or(0);
+        let attributions = attribute_time(
+            spans
+                .iter()
+                .zip(self.span_clusters)
+                .map(|(span, cluster)| (cluster, span.duration_nanos)),
+        );
+        let hotspots = identify_hotspots(&attributions);
+
+        HotspotReport {
+            source: self.config.source,
+            wall_time: Duration::from_nanos(end.saturating_sub(start)),
+            syscall_time: attributions.iter().map(|a| a.total_time).sum(),
+            attributions,
+            hotspots,
+        }
+    }
+}
+
+/// Time per cluster and the hotspots among them
+#[derive(Debug, Clone)]
+pub struct HotspotReport {
+    /// Where the cluster pack was loaded from
+    pub source: String,
+    /// From the first traced syscall to the end of the last
+    pub wall_time: Duration,
+    /// Time spent inside syscalls (summed over processes)
+    pub syscall_time: Duration,
+    /// Clusters by total time, descending
+    pub attributions: Vec<TimeAttribution>,
+    /// Clusters above 5% of syscall time
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl HotspotReport {
+    /// Hotspots not expected for this kind of program
+    pub fn unexpected(&self) -> impl Iterator<Item = &Hotspot> {
+        self.hotspots.iter().filter(|h| !h.is_expected)
+    }
+
+    /// Text report, in the layout of the `-c` summary
+    pub fn to_text(&self) -> String {
+        let mut out = format!("\nTime attribution ({}):\n", self.source);
+        out.push_str(&format!(
+            "wall-clock {:.6}s, {:.6}s in syscalls\n",
+            self.wall_time.as_secs_f64(),
+            self.syscall_time.as_secs_f64()
+        ));
+        if self.attributions.is_empty() {
+            out.push_str("(no syscall time recorded)\n");
+            return out;
+        }
+
+        let separator = "-------------- ----------- ----------- --------- --------------------\n";
+        out.push_str("% syscall time     seconds  usecs/call     calls cluster\n");
+        out.push_str(separator);
+        for attribution in &self.attributions {
+            out.push_str(&format!(
+                "{:14.2} {:>11.6} {:>11} {:>9} {}\n",
+                attribution.percentage,
+                attribution.total_time.as_secs_f64(),
+                attribution.avg_per_call.as_micros(),
+                attribution.call_count,
+                attribution.cluster
+            ));
+        }
+        out.push_str(separator);
+
+        out.push_str("\nHotspots (>5% of syscall time):\n");
+        for hotspot in &self.hotspots {
+            let report = hotspot.to_report_string().replace('\n', "\n  ");
+            out.push_str(&format!("  {}\n", report));
+        }
+        let unexpected: Vec<&str> = self.unexpected().map(|h| h.cluster.as_str()).collect();
+        if !unexpected.is_empty() {
+            out.push_str(&format!(
+                "\n{} unexpected hotspot{}: {}\n",
+                unexpected.len(),
+                if unexpected.len() == 1 { "" } else { "s" },
+                unexpected.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> HotspotTracker {
+        let registry = ClusterRegistry::default_transpiler_clusters().unwrap();
+        HotspotTracker::new(HotspotConfig {
+            registry,
+            source: "built-in".to_string(),
+        })
+    }
+
+    fn record(tracker: &mut HotspotTracker, syscall: &str, result: i64, at: u64, nanos: u64) {
+        tracker.record(42, syscall, &[], [0; 3], result, at, nanos, |_| None);
+    }
+
+    #[test]
+    fn test_spans_are_added_to_the_unified_trace() {
+        let mut tracker = tracker();
+        tracker.start(42, "/bin/true".to_string());
+        record(&mut tracker, "read", 10, 1_000, 500);
+        record(&mut tracker, "openat", -2, 2_000, 100);
+
+        let trace = tracker.trace();
+        assert_eq!(trace.process_span.pid, 42);
+        assert_eq!(trace.syscall_spans.len(), 2);
+        assert_eq!(
+            trace.syscall_spans[1].parent_span_id,
+            trace.process_span.span_id
+        );
+        assert_eq!(trace.syscall_spans[1].errno, Some(2));
+    }
+
+    #[test]
+    fn test_report_flags_unexpected_hotspots() {
+        let mut tracker = tracker();
+        record(&mut tracker, "read", 10, 0, 6_000);
+        record(&mut tracker, "connect", 0, 10_000, 4_000);
+        record(&mut tracker, "mmap", 0, 20_000, 10);
+
+        let report = tracker.finish(0);
+        assert_eq!(report.wall_time, Duration::from_nanos(20_010));
+        assert_eq!(report.attributions[0].cluster, "FileIO");
+        let unexpected: Vec<&str> = report.unexpected().map(|h| h.cluster.as_str()).collect();
+        assert_eq!(unexpected, vec!["Networking"]);
+
+        let text = report.to_text();
+        assert!(text.contains("Time attribution (built-in):"));
+        assert!(text.contains("         59.94    0.000006           6         1 FileIO"));
+        assert!(text.contains("1 unexpected hotspot: Networking"));
+    }
+
+    #[test]
+    fn test_fd_filtered_clusters_match() {
+        let mut tracker = tracker();
+        let nvidia = |fd| (fd == 5).then(|| "/dev/nvidia0".to_string());
+        let ioctl = |tracker: &mut HotspotTracker, fd: u64| {
+            let args = [format!("{}", fd), "0xc0104629".to_string()];
+            tracker.record(42, "ioctl", &args, [fd, 0xc0104629, 0], 0, 0, 9_000, nvidia);
+        };
+        ioctl(&mut tracker, 5);
+        ioctl(&mut tracker, 1);
+        assert_eq!(tracker.trace().syscall_spans[0].args[0].1, "5");
+
+        let report = tracker.finish(0);
+        let clusters: Vec<&str> = report
+            .attributions
+            .iter()
+            .map(|a| a.cluster.as_str())
+            .collect();
+        assert!(clusters.contains(&"GPU"), "{:?}", clusters);
+        assert!(clusters.contains(&"Unclassified"), "{:?}", clusters);
+        assert!(report.unexpected().any(|h| h.cluster == "GPU"));
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let text = tracker().finish(0).to_text();
+        assert!(text.contains("(no syscall time recorded)"));
+    }
+}
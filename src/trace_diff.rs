@@ -0,0 +1,847 @@
+//! Trace comparison for `renacer diff`
+//!
+//! Sprint 56: Wires the single-shot compile tooling together over recorded
+//! traces (Sprint 55): cluster time attribution deltas (`time_attribution`),
+//! new and missing syscall sequences (`sequence`) and a statistically backed
+//! regression verdict (`regression`).
+//!
+//! Each side is either a single trace file or a directory of `.rnc` runs.
+//! With enough runs on both sides the regression t-tests compare per-run
+//! syscall time; otherwise they compare individual call durations.
+
+use crate::cluster::{ClusterRegistry, Severity};
+use crate::memory::MemorySample;
+use crate::regression::{assess_regression, RegressionConfig, RegressionVerdict};
+use crate::sequence::{detect_sequence_anomalies, extract_ngrams, AnomalyType, NGramMap};
+use crate::time_attribution::{calculate_time_attribution, identify_hotspots, TimeAttribution};
+use crate::trace_file::{SyscallRecord, TraceEvent, TraceReader};
+use crate::unified_trace::SyscallSpan;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Default N-gram size (trigrams, per Forrest et al.)
+pub const DEFAULT_NGRAM_SIZE: usize = 3;
+
+/// Default relative change before a sequence frequency change is reported
+pub const DEFAULT_FREQUENCY_THRESHOLD: f64 = 0.30;
+
+/// Process exit code when a regression is detected
+pub const EXIT_REGRESSION: i32 = 2;
+
+/// Sequences listed per category in the text report
+const MAX_REPORTED_SEQUENCES: usize = 20;
+
+/// Syscalls from one or more recorded runs
+#[derive(Debug, Clone)]
+pub struct TraceSet {
+    /// File or directory the runs were loaded from
+    pub path: PathBuf,
+    /// Completed syscalls of each run, in recording order
+    pub runs: Vec<Vec<SyscallRecord>>,
+    /// Traced command of each run (empty for sets built from decoded runs)
+    pub commands: Vec<Vec<String>>,
+    /// RSS samples of each run, with a zero sample when a process exits
+    /// (Sprint 67)
+    pub memory: Vec<Vec<MemorySample>>,
+}
+
+impl TraceSet {
+    /// Load a trace file, or every `.rnc` file in a directory
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let files = if path.is_dir() {
+            let mut files: Vec<PathBuf> = std::fs::read_dir(path)
+                .with_context(|| format!("Failed to read directory {}", path.display()))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rnc"))
+                .collect();
+            files.sort();
+            if files.is_empty() {
+                anyhow::bail!("No .rnc trace files in {}", path.display());
+            }
+            files
+        } else {
+            vec![path.to_path_buf()]
+        };
+
+        let mut runs = Vec::with_capacity(files.len());
+        let mut commands = Vec::with_capacity(files.len());
+        let mut memory = Vec::with_capacity(files.len());
+        for file in &files {
+            let reader = TraceReader::open(file)?;
+            commands.push(reader.header().command.clone());
+            let mut syscalls = Vec::new();
+            let mut samples: Vec<MemorySample> = Vec::new();
+            for event in reader {
+                match event? {
+                    TraceEvent::Syscall(record) => syscalls.push(*record),
+                    TraceEvent::Memory(sample) => samples.push(sample),
+                    TraceEvent::Exit {
+                        timestamp_us, pid, ..
+                    } if samples.iter().any(|s| s.pid == pid) => samples.push(MemorySample {
+                        timestamp_us,
+                        pid,
+                        bytes: 0,
+                    }),
+                    _ => {}
+                }
+            }
+            runs.push(syscalls);
+            memory.push(samples);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            runs,
+            commands,
+            memory,
+        })
+    }
+
+    /// Build a set from already decoded runs
+    pub fn from_runs(path: impl Into<PathBuf>, runs: Vec<Vec<SyscallRecord>>) -> Self {
+        Self {
+            path: path.into(),
+            runs,
+            commands: Vec::new(),
+            memory: Vec::new(),
+        }
+    }
+
+    /// Total number of syscalls across runs
+    pub fn syscall_count(&self) -> usize {
+        self.runs.iter().map(Vec::len).sum()
+    }
+
+    /// Syscall names of each thread, in order, for every run
+    pub fn thread_sequences(&self) -> Vec<Vec<Vec<String>>> {
+        self.runs
+            .iter()
+            .map(|run| {
+                let mut by_thread: BTreeMap<i32, Vec<String>> = BTreeMap::new();
+                for record in run {
+                    by_thread
+                        .entry(record.pid)
+                        .or_default()
+                        .push(record.name.clone());
+                }
+                by_thread.into_values().collect()
+            })
+            .collect()
+    }
+
+    fn run_count(&self) -> f64 {
+        self.runs.len().max(1) as f64
+    }
+
+    /// Time attribution over all runs (totals are summed, percentages pooled)
+    fn attribution(&self, registry: &ClusterRegistry) -> Vec<TimeAttribution> {
+        let spans: Vec<SyscallSpan> = self
+            .runs
+            .iter()
+            .flatten()
+            .map(|record| SyscallSpan {
+                span_id: 0,
+                parent_span_id: 0,
+                name: Cow::Owned(record.name.clone()),
+                args: Vec::new(),
+                return_value: record.result,
+                timestamp_nanos: record.timestamp_us * 1000,
+                duration_nanos: record.duration_us * 1000,
+                errno: (record.result < 0).then(|| -record.result as i32),
+                pid: Some(record.pid),
+                call_site: None,
+            })
+            .collect();
+        calculate_time_attribution(&spans, registry)
+    }
+
+    /// N-gram counts averaged per run
+    ///
+    /// Sequences are extracted per thread so interleaved threads don't
+    /// produce sequences that never happened.
+    fn ngrams(&self, n: usize) -> NGramMap {
+        let mut totals: NGramMap = HashMap::new();
+        for threads in self.thread_sequences() {
+            for names in &threads {
+                for (ngram, count) in extract_ngrams(names, n) {
+                    *totals.entry(ngram).or_insert(0) += count;
+                }
+            }
+        }
+
+        let runs = self.run_count();
+        totals
+            .into_iter()
+            .map(|(ngram, count)| (ngram, ((count as f64 / runs).round() as usize).max(1)))
+            .collect()
+    }
+
+    /// Regression samples (microseconds) per syscall
+    fn samples(&self, basis: SampleBasis) -> HashMap<String, Vec<f32>> {
+        let mut samples: HashMap<String, Vec<f32>> = HashMap::new();
+        match basis {
+            SampleBasis::PerCall => {
+                for record in self.runs.iter().flatten() {
+                    samples
+                        .entry(record.name.clone())
+                        .or_default()
+                        .push(record.duration_us as f32);
+                }
+            }
+            SampleBasis::PerRun => {
+                let names: BTreeSet<&str> = self
+                    .runs
+                    .iter()
+                    .flatten()
+                    .map(|r| r.name.as_str())
+                    .collect();
+                for name in names {
+                    let per_run = self
+                        .runs
+                        .iter()
+                        .map(|run| {
+                            run.iter()
+                                .filter(|r| r.name == name)
+                                .map(|r| r.duration_us as f32)
+                                .sum()
+                        })
+                        .collect();
+                    samples.insert(name.to_string(), per_run);
+                }
+            }
+        }
+        samples
+    }
+}
+
+/// Settings for a trace comparison
+#[derive(Debug, Clone)]
+pub struct DiffConfig {
+    /// N-gram size for sequence comparison
+    pub ngram_size: usize,
+    /// Relative change before a sequence frequency change is reported
+    pub frequency_threshold: f64,
+    /// Statistical regression settings
+    pub regression: RegressionConfig,
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            ngram_size: DEFAULT_NGRAM_SIZE,
+            frequency_threshold: DEFAULT_FREQUENCY_THRESHOLD,
+            regression: RegressionConfig::default(),
+        }
+    }
+}
+
+/// What each regression sample measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleBasis {
+    /// Total time per syscall in each run (enough runs on both sides)
+    PerRun,
+    /// Duration of each individual call
+    PerCall,
+}
+
+/// Summary of one side of the comparison
+#[derive(Debug, Clone, Serialize)]
+pub struct SideSummary {
+    pub path: String,
+    pub runs: usize,
+    pub syscalls: usize,
+}
+
+/// Change in time attributed to one cluster (averaged per run)
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterDelta {
+    pub cluster: String,
+    pub baseline_time_us: f64,
+    pub current_time_us: f64,
+    pub baseline_percent: f64,
+    pub current_percent: f64,
+    pub baseline_calls: f64,
+    pub current_calls: f64,
+    /// Whether the cluster is expected in single-shot compile workflows
+    pub expected: bool,
+    /// Call count change exceeds the cluster's anomaly threshold
+    pub anomalous: bool,
+}
+
+impl ClusterDelta {
+    /// Change in per-run time (microseconds)
+    pub fn delta_time_us(&self) -> f64 {
+        self.current_time_us - self.baseline_time_us
+    }
+}
+
+/// Cluster consuming a large share of the current trace's time
+#[derive(Debug, Clone, Serialize)]
+pub struct HotspotSummary {
+    pub cluster: String,
+    pub percent: f64,
+    pub expected: bool,
+    pub explanation: String,
+}
+
+/// How a syscall sequence changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SequenceChangeKind {
+    New,
+    Missing,
+    FrequencyChange,
+}
+
+/// A syscall sequence that appeared, disappeared or changed frequency
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceChange {
+    pub sequence: Vec<String>,
+    pub kind: SequenceChangeKind,
+    pub baseline_count: usize,
+    pub current_count: usize,
+    pub severity: Severity,
+}
+
+/// Outcome of one t-test
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionTestResult {
+    pub syscall: String,
+    pub pvalue: f32,
+    pub baseline_median_us: f32,
+    pub current_median_us: f32,
+    pub regressed: bool,
+}
+
+/// Statistical regression verdict
+#[derive(Debug, Clone, Serialize)]
+pub struct RegressionSummary {
+    /// "no_regression", "regression" or "insufficient_data"
+    pub verdict: String,
+    pub reason: Option<String>,
+    pub sample_basis: SampleBasis,
+    pub significance_level: f64,
+    pub regressed_syscalls: Vec<String>,
+    pub filtered_syscalls: Vec<String>,
+    pub tests: Vec<RegressionTestResult>,
+    #[serde(skip)]
+    pub outcome: RegressionVerdict,
+}
+
+/// Result of comparing a baseline against a current trace set
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceDiff {
+    pub baseline: SideSummary,
+    pub current: SideSummary,
+    pub clusters: Vec<ClusterDelta>,
+    pub hotspots: Vec<HotspotSummary>,
+    pub ngram_size: usize,
+    pub sequences: Vec<SequenceChange>,
+    pub regression: RegressionSummary,
+}
+
+/// Compare two trace sets
+pub fn compare(
+    baseline: &TraceSet,
+    current: &TraceSet,
+    registry: &ClusterRegistry,
+    config: &DiffConfig,
+) -> Result<TraceDiff> {
+    if config.ngram_size == 0 {
+        anyhow::bail!("N-gram size must be at least 1");
+    }
+
+    let clusters = cluster_deltas(baseline, current, registry);
+
+    let hotspots = identify_hotspots(&current.attribution(registry))
+        .into_iter()
+        .map(|h| HotspotSummary {
+            cluster: h.cluster,
+            percent: h.percentage,
+            expected: h.is_expected,
+            explanation: h.explanation,
+        })
+        .collect();
+
+    let sequences = sequence_changes(
+        &baseline.ngrams(config.ngram_size),
+        &current.ngrams(config.ngram_size),
+        config.frequency_threshold,
+    );
+
+    let regression = regression_summary(baseline, current, &config.regression)?;
+
+    Ok(TraceDiff {
+        baseline: side_summary(baseline),
+        current: side_summary(current),
+        clusters,
+        hotspots,
+        ngram_size: config.ngram_size,
+        sequences,
+        regression,
+    })
+}
+
+fn side_summary(set: &TraceSet) -> SideSummary {
+    SideSummary {
+        path: set.path.display().to_string(),
+        runs: set.runs.len(),
+        syscalls: set.syscall_count(),
+    }
+}
+
+fn cluster_deltas(
+    baseline: &TraceSet,
+    current: &TraceSet,
+    registry: &ClusterRegistry,
+) -> Vec<ClusterDelta> {
+    let index = |set: &TraceSet| -> HashMap<String, TimeAttribution> {
+        set.attribution(registry)
+            .into_iter()
+            .map(|a| (a.cluster.clone(), a))
+            .collect()
+    };
+    let base = index(baseline);
+    let cur = index(current);
+
+    let names: BTreeSet<&String> = base.keys().chain(cur.keys()).collect();
+    let mut deltas: Vec<ClusterDelta> = names
+        .into_iter()
+        .map(|name| {
+            let per_run = |attr: Option<&TimeAttribution>, runs: f64| {
+                attr.map_or((0.0, 0.0, 0.0), |a| {
+                    (
+                        a.total_time.as_micros() as f64 / runs,
+                        a.percentage,
+                        a.call_count as f64 / runs,
+                    )
+                })
+            };
+            let (baseline_time_us, baseline_percent, baseline_calls) =
+                per_run(base.get(name), baseline.run_count());
+            let (current_time_us, current_percent, current_calls) =
+                per_run(cur.get(name), current.run_count());
+
+            let definition = registry.get_cluster(name);
+            ClusterDelta {
+                cluster: name.clone(),
+                baseline_time_us,
+                current_time_us,
+                baseline_percent,
+                current_percent,
+                baseline_calls,
+                current_calls,
+                expected: definition.is_none_or(|d| d.expected_for_transpiler),
+                anomalous: definition.is_some_and(|d| {
+                    d.is_anomalous(
+                        baseline_calls.round() as usize,
+                        current_calls.round() as usize,
+                    )
+                }),
+            }
+        })
+        .collect();
+
+    deltas.sort_by(|a, b| {
+        b.delta_time_us()
+            .abs()
+            .total_cmp(&a.delta_time_us().abs())
+            .then_with(|| a.cluster.cmp(&b.cluster))
+    });
+    deltas
+}
+
+fn sequence_changes(
+    baseline: &NGramMap,
+    current: &NGramMap,
+    frequency_threshold: f64,
+) -> Vec<SequenceChange> {
+    let mut changes: Vec<SequenceChange> =
+        detect_sequence_anomalies(baseline, current, frequency_threshold)
+            .into_iter()
+            .map(|anomaly| SequenceChange {
+                kind: match anomaly.anomaly_type {
+                    AnomalyType::NewSequence => SequenceChangeKind::New,
+                    AnomalyType::MissingSequence => SequenceChangeKind::Missing,
+                    AnomalyType::FrequencyChange => SequenceChangeKind::FrequencyChange,
+                },
+                sequence: anomaly.ngram,
+                baseline_count: anomaly.baseline_freq,
+                current_count: anomaly.current_freq,
+                severity: anomaly.severity,
+            })
+            .collect();
+
+    // Deterministic order: most severe first, then most frequent
+    changes.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.kind.cmp(&b.kind))
+            .then_with(|| {
+                let a_count = a.baseline_count.max(a.current_count);
+                b.baseline_count.max(b.current_count).cmp(&a_count)
+            })
+            .then_with(|| a.sequence.cmp(&b.sequence))
+    });
+    changes
+}
+
+fn regression_summary(
+    baseline: &TraceSet,
+    current: &TraceSet,
+    config: &RegressionConfig,
+) -> Result<RegressionSummary> {
+    let enough_runs = baseline.runs.len() >= config.min_sample_size
+        && current.runs.len() >= config.min_sample_size;
+    let sample_basis = if enough_runs {
+        SampleBasis::PerRun
+    } else {
+        SampleBasis::PerCall
+    };
+
+    let assessment = assess_regression(
+        &baseline.samples(sample_basis),
+        &current.samples(sample_basis),
+        config,
+    )?;
+
+    let (verdict, reason, regressed_syscalls) = match &assessment.verdict {
+        RegressionVerdict::NoRegression => ("no_regression", None, Vec::new()),
+        RegressionVerdict::Regression {
+            regressed_syscalls, ..
+        } => {
+            let mut regressed = regressed_syscalls.clone();
+            regressed.sort();
+            ("regression", None, regressed)
+        }
+        RegressionVerdict::InsufficientData { reason } => {
+            ("insufficient_data", Some(reason.clone()), Vec::new())
+        }
+    };
+
+    let mut tests: Vec<RegressionTestResult> = assessment
+        .tests
+        .iter()
+        .map(|(name, test)| RegressionTestResult {
+            syscall: name.clone(),
+            pvalue: test.pvalue,
+            baseline_median_us: test.baseline_median,
+            current_median_us: test.current_median,
+            regressed: regressed_syscalls.contains(name),
+        })
+        .collect();
+    tests.sort_by(|a, b| a.syscall.cmp(&b.syscall));
+
+    let mut filtered_syscalls = assessment.filtered_syscalls.clone();
+    filtered_syscalls.sort();
+
+    Ok(RegressionSummary {
+        verdict: verdict.to_string(),
+        reason,
+        sample_basis,
+        significance_level: config.significance_level,
+        regressed_syscalls,
+        filtered_syscalls,
+        tests,
+        outcome: assessment.verdict,
+    })
+}
+
+impl TraceDiff {
+    /// Whether a statistically significant regression was found
+    pub fn has_regression(&self) -> bool {
+        matches!(
+            self.regression.outcome,
+            RegressionVerdict::Regression { .. }
+        )
+    }
+
+    /// Process exit code for CI: 0 without regression, [`EXIT_REGRESSION`] with one
+    pub fn exit_code(&self) -> i32 {
+        if self.has_regression() {
+            EXIT_REGRESSION
+        } else {
+            0
+        }
+    }
+
+    /// JSON report
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize diff report")
+    }
+
+    /// Human-readable report
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        let side = |s: &SideSummary| {
+            format!(
+                "{} ({} run{}, {} syscalls)",
+                s.path,
+                s.runs,
+                if s.runs == 1 { "" } else { "s" },
+                s.syscalls
+            )
+        };
+        let _ = writeln!(out, "=== Renacer Trace Diff ===");
+        let _ = writeln!(out, "Baseline: {}", side(&self.baseline));
+        let _ = writeln!(out, "Current:  {}", side(&self.current));
+
+        let _ = writeln!(out, "\nCluster time attribution (per run):");
+        for delta in &self.clusters {
+            let marker = if delta.anomalous || !delta.expected {
+                "  ⚠️"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "  {:<20} {:>10.0}μs ({:>5.1}%) → {:>10.0}μs ({:>5.1}%)  Δ {:>+10.0}μs  calls {:.0} → {:.0}{}",
+                delta.cluster,
+                delta.baseline_time_us,
+                delta.baseline_percent,
+                delta.current_time_us,
+                delta.current_percent,
+                delta.delta_time_us(),
+                delta.baseline_calls,
+                delta.current_calls,
+                marker
+            );
+        }
+
+        if !self.hotspots.is_empty() {
+            let _ = writeln!(out, "\nCurrent hotspots:");
+            for hotspot in &self.hotspots {
+                let marker = if hotspot.expected { "✓" } else { "⚠️" };
+                let _ = writeln!(
+                    out,
+                    "  {} {} ({:.1}%): {}",
+                    marker, hotspot.cluster, hotspot.percent, hotspot.explanation
+                );
+            }
+        }
+
+        let count = |kind| self.sequences.iter().filter(|s| s.kind == kind).count();
+        let _ = writeln!(
+            out,
+            "\nSyscall sequences ({}-grams): {} new, {} missing, {} frequency changes",
+            self.ngram_size,
+            count(SequenceChangeKind::New),
+            count(SequenceChangeKind::Missing),
+            count(SequenceChangeKind::FrequencyChange)
+        );
+        for (kind, sign) in [
+            (SequenceChangeKind::New, '+'),
+            (SequenceChangeKind::Missing, '-'),
+        ] {
+            for change in self
+                .sequences
+                .iter()
+                .filter(|s| s.kind == kind)
+                .take(MAX_REPORTED_SEQUENCES)
+            {
+                let _ = writeln!(
+                    out,
+                    "  {} {} (×{}, {:?})",
+                    sign,
+                    change.sequence.join(" → "),
+                    change.baseline_count.max(change.current_count),
+                    change.severity
+                );
+            }
+        }
+
+        let basis = match self.regression.sample_basis {
+            SampleBasis::PerRun => "per-run syscall time",
+            SampleBasis::PerCall => "per-call durations",
+        };
+        let _ = writeln!(
+            out,
+            "\nRegression verdict ({}, α={}):",
+            basis, self.regression.significance_level
+        );
+        match &self.regression.outcome {
+            RegressionVerdict::NoRegression => {
+                let _ = writeln!(out, "  ✅ NO REGRESSION DETECTED");
+            }
+            RegressionVerdict::Regression { .. } => {
+                let _ = writeln!(
+                    out,
+                    "  ❌ REGRESSION DETECTED: {}",
+                    self.regression.regressed_syscalls.join(", ")
+                );
+            }
+            RegressionVerdict::InsufficientData { reason } => {
+                let _ = writeln!(out, "  ⚠️  INSUFFICIENT DATA: {}", reason);
+            }
+        }
+        if !self.regression.filtered_syscalls.is_empty() {
+            let _ = writeln!(
+                out,
+                "  Filtered noisy syscalls: {}",
+                self.regression.filtered_syscalls.join(", ")
+            );
+        }
+        for test in &self.regression.tests {
+            let _ = writeln!(
+                out,
+                "  {} {} (p={:.4}, median {:.1}μs → {:.1}μs)",
+                if test.regressed { "❌" } else { "  " },
+                test.syscall,
+                test.pvalue,
+                test.baseline_median_us,
+                test.current_median_us
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pid: i32, name: &str, duration_us: u64) -> SyscallRecord {
+        SyscallRecord {
+            pid,
+            timestamp_us: 0,
+            duration_us,
+            number: 0,
+            name: name.to_string(),
+            args: Vec::new(),
+            raw_args: [0; 3],
+            result: 0,
+            source: None,
+            function_name: None,
+            caller_name: None,
+            stack: Vec::new(),
+            wait_object: None,
+            stderr: None,
+        }
+    }
+
+    /// A run of `rounds` open/read/close rounds with the given read duration
+    fn run(rounds: usize, read_us: u64) -> Vec<SyscallRecord> {
+        (0..rounds)
+            .flat_map(|i| {
+                vec![
+                    record(1, "openat", 10),
+                    record(1, "read", read_us + (i % 3) as u64),
+                    record(1, "close", 5),
+                ]
+            })
+            .collect()
+    }
+
+    fn registry() -> ClusterRegistry {
+        ClusterRegistry::default_transpiler_clusters().unwrap()
+    }
+
+    #[test]
+    fn test_identical_traces_have_no_regression() {
+        let baseline = TraceSet::from_runs("base", vec![run(10, 100)]);
+        let current = TraceSet::from_runs("cur", vec![run(10, 100)]);
+
+        let diff = compare(&baseline, &current, &registry(), &DiffConfig::default()).unwrap();
+        assert_eq!(diff.regression.verdict, "no_regression");
+        assert_eq!(diff.regression.sample_basis, SampleBasis::PerCall);
+        assert_eq!(diff.exit_code(), 0);
+        assert!(diff.sequences.is_empty());
+        assert!(diff.clusters.iter().all(|c| c.delta_time_us() == 0.0));
+    }
+
+    #[test]
+    fn test_slower_reads_are_a_regression() {
+        let baseline = TraceSet::from_runs("base", vec![run(10, 100)]);
+        let current = TraceSet::from_runs("cur", vec![run(10, 300)]);
+
+        let diff = compare(&baseline, &current, &registry(), &DiffConfig::default()).unwrap();
+        assert_eq!(diff.regression.regressed_syscalls, vec!["read".to_string()]);
+        assert_eq!(diff.exit_code(), EXIT_REGRESSION);
+
+        let file_io = diff
+            .clusters
+            .iter()
+            .find(|c| c.cluster == "FileIO")
+            .unwrap();
+        assert!(file_io.delta_time_us() > 0.0);
+        assert!(diff.to_text().contains("REGRESSION DETECTED: read"));
+    }
+
+    #[test]
+    fn test_new_and_missing_sequences() {
+        let baseline = TraceSet::from_runs("base", vec![run(3, 100)]);
+        let mut current_run = run(3, 100);
+        current_run.push(record(1, "socket", 5));
+        current_run.push(record(1, "connect", 5));
+        let current = TraceSet::from_runs("cur", vec![current_run]);
+
+        let diff = compare(&baseline, &current, &registry(), &DiffConfig::default()).unwrap();
+        let new: Vec<_> = diff
+            .sequences
+            .iter()
+            .filter(|s| s.kind == SequenceChangeKind::New)
+            .collect();
+        assert!(!new.is_empty());
+        assert_eq!(new[0].severity, Severity::Critical);
+        assert!(new[0].sequence.contains(&"socket".to_string()));
+    }
+
+    #[test]
+    fn test_sequences_are_per_thread() {
+        // Two threads interleaved: no cross-thread sequence should appear
+        let interleaved: Vec<SyscallRecord> = (0..4)
+            .flat_map(|_| vec![record(1, "read", 1), record(2, "futex", 1)])
+            .collect();
+        let set = TraceSet::from_runs("t", vec![interleaved]);
+        let ngrams = set.ngrams(2);
+        assert_eq!(ngrams.len(), 2);
+        assert!(ngrams.contains_key(&vec!["read".to_string(), "read".to_string()]));
+    }
+
+    #[test]
+    fn test_many_runs_use_per_run_samples() {
+        let baseline = TraceSet::from_runs("base", (0..5).map(|i| run(4, 100 + i)).collect());
+        let current = TraceSet::from_runs("cur", (0..5).map(|i| run(4, 100 + i)).collect());
+
+        let diff = compare(&baseline, &current, &registry(), &DiffConfig::default()).unwrap();
+        assert_eq!(diff.regression.sample_basis, SampleBasis::PerRun);
+        assert_eq!(diff.regression.verdict, "no_regression");
+
+        let file_io = diff
+            .clusters
+            .iter()
+            .find(|c| c.cluster == "FileIO")
+            .unwrap();
+        assert_eq!(file_io.baseline_calls, 12.0);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let baseline = TraceSet::from_runs("base", vec![run(10, 100)]);
+        let current = TraceSet::from_runs("cur", vec![run(10, 300)]);
+
+        let diff = compare(&baseline, &current, &registry(), &DiffConfig::default()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["regression"]["verdict"], "regression");
+        assert_eq!(json["regression"]["sample_basis"], "per_call");
+        assert_eq!(json["baseline"]["syscalls"], 30);
+        assert!(!json["clusters"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_zero_ngram_size() {
+        let set = TraceSet::from_runs("t", vec![run(1, 1)]);
+        let config = DiffConfig {
+            ngram_size: 0,
+            ..DiffConfig::default()
+        };
+        assert!(compare(&set, &set, &registry(), &config).is_err());
+    }
+}
@@ -0,0 +1,646 @@
+//! Binary trace files for `renacer record` / `renacer replay`
+//!
+//! Sprint 55: A recorded trace keeps everything the live output and analysis
+//! modes consume (decoded arguments, results, durations, source locations,
+//! call stacks, wait objects and process lifecycle events), so a single
+//! capture can be analysed many different ways offline.
+//!
+//! # Layout
+//!
+//! ```text
+//! magic "RENACER\0" | format version (u16 LE) | frame*
+//! frame = length (u32 LE) | MessagePack payload
+//! ```
+//!
+//! The first frame is the [`TraceHeader`]; every following frame is a
+//! [`TraceEvent`]. The recorder flushes its buffer whenever an event arrives
+//! [`FLUSH_INTERVAL_US`] or more after the previous flush, so a trace cut
+//! short by a crash is still readable up to its last complete frame, missing
+//! at most the events of that final interval.
+
+use crate::dwarf::SourceLocation;
+use crate::memory::{MemorySample, RSS_SAMPLE_INTERVAL_US};
+use crate::offcpu::WaitObject;
+use crate::proc_maps::MemoryMapping;
+use crate::stack_unwind::ResolvedFrame;
+use anyhow::{Context, Result};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// File magic identifying a renacer trace
+pub const MAGIC: &[u8; 8] = b"RENACER\0";
+
+/// Current trace format version
+///
+/// Version 2 (Sprint 67) added [`TraceEvent::Memory`].
+pub const FORMAT_VERSION: u16 = 2;
+
+/// Longest a recorded event may stay in the write buffer (microseconds)
+pub const FLUSH_INTERVAL_US: u64 = 100_000;
+
+/// Upper bound for a single frame, guards against reading garbage lengths
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Metadata written once at the start of a trace
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    /// Traced command line (or the attached process's cmdline)
+    pub command: Vec<String>,
+    /// PID of the main traced process
+    pub pid: i32,
+    /// Wall-clock start of the recording (microseconds since the Unix epoch)
+    pub started_unix_us: u64,
+    /// Version of renacer that wrote the trace
+    pub renacer_version: String,
+}
+
+impl TraceHeader {
+    /// Header for a recording starting now
+    pub fn new(command: Vec<String>, pid: i32) -> Self {
+        let started_unix_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0);
+
+        Self {
+            command,
+            pid,
+            started_unix_us,
+            renacer_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+/// How a new process or thread was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpawnKind {
+    Fork,
+    Vfork,
+    /// clone(2), usually a new thread
+    Clone,
+}
+
+/// A completed syscall (entry and exit combined)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyscallRecord {
+    /// Thread that made the syscall
+    pub pid: i32,
+    /// Syscall entry, in microseconds since the start of the recording
+    pub timestamp_us: u64,
+    /// Time spent inside the syscall
+    pub duration_us: u64,
+    /// Raw syscall number
+    pub number: i64,
+    /// Syscall name ("unknown" if the number is not in the table)
+    pub name: String,
+    /// Decoded arguments, formatted as in structured output
+    pub args: Vec<String>,
+    /// First three raw argument registers
+    pub raw_args: [u64; 3],
+    /// Return value
+    pub result: i64,
+    /// DWARF source location of the syscall site
+    pub source: Option<SourceLocation>,
+    /// User function that made the syscall, and its caller
+    pub function_name: Option<String>,
+    pub caller_name: Option<String>,
+    /// Call stack, innermost frame first
+    pub stack: Vec<ResolvedFrame>,
+    /// What a blocking syscall waited on
+    pub wait_object: Option<WaitObject>,
+    /// Text written to stderr (for decision trace replay)
+    pub stderr: Option<String>,
+}
+
+/// A single event in a trace file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TraceEvent {
+    /// A traced process created a new process or thread
+    Spawn {
+        timestamp_us: u64,
+        parent: i32,
+        pid: i32,
+        kind: SpawnKind,
+    },
+    /// A successful execve/execveat
+    Exec {
+        timestamp_us: u64,
+        pid: i32,
+        program: String,
+    },
+    /// A signal was delivered to a traced process
+    Signal {
+        timestamp_us: u64,
+        pid: i32,
+        signal: i32,
+    },
+    /// Executable mappings of a process, needed to rebuild pprof mappings
+    Mappings { pid: i32, maps: Vec<MemoryMapping> },
+    /// A completed syscall
+    Syscall(Box<SyscallRecord>),
+    /// Resident set of a traced process (Sprint 67)
+    Memory(MemorySample),
+    /// A traced process exited normally (`code`) or was killed (`signal`)
+    Exit {
+        timestamp_us: u64,
+        pid: i32,
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+}
+
+/// Low-level writer for the trace format
+pub struct TraceWriter<W: Write> {
+    inner: W,
+    events: usize,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Write the magic, format version and header
+    pub fn new(mut inner: W, header: &TraceHeader) -> Result<Self> {
+        inner.write_all(MAGIC)?;
+        inner.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        write_frame(&mut inner, header)?;
+        Ok(Self { inner, events: 0 })
+    }
+
+    /// Append an event
+    pub fn write_event(&mut self, event: &TraceEvent) -> Result<()> {
+        write_frame(&mut self.inner, event)?;
+        self.events += 1;
+        Ok(())
+    }
+
+    /// Number of events written so far
+    pub fn event_count(&self) -> usize {
+        self.events
+    }
+
+    /// Flush the underlying writer
+    pub fn flush(&mut self) -> Result<()> {
+        self.inner.flush()?;
+        Ok(())
+    }
+
+    /// Flush and return the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+fn write_frame<W: Write, T: Serialize>(writer: &mut W, value: &T) -> Result<()> {
+    let payload = rmp_serde::to_vec(value).context("Failed to encode trace event")?;
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(&payload)?;
+    Ok(())
+}
+
+/// Reader for the trace format
+pub struct TraceReader<R: Read> {
+    inner: R,
+    header: TraceHeader,
+}
+
+impl TraceReader<BufReader<File>> {
+    /// Open a trace file
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open trace file {}", path.display()))?;
+        Self::new(BufReader::new(file))
+            .with_context(|| format!("Failed to read trace file {}", path.display()))
+    }
+}
+
+impl<R: Read> TraceReader<R> {
+    /// Validate the magic and version, then read the header
+    pub fn new(mut inner: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        inner
+            .read_exact(&mut magic)
+            .context("File is too short to be a renacer trace")?;
+        if &magic != MAGIC {
+            anyhow::bail!("Not a renacer trace file (bad magic)");
+        }
+
+        let mut version = [0u8; 2];
+        inner.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            anyhow::bail!(
+                "Unsupported trace format version {} (expected {})",
+                version,
+                FORMAT_VERSION
+            );
+        }
+
+        let header = read_frame(&mut inner)?.context("Trace file has no header")?;
+        Ok(Self { inner, header })
+    }
+
+    /// Header written at the start of the recording
+    pub fn header(&self) -> &TraceHeader {
+        &self.header
+    }
+
+    /// Read the next event, `None` at the end of the trace
+    pub fn next_event(&mut self) -> Result<Option<TraceEvent>> {
+        read_frame(&mut self.inner)
+    }
+
+    /// Exit code of the main traced process (128 + signal if it was killed)
+    ///
+    /// `None` if the trace ends before the process exits, e.g. because the
+    /// tracer failed.
+    pub fn main_exit_code(mut self) -> Result<Option<i32>> {
+        let main_pid = self.header.pid;
+        while let Some(event) = self.next_event()? {
+            if let TraceEvent::Exit {
+                pid, code, signal, ..
+            } = event
+            {
+                if pid == main_pid {
+                    return Ok(Some(code.unwrap_or(128 + signal.unwrap_or(0))));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: Read> Iterator for TraceReader<R> {
+    type Item = Result<TraceEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+fn read_frame<R: Read, T: for<'de> Deserialize<'de>>(reader: &mut R) -> Result<Option<T>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        anyhow::bail!("Corrupt trace: frame of {} bytes", len);
+    }
+
+    let mut payload = vec![0u8; len];
+    reader
+        .read_exact(&mut payload)
+        .context("Trace ends with a truncated event")?;
+    let value = rmp_serde::from_slice(&payload).context("Failed to decode trace event")?;
+    Ok(Some(value))
+}
+
+/// Streams events from a live trace to a file
+///
+/// Keeps the recording clock and makes sure a [`TraceEvent::Mappings`]
+/// event precedes any stack that points outside the mappings recorded so far.
+/// Processes making syscalls have their RSS sampled as [`TraceEvent::Memory`].
+pub struct TraceRecorder {
+    path: PathBuf,
+    writer: TraceWriter<BufWriter<File>>,
+    start: Instant,
+    mapped: HashMap<i32, Vec<(u64, u64)>>,
+    /// Time of the last RSS sample of each thread
+    sampled: HashMap<i32, u64>,
+    /// Time of the last flush
+    flushed_us: u64,
+}
+
+impl TraceRecorder {
+    /// Create the trace file and write its header
+    pub fn create(path: impl Into<PathBuf>, header: &TraceHeader) -> Result<Self> {
+        let path = path.into();
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create trace file {}", path.display()))?;
+        let writer = TraceWriter::new(BufWriter::new(file), header)?;
+
+        Ok(Self {
+            path,
+            writer,
+            start: Instant::now(),
+            mapped: HashMap::new(),
+            sampled: HashMap::new(),
+            flushed_us: 0,
+        })
+    }
+
+    /// Output path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Microseconds since the recording started
+    pub fn elapsed_us(&self) -> u64 {
+        self.start.elapsed().as_micros() as u64
+    }
+
+    /// Append a lifecycle event, flushing if the interval has passed
+    pub fn record(&mut self, event: TraceEvent) -> Result<()> {
+        self.writer.write_event(&event)?;
+        let now = self.elapsed_us();
+        if now >= self.flushed_us + FLUSH_INTERVAL_US {
+            self.flushed_us = now;
+            self.writer
+                .flush()
+                .with_context(|| format!("Failed to write trace file {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Append a completed syscall, preceded by fresh mappings if needed
+    pub fn record_syscall(&mut self, record: SyscallRecord) -> Result<()> {
+        let pid = record.pid;
+        let unmapped = record.stack.iter().any(|frame| {
+            !self.mapped.get(&pid).is_some_and(|ranges| {
+                ranges
+                    .iter()
+                    .any(|&(start, end)| frame.address >= start && frame.address < end)
+            })
+        });
+
+        if unmapped {
+            if let Ok(maps) = crate::proc_maps::read_maps(Pid::from_raw(pid)) {
+                let maps: Vec<MemoryMapping> =
+                    maps.into_iter().filter(|m| m.is_executable()).collect();
+                self.mapped
+                    .insert(pid, maps.iter().map(|m| (m.start, m.end)).collect());
+                self.record(TraceEvent::Mappings { pid, maps })?;
+            }
+        }
+
+        self.record(TraceEvent::Syscall(Box::new(record)))?;
+        self.sample_rss(pid)
+    }
+
+    /// Record the RSS of `pid` unless it was sampled within the interval
+    fn sample_rss(&mut self, pid: i32) -> Result<()> {
+        let now = self.elapsed_us();
+        if self
+            .sampled
+            .get(&pid)
+            .is_some_and(|&last| now < last + RSS_SAMPLE_INTERVAL_US)
+        {
+            return Ok(());
+        }
+        self.sampled.insert(pid, now);
+        match crate::memory::read_rss(pid) {
+            Some((tgid, bytes)) => self.record(TraceEvent::Memory(MemorySample {
+                timestamp_us: now,
+                pid: tgid,
+                bytes,
+            })),
+            None => Ok(()),
+        }
+    }
+
+    /// Number of events written so far
+    pub fn event_count(&self) -> usize {
+        self.writer.event_count()
+    }
+
+    /// Flush the file, returning the number of events written
+    pub fn finish(self) -> Result<usize> {
+        let events = self.writer.event_count();
+        self.writer
+            .finish()
+            .with_context(|| format!("Failed to write trace file {}", self.path.display()))?;
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_header() -> TraceHeader {
+        TraceHeader {
+            command: vec!["ls".to_string(), "-l".to_string()],
+            pid: 42,
+            started_unix_us: 1_700_000_000_000_000,
+            renacer_version: "0.0.0".to_string(),
+        }
+    }
+
+    fn sample_syscall() -> SyscallRecord {
+        SyscallRecord {
+            pid: 42,
+            timestamp_us: 10,
+            duration_us: 250,
+            number: 257,
+            name: "openat".to_string(),
+            args: vec![
+                "0xffffff9c".to_string(),
+                "\"/etc/passwd\"".to_string(),
+                "0x80000".to_string(),
+            ],
+            raw_args: [0xffffff9c, 0x7ffd0000, 0x80000],
+            result: 3,
+            source: Some(SourceLocation {
+                file: "src/main.rs".to_string(),
+                line: 7,
+                column: Some(5),
+                function: Some("main".to_string()),
+            }),
+            function_name: Some("main".to_string()),
+            caller_name: None,
+            stack: vec![ResolvedFrame {
+                address: 0x401000,
+                function: Some("main".to_string()),
+                file: Some("src/main.rs".to_string()),
+                line: Some(7),
+                module: Some("app".to_string()),
+                inlined: false,
+            }],
+            wait_object: Some(WaitObject::Fd {
+                fd: 3,
+                path: Some("/etc/passwd".to_string()),
+            }),
+            stderr: None,
+        }
+    }
+
+    fn encode(events: &[TraceEvent]) -> Vec<u8> {
+        let mut writer = TraceWriter::new(Vec::new(), &sample_header()).unwrap();
+        for event in events {
+            writer.write_event(event).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let events = vec![
+            TraceEvent::Spawn {
+                timestamp_us: 1,
+                parent: 42,
+                pid: 43,
+                kind: SpawnKind::Clone,
+            },
+            TraceEvent::Syscall(Box::new(sample_syscall())),
+            TraceEvent::Signal {
+                timestamp_us: 300,
+                pid: 42,
+                signal: 17,
+            },
+            TraceEvent::Exit {
+                timestamp_us: 400,
+                pid: 42,
+                code: Some(0),
+                signal: None,
+            },
+        ];
+        let bytes = encode(&events);
+        assert!(bytes.starts_with(MAGIC));
+
+        let reader = TraceReader::new(bytes.as_slice()).unwrap();
+        assert_eq!(reader.header(), &sample_header());
+        let decoded: Vec<TraceEvent> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(decoded, events);
+    }
+
+    #[test]
+    fn test_rejects_bad_magic() {
+        let err = TraceReader::new(&b"NOTATRACE-AT-ALL"[..]).err().unwrap();
+        assert!(err.to_string().contains("bad magic"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_version() {
+        let mut bytes = encode(&[]);
+        bytes[8..10].copy_from_slice(&99u16.to_le_bytes());
+        let err = TraceReader::new(bytes.as_slice()).err().unwrap();
+        assert!(err.to_string().contains("version 99"));
+    }
+
+    #[test]
+    fn test_rejects_previous_version() {
+        // Written before TraceEvent::Memory existed
+        let mut bytes = encode(&[]);
+        bytes[8..10].copy_from_slice(&1u16.to_le_bytes());
+        let err = TraceReader::new(bytes.as_slice()).err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Unsupported trace format version 1 (expected 2)"
+        );
+    }
+
+    #[test]
+    fn test_truncated_event_is_an_error() {
+        let mut bytes = encode(&[TraceEvent::Syscall(Box::new(sample_syscall()))]);
+        bytes.truncate(bytes.len() - 3);
+        let mut reader = TraceReader::new(bytes.as_slice()).unwrap();
+        assert!(reader.next_event().is_err());
+    }
+
+    #[test]
+    fn test_main_exit_code() {
+        let exit = |pid, code, signal| TraceEvent::Exit {
+            timestamp_us: 1,
+            pid,
+            code,
+            signal,
+        };
+        let read = |events: &[TraceEvent]| {
+            let bytes = encode(events);
+            TraceReader::new(bytes.as_slice())
+                .unwrap()
+                .main_exit_code()
+                .unwrap()
+        };
+
+        assert_eq!(
+            read(&[exit(43, Some(0), None), exit(42, Some(3), None)]),
+            Some(3)
+        );
+        assert_eq!(read(&[exit(42, None, Some(9))]), Some(137));
+        // A child exiting is not the end of the run
+        assert_eq!(read(&[exit(43, Some(0), None)]), None);
+    }
+
+    #[test]
+    fn test_recorder_writes_readable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.rnc");
+
+        let mut recorder = TraceRecorder::create(&path, &sample_header()).unwrap();
+        let mut record = sample_syscall();
+        record.pid = std::process::id() as i32;
+        record.stack.clear();
+        recorder.record_syscall(record.clone()).unwrap();
+        assert_eq!(recorder.finish().unwrap(), 2);
+
+        let events: Vec<TraceEvent> = TraceReader::open(&path)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(events[0], TraceEvent::Syscall(Box::new(record.clone())));
+        // The syscall's process has its RSS sampled right after
+        assert!(matches!(
+            events[1],
+            TraceEvent::Memory(MemorySample { pid, bytes, .. }) if pid == record.pid && bytes > 0
+        ));
+    }
+
+    #[test]
+    fn test_recorder_flushes_after_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.rnc");
+        let exit = TraceEvent::Exit {
+            timestamp_us: 0,
+            pid: 42,
+            code: Some(0),
+            signal: None,
+        };
+
+        let mut recorder = TraceRecorder::create(&path, &sample_header()).unwrap();
+        std::thread::sleep(std::time::Duration::from_micros(FLUSH_INTERVAL_US));
+        recorder.record(exit.clone()).unwrap();
+
+        // Readable while the recorder is still open, as after a crash
+        let events: Vec<TraceEvent> = TraceReader::open(&path)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(events, vec![exit]);
+        drop(recorder);
+    }
+
+    #[test]
+    fn test_recorder_emits_mappings_before_unmapped_stacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.rnc");
+
+        let mut recorder = TraceRecorder::create(&path, &sample_header()).unwrap();
+        let mut record = sample_syscall();
+        record.pid = std::process::id() as i32;
+        // An address inside this test binary's executable mapping
+        let text = test_recorder_emits_mappings_before_unmapped_stacks as *const () as usize;
+        record.stack[0].address = text as u64;
+        recorder.record_syscall(record.clone()).unwrap();
+        recorder.record_syscall(record).unwrap();
+        recorder.finish().unwrap();
+
+        let mut events: Vec<TraceEvent> = TraceReader::open(&path)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        events.retain(|event| !matches!(event, TraceEvent::Memory(_)));
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], TraceEvent::Mappings { maps, .. } if !maps.is_empty()));
+        assert!(matches!(events[1], TraceEvent::Syscall(_)));
+        assert!(matches!(events[2], TraceEvent::Syscall(_)));
+    }
+}
@@ -45,6 +45,43 @@ pub struct TracerConfig {
     pub otlp_service_name: String,     // Sprint 30: Service name for OTLP traces
     pub trace_parent: Option<String>,  // Sprint 33: W3C Trace Context for distributed tracing
     pub chaos_config: Option<crate::chaos::ChaosConfig>, // Sprint 47: Chaos engineering (Issue #17)
+    pub flamegraph: Option<crate::flamegraph::FlamegraphConfig>, // Sprint 50: Flamegraph output
+    pub pprof: Option<String>,         // Sprint 51: pprof profile output path
+    pub off_cpu: Option<crate::offcpu::OffCpuConfig>, // Sprint 52: Off-CPU analysis
+    pub record: Option<String>,        // Sprint 55: Trace file written by `renacer record`
+    pub record_stacks: bool, // Sprint 55: Store call stacks in the recorded trace (record --stacks)
+    pub clusters: Option<crate::cluster::ClusterSummaryConfig>, // Sprint 58: -c --by-cluster summary
+    pub sequence_model: Option<crate::sequence::SequenceModel>, // Sprint 59: Learned normal syscall sequences
+    pub file_audit: Option<crate::file_audit::FileAuditConfig>, // Sprint 61: File access audit
+    pub connections: bool, // Sprint 63: Print the network connection table
+    pub hotspots: Option<crate::time_attribution::HotspotConfig>, // Sprint 64: Time per cluster and hotspots
+    pub critical_path: bool, // Sprint 71: Longest dependency chain through all processes
+    pub locks: Option<crate::locks::LockConfig>, // Sprint 73: Lock contention from futex waits
+    pub anti_patterns: bool, // Sprint 74: Syscall-level anti-patterns with call sites
+}
+
+impl TracerConfig {
+    /// Whether call stacks must be unwound and symbolized at syscall entry
+    /// (Sprint 51: shared by flamegraph and pprof output, Sprint 52: off-CPU,
+    /// Sprint 55: record --stacks, Sprint 59: sequence model call sites,
+    /// Sprint 73: lock acquiring stacks, Sprint 74: anti-pattern call sites)
+    fn needs_call_stacks(&self) -> bool {
+        self.flamegraph.is_some()
+            || self.pprof.is_some()
+            || self.off_cpu.is_some()
+            || self.record_stacks
+            || self.sequence_model.is_some()
+            || self.locks.is_some()
+            || self.anti_patterns
+    }
+
+    /// Whether sockets are tracked for the connection table (Sprint 63:
+    /// shown with --connections, after the -c summary and in HTML reports)
+    fn tracks_connections(&self) -> bool {
+        self.connections
+            || self.statistics_mode
+            || matches!(self.output_format, crate::cli::OutputFormat::Html)
+    }
 }
 
 /// Attach to a running process by PID and trace syscalls
@@ -55,6 +92,9 @@ pub struct TracerConfig {
 pub fn attach_to_pid(pid: i32, config: TracerConfig) -> Result<()> {
     let pid = Pid::from_raw(pid);
 
+    // Sprint 55: Open the trace file before touching the process
+    let recorder = create_recorder(&config, pid, read_cmdline(pid))?;
+
     // Attach to the running process
     ptrace::attach(pid).context(format!("Failed to attach to PID {}", pid))?;
 
@@ -64,7 +104,7 @@ pub fn attach_to_pid(pid: i32, config: TracerConfig) -> Result<()> {
     eprintln!("[renacer: Attached to process {}]", pid);
 
     // Use the same tracing logic as trace_command
-    trace_child(pid, config)?;
+    trace_child(pid, config, recorder)?;
 
     Ok(())
 }
@@ -99,7 +139,15 @@ pub fn trace_command(command: &[String], config: TracerConfig) -> Result<()> {
     // Fork: parent will trace, child will exec
     match unsafe { fork() }.context("Failed to fork")? {
         ForkResult::Parent { child } => {
-            trace_child(child, config)?;
+            // Sprint 55: Don't let the command run untraced if the trace file can't be created
+            let recorder = match create_recorder(&config, child, command.to_vec()) {
+                Ok(recorder) => recorder,
+                Err(e) => {
+                    nix::sys::signal::kill(child, nix::sys::signal::Signal::SIGKILL).ok();
+                    return Err(e);
+                }
+            };
+            trace_child(child, config, recorder)?;
             Ok(())
         }
         ForkResult::Child => {
@@ -137,6 +185,18 @@ struct Tracers {
     decision_tracer: Option<crate::decision_trace::DecisionTracer>, // Sprint 26
     #[cfg(feature = "otlp")]
     otlp_exporter: Option<crate::otlp_exporter::OtlpExporter>, // Sprint 30
+    flamegraph: Option<crate::flamegraph::FlamegraphCollector>, // Sprint 50
+    pprof: Option<crate::pprof::PprofCollector>,         // Sprint 51
+    off_cpu: Option<crate::offcpu::OffCpuAnalyzer>,      // Sprint 52
+    recorder: Option<crate::trace_file::TraceRecorder>,  // Sprint 55
+    clusters: Option<crate::cluster::ClusterTracker>,    // Sprint 58
+    sequence_monitor: Option<crate::sequence::SequenceMonitor>, // Sprint 59
+    file_audit: Option<crate::file_audit::FileAudit>,    // Sprint 61
+    connections: Option<crate::network::ConnectionTable>, // Sprint 63
+    hotspots: Option<crate::time_attribution::HotspotTracker>, // Sprint 64
+    process_tree: Option<crate::process_tree::ProcessTreeTracker>, // Sprint 71
+    locks: Option<crate::locks::LockAnalyzer>,           // Sprint 73
+    anti_patterns: Option<crate::syscall_anti_patterns::SyscallPatternDetector>, // Sprint 74
 }
 
 /// Initialize profiling-related tracers
@@ -277,6 +337,63 @@ fn initialize_tracers(config: &TracerConfig) -> Tracers {
         None
     };
 
+    // Sprint 50: Collect unwound stacks for flamegraph output
+    let flamegraph = config
+        .flamegraph
+        .clone()
+        .map(crate::flamegraph::FlamegraphCollector::new);
+
+    // Sprint 51: Collect unwound stacks for pprof output
+    let pprof = config.pprof.as_ref().map(crate::pprof::PprofCollector::new);
+
+    // Sprint 52: Group blocked time by stack and wait object
+    let off_cpu = config
+        .off_cpu
+        .clone()
+        .map(crate::offcpu::OffCpuAnalyzer::new);
+
+    // Sprint 58: Per-cluster summary with live fd tracking
+    let clusters = config
+        .clusters
+        .clone()
+        .map(crate::cluster::ClusterTracker::new);
+
+    // Sprint 59: Check syscall windows against a learned model
+    let sequence_monitor = config
+        .sequence_model
+        .clone()
+        .map(crate::sequence::SequenceMonitor::new);
+
+    // Sprint 61: Files read, written, created and deleted per process
+    let file_audit = config
+        .file_audit
+        .clone()
+        .map(crate::file_audit::FileAudit::new);
+
+    // Sprint 63: Sockets, their endpoints and traffic
+    let connections = config
+        .tracks_connections()
+        .then(crate::network::ConnectionTable::new);
+
+    // Sprint 64: Syscall spans of a unified trace, attributed to clusters at the end
+    let hotspots = config
+        .hotspots
+        .clone()
+        .map(crate::time_attribution::HotspotTracker::new);
+
+    // Sprint 71: Processes, their syscalls and wait4 joins for --critical-path
+    let process_tree = config
+        .critical_path
+        .then(crate::process_tree::ProcessTreeTracker::new);
+
+    // Sprint 73: futex waits and wakes per address for --locks
+    let locks = config.locks.clone().map(crate::locks::LockAnalyzer::new);
+
+    // Sprint 74: Tiny writes, repeated lookups, polling loops... per call site
+    let anti_patterns = config
+        .anti_patterns
+        .then(crate::syscall_anti_patterns::SyscallPatternDetector::default);
+
     Tracers {
         profiling_ctx,
         function_profiler,
@@ -289,9 +406,46 @@ fn initialize_tracers(config: &TracerConfig) -> Tracers {
         decision_tracer,
         #[cfg(feature = "otlp")]
         otlp_exporter,
+        flamegraph,
+        pprof,
+        off_cpu,
+        recorder: None, // Sprint 55: Opened before the tracee starts, see trace_child
+        clusters,
+        sequence_monitor,
+        file_audit,
+        connections,
+        hotspots,
+        process_tree,
+        locks,
+        anti_patterns,
     }
 }
 
+/// Sprint 55: Open the trace file for `renacer record`
+fn create_recorder(
+    config: &TracerConfig,
+    pid: Pid,
+    command: Vec<String>,
+) -> Result<Option<crate::trace_file::TraceRecorder>> {
+    let Some(path) = &config.record else {
+        return Ok(None);
+    };
+    let header = crate::trace_file::TraceHeader::new(command, pid.as_raw());
+    crate::trace_file::TraceRecorder::create(path, &header).map(Some)
+}
+
+/// Command line of a running process from `/proc/PID/cmdline`
+fn read_cmdline(pid: Pid) -> Vec<String> {
+    std::fs::read_to_string(format!("/proc/{}/cmdline", pid))
+        .map(|s| {
+            s.split('\0')
+                .filter(|arg| !arg.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Initialize ptrace options for the child process
 fn setup_ptrace_options(child: Pid, follow_forks: bool) -> Result<()> {
     setup_ptrace_options_internal(child, follow_forks, true)
@@ -329,10 +483,20 @@ fn setup_ptrace_options_internal(child: Pid, follow_forks: bool, wait_first: boo
 }
 
 /// Load DWARF debug info for source correlation
-fn load_dwarf_context(child: Pid) -> Option<crate::dwarf::DwarfContext> {
+///
+/// Sprint 50: PIE executables are relocated using the base address from `maps`.
+fn load_dwarf_context(
+    child: Pid,
+    maps: &[crate::proc_maps::MemoryMapping],
+) -> Option<crate::dwarf::DwarfContext> {
     if let Ok(exe_path) = std::fs::read_link(format!("/proc/{}/exe", child)) {
         match crate::dwarf::DwarfContext::load(&exe_path) {
-            Ok(ctx) => {
+            Ok(mut ctx) => {
+                if ctx.is_position_independent() {
+                    if let Some(base) = crate::proc_maps::base_address(maps, &exe_path) {
+                        ctx.set_load_bias(base.wrapping_sub(ctx.image_base() & !0xfff));
+                    }
+                }
                 eprintln!(
                     "[renacer: DWARF debug info loaded from {}]",
                     exe_path.display()
@@ -356,7 +520,9 @@ fn handle_ptrace_event(
     event: i32,
     processes: &mut std::collections::HashMap<Pid, ProcessState>,
     config: &TracerConfig,
+    recorder: Option<&mut crate::trace_file::TraceRecorder>,
 ) -> Result<()> {
+    use crate::trace_file::{SpawnKind, TraceEvent};
     use nix::libc;
 
     // Check if this is a fork/vfork/clone event
@@ -367,6 +533,31 @@ fn handle_ptrace_event(
                 .context("Failed to get event message for fork/vfork/clone")?;
             let new_pid = Pid::from_raw(new_pid_raw as i32);
 
+            // Sprint 55: Record the new process/thread
+            if let Some(recorder) = recorder {
+                let kind = match event {
+                    libc::PTRACE_EVENT_FORK => SpawnKind::Fork,
+                    libc::PTRACE_EVENT_VFORK => SpawnKind::Vfork,
+                    _ => SpawnKind::Clone,
+                };
+                recorder.record(TraceEvent::Spawn {
+                    timestamp_us: recorder.elapsed_us(),
+                    parent: pid.as_raw(),
+                    pid: new_pid.as_raw(),
+                    kind,
+                })?;
+            }
+
+            // The main loop adopted it if its first stop came first; it was held
+            // there so none of its events are recorded before the spawn
+            if let Some(state) = processes.get_mut(&new_pid) {
+                if std::mem::take(&mut state.awaiting_spawn) {
+                    ptrace::syscall(new_pid, None).ok();
+                }
+                eprintln!("[renacer: Process {} forked child {}]", pid, new_pid);
+                return Ok(());
+            }
+
             // Wait for the new child to stop
             let wait_status = waitpid(new_pid, None).context("Failed to wait for new child")?;
 
@@ -380,7 +571,8 @@ fn handle_ptrace_event(
                     );
                 }
                 _ => {
-                    // Setup ptrace options for the new child (already waited)
+                    // Setup ptrace options for the new child (already waited); this
+                    // also resumes it, so it must not be continued a second time
                     if let Err(e) =
                         setup_ptrace_options_internal(new_pid, config.follow_forks, false)
                     {
@@ -394,25 +586,7 @@ fn handle_ptrace_event(
 
                     // Add to tracking
                     processes.insert(new_pid, ProcessState::new());
-
-                    // Continue the new child process
-                    // Handle ESRCH gracefully - child may have exited between waitpid and syscall
-                    match ptrace::syscall(new_pid, None) {
-                        Ok(_) => {
-                            eprintln!("[renacer: Process {} forked child {}]", pid, new_pid);
-                        }
-                        Err(nix::errno::Errno::ESRCH) => {
-                            // Child already exited, remove from tracking
-                            processes.remove(&new_pid);
-                            eprintln!(
-                                "[renacer: Process {} forked child {} (exited immediately)]",
-                                pid, new_pid
-                            );
-                        }
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Failed to continue new child: {}", e));
-                        }
-                    }
+                    eprintln!("[renacer: Process {} forked child {}]", pid, new_pid);
                 }
             }
         }
@@ -427,10 +601,7 @@ fn handle_ptrace_event(
 /// Handle syscall event (entry or exit)
 fn handle_syscall_event(
     child: Pid,
-    in_syscall: &mut bool,
-    current_syscall_entry: &mut Option<SyscallEntry>,
-    syscall_entry_time: &mut Option<std::time::Instant>,
-    dwarf_ctx: Option<&crate::dwarf::DwarfContext>,
+    state: &mut ProcessState,
     config: &TracerConfig,
     tracers: &mut Tracers,
 ) -> Result<()> {
@@ -438,39 +609,114 @@ fn handle_syscall_event(
     let in_json_mode = tracers.json_output.is_some();
     let in_csv_mode = tracers.csv_output.is_some() || tracers.csv_stats_output.is_some();
     let in_html_mode = tracers.html_output.is_some();
-    let structured_output = in_json_mode || in_csv_mode || in_html_mode;
-
-    if !*in_syscall {
-        // Syscall entry - record start time if timing enabled
-        if config.timing_mode || config.statistics_mode || structured_output {
-            *syscall_entry_time = Some(std::time::Instant::now());
-        }
-
-        *current_syscall_entry = process_syscall_entry(
+    // Sprint 55: Recording also decodes args and suppresses live text output
+    let in_record_mode = tracers.recorder.is_some();
+    // Sprint 58: Cluster arg filters match on decoded args (text output is off with -c)
+    let structured_output =
+        in_json_mode || in_csv_mode || in_html_mode || in_record_mode || tracers.clusters.is_some();
+
+    if !state.in_syscall {
+        state.current_syscall_entry = process_syscall_entry(
             child,
-            dwarf_ctx,
+            state.dwarf_ctx.as_ref(),
+            &mut state.maps,
             config,
             tracers.profiling_ctx.as_mut(),
             structured_output,
         )?;
-        *in_syscall = true;
+
+        // Sprint 73: Identify and name futex words while the tracee's symbols
+        // and maps are at hand
+        if let (Some(entry), Some(analyzer)) =
+            (state.current_syscall_entry.as_mut(), tracers.locks.as_mut())
+        {
+            if entry.name == "futex" {
+                let addr = entry.raw_arg1.unwrap_or(0);
+                if crate::proc_maps::find_mapping(&state.maps, addr).is_none() {
+                    state.maps = crate::proc_maps::read_maps(child).unwrap_or_default();
+                }
+                let shared_memory = crate::proc_maps::find_mapping(&state.maps, addr)
+                    .is_some_and(|mapping| mapping.is_shared());
+                let tgid = analyzer.thread_group(child.as_raw(), crate::proc_maps::thread_group);
+                let key = crate::locks::LockKey::new(
+                    tgid,
+                    addr,
+                    entry.raw_arg2.unwrap_or(0),
+                    shared_memory,
+                );
+                analyzer.resolve(key, |addr| {
+                    crate::locks::lock_symbol(addr, state.dwarf_ctx.as_ref(), &state.maps)
+                });
+                entry.lock = Some(key);
+            }
+        }
+
+        // Syscall entry - record start time if timing enabled. Taken after the
+        // entry has been decoded so stack unwinding and symbol lookups are not
+        // counted as time spent in the syscall.
+        if config.timing_mode
+            || config.statistics_mode
+            || structured_output
+            || config.needs_call_stacks()
+            || config.hotspots.is_some()
+            || config.critical_path
+        {
+            state.syscall_entry_time = Some(std::time::Instant::now());
+        }
+        state.in_syscall = true;
     } else {
         // Syscall exit - calculate duration
-        let duration_us = syscall_entry_time
+        let duration_us = state
+            .syscall_entry_time
             .map(|start| start.elapsed().as_micros() as u64)
             .unwrap_or(0);
 
+        // Sprint 63: Addresses written by the kernel (accept, recvfrom) are read at exit
+        if let Some(entry) = state
+            .current_syscall_entry
+            .as_mut()
+            .filter(|entry| crate::network::address_at_exit(&entry.name))
+        {
+            let regs = ptrace::getregs(child).context("Failed to get registers")?;
+            entry.args = format_syscall_args_for_json(
+                child,
+                &entry.name,
+                syscall_args(&regs),
+                Some(regs.rax as i64),
+            );
+            if !config.statistics_mode && !structured_output {
+                print_replayed_syscall_entry(entry, config.transpiler_map.as_ref());
+            }
+        }
+
+        // Sprint 72: pipe/socketpair fill in their fd pair, read at exit
+        if structured_output || config.critical_path {
+            if let Some(entry) = state
+                .current_syscall_entry
+                .as_mut()
+                .filter(|entry| crate::process_tree::fd_pair_arg(&entry.name).is_some())
+            {
+                let regs = ptrace::getregs(child).context("Failed to get registers")?;
+                entry.args = format_syscall_args_for_json(
+                    child,
+                    &entry.name,
+                    syscall_args(&regs),
+                    Some(regs.rax as i64),
+                );
+            }
+        }
+
         process_syscall_exit(
             child,
-            current_syscall_entry,
+            &state.current_syscall_entry,
             tracers,
             config.timing_mode,
             duration_us,
         )?;
 
-        *current_syscall_entry = None;
-        *syscall_entry_time = None;
-        *in_syscall = false;
+        state.current_syscall_entry = None;
+        state.syscall_entry_time = None;
+        state.in_syscall = false;
     }
     Ok(())
 }
@@ -479,33 +725,106 @@ fn handle_syscall_event(
 fn process_syscall_entry(
     child: Pid,
     dwarf_ctx: Option<&crate::dwarf::DwarfContext>,
+    maps: &mut Vec<crate::proc_maps::MemoryMapping>,
     config: &TracerConfig,
     profiling_ctx: Option<&mut crate::profiling::ProfilingContext>,
     structured_output: bool,
 ) -> Result<Option<SyscallEntry>> {
-    if let Some(prof) = profiling_ctx {
+    // Sprint 50: DWARF may be loaded only to symbolize flamegraph/pprof stacks
+    // Sprint 55: Recorded traces always keep source locations and functions
+    let recording = config.record.is_some();
+    let source_ctx = dwarf_ctx.filter(|_| config.enable_source || recording);
+    let function_time = config.function_time || recording;
+
+    let mut entry = if let Some(prof) = profiling_ctx {
         prof.measure(crate::profiling::ProfilingCategory::Other, || {
             handle_syscall_entry(
                 child,
-                dwarf_ctx,
+                source_ctx,
                 &config.filter,
                 config.statistics_mode,
                 structured_output,
-                config.function_time,
+                function_time,
                 config.transpiler_map.as_ref(),
             )
-        })
+        })?
     } else {
         handle_syscall_entry(
             child,
-            dwarf_ctx,
+            source_ctx,
             &config.filter,
             config.statistics_mode,
             structured_output,
-            config.function_time,
+            function_time,
             config.transpiler_map.as_ref(),
-        )
+        )?
+    };
+
+    // Sprint 61: The file access audit needs decoded paths, even in text mode
+    // Sprint 74: So do the repeated lookup and search path detectors
+    // Sprint 64: And `arg_contains` cluster filters under --hotspots
+    if config.file_audit.is_some() || config.anti_patterns || config.hotspots.is_some() {
+        if let Some(entry) = entry.as_mut().filter(|e| e.args.is_empty()) {
+            if !crate::file_audit::path_arg_indices(&entry.name).is_empty() {
+                let regs = ptrace::getregs(child).context("Failed to get registers")?;
+                entry.args =
+                    format_syscall_args_for_json(child, &entry.name, syscall_args(&regs), None);
+            }
+        }
+    }
+
+    // Sprint 50: Capture the full call stack for flamegraph/pprof output
+    if config.needs_call_stacks() {
+        if let Some(entry) = entry.as_mut() {
+            entry.stack = capture_call_stack(child, dwarf_ctx, maps);
+        }
     }
+
+    // Sprint 52: Resolve the wait object while the fd is still open
+    // Sprint 72: The critical path pairs pipe and futex sleepers with their wakers
+    // Sprint 74: Anti-pattern reports name the fd a tiny write went to
+    if config.off_cpu.is_some() || recording || config.critical_path || config.anti_patterns {
+        if let Some(entry) = entry.as_mut() {
+            entry.wait_object = crate::offcpu::classify_wait(
+                &entry.name,
+                entry.raw_arg1.unwrap_or(0),
+                entry.raw_arg2.unwrap_or(0),
+                |fd| crate::offcpu::fd_path(child, fd),
+            );
+        }
+    }
+
+    Ok(entry)
+}
+
+/// Unwind and symbolize the full call stack at syscall entry (Sprint 50)
+///
+/// Shared libraries are mapped lazily by the dynamic loader, so the cached
+/// memory maps are refreshed when the syscall site is not covered yet. The
+/// frame-pointer walk stops at the first address outside executable code.
+fn capture_call_stack(
+    child: Pid,
+    dwarf_ctx: Option<&crate::dwarf::DwarfContext>,
+    maps: &mut Vec<crate::proc_maps::MemoryMapping>,
+) -> Vec<crate::stack_unwind::ResolvedFrame> {
+    let Ok(mut frames) = crate::stack_unwind::unwind_stack(child) else {
+        return Vec::new();
+    };
+
+    let site = frames.first().map(|frame| frame.rip).unwrap_or(0);
+    if crate::proc_maps::find_mapping(maps, site).is_none() {
+        if let Ok(fresh) = crate::proc_maps::read_maps(child) {
+            *maps = fresh;
+        }
+    }
+
+    if let Some(end) = frames.iter().skip(1).position(|frame| {
+        !crate::proc_maps::find_mapping(maps, frame.rip).is_some_and(|m| m.is_executable())
+    }) {
+        frames.truncate(end + 1);
+    }
+
+    crate::stack_unwind::resolve_frames(&frames, dwarf_ctx, maps)
 }
 
 /// Process syscall exit event
@@ -1004,6 +1323,18 @@ fn print_summaries(tracers: Tracers, timing_mode: bool, exit_code: i32, analysis
         decision_tracer, // Sprint 26: Now used for decision trace output
         #[cfg(feature = "otlp")]
         mut otlp_exporter, // Sprint 30: OTLP exporter
+        flamegraph,      // Sprint 50: Flamegraph output
+        pprof,           // Sprint 51: pprof output
+        off_cpu,         // Sprint 52: Off-CPU analysis
+        recorder,        // Sprint 55: Trace recording
+        clusters,        // Sprint 58: Cluster summary
+        sequence_monitor, // Sprint 59: Sequence model report
+        file_audit,      // Sprint 61: File access audit
+        connections,     // Sprint 63: Connection table
+        hotspots,        // Sprint 64: Time attribution
+        process_tree,    // Sprint 71: Critical path
+        locks,           // Sprint 73: Lock contention
+        anti_patterns,   // Sprint 74: Syscall anti-patterns
     } = tracers;
 
     // Sprint 31: Export decision traces to OTLP (before ending root span)
@@ -1046,8 +1377,16 @@ fn print_summaries(tracers: Tracers, timing_mode: bool, exit_code: i32, analysis
         exporter.shutdown();
     }
 
+    // Sprint 64: Attribute the unified trace's syscall time to clusters
+    let hotspot_report = hotspots.map(|tracker| tracker.finish(exit_code));
+    let hotspots_in_report = json_output.is_some() || html_output.is_some();
+
     // Print JSON output if in JSON mode
     if let Some(mut output) = json_output {
+        // Sprint 64: Time attribution and hotspots
+        if let Some(report) = &hotspot_report {
+            output.set_hotspot_analysis(report);
+        }
         // Add ML analysis to JSON if enabled
         if analysis.ml_anomaly {
             if let Some(report) =
@@ -1099,18 +1438,165 @@ fn print_summaries(tracers: Tracers, timing_mode: bool, exit_code: i32, analysis
     }
 
     // Print HTML output if in HTML mode
-    if let Some(output) = html_output {
+    // Sprint 63: The connection table goes into the report instead of stderr
+    if let Some(mut output) = html_output {
+        if let Some(table) = connections.as_ref() {
+            output.set_connections(table.connections().to_vec(), table.last_us());
+        }
+        // Sprint 64: Stacked bar of time per cluster
+        if let Some(report) = hotspot_report.clone() {
+            output.set_time_attribution(report);
+        }
         print!("{}", output.to_html(stats_tracker.as_ref()));
+    } else if let Some(table) = connections.as_ref().filter(|table| !table.is_empty()) {
+        eprint!("{}", table.report());
+    }
+
+    // Sprint 58: Per-cluster summary after the -c table
+    if let Some(tracker) = clusters {
+        eprint!("{}", tracker.report());
+    }
+
+    // Sprint 64: Hotspots (JSON and HTML reports include them instead)
+    if let Some(report) = hotspot_report.filter(|_| !hotspots_in_report) {
+        eprint!("{}", report.to_text());
+    }
+
+    // Sprint 71: Longest dependency chain through the traced processes
+    if let Some(tracker) = process_tree {
+        match tracker.finish() {
+            Ok(report) => eprint!("{}", report.to_text()),
+            Err(e) => eprintln!("[renacer: Critical path analysis failed: {:#}]", e),
+        }
     }
 
     // Print profiling and tracing summaries
     print_optional_summaries(profiling_ctx, function_profiler, anomaly_detector);
 
+    // Sprint 59: Windows outside the learned sequence model
+    if let Some(monitor) = sequence_monitor {
+        eprint!("{}", monitor.report());
+    }
+
     // Print analysis reports (HPU, ML)
     print_analysis_summaries(&stats_tracker, analysis);
 
     // Sprint 26: Print decision trace summary
     print_decision_trace_summary(decision_tracer);
+
+    // Sprint 50: Write flamegraph files
+    write_flamegraph_outputs(flamegraph);
+
+    // Sprint 51: Write pprof profile
+    write_pprof_output(pprof);
+
+    // Sprint 52: Off-CPU report and folded stacks
+    print_off_cpu_report(off_cpu);
+
+    // Sprint 73: Contended locks
+    if let Some(analyzer) = locks {
+        eprint!("{}", analyzer.report());
+    }
+
+    // Sprint 74: Syscall anti-patterns
+    if let Some(detector) = anti_patterns {
+        eprint!("{}", detector.report());
+    }
+
+    // Sprint 61: File access audit tree and JSON report
+    print_file_audit(file_audit);
+
+    // Sprint 55: Flush the recorded trace
+    finish_trace_recording(recorder);
+}
+
+/// Sprint 61: Print the file access tree and write its JSON report
+fn print_file_audit(file_audit: Option<crate::file_audit::FileAudit>) {
+    let Some(audit) = file_audit else {
+        return;
+    };
+
+    if audit.config().text {
+        eprint!("{}", audit.to_text());
+    }
+    match audit.write_json() {
+        Ok(Some(path)) => eprintln!("[renacer: File access audit written to {}]", path.display()),
+        Ok(None) => {}
+        Err(e) => eprintln!("[renacer: Failed to write file access audit: {:#}]", e),
+    }
+}
+
+/// Sprint 52: Print the off-CPU report and write its folded stacks
+fn print_off_cpu_report(off_cpu: Option<crate::offcpu::OffCpuAnalyzer>) {
+    let Some(analyzer) = off_cpu else {
+        return;
+    };
+
+    eprint!("{}", analyzer.report());
+    match analyzer.write_folded() {
+        Ok(Some(path)) => eprintln!(
+            "[renacer: Off-CPU stacks written to {} ({} unique stacks)]",
+            path.display(),
+            analyzer.folded().len()
+        ),
+        Ok(None) => {}
+        Err(e) => eprintln!("[renacer: Failed to write off-CPU stacks: {:#}]", e),
+    }
+}
+
+/// Sprint 51: Write the gzip-compressed pprof profile collected during tracing
+fn write_pprof_output(pprof: Option<crate::pprof::PprofCollector>) {
+    let Some(collector) = pprof else {
+        return;
+    };
+
+    let path = collector.path().clone();
+    let samples = collector.sample_count();
+    match collector.write() {
+        Ok(()) => eprintln!(
+            "[renacer: pprof profile written to {} ({} unique stacks)]",
+            path.display(),
+            samples
+        ),
+        Err(e) => eprintln!("[renacer: Failed to write pprof profile: {:#}]", e),
+    }
+}
+
+/// Sprint 55: Flush the trace file written by `renacer record`
+fn finish_trace_recording(recorder: Option<crate::trace_file::TraceRecorder>) {
+    let Some(recorder) = recorder else {
+        return;
+    };
+
+    let path = recorder.path().to_path_buf();
+    match recorder.finish() {
+        Ok(events) => eprintln!(
+            "[renacer: trace recorded to {} ({} events)]",
+            path.display(),
+            events
+        ),
+        Err(e) => eprintln!("[renacer: Failed to write trace: {:#}]", e),
+    }
+}
+
+/// Sprint 50: Write flamegraph SVG / folded stacks collected during tracing
+fn write_flamegraph_outputs(flamegraph: Option<crate::flamegraph::FlamegraphCollector>) {
+    let Some(collector) = flamegraph else {
+        return;
+    };
+
+    match collector.write_outputs() {
+        Ok(()) => {
+            for path in collector.output_paths() {
+                eprintln!(
+                    "[renacer: Flamegraph written to {} ({} unique stacks)]",
+                    path.display(),
+                    collector.stacks().len()
+                );
+            }
+        }
+        Err(e) => eprintln!("[renacer: Failed to write flamegraph: {:#}]", e),
+    }
 }
 
 /// Per-process state for multi-process tracing
@@ -1121,6 +1607,9 @@ struct ProcessState {
     syscall_entry_time: Option<std::time::Instant>,
     dwarf_ctx: Option<crate::dwarf::DwarfContext>,
     dwarf_loaded: bool,
+    maps: Vec<crate::proc_maps::MemoryMapping>, // Sprint 50: For PIE relocation and frame modules
+    /// Stopped before its parent's fork/clone event; held until that event is handled
+    awaiting_spawn: bool,
 }
 
 impl ProcessState {
@@ -1131,7 +1620,37 @@ impl ProcessState {
             syscall_entry_time: None,
             dwarf_ctx: None,
             dwarf_loaded: false,
+            maps: Vec::new(),
+            awaiting_spawn: false,
+        }
+    }
+}
+
+/// Resume held children whose creator is no longer traced.
+///
+/// A parent killed outright never reports its fork/clone event, so its
+/// children must not stay stopped waiting for it. A thread's creator is taken
+/// to be its thread group leader.
+fn release_orphaned_children(processes: &mut std::collections::HashMap<Pid, ProcessState>) {
+    use crate::proc_maps::{parent_pid, thread_group};
+
+    let orphaned: Vec<Pid> = processes
+        .iter()
+        .filter(|(_, state)| state.awaiting_spawn)
+        .map(|(pid, _)| *pid)
+        .filter(|pid| {
+            let creator = match thread_group(pid.as_raw()) {
+                Some(tgid) if tgid != pid.as_raw() => Some(tgid),
+                _ => parent_pid(pid.as_raw()),
+            };
+            creator.is_none_or(|creator| !processes.contains_key(&Pid::from_raw(creator)))
+        })
+        .collect();
+    for pid in orphaned {
+        if let Some(state) = processes.get_mut(&pid) {
+            state.awaiting_spawn = false;
         }
+        ptrace::syscall(pid, None).ok();
     }
 }
 
@@ -1142,10 +1661,23 @@ fn handle_traced_process_status(
     main_pid: Pid,
     main_exit_code: &mut i32,
     config: &TracerConfig,
+    recorder: Option<&mut crate::trace_file::TraceRecorder>,
 ) -> Result<Option<Pid>> {
+    use crate::trace_file::TraceEvent;
+
     match status {
         WaitStatus::Exited(p, code) => {
+            // Sprint 55: Record process exit
+            if let Some(recorder) = recorder {
+                recorder.record(TraceEvent::Exit {
+                    timestamp_us: recorder.elapsed_us(),
+                    pid: p.as_raw(),
+                    code: Some(code),
+                    signal: None,
+                })?;
+            }
             processes.remove(&p);
+            release_orphaned_children(processes);
             if p == main_pid {
                 *main_exit_code = code;
             }
@@ -1153,7 +1685,16 @@ fn handle_traced_process_status(
         }
         WaitStatus::Signaled(p, sig, _) => {
             eprintln!("Process {} killed by signal: {:?}", p, sig);
+            if let Some(recorder) = recorder {
+                recorder.record(TraceEvent::Exit {
+                    timestamp_us: recorder.elapsed_us(),
+                    pid: p.as_raw(),
+                    code: None,
+                    signal: Some(sig as i32),
+                })?;
+            }
             processes.remove(&p);
+            release_orphaned_children(processes);
             if p == main_pid {
                 *main_exit_code = 128 + sig as i32;
             }
@@ -1161,10 +1702,42 @@ fn handle_traced_process_status(
         }
         WaitStatus::PtraceSyscall(p) => Ok(Some(p)),
         WaitStatus::PtraceEvent(p, _sig, event) => {
-            handle_ptrace_event(p, event, processes, config)?;
+            handle_ptrace_event(p, event, processes, config, recorder)?;
             ptrace::syscall(p, None).context("Failed to PTRACE_SYSCALL after event")?;
             Ok(None)
         }
+        // A new child's first stop can be reported before its parent's
+        // fork/clone event. Waiting for it again in handle_ptrace_event would
+        // block until the child stops once more, which never happens if it
+        // needs a lock held by a tracee that is still stopped. Adopt it but
+        // leave it stopped: the parent is already in its event stop, and
+        // handle_ptrace_event resumes the child once its spawn is recorded.
+        WaitStatus::Stopped(p, nix::sys::signal::Signal::SIGSTOP)
+            if !processes.contains_key(&p) =>
+        {
+            let mut state = ProcessState::new();
+            state.awaiting_spawn = true;
+            processes.insert(p, state);
+            Ok(None)
+        }
+        WaitStatus::Stopped(p, sig) => {
+            // Sprint 55: Record signal-delivery stops
+            if let Some(recorder) = recorder {
+                recorder.record(TraceEvent::Signal {
+                    timestamp_us: recorder.elapsed_us(),
+                    pid: p.as_raw(),
+                    signal: sig as i32,
+                })?;
+            }
+            // Sprint 71: Pass signals on, or a shell's `wait` never sees SIGCHLD.
+            // SIGTRAP follows exec and SIGSTOP starts new children: both are ptrace's.
+            let deliver = match sig {
+                nix::sys::signal::Signal::SIGTRAP | nix::sys::signal::Signal::SIGSTOP => None,
+                sig => Some(sig),
+            };
+            ptrace::syscall(p, deliver).ok();
+            Ok(None)
+        }
         _ => {
             if let Some(p) = status.pid() {
                 ptrace::syscall(p, None).ok();
@@ -1190,30 +1763,29 @@ fn process_syscall_for_pid(
     };
 
     // Load DWARF context on first syscall if needed
-    if config.enable_source && !state.dwarf_loaded {
+    // Sprint 50: Flamegraphs (and Sprint 51 pprof profiles) need symbols even without --source
+    if (config.enable_source || config.needs_call_stacks()) && !state.dwarf_loaded {
         state.dwarf_loaded = true;
-        state.dwarf_ctx = load_dwarf_context(pid);
+        state.maps = crate::proc_maps::read_maps(pid).unwrap_or_default();
+        state.dwarf_ctx = load_dwarf_context(pid, &state.maps);
     }
 
     // Handle syscall entry/exit
-    handle_syscall_event(
-        pid,
-        &mut state.in_syscall,
-        &mut state.current_syscall_entry,
-        &mut state.syscall_entry_time,
-        state.dwarf_ctx.as_ref(),
-        config,
-        tracers,
-    )?;
+    handle_syscall_event(pid, state, config, tracers)?;
 
     ptrace::syscall(pid, None).context("Failed to PTRACE_SYSCALL")
 }
 
 /// Trace a child process, filtering syscalls based on filter
-fn trace_child(child: Pid, config: TracerConfig) -> Result<i32> {
+fn trace_child(
+    child: Pid,
+    config: TracerConfig,
+    recorder: Option<crate::trace_file::TraceRecorder>,
+) -> Result<i32> {
     info!(pid = %child, "starting trace_child");
 
     let mut tracers = initialize_tracers(&config);
+    tracers.recorder = recorder; // Sprint 55
     trace!("tracers initialized");
 
     // Sprint 30: Start root span for OTLP exporter
@@ -1228,10 +1800,25 @@ fn trace_child(child: Pid, config: TracerConfig) -> Result<i32> {
         exporter.start_root_span(&program_name, child.as_raw());
     }
 
+    // Sprint 64: Root process span of the unified trace
+    if let Some(tracker) = tracers.hotspots.as_mut() {
+        let program = read_cmdline(child).into_iter().next();
+        tracker.start(
+            child.as_raw(),
+            program.unwrap_or_else(|| format!("pid:{}", child)),
+        );
+    }
+
     trace!("calling setup_ptrace_options");
     setup_ptrace_options(child, config.follow_forks)?;
     trace!("ptrace options set successfully");
 
+    // Sprint 71: Root of the process tree (the child has exec'd by its first stop)
+    if let Some(tracker) = tracers.process_tree.as_mut() {
+        let program = read_cmdline(child).into_iter().next();
+        tracker.start(child.as_raw(), &program.unwrap_or_default());
+    }
+
     use std::collections::HashMap;
     let mut processes: HashMap<Pid, ProcessState> = HashMap::new();
     processes.insert(child, ProcessState::new());
@@ -1269,6 +1856,7 @@ fn trace_child(child: Pid, config: TracerConfig) -> Result<i32> {
             main_pid,
             &mut main_exit_code,
             &config,
+            tracers.recorder.as_mut(),
         )? {
             Some(p) => {
                 trace!(pid = %p, "handle_traced_process_status returned pid");
@@ -1291,40 +1879,273 @@ fn trace_child(child: Pid, config: TracerConfig) -> Result<i32> {
         tracers,
         config.timing_mode,
         main_exit_code,
-        &AnalysisConfig {
-            stats_extended: config.stats_extended,
-            anomaly_threshold: config.anomaly_threshold,
-            hpu_analysis: config.hpu_analysis,
-            hpu_cpu_only: config.hpu_cpu_only,
-            ml_anomaly: config.ml_anomaly,
-            ml_clusters: config.ml_clusters,
-            ml_compare: config.ml_compare,
-            ml_outliers: config.ml_outliers, // Sprint 22
-            ml_outlier_threshold: config.ml_outlier_threshold, // Sprint 22
-            ml_outlier_trees: config.ml_outlier_trees, // Sprint 22
-            dl_anomaly: config.dl_anomaly,   // Sprint 23
-            dl_threshold: config.dl_threshold, // Sprint 23
-            dl_hidden_size: config.dl_hidden_size, // Sprint 23
-            dl_epochs: config.dl_epochs,     // Sprint 23
-            explain: config.explain,         // Sprint 22/23
-        },
+        &analysis_config(&config),
     );
     std::process::exit(main_exit_code);
 }
 
+/// Replay a recorded trace through the output and analysis modes
+///
+/// Sprint 55: `renacer replay FILE` feeds recorded syscalls to the same
+/// tracers a live run uses, so any output format or analysis can be
+/// produced offline from a single capture.
+pub fn replay_trace(path: &str, config: TracerConfig) -> Result<()> {
+    use crate::trace_file::{SpawnKind, TraceEvent};
+
+    let reader = crate::trace_file::TraceReader::open(path)?;
+    let header = reader.header().clone();
+    eprintln!(
+        "[renacer: Replaying {} (recorded from: {})]",
+        path,
+        header.command.join(" ")
+    );
+
+    let mut tracers = initialize_tracers(&config);
+
+    // Sprint 30: Start root span for OTLP exporter
+    #[cfg(feature = "otlp")]
+    if let Some(ref mut exporter) = tracers.otlp_exporter {
+        let program_name = header
+            .command
+            .first()
+            .cloned()
+            .unwrap_or_else(|| format!("pid:{}", header.pid));
+        exporter.start_root_span(&program_name, header.pid);
+    }
+
+    // Sprint 61: The traced program's own exec happened before recording started
+    if let (Some(audit), Some(program)) = (tracers.file_audit.as_mut(), header.command.first()) {
+        audit.set_exe(header.pid, program.clone());
+    }
+
+    // Sprint 64: Root process span of the unified trace
+    if let Some(tracker) = tracers.hotspots.as_mut() {
+        let program = header.command.first().cloned();
+        tracker.start(
+            header.pid,
+            program.unwrap_or_else(|| format!("pid:{}", header.pid)),
+        );
+    }
+
+    // Sprint 71: Root of the process tree
+    if let Some(tracker) = tracers.process_tree.as_mut() {
+        let program = header.command.first().cloned();
+        tracker.start(header.pid, &program.unwrap_or_default());
+    }
+
+    let mut main_exit_code = 0;
+    for event in reader {
+        match event? {
+            TraceEvent::Syscall(record) => replay_syscall(*record, &config, &mut tracers),
+            TraceEvent::Mappings { pid, maps } => {
+                if let Some(collector) = tracers.pprof.as_mut() {
+                    collector.add_mappings(pid, &maps);
+                }
+            }
+            TraceEvent::Spawn {
+                parent, pid, kind, ..
+            } => {
+                eprintln!("[renacer: Process {} forked child {}]", parent, pid);
+                // Sprint 73: Cloned threads share their parent's futex words
+                if let (SpawnKind::Clone, Some(analyzer)) = (kind, tracers.locks.as_mut()) {
+                    analyzer.thread_spawned(parent, pid);
+                }
+            }
+            TraceEvent::Exit {
+                pid, code, signal, ..
+            } => {
+                if let Some(sig) = signal {
+                    match nix::sys::signal::Signal::try_from(sig) {
+                        Ok(sig) => eprintln!("Process {} killed by signal: {:?}", pid, sig),
+                        Err(_) => eprintln!("Process {} killed by signal: {}", pid, sig),
+                    }
+                }
+                if pid == header.pid {
+                    main_exit_code = code.unwrap_or(128 + signal.unwrap_or(0));
+                }
+            }
+            // Sprint 71: Steps of the critical path are named after the exec'd program
+            TraceEvent::Exec { pid, program, .. } => {
+                if let Some(tracker) = tracers.process_tree.as_mut() {
+                    tracker.exec(pid, &program);
+                }
+            }
+            TraceEvent::Signal { .. } | TraceEvent::Memory(_) => {}
+        }
+    }
+
+    print_summaries(
+        tracers,
+        config.timing_mode,
+        main_exit_code,
+        &analysis_config(&config),
+    );
+    Ok(())
+}
+
+/// Sprint 55: Feed one recorded syscall through the tracers
+fn replay_syscall(
+    record: crate::trace_file::SyscallRecord,
+    config: &TracerConfig,
+    tracers: &mut Tracers,
+) {
+    if !config.filter.should_trace(&record.name) {
+        return;
+    }
+
+    let structured_output = tracers.json_output.is_some()
+        || tracers.csv_output.is_some()
+        || tracers.csv_stats_output.is_some()
+        || tracers.html_output.is_some();
+
+    // Sprint 73: Recordings don't say which memory is shared, so futex words
+    // stay per process
+    let lock = tracers
+        .locks
+        .as_mut()
+        .filter(|_| record.name == "futex")
+        .map(|analyzer| {
+            let tgid = analyzer.thread_group(record.pid, |_| None);
+            crate::locks::LockKey::new(tgid, record.raw_args[0], record.raw_args[1], false)
+        });
+
+    let entry = SyscallEntry {
+        name: record.name,
+        number: record.number,
+        args: record.args,
+        // Source locations are always recorded; like a live run, only show them with -s
+        source: record.source.filter(|_| config.enable_source),
+        function_name: record.function_name,
+        caller_name: record.caller_name,
+        raw_arg1: Some(record.raw_args[0]),
+        raw_arg2: Some(record.raw_args[1]),
+        raw_arg3: Some(record.raw_args[2]),
+        stack: record.stack,
+        wait_object: record.wait_object,
+        lock,
+    };
+
+    if !config.statistics_mode && !structured_output {
+        print_replayed_syscall_entry(&entry, config.transpiler_map.as_ref());
+    }
+
+    // Sprint 58: The recorded fd table is rebuilt from open/dup/close; the
+    // recording process is gone, so /proc can't fill the gaps
+    if let Some(tracker) = tracers.clusters.as_mut() {
+        let pid = Pid::from_raw(record.pid);
+        record_cluster_syscall(
+            tracker,
+            pid,
+            &entry,
+            record.result,
+            record.duration_us,
+            |_| None,
+        );
+    }
+
+    // Sprint 61: Likewise, cwd and fds come only from the recorded syscalls
+    if let Some(audit) = tracers.file_audit.as_mut() {
+        record_file_access(
+            audit,
+            Pid::from_raw(record.pid),
+            &entry,
+            record.result,
+            |_, _| None,
+        );
+    }
+
+    // Sprint 63: Connection lifetimes use the recorded timestamps
+    if let Some(table) = tracers.connections.as_mut() {
+        record_connection(
+            table,
+            Pid::from_raw(record.pid),
+            &entry,
+            record.result,
+            record.timestamp_us + record.duration_us,
+        );
+    }
+
+    // Sprint 64: Spans keep the recorded timing; fds are only known from the trace
+    if let Some(tracker) = tracers.hotspots.as_mut() {
+        tracker.record(
+            record.pid,
+            &entry.name,
+            &entry.args,
+            record.raw_args,
+            record.result,
+            record.timestamp_us * 1000,
+            record.duration_us * 1000,
+            |_| None,
+        );
+    }
+
+    // Sprint 71: Syscall spans of the process tree, at their recorded times
+    if let Some(tracker) = tracers.process_tree.as_mut() {
+        tracker.record_syscall(&crate::process_tree::SyscallEvent {
+            args: &entry.args,
+            raw_args: record.raw_args,
+            wait_object: entry.wait_object.as_ref(),
+            ..crate::process_tree::SyscallEvent::new(
+                record.pid,
+                &entry.name,
+                record.result,
+                record.timestamp_us * 1000,
+                record.duration_us * 1000,
+            )
+        });
+    }
+
+    record_syscall_exit(
+        Pid::from_raw(record.pid),
+        &Some(entry),
+        tracers,
+        record.result,
+        config.timing_mode,
+        record.duration_us,
+        record.stderr.as_deref(),
+    );
+}
+
+/// Analysis settings used when printing summaries
+fn analysis_config(config: &TracerConfig) -> AnalysisConfig {
+    AnalysisConfig {
+        stats_extended: config.stats_extended,
+        anomaly_threshold: config.anomaly_threshold,
+        hpu_analysis: config.hpu_analysis,
+        hpu_cpu_only: config.hpu_cpu_only,
+        ml_anomaly: config.ml_anomaly,
+        ml_clusters: config.ml_clusters,
+        ml_compare: config.ml_compare,
+        ml_outliers: config.ml_outliers, // Sprint 22
+        ml_outlier_threshold: config.ml_outlier_threshold, // Sprint 22
+        ml_outlier_trees: config.ml_outlier_trees, // Sprint 22
+        dl_anomaly: config.dl_anomaly,   // Sprint 23
+        dl_threshold: config.dl_threshold, // Sprint 23
+        dl_hidden_size: config.dl_hidden_size, // Sprint 23
+        dl_epochs: config.dl_epochs,     // Sprint 23
+        explain: config.explain,         // Sprint 22/23
+    }
+}
+
 /// Syscall entry data for JSON output
 #[derive(Debug)]
 struct SyscallEntry {
     name: String,
+    number: i64, // Sprint 55: Kept in recorded traces ("unknown" syscalls)
     args: Vec<String>,
-    source: Option<crate::json_output::JsonSourceLocation>,
+    source: Option<crate::dwarf::SourceLocation>,
     function_name: Option<String>,
     caller_name: Option<String>,
     // Sprint 26: Raw args for decision trace capture (write syscall interception)
     raw_arg1: Option<u64>,
     raw_arg2: Option<u64>,
-    #[allow(dead_code)] // May be used in future for more complex decision trace patterns
     raw_arg3: Option<u64>,
+    // Sprint 50: Full call stack (innermost first) for flamegraph output
+    stack: Vec<crate::stack_unwind::ResolvedFrame>,
+    // Sprint 52: What a blocking syscall waits on (off-CPU analysis)
+    wait_object: Option<crate::offcpu::WaitObject>,
+    // Sprint 73: The futex word a futex call is on (--locks)
+    lock: Option<crate::locks::LockKey>,
 }
 
 /// Find the user function that triggered a syscall by unwinding the stack
@@ -1383,29 +2204,66 @@ fn find_user_function_with_caller(
 }
 
 /// Format syscall arguments for JSON output
+///
+/// Sprint 55: The execve program path names Exec events in recorded traces.
+/// Sprint 61: Every path argument is decoded for the file access audit;
+/// renameat/renameat2 also get their fourth argument (the new path).
+/// Sprint 63: Network syscalls get socket constants and decoded addresses;
+/// `completed` is the result when called at syscall exit.
+/// Sprint 72: pipe/socketpair show the fd pair they created.
+/// Sprint 73: futex ops are named (`FUTEX_WAIT_PRIVATE`).
 fn format_syscall_args_for_json(
     child: Pid,
     name: &str,
-    arg1: u64,
-    arg2: u64,
-    arg3: u64,
+    raw_args: [u64; 6],
+    completed: Option<i64>,
 ) -> Vec<String> {
-    match name {
-        "openat" => {
-            let filename =
-                read_string(child, arg2 as usize).unwrap_or_else(|_| format!("{:#x}", arg2));
-            vec![
-                format!("{:#x}", arg1),
-                format!("\"{}\"", filename),
-                format!("{:#x}", arg3),
-            ]
-        }
-        _ => vec![
-            format!("{:#x}", arg1),
-            format!("{:#x}", arg2),
-            format!("{:#x}", arg3),
-        ],
-    }
+    if let Some(args) = crate::network::format_args(name, raw_args, completed, |addr, len| {
+        read_bytes(child, addr as usize, len)
+    }) {
+        return args;
+    }
+
+    if name == "futex" {
+        return crate::locks::format_futex_args(raw_args);
+    }
+
+    // Sprint 72: A completed pipe/socketpair shows the fds it created
+    let pair = crate::process_tree::fd_pair_arg(name).filter(|_| completed == Some(0));
+    if let Some(index) = pair {
+        let mut args: Vec<String> = raw_args[..3.max(index + 1)]
+            .iter()
+            .map(|arg| format!("{:#x}", arg))
+            .collect();
+        if let Some(bytes) = read_bytes(child, raw_args[index] as usize, 8) {
+            let fd = |at: usize| i32::from_ne_bytes(bytes[at..at + 4].try_into().unwrap());
+            args[index] = crate::process_tree::format_fd_pair([fd(0), fd(4)], |fd| {
+                crate::offcpu::fd_path(child, fd)
+            });
+        }
+        return args;
+    }
+
+    let paths = crate::file_audit::path_arg_indices(name);
+    let count = if paths.contains(&3) { 4 } else { 3 };
+    raw_args[..count]
+        .iter()
+        .enumerate()
+        .map(|(index, &arg)| {
+            if paths.contains(&index) {
+                let path =
+                    read_string(child, arg as usize).unwrap_or_else(|_| format!("{:#x}", arg));
+                format!("\"{}\"", path)
+            } else {
+                format!("{:#x}", arg)
+            }
+        })
+        .collect()
+}
+
+/// Argument registers of a syscall (rdi, rsi, rdx, r10, r8, r9 on x86_64)
+fn syscall_args(regs: &nix::libc::user_regs_struct) -> [u64; 6] {
+    [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9]
 }
 
 /// Print syscall entry with optional source location
@@ -1420,20 +2278,7 @@ fn print_syscall_entry(
     source_info: &Option<crate::dwarf::SourceLocation>,
     transpiler_map: Option<&crate::transpiler_map::TranspilerMap>,
 ) {
-    // Print source location if available
-    if let Some(src) = source_info {
-        // Try to map to transpiler source first
-        if let Some(transpiled_source) = map_to_transpiler_source(src, transpiler_map) {
-            // Show both Rust and original source
-            print!("{} ", transpiled_source);
-        } else {
-            // Show just Rust source from DWARF
-            print!("{}:{} ", src.file, src.line);
-            if let Some(func) = &src.function {
-                print!("{} ", func);
-            }
-        }
-    }
+    print_source_prefix(source_info, transpiler_map);
 
     // Print syscall with arguments
     match name {
@@ -1455,6 +2300,40 @@ fn print_syscall_entry(
     std::io::Write::flush(&mut std::io::stdout()).ok();
 }
 
+/// Sprint 55: Print a recorded syscall entry in the live text format
+fn print_replayed_syscall_entry(
+    entry: &SyscallEntry,
+    transpiler_map: Option<&crate::transpiler_map::TranspilerMap>,
+) {
+    print_source_prefix(&entry.source, transpiler_map);
+
+    if entry.name == "unknown" {
+        print!("syscall_{}({}) = ", entry.number, entry.args.join(", "));
+    } else {
+        print!("{}({}) = ", entry.name, entry.args.join(", "));
+    }
+}
+
+/// Print the source location that precedes a syscall in text output
+fn print_source_prefix(
+    source_info: &Option<crate::dwarf::SourceLocation>,
+    transpiler_map: Option<&crate::transpiler_map::TranspilerMap>,
+) {
+    if let Some(src) = source_info {
+        // Try to map to transpiler source first
+        if let Some(transpiled_source) = map_to_transpiler_source(src, transpiler_map) {
+            // Show both Rust and original source
+            print!("{} ", transpiled_source);
+        } else {
+            // Show just Rust source from DWARF
+            print!("{}:{} ", src.file, src.line);
+            if let Some(func) = &src.function {
+                print!("{} ", func);
+            }
+        }
+    }
+}
+
 /// Extract function name and caller from DWARF context
 fn extract_function_names(
     child: Pid,
@@ -1519,14 +2398,26 @@ fn handle_syscall_entry(
     };
 
     // Format arguments for structured output modes (JSON, CSV, HTML) if needed
-    let args = if structured_output {
-        format_syscall_args_for_json(child, name, arg1, arg2, arg3)
+    // Sprint 63: Network arguments are always decoded (text output, connection table)
+    // Sprint 73: So are futex ops
+    let network = crate::network::is_network_syscall(name);
+    let decoded = network || name == "futex";
+    let args = if structured_output || decoded {
+        format_syscall_args_for_json(child, name, syscall_args(&regs), None)
     } else {
         Vec::new()
     };
 
     // Print syscall entry if not in statistics or structured output mode
-    if !statistics_mode && !structured_output {
+    let text_output = !statistics_mode && !structured_output;
+    if text_output && decoded {
+        // Sprint 63: Syscalls returning an address are printed at exit, once it is known
+        if !crate::network::address_at_exit(name) {
+            print_source_prefix(&source_info, transpiler_map);
+            print!("{}({}) = ", name, args.join(", "));
+            std::io::Write::flush(&mut std::io::stdout()).ok();
+        }
+    } else if text_output {
         print_syscall_entry(
             child,
             name,
@@ -1543,25 +2434,21 @@ fn handle_syscall_entry(
     let (function_name, caller_name) =
         extract_function_names(child, dwarf_ctx, &source_info, function_profiling_enabled);
 
-    let json_source = source_info
-        .as_ref()
-        .map(|src| crate::json_output::JsonSourceLocation {
-            file: src.file.to_string(),
-            line: src.line,
-            function: src.function.clone().map(|s| s.to_string()),
-        });
-
     // Return syscall entry data
     Ok(Some(SyscallEntry {
         name: name.to_string(),
+        number: syscall_num,
         args,
-        source: json_source,
+        source: source_info,
         function_name,
         caller_name,
         // Sprint 26: Store raw args for decision trace capture
         raw_arg1: Some(arg1),
         raw_arg2: Some(arg2),
         raw_arg3: Some(arg3),
+        stack: Vec::new(),
+        wait_object: None,
+        lock: None,
     }))
 }
 
@@ -1575,14 +2462,15 @@ fn map_to_transpiler_source(
         // Extract line number from DWARF source location
         let rust_line = dwarf_source.line as usize;
 
-        // Look up in transpiler map
-        if let Some(mapping) = map.lookup_line(rust_line) {
+        // Sprint 53: Look up by (file, line, column) so v2 multi-file maps resolve
+        let column = dwarf_source.column.map(|c| c as usize);
+        if let Some(mapping) = map.lookup(&dwarf_source.file, rust_line, column) {
             // Format as "python_file:line in python_function"
             return Some(format!(
                 "{}:{} in {} [{}]",
-                map.source_file().display(),
-                mapping.python_line,
-                mapping.python_function,
+                map.file_pair(mapping).source_file,
+                mapping.source.line,
+                mapping.source_function,
                 map.source_language()
             ));
         }
@@ -1617,6 +2505,19 @@ fn read_string(child: Pid, addr: usize) -> Result<String> {
     Ok(String::from_utf8_lossy(&buf[..null_pos]).to_string())
 }
 
+/// Sprint 63: Read `len` bytes of tracee memory (socket addresses)
+fn read_bytes(child: Pid, addr: usize, len: usize) -> Option<Vec<u8>> {
+    use nix::sys::uio::{process_vm_readv, RemoteIoVec};
+    use std::io::IoSliceMut;
+
+    let mut buf = vec![0u8; len];
+    let mut local_iov = [IoSliceMut::new(&mut buf)];
+    let remote_iov = [RemoteIoVec { base: addr, len }];
+    let bytes_read = process_vm_readv(child, &mut local_iov, &remote_iov).ok()?;
+    buf.truncate(bytes_read);
+    Some(buf)
+}
+
 /// Record statistics for a syscall
 fn record_stats_for_syscall(
     syscall_entry: &Option<SyscallEntry>,
@@ -1649,11 +2550,22 @@ fn record_json_for_syscall(
             args: entry.args.clone(),
             result,
             duration_us: duration,
-            source: entry.source.clone(),
+            source: entry.source.as_ref().map(json_source_location),
         });
     }
 }
 
+/// Source location as reported in JSON output
+fn json_source_location(
+    src: &crate::dwarf::SourceLocation,
+) -> crate::json_output::JsonSourceLocation {
+    crate::json_output::JsonSourceLocation {
+        file: src.file.clone(),
+        line: src.line,
+        function: src.function.clone(),
+    }
+}
+
 /// Record CSV output for a syscall
 fn record_csv_for_syscall(
     syscall_entry: &Option<SyscallEntry>,
@@ -1746,6 +2658,17 @@ fn record_function_profiling(
     }
 }
 
+/// Sprint 50: Record the syscall's call stack for flamegraph output
+fn record_flamegraph_for_syscall(
+    syscall_entry: &Option<SyscallEntry>,
+    flamegraph: Option<&mut crate::flamegraph::FlamegraphCollector>,
+    duration_us: u64,
+) {
+    if let (Some(entry), Some(collector)) = (syscall_entry, flamegraph) {
+        collector.record(&entry.name, &entry.stack, duration_us);
+    }
+}
+
 /// Print syscall result
 fn print_syscall_result(result: i64, timing_mode: bool, duration_us: u64) {
     if timing_mode && duration_us > 0 {
@@ -1782,6 +2705,56 @@ fn handle_anomaly_detection(
     }
 }
 
+/// Sprint 59: Alert on the first occurrence of each window outside the model
+fn handle_sequence_anomaly(
+    child: Pid,
+    syscall_entry: &Option<SyscallEntry>,
+    monitor: Option<&mut crate::sequence::SequenceMonitor>,
+) {
+    let (Some(entry), Some(monitor)) = (syscall_entry, monitor) else {
+        return;
+    };
+    let Some(mismatch) = monitor.observe(child.as_raw(), &entry.name, || syscall_call_site(entry))
+    else {
+        return;
+    };
+    if mismatch.first_occurrence {
+        eprintln!(
+            "⚠️  SEQUENCE ANOMALY: {} not in model (pid {}, at {})",
+            mismatch.window.join(" → "),
+            mismatch.pid,
+            mismatch.call_site.as_deref().unwrap_or("unknown call site")
+        );
+    }
+}
+
+/// Sprint 59: Where a syscall was made, innermost user source location first
+fn syscall_call_site(entry: &SyscallEntry) -> Option<String> {
+    if let Some(source) = &entry.source {
+        return Some(match &source.function {
+            Some(function) => format!("{}:{} in {}", source.file, source.line, function),
+            None => format!("{}:{}", source.file, source.line),
+        });
+    }
+    if let Some(frame) = entry.stack.iter().find(|f| f.file.is_some()) {
+        return Some(format!(
+            "{}:{} in {}",
+            frame.file.as_deref().unwrap_or_default(),
+            frame.line.unwrap_or(0),
+            frame.label()
+        ));
+    }
+    if let Some(function) = &entry.function_name {
+        return Some(function.clone());
+    }
+    entry
+        .stack
+        .iter()
+        .find(|frame| frame.function.is_some())
+        .or(entry.stack.first())
+        .map(|frame| format!("{} (0x{:x})", frame.label(), frame.address))
+}
+
 /// Check if syscall result should be printed to stdout
 fn should_print_result(
     syscall_entry: &Option<SyscallEntry>,
@@ -1789,42 +2762,39 @@ fn should_print_result(
     in_json_mode: bool,
     in_csv_mode: bool,
     in_html_mode: bool,
+    in_record_mode: bool,
 ) -> bool {
-    syscall_entry.is_some() && !in_stats_mode && !in_json_mode && !in_csv_mode && !in_html_mode
+    syscall_entry.is_some()
+        && !in_stats_mode
+        && !in_json_mode
+        && !in_csv_mode
+        && !in_html_mode
+        && !in_record_mode
 }
 
-/// Sprint 26: Capture decision traces from write() syscalls to stderr
+/// Sprint 26: Read the text of a write() syscall to stderr
 ///
-/// Intercepts write(2, buffer, count) calls and parses [DECISION] and [RESULT] lines
-fn capture_decision_trace(
+/// Returns None unless this was a successful write(2, buffer, count).
+fn read_stderr_write(
     child: Pid,
     syscall_entry: &Option<SyscallEntry>,
-    decision_tracer: Option<&mut crate::decision_trace::DecisionTracer>,
     bytes_written: i64,
-) {
-    // Only process if decision tracing is enabled
-    let Some(tracer) = decision_tracer else {
-        return;
-    };
-
-    // Only process if we have a syscall entry
-    let Some(entry) = syscall_entry else {
-        return;
-    };
+) -> Option<String> {
+    let entry = syscall_entry.as_ref()?;
 
     // Only intercept write() syscalls
     if entry.name != "write" {
-        return;
+        return None;
     }
 
     // Check if writing to stderr (fd = 2)
     if entry.raw_arg1 != Some(2) {
-        return;
+        return None;
     };
 
     // Only process successful writes
     if bytes_written <= 0 {
-        return;
+        return None;
     }
 
     // Get buffer address and size
@@ -1844,11 +2814,24 @@ fn capture_decision_trace(
 
     // Try to read; silently ignore errors (child may have exited, etc.)
     if process_vm_readv(child, &mut local_iov, &remote_iov).is_err() {
-        return;
+        return None;
     }
 
     // Convert to string, replacing invalid UTF-8 with replacement character
-    let content = String::from_utf8_lossy(&buffer);
+    Some(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Sprint 26: Capture decision traces from write() syscalls to stderr
+///
+/// Parses [DECISION] and [RESULT] lines from text written to stderr
+fn capture_decision_trace(
+    stderr: Option<&str>,
+    decision_tracer: Option<&mut crate::decision_trace::DecisionTracer>,
+) {
+    // Only process if decision tracing is enabled and stderr was written
+    let (Some(content), Some(tracer)) = (stderr, decision_tracer) else {
+        return;
+    };
 
     // Parse each line through DecisionTracer
     for line in content.lines() {
@@ -1867,11 +2850,246 @@ fn handle_syscall_exit(
     let regs = ptrace::getregs(child).context("Failed to get registers")?;
     let result = regs.rax as i64;
 
+    // Sprint 26: Text written to stderr feeds the decision tracer
+    // Sprint 55: ...and is kept in recorded traces so replays can parse it too
+    let stderr = if tracers.decision_tracer.is_some() || tracers.recorder.is_some() {
+        read_stderr_write(child, syscall_entry, result)
+    } else {
+        None
+    };
+
+    // Sprint 55: Append the completed syscall to the trace file
+    if let (Some(entry), Some(recorder)) = (syscall_entry, tracers.recorder.as_mut()) {
+        record_trace_syscall(recorder, child, entry, result, duration_us, stderr.clone())?;
+    }
+
+    // Sprint 58: Unknown fds (inherited, or opened before attach) come from /proc
+    if let (Some(entry), Some(tracker)) = (syscall_entry, tracers.clusters.as_mut()) {
+        record_cluster_syscall(tracker, child, entry, result, duration_us, |fd| {
+            crate::offcpu::fd_path(child, fd)
+        });
+    }
+
+    // Sprint 61: The starting cwd, inherited fds and exe come from /proc
+    if let (Some(entry), Some(audit)) = (syscall_entry, tracers.file_audit.as_mut()) {
+        record_file_access(audit, child, entry, result, proc_lookup);
+    }
+
+    // Sprint 63: Socket endpoints and traffic
+    if let (Some(entry), Some(table)) = (syscall_entry, tracers.connections.as_mut()) {
+        let now_us = table.elapsed_us();
+        record_connection(table, child, entry, result, now_us);
+    }
+
+    // Sprint 64: The span started `duration_us` before now
+    if let (Some(entry), Some(tracker)) = (syscall_entry, tracers.hotspots.as_mut()) {
+        let duration_nanos = duration_us * 1000;
+        let timestamp_nanos = tracker.elapsed_nanos().saturating_sub(duration_nanos);
+        tracker.record(
+            child.as_raw(),
+            &entry.name,
+            &entry.args,
+            [
+                entry.raw_arg1.unwrap_or(0),
+                entry.raw_arg2.unwrap_or(0),
+                entry.raw_arg3.unwrap_or(0),
+            ],
+            result,
+            timestamp_nanos,
+            duration_nanos,
+            |fd| crate::offcpu::fd_path(child, fd),
+        );
+    }
+
+    // Sprint 71: Syscall spans of the process tree; a successful exec renames the process
+    if let (Some(entry), Some(tracker)) = (syscall_entry, tracers.process_tree.as_mut()) {
+        let duration_nanos = duration_us * 1000;
+        let timestamp_nanos = tracker.elapsed_nanos().saturating_sub(duration_nanos);
+        tracker.record_syscall(&crate::process_tree::SyscallEvent {
+            args: &entry.args,
+            raw_args: [
+                entry.raw_arg1.unwrap_or(0),
+                entry.raw_arg2.unwrap_or(0),
+                entry.raw_arg3.unwrap_or(0),
+            ],
+            wait_object: entry.wait_object.as_ref(),
+            ..crate::process_tree::SyscallEvent::new(
+                child.as_raw(),
+                &entry.name,
+                result,
+                timestamp_nanos,
+                duration_nanos,
+            )
+        });
+        if result == 0 && matches!(entry.name.as_str(), "execve" | "execveat") {
+            if let Some(program) = read_cmdline(child).into_iter().next() {
+                tracker.exec(child.as_raw(), &program);
+            }
+        }
+    }
+
+    record_syscall_exit(
+        child,
+        syscall_entry,
+        tracers,
+        result,
+        timing_mode,
+        duration_us,
+        stderr.as_deref(),
+    );
+
+    Ok(())
+}
+
+/// Sprint 58: Classify a completed syscall for the per-cluster summary
+fn record_cluster_syscall(
+    tracker: &mut crate::cluster::ClusterTracker,
+    child: Pid,
+    entry: &SyscallEntry,
+    result: i64,
+    duration_us: u64,
+    resolve_fd: impl Fn(i32) -> Option<String>,
+) {
+    tracker.record(
+        child.as_raw(),
+        &entry.name,
+        &entry.args,
+        [
+            entry.raw_arg1.unwrap_or(0),
+            entry.raw_arg2.unwrap_or(0),
+            entry.raw_arg3.unwrap_or(0),
+        ],
+        result,
+        duration_us,
+        resolve_fd,
+    );
+}
+
+/// Sprint 61: Feed a completed syscall to the file access audit
+fn record_file_access(
+    audit: &mut crate::file_audit::FileAudit,
+    child: Pid,
+    entry: &SyscallEntry,
+    result: i64,
+    resolve: impl Fn(i32, crate::file_audit::ProcLookup) -> Option<String>,
+) {
+    audit.record(
+        child.as_raw(),
+        &entry.name,
+        &entry.args,
+        [
+            entry.raw_arg1.unwrap_or(0),
+            entry.raw_arg2.unwrap_or(0),
+            entry.raw_arg3.unwrap_or(0),
+        ],
+        result,
+        resolve,
+    );
+}
+
+/// Sprint 63: Feed a completed syscall to the connection table
+fn record_connection(
+    table: &mut crate::network::ConnectionTable,
+    child: Pid,
+    entry: &SyscallEntry,
+    result: i64,
+    timestamp_us: u64,
+) {
+    table.record(
+        child.as_raw(),
+        &entry.name,
+        &entry.args,
+        [
+            entry.raw_arg1.unwrap_or(0),
+            entry.raw_arg2.unwrap_or(0),
+            entry.raw_arg3.unwrap_or(0),
+        ],
+        result,
+        timestamp_us,
+    );
+}
+
+/// Sprint 61: Read a traced process's cwd, exe or fd target from /proc
+fn proc_lookup(pid: i32, lookup: crate::file_audit::ProcLookup) -> Option<String> {
+    let link = match lookup {
+        crate::file_audit::ProcLookup::Cwd => "cwd",
+        crate::file_audit::ProcLookup::Exe => "exe",
+        crate::file_audit::ProcLookup::Fd(fd) => {
+            return crate::offcpu::fd_path(Pid::from_raw(pid), fd)
+        }
+    };
+    std::fs::read_link(format!("/proc/{}/{}", pid, link))
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+/// Sprint 55: Write a completed syscall (and a successful exec) to the trace file
+fn record_trace_syscall(
+    recorder: &mut crate::trace_file::TraceRecorder,
+    child: Pid,
+    entry: &SyscallEntry,
+    result: i64,
+    duration_us: u64,
+    stderr: Option<String>,
+) -> Result<()> {
+    let pid = child.as_raw();
+    let exit_us = recorder.elapsed_us();
+
+    recorder.record_syscall(crate::trace_file::SyscallRecord {
+        pid,
+        timestamp_us: exit_us.saturating_sub(duration_us),
+        duration_us,
+        number: entry.number,
+        name: entry.name.clone(),
+        args: entry.args.clone(),
+        raw_args: [
+            entry.raw_arg1.unwrap_or(0),
+            entry.raw_arg2.unwrap_or(0),
+            entry.raw_arg3.unwrap_or(0),
+        ],
+        result,
+        source: entry.source.clone(),
+        function_name: entry.function_name.clone(),
+        caller_name: entry.caller_name.clone(),
+        stack: entry.stack.clone(),
+        wait_object: entry.wait_object.clone(),
+        stderr,
+    })?;
+
+    let program_arg = match entry.name.as_str() {
+        "execve" => 0,
+        "execveat" => 1,
+        _ => return Ok(()),
+    };
+    if let (0, Some(program)) = (result, entry.args.get(program_arg)) {
+        recorder.record(crate::trace_file::TraceEvent::Exec {
+            timestamp_us: exit_us,
+            pid,
+            program: program.trim_matches('"').to_string(),
+        })?;
+    }
+    Ok(())
+}
+
+/// Feed a completed syscall to every enabled output and analysis
+///
+/// Sprint 55: Shared by live tracing and `renacer replay`, so it must not
+/// touch the tracee.
+fn record_syscall_exit(
+    child: Pid,
+    syscall_entry: &Option<SyscallEntry>,
+    tracers: &mut Tracers,
+    result: i64,
+    timing_mode: bool,
+    duration_us: u64,
+    stderr: Option<&str>,
+) {
     // Check modes before borrowing
     let in_stats_mode = tracers.stats_tracker.is_some();
     let in_json_mode = tracers.json_output.is_some();
     let in_csv_mode = tracers.csv_output.is_some() || tracers.csv_stats_output.is_some();
     let in_html_mode = tracers.html_output.is_some();
+    let in_record_mode = tracers.recorder.is_some();
 
     // Record statistics
     record_stats_for_syscall(
@@ -1909,12 +3127,7 @@ fn handle_syscall_exit(
     );
 
     // Sprint 26: Capture decision traces from stderr writes
-    capture_decision_trace(
-        child,
-        syscall_entry,
-        tracers.decision_tracer.as_mut(),
-        result,
-    );
+    capture_decision_trace(stderr, tracers.decision_tracer.as_mut());
 
     // Record CSV stats (we'll handle this in print_summaries)
     if let (Some(entry), Some(stats)) = (syscall_entry, tracers.csv_stats_output.as_mut()) {
@@ -1936,6 +3149,68 @@ fn handle_syscall_exit(
         duration_us,
     );
 
+    // Sprint 59: Syscall windows outside the learned model
+    handle_sequence_anomaly(child, syscall_entry, tracers.sequence_monitor.as_mut());
+
+    // Sprint 50: Flamegraph stacks
+    record_flamegraph_for_syscall(syscall_entry, tracers.flamegraph.as_mut(), duration_us);
+
+    // Sprint 51: pprof samples
+    if let (Some(entry), Some(collector)) = (syscall_entry, tracers.pprof.as_mut()) {
+        collector.record(child, &entry.name, &entry.stack, duration_us);
+    }
+
+    // Sprint 52: Off-CPU analysis
+    if let (Some(entry), Some(analyzer)) = (syscall_entry, tracers.off_cpu.as_mut()) {
+        analyzer.record(
+            child.as_raw(),
+            &entry.name,
+            entry.wait_object.as_ref(),
+            &entry.stack,
+            duration_us,
+        );
+    }
+
+    // Sprint 73: Lock contention
+    if let (Some(entry), Some(analyzer)) = (syscall_entry, tracers.locks.as_mut()) {
+        if let Some(key) = entry.lock {
+            analyzer.record(
+                child.as_raw(),
+                key,
+                entry.raw_arg2.unwrap_or(0),
+                result,
+                &entry.stack,
+                duration_us,
+            );
+        }
+    }
+
+    // Sprint 74: Syscall anti-patterns, attributed to the call site
+    if let (Some(entry), Some(detector)) = (syscall_entry, tracers.anti_patterns.as_mut()) {
+        let fd_path = match &entry.wait_object {
+            Some(crate::offcpu::WaitObject::Fd { path, .. }) => path.as_deref(),
+            _ => None,
+        };
+        detector.record(&crate::syscall_anti_patterns::Call {
+            pid: child.as_raw(),
+            name: &entry.name,
+            args: &entry.args,
+            raw_args: [
+                entry.raw_arg1.unwrap_or(0),
+                entry.raw_arg2.unwrap_or(0),
+                entry.raw_arg3.unwrap_or(0),
+            ],
+            result,
+            duration_us,
+            fd_path,
+            call_site: crate::check::format_call_site(
+                entry.source.as_ref(),
+                entry.function_name.as_ref(),
+                &entry.stack,
+            ),
+        });
+    }
+
     // Sprint 30: Record syscall to OTLP exporter
     #[cfg(feature = "otlp")]
     if let (Some(entry), Some(exporter)) = (syscall_entry, tracers.otlp_exporter.as_ref()) {
@@ -1962,11 +3237,10 @@ fn handle_syscall_exit(
         in_json_mode,
         in_csv_mode,
         in_html_mode,
+        in_record_mode,
     ) {
         print_syscall_result(result, timing_mode, duration_us);
     }
-
-    Ok(())
 }
 
 #[cfg(test)]
@@ -2008,6 +3282,19 @@ mod tests {
             otlp_service_name: "renacer".to_string(), // Sprint 30
             trace_parent: None,                       // Sprint 33
             chaos_config: None,                       // Sprint 47
+            flamegraph: None,                         // Sprint 50
+            pprof: None,                              // Sprint 51
+            off_cpu: None,                            // Sprint 52
+            record: None,                             // Sprint 55
+            record_stacks: false,                     // Sprint 55
+            clusters: None,                           // Sprint 58
+            sequence_model: None,                     // Sprint 59
+            file_audit: None,                         // Sprint 61
+            connections: false,                       // Sprint 63
+            hotspots: None,                           // Sprint 64
+            critical_path: false,                     // Sprint 71
+            locks: None,                              // Sprint 73
+            anti_patterns: false,                     // Sprint 74
         };
         let result = trace_command(&empty, config);
         assert!(result.is_err());
@@ -2050,6 +3337,19 @@ mod tests {
             otlp_service_name: "renacer".to_string(), // Sprint 30
             trace_parent: None,                       // Sprint 33
             chaos_config: None,                       // Sprint 47
+            flamegraph: None,                         // Sprint 50
+            pprof: None,                              // Sprint 51
+            off_cpu: None,                            // Sprint 52
+            record: None,                             // Sprint 55
+            record_stacks: false,                     // Sprint 55
+            clusters: None,                           // Sprint 58
+            sequence_model: None,                     // Sprint 59
+            file_audit: None,                         // Sprint 61
+            connections: false,                       // Sprint 63
+            hotspots: None,                           // Sprint 64
+            critical_path: false,                     // Sprint 71
+            locks: None,                              // Sprint 73
+            anti_patterns: false,                     // Sprint 74
         };
         let result = trace_command(&cmd, config);
         assert!(result.is_err());
@@ -2059,6 +3359,7 @@ mod tests {
     fn test_syscall_entry_creation() {
         let entry = SyscallEntry {
             name: "open".to_string(),
+            number: 0,
             args: vec!["arg1".to_string(), "arg2".to_string()],
             source: None,
             function_name: None,
@@ -2066,6 +3367,9 @@ mod tests {
             raw_arg1: Some(1),
             raw_arg2: Some(2),
             raw_arg3: Some(3),
+            stack: Vec::new(),
+            wait_object: None,
+            lock: None,
         };
         assert_eq!(entry.name, "open");
         assert_eq!(entry.args.len(), 2);
@@ -2075,13 +3379,15 @@ mod tests {
 
     #[test]
     fn test_syscall_entry_with_source() {
-        let source = crate::json_output::JsonSourceLocation {
+        let source = crate::dwarf::SourceLocation {
             file: "test.rs".to_string(),
             line: 42,
+            column: None,
             function: Some("main".to_string()),
         };
         let entry = SyscallEntry {
             name: "read".to_string(),
+            number: 0,
             args: vec![],
             source: Some(source),
             function_name: Some("main".to_string()),
@@ -2089,6 +3395,9 @@ mod tests {
             raw_arg1: Some(0),
             raw_arg2: Some(0),
             raw_arg3: Some(0),
+            stack: Vec::new(),
+            wait_object: None,
+            lock: None,
         };
         assert_eq!(entry.name, "read");
         assert!(entry.source.is_some());
@@ -2134,6 +3443,19 @@ mod tests {
             otlp_service_name: "renacer".to_string(), // Sprint 30
             trace_parent: None,                       // Sprint 33
             chaos_config: None,                       // Sprint 47
+            flamegraph: None,                         // Sprint 50
+            pprof: None,                              // Sprint 51
+            off_cpu: None,                            // Sprint 52
+            record: None,                             // Sprint 55
+            record_stacks: false,                     // Sprint 55
+            clusters: None,                           // Sprint 58
+            sequence_model: None,                     // Sprint 59
+            file_audit: None,                         // Sprint 61
+            connections: false,                       // Sprint 63
+            hotspots: None,                           // Sprint 64
+            critical_path: false,                     // Sprint 71
+            locks: None,                              // Sprint 73
+            anti_patterns: false,                     // Sprint 74
         };
         let result = attach_to_pid(999999, config);
         assert!(result.is_err());
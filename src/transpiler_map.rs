@@ -1,4 +1,4 @@
-// Transpiler Source Map Support (Sprint 24, extended Sprint 28)
+// Transpiler Source Map Support (Sprint 24, extended Sprint 28 and Sprint 53)
 //
 // Parse and manage source maps for transpiled code:
 // - Python→Rust (Depyler)
@@ -7,6 +7,37 @@
 // - Any other source language
 //
 // Enables mapping Rust line numbers/functions back to original source language
+//
+// Sprint 53: Version 2 maps cover many source/generated file pairs with
+// column ranges. Both directions are indexed with interval trees:
+//
+// ```json
+// {
+//   "version": 2,
+//   "source_language": "python",
+//   "files": [
+//     { "source_file": "pkg/util.py", "generated_file": "src/util.rs" }
+//   ],
+//   "mappings": [
+//     {
+//       "file": 0,
+//       "rust": { "line": 42, "column": 5, "end_line": 44, "end_column": 6 },
+//       "source": { "line": 17, "column": 1, "end_column": 30 },
+//       "rust_function": "parse",
+//       "source_function": "parse",
+//       "source_context": "return int(x)"
+//     }
+//   ],
+//   "function_map": { "parse": "parse" }
+// }
+// ```
+//
+// Omitted columns cover the whole line and an omitted `end_line` means the
+// range ends on its start line. Version 1 maps are still accepted and are
+// loaded as whole-line ranges of a single file pair.
+//
+// Sprint 54: Standard Source Map v3 files (VLQ `mappings`, index maps) are
+// detected automatically and decoded by `crate::sourcemap_v3`.
 
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
@@ -14,11 +45,15 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
-/// Source map version (currently only v1 supported)
-const SUPPORTED_VERSION: u32 = 1;
+/// Supported source map versions
+const SUPPORTED_VERSIONS: &[u32] = &[1, 2, 3];
 
 /// Complete transpiler source map
+///
+/// Serializes its public fields; the lookup indexes are rebuilt when it is
+/// deserialized.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "MapFields")]
 pub struct TranspilerMap {
     /// Source map format version
     pub version: u32,
@@ -26,17 +61,31 @@ pub struct TranspilerMap {
     /// Source language (e.g., "python", "typescript")
     pub source_language: String,
 
-    /// Original source file path
+    /// Original source file path (first file pair for v2 maps)
     pub source_file: String,
 
-    /// Generated Rust file path
+    /// Generated Rust file path (first file pair for v2 maps)
     pub generated_file: String,
 
-    /// Line mappings: Rust line → Original source location
+    /// Line mappings: Rust line → Original source location (v1 maps only)
     pub mappings: Vec<SourceMapping>,
 
     /// Function name mappings: Rust function → Original function/description
     pub function_map: HashMap<String, String>,
+
+    /// Source/generated file pairs (Sprint 53)
+    pub files: Vec<FilePair>,
+
+    /// Range mappings for all file pairs, v1 lines included (Sprint 53)
+    pub ranges: Vec<RangeMapping>,
+
+    /// Rust ranges per generated file
+    #[serde(skip)]
+    forward: HashMap<String, IntervalIndex>,
+
+    /// Source ranges per original source file
+    #[serde(skip)]
+    reverse: HashMap<String, IntervalIndex>,
 }
 
 /// Single source mapping entry (Rust line → Python line)
@@ -58,6 +107,304 @@ pub struct SourceMapping {
     pub python_context: String,
 }
 
+/// Original source file and the Rust file generated from it (Sprint 53)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePair {
+    /// Original source file path
+    pub source_file: String,
+
+    /// Generated Rust file path
+    pub generated_file: String,
+}
+
+/// Line/column range within a file (1-based, inclusive) (Sprint 53)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRange {
+    /// First line
+    pub line: usize,
+
+    /// First column (start of line if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+
+    /// Last line (same as `line` if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+
+    /// Last column (end of line if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+}
+
+/// Position as (line, column)
+type Position = (usize, usize);
+
+impl SourceRange {
+    /// Range covering a whole line
+    pub fn line(line: usize) -> Self {
+        Self {
+            line,
+            column: None,
+            end_line: None,
+            end_column: None,
+        }
+    }
+
+    /// First position of the range
+    pub fn start(&self) -> Position {
+        (self.line, self.column.unwrap_or(0))
+    }
+
+    /// Last position of the range
+    pub fn end(&self) -> Position {
+        (
+            self.end_line.unwrap_or(self.line),
+            self.end_column.unwrap_or(usize::MAX),
+        )
+    }
+
+    /// Whether a position lies within the range
+    ///
+    /// A missing column matches any column of the line.
+    pub fn contains(&self, line: usize, column: Option<usize>) -> bool {
+        let (lo, hi) = match column {
+            Some(column) => ((line, column), (line, column)),
+            None => ((line, 0), (line, usize::MAX)),
+        };
+        self.start() <= hi && self.end() >= lo
+    }
+
+    /// Rough size of the range, used to pick the most specific match
+    fn span(&self) -> (usize, usize) {
+        let (start_line, start_col) = self.start();
+        let (end_line, end_col) = self.end();
+        (end_line - start_line, end_col.saturating_sub(start_col))
+    }
+}
+
+impl std::fmt::Display for SourceRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{}", column)?;
+        }
+        match (self.end_line, self.end_column) {
+            (Some(end_line), Some(end_column)) if end_line != self.line => {
+                write!(f, "-{}:{}", end_line, end_column)
+            }
+            (Some(end_line), None) if end_line != self.line => write!(f, "-{}", end_line),
+            (_, Some(end_column)) => write!(f, "-{}", end_column),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Mapping between a Rust range and an original source range (Sprint 53)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeMapping {
+    /// Index into the map's file pairs
+    #[serde(default)]
+    pub file: usize,
+
+    /// Range in the generated Rust file
+    pub rust: SourceRange,
+
+    /// Range in the original source file
+    pub source: SourceRange,
+
+    /// Function name in generated Rust code
+    #[serde(default)]
+    pub rust_function: String,
+
+    /// Function name in original source
+    #[serde(default)]
+    pub source_function: String,
+
+    /// Original source code context (for debugging)
+    #[serde(default)]
+    pub source_context: String,
+}
+
+/// On-disk layout of a version 1 map
+#[derive(Debug, Deserialize)]
+struct MapFileV1 {
+    source_language: String,
+    source_file: String,
+    generated_file: String,
+    mappings: Vec<SourceMapping>,
+    function_map: HashMap<String, String>,
+}
+
+/// On-disk layout of a version 2 map
+#[derive(Debug, Deserialize)]
+struct MapFileV2 {
+    source_language: String,
+    files: Vec<FilePair>,
+    #[serde(default)]
+    mappings: Vec<RangeMapping>,
+    #[serde(default)]
+    function_map: HashMap<String, String>,
+}
+
+/// Serialized form of a [`TranspilerMap`]
+///
+/// `files` and `ranges` are missing from maps serialized before Sprint 53;
+/// those are rebuilt from the v1 fields.
+#[derive(Debug, Deserialize)]
+struct MapFields {
+    version: u32,
+    source_language: String,
+    source_file: String,
+    generated_file: String,
+    mappings: Vec<SourceMapping>,
+    function_map: HashMap<String, String>,
+    #[serde(default)]
+    files: Vec<FilePair>,
+    #[serde(default)]
+    ranges: Vec<RangeMapping>,
+}
+
+impl From<MapFields> for TranspilerMap {
+    fn from(fields: MapFields) -> Self {
+        if fields.files.is_empty() {
+            let mut map = Self::from_v1(MapFileV1 {
+                source_language: fields.source_language,
+                source_file: fields.source_file,
+                generated_file: fields.generated_file,
+                mappings: fields.mappings,
+                function_map: fields.function_map,
+            });
+            map.version = fields.version;
+            return map;
+        }
+        Self::new(
+            fields.version,
+            fields.source_language,
+            fields.files,
+            fields.mappings,
+            fields.ranges,
+            fields.function_map,
+        )
+    }
+}
+
+/// Static interval tree over sorted ranges (implicit balanced BST)
+///
+/// Each node is the midpoint of its slice; `max_end` holds the largest end
+/// position of the node's subtree so non-overlapping subtrees are skipped.
+#[derive(Debug, Clone, Default)]
+struct IntervalIndex {
+    /// (start, end, mapping index), sorted by start
+    intervals: Vec<(Position, Position, usize)>,
+    max_end: Vec<Position>,
+}
+
+impl IntervalIndex {
+    fn build(mut intervals: Vec<(Position, Position, usize)>) -> Self {
+        intervals.sort();
+        let mut index = Self {
+            max_end: vec![(0, 0); intervals.len()],
+            intervals,
+        };
+        index.fill_max_end(0, index.intervals.len());
+        index
+    }
+
+    fn fill_max_end(&mut self, left: usize, right: usize) -> Position {
+        if left >= right {
+            return (0, 0);
+        }
+        let mid = left + (right - left) / 2;
+        let max_end = self.intervals[mid]
+            .1
+            .max(self.fill_max_end(left, mid))
+            .max(self.fill_max_end(mid + 1, right));
+        self.max_end[mid] = max_end;
+        max_end
+    }
+
+    /// Indices of all intervals overlapping `[lo, hi]`
+    fn query(&self, lo: Position, hi: Position) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.visit(0, self.intervals.len(), lo, hi, &mut out);
+        out
+    }
+
+    fn visit(&self, left: usize, right: usize, lo: Position, hi: Position, out: &mut Vec<usize>) {
+        if left >= right {
+            return;
+        }
+        let mid = left + (right - left) / 2;
+        if self.max_end[mid] < lo {
+            return;
+        }
+        self.visit(left, mid, lo, hi, out);
+
+        let (start, end, idx) = self.intervals[mid];
+        if start > hi {
+            // Everything to the right starts even later
+            return;
+        }
+        if end >= lo {
+            out.push(idx);
+        }
+        self.visit(mid + 1, right, lo, hi, out);
+    }
+}
+
+/// Whether a path reported at runtime refers to a path from the map
+///
+/// DWARF paths are usually absolute while maps use project-relative paths,
+/// so paths match when one is a component-wise suffix of the other.
+fn path_matches(actual: &str, mapped: &str) -> bool {
+    actual == mapped || Path::new(actual).ends_with(mapped) || Path::new(mapped).ends_with(actual)
+}
+
+fn build_index<'a>(
+    ranges: &[RangeMapping],
+    key: impl Fn(&RangeMapping) -> (&'a str, SourceRange),
+) -> HashMap<String, IntervalIndex> {
+    let mut grouped: HashMap<String, Vec<(Position, Position, usize)>> = HashMap::new();
+    for (idx, mapping) in ranges.iter().enumerate() {
+        let (file, range) = key(mapping);
+        grouped
+            .entry(file.to_string())
+            .or_default()
+            .push((range.start(), range.end(), idx));
+    }
+    grouped
+        .into_iter()
+        .map(|(file, intervals)| (file, IntervalIndex::build(intervals)))
+        .collect()
+}
+
+/// Generated file for a v3 map without `file`: "app.rs.map" → "app.rs"
+fn generated_file_for_map(path: &Path) -> Option<String> {
+    path.file_name()?
+        .to_str()?
+        .strip_suffix(".map")
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// Guess the source language of a v3 map from its first source file
+fn source_language_for(source_file: &str) -> String {
+    let extension = Path::new(source_file)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("");
+    match extension {
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "py" => "python",
+        "c" | "h" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" => "cpp",
+        "ruchy" => "ruchy",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
 impl TranspilerMap {
     /// Load and parse source map from JSON file
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -71,19 +418,50 @@ impl TranspilerMap {
         // Read file
         let contents = fs::read_to_string(path_ref).context("Failed to read source map file")?;
 
+        Self::parse(&contents, Some(path_ref))
+    }
+
+    /// Parse a source map from a JSON string (Sprint 53)
+    pub fn from_json(contents: &str) -> Result<Self> {
+        Self::parse(contents, None)
+    }
+
+    fn parse(contents: &str, path: Option<&Path>) -> Result<Self> {
         // Parse JSON
-        let map: TranspilerMap =
-            serde_json::from_str(&contents).context("Invalid source map JSON")?;
+        let value: serde_json::Value =
+            serde_json::from_str(contents).context("Invalid source map JSON")?;
 
         // Validate version
-        if map.version != SUPPORTED_VERSION {
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .context("Invalid source map JSON: missing version")? as u32;
+        if !SUPPORTED_VERSIONS.contains(&version) {
             bail!(
-                "Unsupported source map version: {} (expected {})",
-                map.version,
-                SUPPORTED_VERSION
+                "Unsupported source map version: {} (expected 1, 2 or 3)",
+                version
             );
         }
 
+        let map = if version == 3 {
+            // Sprint 54: Standard source map with VLQ mappings
+            if !crate::sourcemap_v3::is_v3(&value) {
+                bail!(
+                    "Invalid source map: version 3 maps need a \"mappings\" string or \"sections\""
+                );
+            }
+            let generated = path.and_then(generated_file_for_map);
+            Self::from_v3(value, generated.as_deref())?
+        } else if version == 1 {
+            let file: MapFileV1 =
+                serde_json::from_value(value).context("Invalid source map JSON")?;
+            Self::from_v1(file)
+        } else {
+            let file: MapFileV2 =
+                serde_json::from_value(value).context("Invalid source map JSON")?;
+            Self::from_v2(file)?
+        };
+
         // Validate required fields
         if map.source_language.is_empty() {
             bail!("Invalid source map: missing source_language");
@@ -95,11 +473,183 @@ impl TranspilerMap {
         Ok(map)
     }
 
+    fn from_v1(file: MapFileV1) -> Self {
+        let ranges = file
+            .mappings
+            .iter()
+            .map(|m| RangeMapping {
+                file: 0,
+                rust: SourceRange::line(m.rust_line),
+                source: SourceRange::line(m.python_line),
+                rust_function: m.rust_function.clone(),
+                source_function: m.python_function.clone(),
+                source_context: m.python_context.clone(),
+            })
+            .collect();
+        let files = vec![FilePair {
+            source_file: file.source_file.clone(),
+            generated_file: file.generated_file.clone(),
+        }];
+
+        Self::new(
+            1,
+            file.source_language,
+            files,
+            file.mappings,
+            ranges,
+            file.function_map,
+        )
+    }
+
+    fn from_v2(file: MapFileV2) -> Result<Self> {
+        if file.files.is_empty() {
+            bail!("Invalid source map: missing files");
+        }
+        for (i, mapping) in file.mappings.iter().enumerate() {
+            if mapping.file >= file.files.len() {
+                bail!(
+                    "Invalid source map: mapping {} refers to file {} (only {} files)",
+                    i,
+                    mapping.file,
+                    file.files.len()
+                );
+            }
+            for range in [&mapping.rust, &mapping.source] {
+                if range.end() < range.start() {
+                    bail!("Invalid source map: mapping {} has an empty range", i);
+                }
+            }
+        }
+
+        Ok(Self::new(
+            2,
+            file.source_language,
+            file.files,
+            Vec::new(),
+            file.mappings,
+            file.function_map,
+        ))
+    }
+
+    fn from_v3(value: serde_json::Value, generated_file: Option<&str>) -> Result<Self> {
+        let decoded = crate::sourcemap_v3::decode(value, generated_file)?;
+        if decoded.files.is_empty() {
+            bail!("Invalid source map: missing sources");
+        }
+        let language = source_language_for(&decoded.files[0].source_file);
+
+        Ok(Self::new(
+            3,
+            language,
+            decoded.files,
+            Vec::new(),
+            decoded.ranges,
+            HashMap::new(),
+        ))
+    }
+
+    fn new(
+        version: u32,
+        source_language: String,
+        files: Vec<FilePair>,
+        mappings: Vec<SourceMapping>,
+        ranges: Vec<RangeMapping>,
+        function_map: HashMap<String, String>,
+    ) -> Self {
+        let forward = build_index(&ranges, |m| (files[m.file].generated_file.as_str(), m.rust));
+        let reverse = build_index(&ranges, |m| (files[m.file].source_file.as_str(), m.source));
+        let primary = files.first().cloned().unwrap_or(FilePair {
+            source_file: String::new(),
+            generated_file: String::new(),
+        });
+
+        Self {
+            version,
+            source_language,
+            source_file: primary.source_file,
+            generated_file: primary.generated_file,
+            mappings,
+            function_map,
+            files,
+            ranges,
+            forward,
+            reverse,
+        }
+    }
+
     /// Look up original source location for a Rust line number
+    ///
+    /// Only covers v1 line mappings; use [`TranspilerMap::lookup`] for
+    /// multi-file v2 maps.
     pub fn lookup_line(&self, rust_line: usize) -> Option<&SourceMapping> {
         self.mappings.iter().find(|m| m.rust_line == rust_line)
     }
 
+    /// Look up the original source range for a Rust location (Sprint 53)
+    ///
+    /// `rust_file` may be absolute; it is matched against the map's generated
+    /// files by path suffix. v1 and v3 maps describe a single generated
+    /// file, so any file name falls back to it. When several ranges contain the
+    /// location, the narrowest one wins.
+    pub fn lookup(
+        &self,
+        rust_file: &str,
+        line: usize,
+        column: Option<usize>,
+    ) -> Option<&RangeMapping> {
+        let index = self
+            .index_for(&self.forward, rust_file)
+            .or_else(|| match self.version {
+                1 | 3 => self.forward.get(&self.generated_file),
+                _ => None,
+            })?;
+
+        let (lo, hi) = match column {
+            Some(column) => ((line, column), (line, column)),
+            None => ((line, 0), (line, usize::MAX)),
+        };
+        index
+            .query(lo, hi)
+            .into_iter()
+            .map(|idx| &self.ranges[idx])
+            .min_by_key(|m| (m.rust.span(), m.rust.start()))
+    }
+
+    /// Reverse lookup: Rust ranges generated from an original source line (Sprint 53)
+    ///
+    /// Results are ordered by generated file and Rust position.
+    pub fn lookup_source(&self, source_file: &str, line: usize) -> Vec<&RangeMapping> {
+        let Some(index) = self.index_for(&self.reverse, source_file) else {
+            return Vec::new();
+        };
+
+        let mut found: Vec<&RangeMapping> = index
+            .query((line, 0), (line, usize::MAX))
+            .into_iter()
+            .map(|idx| &self.ranges[idx])
+            .collect();
+        found.sort_by_key(|m| (m.file, m.rust.start()));
+        found
+    }
+
+    fn index_for<'a>(
+        &self,
+        indexes: &'a HashMap<String, IntervalIndex>,
+        file: &str,
+    ) -> Option<&'a IntervalIndex> {
+        indexes.get(file).or_else(|| {
+            indexes
+                .iter()
+                .find(|(mapped, _)| path_matches(file, mapped))
+                .map(|(_, index)| index)
+        })
+    }
+
+    /// File pair a range mapping belongs to (Sprint 53)
+    pub fn file_pair(&self, mapping: &RangeMapping) -> &FilePair {
+        &self.files[mapping.file]
+    }
+
     /// Look up original function name for a Rust function name
     pub fn lookup_function(&self, rust_fn: &str) -> Option<&str> {
         self.function_map.get(rust_fn).map(String::as_str)
@@ -122,13 +672,18 @@ impl TranspilerMap {
 
     /// Get total number of mappings
     pub fn mapping_count(&self) -> usize {
-        self.mappings.len()
+        self.ranges.len()
     }
 
     /// Get total number of function mappings
     pub fn function_mapping_count(&self) -> usize {
         self.function_map.len()
     }
+
+    /// Get total number of source/generated file pairs (Sprint 53)
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
 }
 
 #[cfg(test)]
@@ -369,4 +924,346 @@ mod tests {
             "temporary: sizeof(struct data)"
         );
     }
+
+    // Sprint 53: Version 2 maps
+
+    const V2_MAP: &str = r#"{
+        "version": 2,
+        "source_language": "python",
+        "files": [
+            { "source_file": "pkg/util.py", "generated_file": "src/util.rs" },
+            { "source_file": "pkg/main.py", "generated_file": "src/main.rs" }
+        ],
+        "mappings": [
+            {
+                "file": 0,
+                "rust": { "line": 10, "end_line": 20 },
+                "source": { "line": 5, "end_line": 8 },
+                "rust_function": "parse",
+                "source_function": "parse"
+            },
+            {
+                "file": 0,
+                "rust": { "line": 12, "column": 5, "end_column": 40 },
+                "source": { "line": 6, "column": 12, "end_column": 25 },
+                "rust_function": "parse",
+                "source_function": "parse",
+                "source_context": "int(x)"
+            },
+            {
+                "file": 0,
+                "rust": { "line": 12, "column": 41, "end_column": 60 },
+                "source": { "line": 6, "column": 1, "end_column": 11 },
+                "rust_function": "parse",
+                "source_function": "parse",
+                "source_context": "return"
+            },
+            {
+                "file": 1,
+                "rust": { "line": 12 },
+                "source": { "line": 3 },
+                "rust_function": "main",
+                "source_function": "main"
+            }
+        ],
+        "function_map": { "parse": "parse", "main": "main" }
+    }"#;
+
+    #[test]
+    fn test_parse_v2_map() {
+        let map = TranspilerMap::from_json(V2_MAP).unwrap();
+
+        assert_eq!(map.version, 2);
+        assert_eq!(map.file_count(), 2);
+        assert_eq!(map.mapping_count(), 4);
+        assert_eq!(map.function_mapping_count(), 2);
+        // First pair doubles as the primary (v1-style) file pair
+        assert_eq!(map.source_file(), Path::new("pkg/util.py"));
+        assert_eq!(map.generated_file(), Path::new("src/util.rs"));
+        assert!(map.mappings.is_empty());
+    }
+
+    #[test]
+    fn test_v2_lookup_by_file_line_column() {
+        let map = TranspilerMap::from_json(V2_MAP).unwrap();
+
+        // Narrowest range containing the column wins
+        let hit = map.lookup("src/util.rs", 12, Some(20)).unwrap();
+        assert_eq!(hit.source_context, "int(x)");
+        let hit = map.lookup("src/util.rs", 12, Some(50)).unwrap();
+        assert_eq!(hit.source_context, "return");
+
+        // Outside the column ranges falls back to the enclosing block
+        let hit = map.lookup("src/util.rs", 12, Some(70)).unwrap();
+        assert_eq!(hit.source.line, 5);
+        let hit = map.lookup("src/util.rs", 15, None).unwrap();
+        assert_eq!(hit.source.end_line, Some(8));
+
+        // Same line in another file maps elsewhere
+        let hit = map.lookup("src/main.rs", 12, Some(1)).unwrap();
+        assert_eq!(map.file_pair(hit).source_file, "pkg/main.py");
+        assert_eq!(hit.source.line, 3);
+
+        assert!(map.lookup("src/util.rs", 21, None).is_none());
+        assert!(map.lookup("src/other.rs", 12, None).is_none());
+    }
+
+    #[test]
+    fn test_v2_lookup_matches_absolute_paths() {
+        let map = TranspilerMap::from_json(V2_MAP).unwrap();
+        let hit = map
+            .lookup("/home/dev/project/src/main.rs", 12, None)
+            .unwrap();
+        assert_eq!(hit.rust_function, "main");
+
+        // Suffix matching is per path component
+        assert!(map
+            .lookup("/home/dev/project/xsrc/main.rs", 12, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_v2_reverse_lookup() {
+        let map = TranspilerMap::from_json(V2_MAP).unwrap();
+
+        let ranges = map.lookup_source("pkg/util.py", 6);
+        let rust: Vec<(usize, Option<usize>)> = ranges
+            .iter()
+            .map(|m| (m.rust.line, m.rust.column))
+            .collect();
+        assert_eq!(rust, vec![(10, None), (12, Some(5)), (12, Some(41))]);
+
+        let ranges = map.lookup_source("pkg/util.py", 8);
+        assert_eq!(ranges.len(), 1);
+
+        assert_eq!(map.lookup_source("pkg/main.py", 3).len(), 1);
+        assert!(map.lookup_source("pkg/util.py", 9).is_empty());
+        assert!(map.lookup_source("pkg/missing.py", 6).is_empty());
+    }
+
+    #[test]
+    fn test_serde_round_trip_rebuilds_indexes() {
+        let map = TranspilerMap::from_json(V2_MAP).unwrap();
+        let json = serde_json::to_string(&map).unwrap();
+        let restored: TranspilerMap = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.version, 2);
+        assert_eq!(restored.file_count(), 2);
+        let hit = restored.lookup("src/util.rs", 12, Some(20)).unwrap();
+        assert_eq!(hit.source_context, "int(x)");
+        assert_eq!(restored.lookup_source("pkg/main.py", 3).len(), 1);
+    }
+
+    #[test]
+    fn test_deserialize_pre_v2_serialized_map() {
+        // Layout of a TranspilerMap serialized before Sprint 53
+        let restored: TranspilerMap = serde_json::from_str(
+            r#"{
+                "version": 1,
+                "source_language": "python",
+                "source_file": "test.py",
+                "generated_file": "test.rs",
+                "mappings": [{
+                    "rust_line": 10,
+                    "rust_function": "main",
+                    "python_line": 5,
+                    "python_function": "main",
+                    "python_context": "def main():"
+                }],
+                "function_map": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(restored.file_count(), 1);
+        let hit = restored.lookup("test.rs", 10, None).unwrap();
+        assert_eq!(hit.source.line, 5);
+    }
+
+    #[test]
+    fn test_v1_ranges_and_lookup_backward_compatible() {
+        let map = TranspilerMap::from_json(
+            r#"{
+                "version": 1,
+                "source_language": "python",
+                "source_file": "test.py",
+                "generated_file": "test.rs",
+                "mappings": [
+                    {
+                        "rust_line": 192,
+                        "rust_function": "process_data",
+                        "python_line": 143,
+                        "python_function": "process_data",
+                        "python_context": "x = position[0]"
+                    }
+                ],
+                "function_map": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(map.file_count(), 1);
+        assert_eq!(map.mapping_count(), 1);
+
+        // v1 maps have a single generated file, so any DWARF path matches
+        let hit = map.lookup("/build/out/generated.rs", 192, Some(7)).unwrap();
+        assert_eq!(hit.source.line, 143);
+        assert_eq!(hit.source_context, "x = position[0]");
+
+        let reverse = map.lookup_source("test.py", 143);
+        assert_eq!(reverse[0].rust.line, 192);
+    }
+
+    #[test]
+    fn test_v2_invalid_file_index() {
+        let err = TranspilerMap::from_json(
+            r#"{
+                "version": 2,
+                "source_language": "python",
+                "files": [{ "source_file": "a.py", "generated_file": "a.rs" }],
+                "mappings": [{ "file": 3, "rust": { "line": 1 }, "source": { "line": 1 } }]
+            }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("refers to file 3"));
+    }
+
+    #[test]
+    fn test_v2_missing_files() {
+        let err = TranspilerMap::from_json(
+            r#"{ "version": 2, "source_language": "python", "files": [] }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("missing files"));
+    }
+
+    #[test]
+    fn test_source_range_display_and_contains() {
+        let whole = SourceRange::line(7);
+        assert_eq!(whole.to_string(), "7");
+        assert!(whole.contains(7, Some(300)));
+        assert!(!whole.contains(8, None));
+
+        let cols = SourceRange {
+            line: 3,
+            column: Some(4),
+            end_line: None,
+            end_column: Some(9),
+        };
+        assert_eq!(cols.to_string(), "3:4-9");
+        assert!(cols.contains(3, Some(9)));
+        assert!(!cols.contains(3, Some(10)));
+        assert!(cols.contains(3, None));
+
+        let multi = SourceRange {
+            line: 3,
+            column: Some(4),
+            end_line: Some(5),
+            end_column: Some(2),
+        };
+        assert_eq!(multi.to_string(), "3:4-5:2");
+        assert!(multi.contains(4, Some(1000)));
+        assert!(!multi.contains(5, Some(3)));
+    }
+
+    #[test]
+    fn test_interval_index_matches_linear_scan() {
+        // Deterministic pseudo-random ranges
+        let mut seed = 42u64;
+        let mut next = |modulo: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) % modulo) as usize
+        };
+        let intervals: Vec<(Position, Position, usize)> = (0..200)
+            .map(|i| {
+                let start = (next(100) + 1, next(80));
+                let end = (start.0 + next(5), next(120));
+                let end = if end < start { start } else { end };
+                (start, end, i)
+            })
+            .collect();
+        let index = IntervalIndex::build(intervals.clone());
+
+        for line in 0..110 {
+            for column in [0, 10, 50, 100] {
+                let point = (line, column);
+                let mut expected: Vec<usize> = intervals
+                    .iter()
+                    .filter(|(s, e, _)| *s <= point && *e >= point)
+                    .map(|(_, _, i)| *i)
+                    .collect();
+                let mut got = index.query(point, point);
+                expected.sort();
+                got.sort();
+                assert_eq!(got, expected, "point {:?}", point);
+            }
+        }
+    }
+
+    // Sprint 54: Source Map v3
+
+    const V3_MAP: &str = r#"{
+        "version": 3,
+        "file": "app.rs",
+        "sources": ["app.ts", "lib.ts"],
+        "sourcesContent": ["function main() {\n  run();\n}\n", null],
+        "names": ["main", "run"],
+        "mappings": "AAAAA,QAAQ;AACNC;ACDA"
+    }"#;
+
+    #[test]
+    fn test_v3_map_detected() {
+        let map = TranspilerMap::from_json(V3_MAP).unwrap();
+        assert_eq!(map.version, 3);
+        assert_eq!(map.source_language(), "typescript");
+        assert_eq!(map.source_file(), Path::new("app.ts"));
+        assert_eq!(map.generated_file(), Path::new("app.rs"));
+        assert_eq!(map.file_count(), 2);
+        assert_eq!(map.mapping_count(), 4);
+    }
+
+    #[test]
+    fn test_v3_forward_and_reverse_lookup() {
+        let map = TranspilerMap::from_json(V3_MAP).unwrap();
+
+        let hit = map.lookup("/build/app.rs", 1, Some(3)).unwrap();
+        assert_eq!(hit.source_function, "main");
+        assert_eq!(hit.source_context, "function main() {");
+
+        let hit = map.lookup("/build/app.rs", 2, None).unwrap();
+        assert_eq!(hit.source_function, "run");
+        assert_eq!(hit.source.line, 2);
+
+        let hit = map.lookup("app.rs", 3, Some(1)).unwrap();
+        assert_eq!(map.file_pair(hit).source_file, "lib.ts");
+
+        let rust = map.lookup_source("app.ts", 1);
+        assert_eq!(rust.len(), 2);
+        assert_eq!(rust[1].rust.column, Some(9));
+    }
+
+    #[test]
+    fn test_v3_generated_file_from_map_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("module.rs.map");
+        std::fs::write(
+            &path,
+            r#"{"version": 3, "sources": ["module.py"], "names": [], "mappings": "AAAA"}"#,
+        )
+        .unwrap();
+
+        let map = TranspilerMap::from_file(&path).unwrap();
+        assert_eq!(map.generated_file(), Path::new("module.rs"));
+        assert_eq!(map.source_language(), "python");
+    }
+
+    #[test]
+    fn test_v3_requires_mappings_and_sources() {
+        let err = TranspilerMap::from_json(r#"{"version": 3, "sources": ["a.ts"]}"#).unwrap_err();
+        assert!(err.to_string().contains("\"mappings\" string"));
+
+        let err = TranspilerMap::from_json(r#"{"version": 3, "sources": [], "mappings": ""}"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("missing sources"));
+    }
 }
@@ -15,6 +15,7 @@
 use std::borrow::Cow;
 
 use crate::decision_trace::DecisionTrace;
+use crate::memory::MemorySample;
 use crate::otlp_exporter::{ComputeBlock, GpuKernel, GpuMemoryTransfer};
 use crate::trace_context::LamportClock;
 
@@ -90,6 +91,13 @@ pub struct SyscallSpan {
     pub duration_nanos: u64,
     /// Error number (errno) if syscall failed
     pub errno: Option<i32>,
+    /// Process that made the syscall in a multi-process trace (Sprint 66)
+    ///
+    /// `None` when unknown, which consumers treat as the root
+    /// [`ProcessSpan`]'s process.
+    pub pid: Option<i32>,
+    /// Source location or function that made the syscall (Sprint 69)
+    pub call_site: Option<String>,
 }
 
 impl SyscallSpan {
@@ -114,9 +122,23 @@ impl SyscallSpan {
             timestamp_nanos,
             duration_nanos,
             errno,
+            pid: None,
+            call_site: None,
         }
     }
 
+    /// Attribute the syscall to process `pid` (Sprint 66)
+    pub fn with_pid(mut self, pid: i32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+
+    /// Record where the syscall was made (Sprint 69)
+    pub fn with_call_site(mut self, call_site: Option<String>) -> Self {
+        self.call_site = call_site;
+        self
+    }
+
     /// Check if syscall failed (return value < 0)
     pub fn is_error(&self) -> bool {
         self.return_value < 0
@@ -141,6 +163,8 @@ pub struct UnifiedTrace {
     pub simd_spans: Vec<ComputeBlock>,
     /// Transpiler decision points (Layer 5)
     pub transpiler_spans: Vec<DecisionTrace>,
+    /// Resident set samples taken while recording (Sprint 67)
+    pub rss_samples: Vec<MemorySample>,
     /// Lamport clock for causal ordering
     pub clock: LamportClock,
 }
@@ -158,6 +182,7 @@ impl UnifiedTrace {
             gpu_memory_transfers: Vec::new(),
             simd_spans: Vec::new(),
             transpiler_spans: Vec::new(),
+            rss_samples: Vec::new(),
             clock,
         }
     }
@@ -0,0 +1,36 @@
+// Shared helpers for the renacer.toml assertion tests (Sprints 65-70)
+#![allow(dead_code)] // each test crate uses a subset
+
+use std::ffi::OsStr;
+use std::fs;
+use tempfile::TempDir;
+
+/// Project directory with `config` as its renacer.toml
+pub fn project(config: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("renacer.toml"), config).unwrap();
+    dir
+}
+
+/// Run `renacer check -- command` in a fresh project with `config`
+pub fn check<S: AsRef<OsStr>>(config: &str, command: &[S]) -> assert_cmd::assert::Assert {
+    let dir = project(config);
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("check")
+        .arg("--")
+        .args(command);
+    cmd.assert()
+}
+
+/// `count` consecutive write() calls from the shell's printf builtin
+pub fn shell_writes(count: usize) -> Vec<String> {
+    vec![
+        "sh".to_string(),
+        "-c".to_string(),
+        format!(
+            "exec >/dev/null; i=0; while [ $i -lt {} ]; do printf x; i=$((i + 1)); done",
+            count
+        ),
+    ]
+}
@@ -233,27 +233,27 @@ fn test_compare_traces_different() {
     let mut trace2 = UnifiedTrace::new(1234, "test_process".to_string());
 
     // Add different syscalls
-    trace1.add_syscall(SyscallSpan {
-        span_id: 1,
-        parent_span_id: 0,
-        name: Cow::Borrowed("read"),
-        args: vec![],
-        return_value: 100,
-        timestamp_nanos: 1000,
-        duration_nanos: 50,
-        errno: None,
-    });
-
-    trace2.add_syscall(SyscallSpan {
-        span_id: 2,
-        parent_span_id: 0,
-        name: Cow::Borrowed("write"),
-        args: vec![],
-        return_value: 100,
-        timestamp_nanos: 1000,
-        duration_nanos: 50,
-        errno: None,
-    });
+    trace1.add_syscall(SyscallSpan::new(
+        0,
+        Cow::Borrowed("read"),
+        vec![],
+        100,
+        1000,
+        50,
+        None,
+        &trace1.clock,
+    ));
+
+    trace2.add_syscall(SyscallSpan::new(
+        0,
+        Cow::Borrowed("write"),
+        vec![],
+        100,
+        1000,
+        50,
+        None,
+        &trace2.clock,
+    ));
 
     let score = compare_traces(&trace1, &trace2);
 
@@ -272,27 +272,27 @@ fn test_compare_traces_timing_variance() {
     let mut trace2 = UnifiedTrace::new(1234, "test_process".to_string());
 
     // Same syscall, different duration
-    trace1.add_syscall(SyscallSpan {
-        span_id: 1,
-        parent_span_id: 0,
-        name: Cow::Borrowed("read"),
-        args: vec![],
-        return_value: 100,
-        timestamp_nanos: 1000,
-        duration_nanos: 50,
-        errno: None,
-    });
-
-    trace2.add_syscall(SyscallSpan {
-        span_id: 1,
-        parent_span_id: 0,
-        name: Cow::Borrowed("read"),
-        args: vec![],
-        return_value: 100,
-        timestamp_nanos: 1000,
-        duration_nanos: 500, // 10x slower
-        errno: None,
-    });
+    trace1.add_syscall(SyscallSpan::new(
+        0,
+        Cow::Borrowed("read"),
+        vec![],
+        100,
+        1000,
+        50,
+        None,
+        &trace1.clock,
+    ));
+
+    trace2.add_syscall(SyscallSpan::new(
+        0,
+        Cow::Borrowed("read"),
+        vec![],
+        100,
+        1000,
+        500, // 10x slower
+        None,
+        &trace2.clock,
+    ));
 
     let score = compare_traces(&trace1, &trace2);
 
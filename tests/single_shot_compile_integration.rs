@@ -13,21 +13,22 @@ use renacer::cluster::ClusterRegistry;
 use renacer::regression::{assess_regression, RegressionConfig};
 use renacer::sequence::{detect_sequence_anomalies, extract_ngrams};
 use renacer::time_attribution::{calculate_time_attribution, identify_hotspots};
+use renacer::trace_context::LamportClock;
 use renacer::unified_trace::SyscallSpan;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
 fn make_span(name: &'static str, duration_nanos: u64) -> SyscallSpan {
-    SyscallSpan {
-        span_id: 1,
-        parent_span_id: 0,
-        name: Cow::Borrowed(name),
-        args: vec![],
-        return_value: 0,
-        timestamp_nanos: 0,
+    SyscallSpan::new(
+        0,
+        Cow::Borrowed(name),
+        vec![],
+        0,
+        0,
         duration_nanos,
-        errno: None,
-    }
+        None,
+        &LamportClock::new(),
+    )
 }
 
 /// Test complete workflow: Normal transpiler execution (baseline)
@@ -33,7 +33,7 @@ fn test_function_time_output_format() {
     // Should show either the profiling table header or "No function profiling data"
     // (depends on whether DWARF info is available for echo)
     assert!(
-        stderr.contains("Function Profiling Summary")
+        stderr.contains("Function Timing Summary")
             || stderr.contains("No function profiling data collected")
             || stderr.contains("═══")
     );
@@ -59,8 +59,7 @@ fn test_function_time_with_filter() {
     // Syscall traces go to stdout, profiling summary to stderr
     assert!(stdout.contains("write("));
     assert!(
-        stderr.contains("No function profiling data")
-            || stderr.contains("Function Profiling Summary")
+        stderr.contains("No function profiling data") || stderr.contains("Function Timing Summary")
     );
 }
 
@@ -76,6 +75,6 @@ fn test_function_time_without_flag_no_profiling() {
     let stderr = String::from_utf8_lossy(&output.stderr);
 
     // Should NOT show function profiling messages
-    assert!(!stderr.contains("Function Profiling Summary"));
+    assert!(!stderr.contains("Function Timing Summary"));
     assert!(!stderr.contains("No function profiling data collected"));
 }
@@ -63,7 +63,7 @@ fn test_stack_unwinding_with_function_time_disabled() {
 
     let stderr = String::from_utf8_lossy(&output.stderr);
     // Should NOT show function profiling
-    assert!(!stderr.contains("Function Profiling Summary"));
+    assert!(!stderr.contains("Function Timing Summary"));
 }
 
 #[test]
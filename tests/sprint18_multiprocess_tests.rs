@@ -412,6 +412,27 @@ int main() {
     cmd.assert().success();
 }
 
+#[test]
+fn test_follow_forks_resumes_child_once() {
+    // Setting ptrace options already resumes a new child; a second resume
+    // fails with ESRCH and used to drop the child from tracing
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-f")
+        .arg("-e")
+        .arg("trace=write")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("(echo child); echo parent");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::function(|out: &str| {
+            out.matches("write(").count() == 2
+        }))
+        .stderr(predicate::str::contains("exited immediately").not());
+}
+
 #[test]
 fn test_follow_vfork() {
     // Test vfork() variant (shares memory until exec)
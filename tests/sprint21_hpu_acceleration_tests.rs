@@ -359,9 +359,11 @@ int main() {
     // Function profiling summary goes to stderr, HPU report goes to stdout
     cmd.assert()
         .success()
-        .stderr(predicate::str::contains("Function Profiling Summary").or(
-            predicate::str::contains("No function profiling data collected"),
-        ))
+        .stderr(
+            predicate::str::contains("Function Timing Summary").or(predicate::str::contains(
+                "No function profiling data collected",
+            )),
+        )
         .stdout(predicate::str::contains("HPU Analysis Report"));
 }
 
@@ -0,0 +1,115 @@
+// Sprint 50: Flamegraph output from unwound syscall stacks
+
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_flamegraph_folded_output() {
+    let dir = TempDir::new().unwrap();
+    let folded = dir.path().join("trace.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--flamegraph-folded")
+        .arg(&folded)
+        .arg("--")
+        .arg("echo")
+        .arg("hello");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Flamegraph written to"));
+
+    let contents = fs::read_to_string(&folded).unwrap();
+    assert!(!contents.is_empty());
+    for line in contents.lines() {
+        // Every line is "stack weight" with the syscall as leaf frame
+        let (stack, weight) = line.rsplit_once(' ').expect("folded line");
+        assert!(weight.parse::<u64>().unwrap() > 0);
+        assert!(!stack.is_empty());
+    }
+    assert!(contents
+        .lines()
+        .any(|l| l.contains(";write ") || l.starts_with("write ")));
+}
+
+#[test]
+fn test_flamegraph_svg_output() {
+    let dir = TempDir::new().unwrap();
+    let svg = dir.path().join("trace.svg");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--flamegraph")
+        .arg(&svg)
+        .arg("--flamegraph-weight")
+        .arg("time")
+        .arg("--")
+        .arg("echo")
+        .arg("hello");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let contents = fs::read_to_string(&svg).unwrap();
+    assert!(contents.starts_with("<?xml"));
+    assert!(contents.contains("Syscall Flame Graph"));
+    assert!(contents.contains("μs"));
+    assert!(contents.contains("<script>"));
+}
+
+#[test]
+fn test_flamegraph_off_cpu_icicle() {
+    let dir = TempDir::new().unwrap();
+    let svg = dir.path().join("offcpu.svg");
+    let folded = dir.path().join("offcpu.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--flamegraph")
+        .arg(&svg)
+        .arg("--flamegraph-folded")
+        .arg(&folded)
+        .arg("--flamegraph-style")
+        .arg("icicle")
+        .arg("--flamegraph-off-cpu")
+        .arg("--")
+        .arg("sleep")
+        .arg("0.05");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let contents = fs::read_to_string(&svg).unwrap();
+    assert!(contents.contains("Off-CPU Syscall Icicle Graph"));
+
+    // Only blocking syscalls are recorded
+    let stacks = fs::read_to_string(&folded).unwrap();
+    assert!(stacks.lines().any(|l| l.contains("nanosleep")));
+    assert!(!stacks.lines().any(|l| l.contains(";mmap ")));
+}
+
+#[test]
+fn test_flamegraph_does_not_change_text_output() {
+    let dir = TempDir::new().unwrap();
+    let folded = dir.path().join("trace.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--flamegraph-folded")
+        .arg(&folded)
+        .arg("-e")
+        .arg("trace=write")
+        .arg("--")
+        .arg("echo")
+        .arg("hello");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    // Symbols are loaded for the flamegraph but source locations are only
+    // printed with --source
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|l| l.starts_with("write(")));
+}
@@ -0,0 +1,89 @@
+// Sprint 51: pprof profile.proto export
+
+use flate2::read::GzDecoder;
+use prost::Message;
+use renacer::pprof::Profile;
+use std::fs;
+use std::io::Read;
+use tempfile::TempDir;
+
+fn decode(path: &std::path::Path) -> Profile {
+    let bytes = fs::read(path).unwrap();
+    let mut raw = Vec::new();
+    GzDecoder::new(&bytes[..]).read_to_end(&mut raw).unwrap();
+    Profile::decode(&raw[..]).unwrap()
+}
+
+#[test]
+fn test_pprof_profile_written() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("profile.pb.gz");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--pprof")
+        .arg(&path)
+        .arg("--")
+        .arg("echo")
+        .arg("hello");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pprof profile written to"));
+
+    let profile = decode(&path);
+    let strings = &profile.string_table;
+    let types: Vec<&str> = profile
+        .sample_type
+        .iter()
+        .map(|vt| strings[vt.r#type as usize].as_str())
+        .collect();
+    assert_eq!(types, vec!["syscalls", "syscall_time", "slow_io"]);
+
+    assert!(!profile.sample.is_empty());
+    assert!(!profile.mapping.is_empty());
+    for sample in &profile.sample {
+        assert_eq!(sample.value.len(), 3);
+        assert!(sample.value[0] > 0);
+        for id in &sample.location_id {
+            assert!(profile.location.iter().any(|l| l.id == *id));
+        }
+    }
+
+    // The syscall is the leaf function of each sample
+    let has_write = profile.sample.iter().any(|sample| {
+        let leaf = &profile.location[(sample.location_id[0] - 1) as usize];
+        let function = &profile.function[(leaf.line[0].function_id - 1) as usize];
+        strings[function.name as usize] == "write"
+    });
+    assert!(has_write);
+}
+
+#[test]
+fn test_pprof_with_flamegraph() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("profile.pb.gz");
+    let folded = dir.path().join("trace.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--pprof")
+        .arg(&path)
+        .arg("--flamegraph-folded")
+        .arg(&folded)
+        .arg("--")
+        .arg("true");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let profile = decode(&path);
+    let total: i64 = profile.sample.iter().map(|s| s.value[0]).sum();
+    let folded_total: u64 = fs::read_to_string(&folded)
+        .unwrap()
+        .lines()
+        .filter_map(|l| l.rsplit_once(' ').and_then(|(_, w)| w.parse::<u64>().ok()))
+        .sum();
+    assert_eq!(total as u64, folded_total);
+}
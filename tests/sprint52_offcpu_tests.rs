@@ -0,0 +1,109 @@
+// Sprint 52: Off-CPU (blocking time) analysis
+
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_off_cpu_report_groups_sleep() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--off-cpu")
+        .arg("--")
+        .arg("sleep")
+        .arg("0.05");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Off-CPU Analysis"));
+    assert!(stderr.contains("Top wait objects:"));
+    assert!(stderr.contains("Top blocking stacks:"));
+
+    // The sleep dominates blocked time, so it is the first wait object
+    let first_object = stderr
+        .lines()
+        .skip_while(|l| !l.contains("Top wait objects:"))
+        .nth(1)
+        .unwrap();
+    assert!(
+        first_object.trim_end().ends_with("sleep"),
+        "{}",
+        first_object
+    );
+    assert!(stderr.contains("nanosleep"));
+}
+
+#[test]
+fn test_off_cpu_resolves_fd_paths() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--off-cpu")
+        .arg("-f")
+        .arg("-e")
+        .arg("trace=read")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("echo hello | cat");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("(pipe:["), "{}", stderr);
+}
+
+#[test]
+fn test_regular_file_reads_are_not_off_cpu() {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello\n".repeat(10_000)).unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--off-cpu")
+        .arg("-e")
+        .arg("trace=read")
+        .arg("--")
+        .arg("cat")
+        .arg(&input);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("=== Off-CPU Analysis"), "{}", stderr);
+    assert!(!stderr.contains("input.txt"), "{}", stderr);
+}
+
+#[test]
+fn test_off_cpu_folded_output() {
+    let dir = TempDir::new().unwrap();
+    let folded = dir.path().join("offcpu.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--off-cpu-folded")
+        .arg(&folded)
+        .arg("--off-cpu-top")
+        .arg("1")
+        .arg("--")
+        .arg("sleep")
+        .arg("0.02");
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Off-CPU stacks written to"));
+
+    let contents = fs::read_to_string(&folded).unwrap();
+    let sleep_line = contents
+        .lines()
+        .find(|l| l.contains("nanosleep;[sleep] "))
+        .expect("sleep stack");
+    let (_, weight) = sleep_line.rsplit_once(' ').unwrap();
+    assert!(weight.parse::<u64>().unwrap() >= 20_000);
+
+    // Only blocking syscalls are written
+    assert!(!contents.lines().any(|l| l.contains(";mmap")));
+}
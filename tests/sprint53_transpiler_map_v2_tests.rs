@@ -0,0 +1,87 @@
+// Sprint 53: Transpiler source map v2 (multi-file, column ranges, reverse lookups)
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const V2_MAP: &str = r#"{
+    "version": 2,
+    "source_language": "python",
+    "files": [
+        { "source_file": "pkg/util.py", "generated_file": "src/util.rs" },
+        { "source_file": "pkg/main.py", "generated_file": "src/main.rs" }
+    ],
+    "mappings": [
+        {
+            "file": 0,
+            "rust": { "line": 12, "column": 5, "end_column": 40 },
+            "source": { "line": 6, "column": 12, "end_column": 25 },
+            "rust_function": "parse",
+            "source_function": "parse"
+        },
+        {
+            "file": 1,
+            "rust": { "line": 30, "end_line": 34 },
+            "source": { "line": 3 },
+            "rust_function": "main",
+            "source_function": "main"
+        }
+    ],
+    "function_map": { "parse": "parse" }
+}"#;
+
+#[test]
+fn test_v2_map_accepted_with_tracing() {
+    let dir = TempDir::new().unwrap();
+    let map = dir.path().join("app.sourcemap.json");
+    fs::write(&map, V2_MAP).unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--transpiler-map").arg(&map).arg("--").arg("true");
+    cmd.assert().success();
+}
+
+#[test]
+fn test_v2_map_stacktrace_mappings_show_all_files() {
+    let dir = TempDir::new().unwrap();
+    let map = dir.path().join("app.sourcemap.json");
+    fs::write(&map, V2_MAP).unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--transpiler-map")
+        .arg(&map)
+        .arg("--rewrite-stacktrace")
+        .arg("--")
+        .arg("true");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "parse (src/util.rs:12:5-40) -> pkg/util.py:6:12-25",
+        ))
+        .stdout(predicate::str::contains(
+            "main (src/main.rs:30-34) -> pkg/main.py:3",
+        ));
+}
+
+#[test]
+fn test_v2_map_invalid_file_index_rejected() {
+    let dir = TempDir::new().unwrap();
+    let map = dir.path().join("bad.sourcemap.json");
+    fs::write(
+        &map,
+        r#"{
+            "version": 2,
+            "source_language": "python",
+            "files": [{ "source_file": "a.py", "generated_file": "a.rs" }],
+            "mappings": [{ "file": 1, "rust": { "line": 1 }, "source": { "line": 1 } }]
+        }"#,
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--transpiler-map").arg(&map).arg("--").arg("true");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid source map"));
+}
@@ -0,0 +1,108 @@
+// Sprint 54: Standard Source Map v3 (VLQ) ingestion via --transpiler-map
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// Program issuing `write` from line 3 of its own source (not from libc),
+/// so DWARF resolves the syscall site to `prog.c:3`
+const PROGRAM: &str = r#"static long raw_write(int fd, const void *buf, unsigned long n) {
+    long ret;
+    __asm__ volatile("syscall" : "=a"(ret) : "a"(1), "D"(fd), "S"(buf), "d"(n) : "rcx", "r11", "memory");
+    return ret;
+}
+int main(void) {
+    raw_write(1, "hi\n", 3);
+    return 0;
+}
+"#;
+
+/// prog.c line 3 → app.ts line 10, column 5, name "rawWrite"
+const V3_MAP: &str = r#"{
+    "version": 3,
+    "file": "prog.c",
+    "sources": ["app.ts"],
+    "sourcesContent": [null],
+    "names": ["rawWrite"],
+    "mappings": ";;AASIA"
+}"#;
+
+#[test]
+fn test_v3_map_rewrites_source_locations() {
+    let dir = TempDir::new().unwrap();
+    let source = dir.path().join("prog.c");
+    let program = dir.path().join("prog");
+    let map = dir.path().join("prog.c.map");
+    fs::write(&source, PROGRAM).unwrap();
+    fs::write(&map, V3_MAP).unwrap();
+
+    let status = std::process::Command::new("gcc")
+        .arg("-g")
+        .arg("-O0")
+        .arg(&source)
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("Failed to compile test program");
+    assert!(status.success());
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-s")
+        .arg("--transpiler-map")
+        .arg(&map)
+        .arg("-e")
+        .arg("trace=write")
+        .arg("--")
+        .arg(&program);
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "app.ts:10 in rawWrite [typescript] write(",
+    ));
+}
+
+#[test]
+fn test_v3_map_stacktrace_and_error_mappings() {
+    let dir = TempDir::new().unwrap();
+    let map = dir.path().join("app.rs.map");
+    fs::write(
+        &map,
+        r#"{
+            "version": 3,
+            "sources": ["app.ts"],
+            "names": ["main"],
+            "mappings": "AAAAA;;AAEA"
+        }"#,
+    )
+    .unwrap();
+
+    // "file" is missing, so the generated file comes from the map name
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--transpiler-map")
+        .arg(&map)
+        .arg("--rewrite-stacktrace")
+        .arg("--rewrite-errors")
+        .arg("--")
+        .arg("true");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main (app.rs:1:1) -> app.ts:1:1"))
+        .stdout(predicate::str::contains("  app.rs:3:1 -> app.ts:3:1"));
+}
+
+#[test]
+fn test_v3_map_invalid_vlq_rejected() {
+    let dir = TempDir::new().unwrap();
+    let map = dir.path().join("bad.map");
+    fs::write(
+        &map,
+        r#"{"version": 3, "sources": ["a.ts"], "names": [], "mappings": "A$AA"}"#,
+    )
+    .unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--transpiler-map").arg(&map).arg("--").arg("true");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("bad VLQ character"));
+}
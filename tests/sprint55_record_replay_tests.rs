@@ -0,0 +1,181 @@
+// Sprint 55: Trace recording and offline replay
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Record `cat <input>` into `trace`, with call stacks
+fn record_cat(trace: &Path, input: &Path) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(trace)
+        .arg("--stacks")
+        .arg("--")
+        .arg("cat")
+        .arg(input);
+
+    cmd.assert()
+        .success()
+        .stdout("hello\n")
+        .stderr(predicate::str::contains("trace recorded to"));
+}
+
+fn setup() -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let input = dir.path().join("input.txt");
+    fs::write(&input, "hello\n").unwrap();
+    let trace = dir.path().join("trace.rnc");
+    record_cat(&trace, &input);
+    (dir, trace)
+}
+
+#[test]
+fn test_record_writes_trace_file() {
+    let (_dir, trace) = setup();
+    let bytes = fs::read(&trace).unwrap();
+    assert!(bytes.starts_with(b"RENACER\0"));
+}
+
+#[test]
+fn test_record_propagates_exit_code() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("trace.rnc");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("exit 3");
+    cmd.assert().code(3);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&trace).arg("--format").arg("json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"exit_code\": 3"));
+}
+
+#[test]
+fn test_replay_text_output() {
+    let (_dir, trace) = setup();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&trace);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("input.txt\", 0x0) = 3"))
+        .stdout(predicate::str::contains("write(0x1, "));
+}
+
+#[test]
+fn test_replay_statistics_and_filter() {
+    let (_dir, trace) = setup();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay")
+        .arg(&trace)
+        .arg("-c")
+        .arg("-e")
+        .arg("trace=read,write");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("% time"), "{}", stderr);
+    assert!(stderr.contains(" read"));
+    assert!(stderr.contains(" write"));
+    assert!(!stderr.contains("openat"));
+}
+
+#[test]
+fn test_replay_json_output() {
+    let (_dir, trace) = setup();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&trace).arg("--format").arg("json");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let syscalls = json["syscalls"].as_array().unwrap();
+    assert!(syscalls.iter().any(|s| {
+        s["name"] == "openat"
+            && s["args"][1]
+                .as_str()
+                .is_some_and(|path| path.ends_with("input.txt\""))
+    }));
+}
+
+#[test]
+fn test_replay_flamegraph_from_recorded_stacks() {
+    let (dir, trace) = setup();
+    let folded = dir.path().join("stacks.folded");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay")
+        .arg(&trace)
+        .arg("--flamegraph-folded")
+        .arg(&folded);
+    cmd.assert().success();
+
+    let contents = fs::read_to_string(&folded).unwrap();
+    assert!(contents.lines().any(|line| line.contains(";read ")));
+}
+
+#[test]
+fn test_replay_rejects_non_trace_file() {
+    let dir = TempDir::new().unwrap();
+    let bogus = dir.path().join("bogus.rnc");
+    fs::write(&bogus, "definitely not a trace").unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&bogus);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Not a renacer trace file"));
+}
+
+#[test]
+fn test_record_spawn_precedes_child_events() {
+    use renacer::trace_file::{TraceEvent, TraceReader};
+
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("trace.rnc");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-f")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("for i in 1 2 3 4 5 6 7 8; do true & done; wait");
+    cmd.assert().success();
+
+    let reader = TraceReader::open(&trace).unwrap();
+    let mut known = std::collections::HashSet::from([reader.header().pid]);
+    let mut spawns = 0;
+    for event in reader {
+        let pid = match event.unwrap() {
+            TraceEvent::Spawn { pid, .. } => {
+                spawns += 1;
+                known.insert(pid);
+                continue;
+            }
+            TraceEvent::Exec { pid, .. }
+            | TraceEvent::Signal { pid, .. }
+            | TraceEvent::Mappings { pid, .. }
+            | TraceEvent::Exit { pid, .. } => pid,
+            TraceEvent::Syscall(record) => record.pid,
+            TraceEvent::Memory(sample) => sample.pid,
+        };
+        assert!(known.contains(&pid), "event for {} before its spawn", pid);
+    }
+    assert_eq!(spawns, 8);
+}
@@ -0,0 +1,102 @@
+// Sprint 56: renacer diff over recorded traces
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Record `sh -c <script>` into `trace`
+fn record_sh(trace: &Path, script: &str) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(trace)
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg(script);
+    cmd.assert().success();
+}
+
+#[test]
+fn test_diff_text_report() {
+    let dir = TempDir::new().unwrap();
+    let baseline = dir.path().join("base.rnc");
+    let current = dir.path().join("cur.rnc");
+    record_sh(&baseline, "true");
+    record_sh(&current, "cat /dev/null; true");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("diff").arg(&baseline).arg(&current);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(matches!(output.status.code(), Some(0) | Some(2)));
+    assert!(stdout.contains("=== Renacer Trace Diff ==="), "{}", stdout);
+    assert!(stdout.contains("Cluster time attribution"));
+    assert!(stdout.contains("Syscall sequences (3-grams)"));
+    assert!(stdout.contains("Regression verdict (per-call durations"));
+}
+
+#[test]
+fn test_diff_json_report_over_run_directories() {
+    let dir = TempDir::new().unwrap();
+    let baseline = dir.path().join("baseline");
+    let current = dir.path().join("current");
+    fs::create_dir(&baseline).unwrap();
+    fs::create_dir(&current).unwrap();
+    for i in 0..2 {
+        record_sh(&baseline.join(format!("run{}.rnc", i)), "true");
+        record_sh(&current.join(format!("run{}.rnc", i)), "true");
+    }
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("diff")
+        .arg(&baseline)
+        .arg(&current)
+        .arg("--ngram")
+        .arg("2")
+        .arg("--format")
+        .arg("json");
+    let output = cmd.output().unwrap();
+    assert!(matches!(output.status.code(), Some(0) | Some(2)));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["baseline"]["runs"], 2);
+    assert_eq!(json["current"]["runs"], 2);
+    assert_eq!(json["ngram_size"], 2);
+    assert!(json["regression"]["verdict"].is_string());
+    assert!(!json["clusters"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_diff_rejects_csv_format() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("t.rnc");
+    record_sh(&trace, "true");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("diff")
+        .arg(&trace)
+        .arg(&trace)
+        .arg("--format")
+        .arg("csv");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("supports text and json"));
+}
+
+#[test]
+fn test_diff_empty_directory_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("t.rnc");
+    record_sh(&trace, "true");
+    let empty = dir.path().join("empty");
+    fs::create_dir(&empty).unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("diff").arg(&empty).arg(&trace);
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("No .rnc trace files"));
+}
@@ -0,0 +1,98 @@
+// Sprint 57: renacer bench multi-run baselines
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_bench_saves_baseline_with_one_sample_per_run() {
+    let dir = TempDir::new().unwrap();
+    let baseline = dir.path().join("baseline.json");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("bench")
+        .arg("--runs")
+        .arg("3")
+        .arg("--warmup")
+        .arg("1")
+        .arg("-o")
+        .arg(&baseline)
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("calls/run"))
+        .stderr(predicate::str::contains("warm-up run 1/1"))
+        .stderr(predicate::str::contains("baseline saved to"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&baseline).unwrap()).unwrap();
+    assert_eq!(json["runs"], 3);
+    assert_eq!(json["warmup"], 1);
+    assert_eq!(json["command"][0], "true");
+    let syscalls = json["syscalls"].as_object().unwrap();
+    assert!(!syscalls.is_empty());
+    for samples in syscalls.values() {
+        assert_eq!(samples["counts"].as_array().unwrap().len(), 3);
+        assert_eq!(samples["total_time_us"].as_array().unwrap().len(), 3);
+    }
+}
+
+#[test]
+fn test_bench_compare_baseline_reports_verdict() {
+    let dir = TempDir::new().unwrap();
+    let baseline = dir.path().join("baseline.json");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("bench")
+        .arg("--runs")
+        .arg("5")
+        .arg("-o")
+        .arg(&baseline)
+        .arg("--")
+        .arg("true");
+    cmd.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("bench")
+        .arg("--runs")
+        .arg("5")
+        .arg("--compare-baseline")
+        .arg(&baseline)
+        .arg("--")
+        .arg("true");
+    let output = cmd.output().unwrap();
+    assert!(matches!(output.status.code(), Some(0) | Some(2)));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("=== Regression vs"), "{}", stdout);
+    assert!(stdout.contains("Statistical tests") || stdout.contains("Statistical Tests"));
+}
+
+#[test]
+fn test_bench_fails_when_command_fails() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("bench")
+        .arg("--runs")
+        .arg("2")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg("exit 4");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("failed under trace"));
+}
+
+#[test]
+fn test_bench_missing_baseline_is_an_error() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("bench")
+        .arg("--compare-baseline")
+        .arg("/nonexistent/baseline.json")
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains("Failed to read baseline"));
+}
@@ -0,0 +1,146 @@
+// Sprint 58: Cluster packs from the CLI and -c --by-cluster summaries
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+/// Cluster pack classifying ioctl on /dev/null
+const NULL_DEVICE_PACK: &str = r#"
+[[cluster]]
+name = "NullDevice"
+description = "ioctl on /dev/null"
+syscalls = ["ioctl"]
+expected_for_transpiler = false
+anomaly_threshold = 0.0
+severity = "high"
+
+[cluster.args_filter]
+fd_path_pattern = "/dev/null"
+
+[[cluster]]
+name = "Listing"
+description = "Directory reads"
+syscalls = ["getdents64"]
+expected_for_transpiler = true
+anomaly_threshold = 0.5
+severity = "low"
+"#;
+
+/// Run `renacer <args> -- ls /` in `dir` with stdout on /dev/null, returning stderr
+fn run_ls_with_null_stdout(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_renacer"))
+        .current_dir(dir)
+        .args(args)
+        .args(["--", "ls", "/"])
+        .stdout(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn test_by_cluster_uses_builtin_pack() {
+    let dir = TempDir::new().unwrap();
+    let stderr = run_ls_with_null_stdout(dir.path(), &["-c", "--by-cluster"]);
+
+    assert!(stderr.contains("% time"), "{}", stderr);
+    assert!(stderr.contains("Cluster summary (built-in clusters-default.toml)"));
+    assert!(stderr.contains("FileIO"));
+    assert!(stderr.contains("MemoryAllocation"));
+}
+
+#[test]
+fn test_project_pack_and_inherited_fd_filter() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("renacer-clusters.toml"), NULL_DEVICE_PACK).unwrap();
+
+    // ls probes stdout with ioctl; stdout is the inherited /dev/null
+    let stderr = run_ls_with_null_stdout(dir.path(), &["-c", "--by-cluster"]);
+    assert!(
+        stderr.contains("Cluster summary (renacer-clusters.toml)"),
+        "{}",
+        stderr
+    );
+    assert!(stderr
+        .lines()
+        .any(|line| line.contains("NullDevice") && line.ends_with("YES (high)")));
+    assert!(stderr
+        .lines()
+        .any(|line| line.contains("Listing") && line.ends_with('-')));
+}
+
+#[test]
+fn test_explicit_clusters_flag_overrides_project_pack() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("renacer-clusters.toml"), NULL_DEVICE_PACK).unwrap();
+    let custom = dir.path().join("custom.toml");
+    fs::write(
+        &custom,
+        r#"
+[[cluster]]
+name = "Mapping"
+description = "Memory mappings"
+syscalls = ["mmap"]
+expected_for_transpiler = true
+anomaly_threshold = 0.5
+severity = "low"
+"#,
+    )
+    .unwrap();
+
+    let stderr = run_ls_with_null_stdout(
+        dir.path(),
+        &["-c", "--by-cluster", "--clusters", custom.to_str().unwrap()],
+    );
+    assert!(stderr.contains("Mapping"), "{}", stderr);
+    assert!(!stderr.contains("NullDevice"));
+}
+
+#[test]
+fn test_by_cluster_requires_statistics() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--by-cluster").arg("--").arg("true");
+    cmd.assert().failure();
+}
+
+#[test]
+fn test_invalid_cluster_pack_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let bad = dir.path().join("bad.toml");
+    fs::write(&bad, "not = [valid").unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--by-cluster")
+        .arg("--clusters")
+        .arg(&bad)
+        .arg("--")
+        .arg("true");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Failed to parse TOML cluster definitions",
+    ));
+}
+
+#[test]
+fn test_replay_by_cluster() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("trace.rnc");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("true");
+    cmd.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&trace).arg("-c").arg("--by-cluster");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Cluster summary"))
+        .stderr(predicate::str::contains("MemoryAllocation"));
+}
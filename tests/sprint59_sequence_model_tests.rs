@@ -0,0 +1,124 @@
+// Sprint 59: Learned syscall sequence models and realtime window checks
+
+use predicates::prelude::*;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Learn a model of `command` into `model`
+fn learn(model: &Path, runs: &str, command: &[&str]) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("learn")
+        .arg("-o")
+        .arg(model)
+        .arg("--runs")
+        .arg(runs)
+        .arg("--")
+        .args(command);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("sequence model saved to"));
+}
+
+#[test]
+fn test_learned_program_stays_inside_model() {
+    let dir = TempDir::new().unwrap();
+    let model = dir.path().join("true.apr");
+    learn(&model, "2", &["true"]);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--anomaly-realtime")
+        .arg("--sequence-model")
+        .arg(&model)
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("=== Sequence Model Report ==="))
+        .stderr(predicate::str::contains("Model: true ("))
+        .stderr(predicate::str::contains("SEQUENCE ANOMALY").not());
+}
+
+#[test]
+fn test_other_program_is_flagged() {
+    let dir = TempDir::new().unwrap();
+    let model = dir.path().join("true.apr");
+    learn(&model, "1", &["true"]);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--anomaly-realtime")
+        .arg("--sequence-model")
+        .arg(&model)
+        .arg("--")
+        .arg("ls")
+        .arg("/");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "sequence model was learned from true, not ls",
+        ))
+        .stderr(predicate::str::contains("SEQUENCE ANOMALY"))
+        .stderr(predicate::str::contains("(pid "));
+}
+
+#[test]
+fn test_learn_from_recorded_traces() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("run.rnc");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("true");
+    cmd.assert().success();
+
+    let model = dir.path().join("model.apr");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("learn")
+        .arg("-o")
+        .arg(&model)
+        .arg("--trace")
+        .arg(&trace)
+        .arg("--window")
+        .arg("2");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("syscalls from 1 runs of true"));
+
+    // Replays are checked against the model too
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay")
+        .arg(&trace)
+        .arg("-c")
+        .arg("--anomaly-realtime")
+        .arg("--sequence-model")
+        .arg(&model);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("outside model: 0 "));
+}
+
+#[test]
+fn test_learn_requires_command_or_trace() {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("learn").arg("-o").arg(dir.path().join("m.apr"));
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Must specify a command or --trace",
+    ));
+}
+
+#[test]
+fn test_missing_model_is_an_error() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--anomaly-realtime")
+        .arg("--sequence-model")
+        .arg("/nonexistent/model.apr")
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Model file not found"));
+}
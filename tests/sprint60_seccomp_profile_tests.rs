@@ -0,0 +1,107 @@
+// Sprint 60: Seccomp allow-lists from recorded traces
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Record `ls /` into `trace`
+fn record_ls(trace: &Path) {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(trace)
+        .arg("--")
+        .arg("ls")
+        .arg("/");
+    cmd.assert().success();
+}
+
+#[test]
+fn test_oci_profile_allows_observed_syscalls() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("ls.rnc");
+    record_ls(&trace);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("seccomp-profile").arg(&trace);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["defaultAction"], "SCMP_ACT_ERRNO");
+    let names: Vec<&str> = json["syscalls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|rule| rule["names"].as_array().unwrap())
+        .filter_map(|name| name.as_str())
+        .collect();
+    assert!(names.contains(&"openat"));
+    assert!(names.contains(&"getdents64"));
+    assert!(!names.contains(&"socket"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("syscalls allowed from 1 runs"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_error_paths_are_reported_and_can_be_denied() {
+    let dir = TempDir::new().unwrap();
+    let trace = dir.path().join("ls.rnc");
+    record_ls(&trace);
+
+    // ls probes its (non-terminal) stdout with ioctl, which fails with ENOTTY
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("seccomp-profile")
+        .arg(&trace)
+        .arg("--emit")
+        .arg("c")
+        .arg("--deny-error-only");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "seccomp_init(SCMP_ACT_ERRNO(EPERM))",
+        ))
+        .stdout(predicate::str::contains("SCMP_SYS(ioctl)").not())
+        .stderr(predicate::str::contains(
+            "Syscalls seen only on error paths (denied):",
+        ))
+        .stderr(predicate::str::contains("ENOTTY"));
+}
+
+#[test]
+fn test_rust_filter_over_run_directory() {
+    let dir = TempDir::new().unwrap();
+    let runs = dir.path().join("runs");
+    fs::create_dir(&runs).unwrap();
+    record_ls(&runs.join("run0.rnc"));
+    record_ls(&runs.join("run1.rnc"));
+    let out = dir.path().join("filter.rs");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("seccomp-profile")
+        .arg(&runs)
+        .arg("--emit")
+        .arg("rust")
+        .arg("-o")
+        .arg(&out);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("from 2 runs"));
+
+    let rust = fs::read_to_string(&out).unwrap();
+    assert!(rust.contains("pub fn install_seccomp_filter()"));
+    assert!(rust.contains("(libc::SYS_openat, vec![]),"));
+}
+
+#[test]
+fn test_seccomp_profile_requires_traces() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("seccomp-profile");
+    cmd.assert().failure();
+}
@@ -0,0 +1,97 @@
+// Sprint 61: File access audit (reads, writes, creates, deletes per process)
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_text_tree_resolves_relative_paths() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("in.txt"), "data").unwrap();
+    let root = dir.path().canonicalize().unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("--file-audit")
+        .arg("--")
+        .arg("cp")
+        .arg("in.txt")
+        .arg("out.txt");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("=== File Access Audit ==="), "{}", stderr);
+    assert!(stderr.contains("/cp"));
+    assert!(stderr.contains(&format!("read     {}/in.txt", root.display())));
+    assert!(stderr.contains(&format!("create   {}/out.txt", root.display())));
+    // Text output of the syscalls themselves is unchanged
+    assert!(String::from_utf8_lossy(&output.stdout).contains("openat("));
+}
+
+#[test]
+fn test_json_report_lists_deletes_and_missing_files() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("old.txt"), "data").unwrap();
+    let report = dir.path().join("files.json");
+    let root = dir.path().canonicalize().unwrap();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("-c")
+        .arg("--file-audit-json")
+        .arg(&report)
+        .arg("--")
+        .arg("rm")
+        .arg("-f")
+        .arg("old.txt")
+        .arg("nosuch.txt");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("File access audit written to"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let process = &json["processes"][0];
+    let deletes: Vec<&str> = process["deletes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|p| p.as_str())
+        .collect();
+    assert_eq!(deletes, vec![format!("{}/old.txt", root.display())]);
+    let missing: Vec<&str> = process["missing"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|p| p.as_str())
+        .collect();
+    assert!(missing.contains(&format!("{}/nosuch.txt", root.display()).as_str()));
+}
+
+#[test]
+fn test_replay_file_audit() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("in.txt"), "data").unwrap();
+    let trace = dir.path().join("cp.rnc");
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("cp")
+        .arg("in.txt")
+        .arg("copy.txt");
+    cmd.assert().success();
+
+    // The recording's cwd is unknown, so paths stay relative
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("replay").arg(&trace).arg("-c").arg("--file-audit");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("] cp\n"))
+        .stderr(predicate::str::contains("read     in.txt"))
+        .stderr(predicate::str::contains("create   copy.txt"));
+}
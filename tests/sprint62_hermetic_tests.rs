@@ -0,0 +1,135 @@
+// Sprint 62: Build hermeticity checks (renacer hermetic)
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Project with one input file and an allow-list for it
+fn project(extra: &str) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::create_dir(dir.path().join("out")).unwrap();
+    fs::create_dir(dir.path().join("home")).unwrap();
+    fs::write(dir.path().join("src/in.txt"), "data").unwrap();
+    fs::write(dir.path().join("home/.buildrc"), "opt=1").unwrap();
+    fs::write(
+        dir.path().join("inputs.toml"),
+        format!(
+            "inputs = [\"src\"]\n\
+             toolchain = [\"/usr\", \"/lib\", \"/lib64\", \"/etc\"]\n\
+             outputs = [\"out\"]\n{}",
+            extra
+        ),
+    )
+    .unwrap();
+    dir
+}
+
+fn hermetic(dir: &Path, script: &str) -> std::process::Output {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir)
+        .env("HOME", dir.join("home"))
+        .arg("hermetic")
+        .arg("--allow-list")
+        .arg("inputs.toml")
+        .arg("--")
+        .arg("sh")
+        .arg("-c")
+        .arg(script);
+    cmd.output().unwrap()
+}
+
+#[test]
+fn test_declared_build_passes() {
+    let dir = project("");
+    let output = hermetic(dir.path(), "cp src/in.txt out/in.txt");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("=== Hermeticity Check ==="));
+    assert!(stdout.contains("PASS"));
+    assert!(dir.path().join("out/in.txt").exists());
+}
+
+#[test]
+fn test_undeclared_files_fail_the_check() {
+    let dir = project("");
+    let root = dir.path().canonicalize().unwrap();
+    let leak = root.join("leak.txt");
+    let output = hermetic(
+        dir.path(),
+        &format!("cat \"$HOME/.buildrc\" src/in.txt > {}", leak.display()),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains(&format!(
+        "environment input  {}/home/.buildrc (under $HOME)",
+        root.display()
+    )));
+    assert!(stdout.contains(&format!("undeclared output  {}", leak.display())));
+    assert!(!stdout.contains("src/in.txt"));
+    assert!(stdout.contains("FAIL: Build is not hermetic: 2 violations"));
+}
+
+#[test]
+fn test_failed_build_is_reported_with_its_output() {
+    let dir = project("");
+    let output = hermetic(
+        dir.path(),
+        "echo compiling; cp src/in.txt leak.txt; echo oops >&2; exit 4",
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.starts_with("compiling\n"), "{}", stdout);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("oops"));
+    assert!(stdout.contains("undeclared output"));
+    assert!(stdout.contains("Build exited with status 4"));
+}
+
+#[test]
+fn test_network_sockets_fail_unless_allowed() {
+    // bash opens an AF_INET socket for /dev/tcp; the connection itself may fail
+    let script = "bash -c 'echo > /dev/tcp/127.0.0.1/9' 2>/dev/null; true";
+
+    let dir = project("");
+    let output = hermetic(dir.path(), script);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains("network access     socket(AF_INET)"));
+
+    let dir = project("allow_network = true\n");
+    let output = hermetic(dir.path(), script);
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("network access"));
+}
+
+#[test]
+fn test_recorded_trace_is_checked() {
+    let dir = project("");
+    let trace = dir.path().join("build.rnc");
+    let mut record = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    record
+        .current_dir(dir.path())
+        .arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("cp")
+        .arg("src/in.txt")
+        .arg("copy.txt");
+    record.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("hermetic")
+        .arg("--allow-list")
+        .arg("inputs.toml")
+        .arg("--trace")
+        .arg(&trace);
+    cmd.assert()
+        .code(1)
+        .stdout(predicate::str::contains("undeclared output"))
+        .stdout(predicate::str::contains("copy.txt"));
+}
@@ -0,0 +1,104 @@
+// Sprint 63: Network activity decoding and per-process connection table
+
+use predicates::prelude::*;
+use std::net::TcpListener;
+use std::thread;
+
+/// Local listener that accepts one connection and echoes nothing back
+fn listener() -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let handle = thread::spawn(move || {
+        let _ = listener.accept();
+    });
+    (port, handle)
+}
+
+/// bash connects through /dev/tcp and writes one line
+fn client(port: u16) -> String {
+    format!("echo hello > /dev/tcp/127.0.0.1/{}", port)
+}
+
+#[test]
+fn test_socket_and_connect_arguments_are_decoded() {
+    let (port, server) = listener();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-e")
+        .arg("trace=network")
+        .arg("--")
+        .arg("bash")
+        .arg("-c")
+        .arg(client(port));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("socket(AF_INET, SOCK_STREAM"))
+        .stdout(predicate::str::contains(format!(
+            "connect(3, {{127.0.0.1:{}}}, 0x10) = 0",
+            port
+        )));
+    server.join().unwrap();
+}
+
+#[test]
+fn test_connection_table_lists_endpoints_and_bytes() {
+    let (port, server) = listener();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--connections")
+        .arg("--")
+        .arg("bash")
+        .arg("-c")
+        .arg(client(port));
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(stderr.contains("=== Network Connections ==="), "{}", stderr);
+    let row = stderr
+        .lines()
+        .find(|line| line.contains(&format!("127.0.0.1:{}", port)))
+        .unwrap_or_else(|| panic!("no connection row in {}", stderr));
+    assert!(row.contains(" tcp "), "{}", row);
+    // "hello\n" written to the socket
+    assert!(row.split_whitespace().any(|field| field == "6"), "{}", row);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_summary_and_html_include_connections() {
+    let (port, server) = listener();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-c")
+        .arg("--")
+        .arg("bash")
+        .arg("-c")
+        .arg(client(port));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("=== Network Connections ==="));
+    server.join().unwrap();
+
+    let (port, server) = listener();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--format")
+        .arg("html")
+        .arg("--")
+        .arg("bash")
+        .arg("-c")
+        .arg(client(port));
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("<h2>Network Connections</h2>"))
+        .stdout(predicate::str::contains(format!("127.0.0.1:{}", port)));
+    server.join().unwrap();
+}
+
+#[test]
+fn test_without_sockets_no_table_is_printed() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--connections").arg("--").arg("true");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Network Connections").not());
+}
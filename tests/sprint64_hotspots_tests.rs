@@ -0,0 +1,124 @@
+// Sprint 64: Time attribution per cluster and hotspots in the live tracer
+
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+/// Cluster pack where sleeping is its own (unexpected) cluster
+const SLEEP_PACK: &str = r#"
+[[cluster]]
+name = "Sleeping"
+description = "Timed waits"
+syscalls = ["nanosleep", "clock_nanosleep"]
+expected_for_transpiler = false
+anomaly_threshold = 0.0
+severity = "high"
+"#;
+
+fn sleep_pack() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("sleep.toml"), SLEEP_PACK).unwrap();
+    dir
+}
+
+#[test]
+fn test_text_report_flags_unexpected_hotspot() {
+    let dir = sleep_pack();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("--hotspots")
+        .arg("--clusters")
+        .arg("sleep.toml")
+        .arg("--")
+        .arg("sleep")
+        .arg("0.2");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        stderr.contains("Time attribution (sleep.toml):"),
+        "{}",
+        stderr
+    );
+    let row = stderr
+        .lines()
+        .find(|line| line.ends_with(" Sleeping"))
+        .unwrap_or_else(|| panic!("no Sleeping row in {}", stderr));
+    let percent: f64 = row.split_whitespace().next().unwrap().parse().unwrap();
+    assert!(percent > 50.0, "{}", row);
+    assert!(stderr.contains("⚠️ Sleeping"));
+    assert!(stderr.contains("unexpected hotspot"));
+}
+
+#[test]
+fn test_json_report_includes_hotspots() {
+    let dir = sleep_pack();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("--hotspots")
+        .arg("--clusters")
+        .arg("sleep.toml")
+        .arg("--format")
+        .arg("json")
+        .arg("--")
+        .arg("sleep")
+        .arg("0.1");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Time attribution"));
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let analysis = &json["hotspots"];
+    assert_eq!(analysis["clusters_source"], "sleep.toml");
+    assert!(analysis["wall_time_us"].as_u64().unwrap() >= 100_000);
+    let hotspot = analysis["hotspots"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|h| h["cluster"] == "Sleeping")
+        .expect("Sleeping hotspot");
+    assert_eq!(hotspot["expected"], false);
+}
+
+#[test]
+fn test_html_report_has_stacked_bar() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--hotspots")
+        .arg("--format")
+        .arg("html")
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("<h2>Time Attribution</h2>"))
+        .stdout(predicate::str::contains("<div class=\"cluster-bar\">"))
+        .stdout(predicate::str::contains("title=\"MemoryAllocation: "));
+}
+
+#[test]
+fn test_replay_hotspots_use_recorded_timing() {
+    let dir = sleep_pack();
+    let trace = dir.path().join("sleep.rnc");
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("sleep")
+        .arg("0.1");
+    cmd.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("replay")
+        .arg(&trace)
+        .arg("-c")
+        .arg("--hotspots")
+        .arg("--clusters")
+        .arg("sleep.toml");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Time attribution (sleep.toml):"))
+        .stderr(predicate::str::contains("unexpected hotspot"));
+}
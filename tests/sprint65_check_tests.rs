@@ -0,0 +1,119 @@
+// Sprint 65: renacer check (renacer.toml assertions against a traced run)
+
+mod common;
+
+use common::{check, project};
+use predicates::prelude::*;
+use std::fs;
+use tempfile::TempDir;
+
+const GENEROUS: &str = r#"
+[[assertion]]
+name = "few_syscalls"
+type = "span_count"
+max_spans = 100000
+fail_on_violation = true
+"#;
+
+const STRICT: &str = r#"
+[[assertion]]
+name = "few_syscalls"
+type = "span_count"
+max_spans = 1
+fail_on_violation = true
+
+[[assertion]]
+name = "soft_latency"
+type = "critical_path"
+max_duration_ms = 0
+fail_on_violation = false
+"#;
+
+#[test]
+fn test_passing_check_exits_zero() {
+    check(GENEROUS, &["true"])
+        .success()
+        .stdout(predicate::str::contains(
+            "=== Assertion Check (renacer.toml) ===",
+        ))
+        .stdout(predicate::str::contains("✅ PASS few_syscalls"))
+        .stdout(predicate::str::contains("✅ All 1 assertions passed"));
+}
+
+#[test]
+fn test_failing_check_writes_junit_and_exits_one() {
+    let dir = project(STRICT);
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("check")
+        .arg("--junit")
+        .arg("results.xml")
+        .arg("--")
+        .arg("ls");
+    cmd.assert()
+        .code(1)
+        .stdout(predicate::str::contains("❌ FAIL few_syscalls"))
+        .stdout(predicate::str::contains("🛑 ANDON: stop the line, 1 of 2"))
+        .stderr(predicate::str::contains(
+            "JUnit report written to results.xml",
+        ));
+
+    let junit = fs::read_to_string(dir.path().join("results.xml")).unwrap();
+    assert!(junit.starts_with("<?xml"));
+    assert!(junit.contains("tests=\"2\" failures=\"1\" skipped=\"0\""));
+    assert!(junit.contains("<testcase classname=\"renacer.check\" name=\"few_syscalls\">"));
+    assert!(junit.contains("<failure message=\"Span count "));
+}
+
+#[test]
+fn test_failing_command_is_still_checked() {
+    check(STRICT, &["sh", "-c", "exit 3"])
+        .code(1)
+        .stdout(predicate::str::contains("❌ FAIL few_syscalls"))
+        .stdout(predicate::str::contains("soft_latency"))
+        .stdout(predicate::str::contains("Command exited with status 3"));
+
+    check(GENEROUS, &["sh", "-c", "exit 3"])
+        .success()
+        .stdout(predicate::str::contains("✅ PASS few_syscalls"))
+        .stdout(predicate::str::contains("Command exited with status 3"));
+}
+
+#[test]
+fn test_recorded_trace_with_explicit_config() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("strict.toml"), STRICT).unwrap();
+    let trace = dir.path().join("ls.rnc");
+    let mut record = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    record
+        .arg("record")
+        .arg("-o")
+        .arg(&trace)
+        .arg("--")
+        .arg("ls");
+    record.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("check")
+        .arg("--config")
+        .arg("strict.toml")
+        .arg("--trace")
+        .arg(&trace);
+    cmd.assert()
+        .code(1)
+        .stdout(predicate::str::contains("Assertion Check (strict.toml)"));
+}
+
+#[test]
+fn test_missing_config_is_an_error() {
+    let dir = TempDir::new().unwrap();
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .arg("check")
+        .arg("--")
+        .arg("true");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("Failed to read renacer.toml"));
+}
@@ -0,0 +1,83 @@
+// Sprint 66: anti-pattern assertions evaluated on the causal graph of a traced run
+
+mod common;
+
+use common::{check, shell_writes};
+use predicates::prelude::*;
+
+fn tight_loop(threshold: f64, process_name_pattern: &str) -> String {
+    format!(
+        r#"
+[[assertion]]
+name = "no_tight_loop"
+type = "anti_pattern"
+pattern = "TightLoop"
+threshold = {}
+process_name_pattern = "{}"
+fail_on_violation = true
+"#,
+        threshold, process_name_pattern
+    )
+}
+
+#[test]
+fn test_tight_loop_stops_the_line() {
+    check(&tight_loop(0.5, "sh"), &shell_writes(1500))
+        .code(1)
+        .stdout(predicate::str::contains(
+            "❌ FAIL no_tight_loop: Tight Loop in",
+        ))
+        .stdout(predicate::str::contains(
+            "Syscall 'write' repeated 1500 times",
+        ))
+        .stdout(predicate::str::contains("(write x1500)"))
+        .stdout(predicate::str::contains(
+            "Recommendation: Use vectorized I/O (readv/writev)",
+        ))
+        .stdout(predicate::str::contains("🛑 ANDON"));
+}
+
+#[test]
+fn test_tight_loop_below_threshold_passes() {
+    check(&tight_loop(0.99, "sh"), &shell_writes(1500))
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ PASS no_tight_loop: Tight Loop in",
+        ))
+        .stdout(predicate::str::contains("below threshold"));
+}
+
+#[test]
+fn test_process_name_pattern_excludes_other_programs() {
+    check(&tight_loop(0.5, "^python"), &shell_writes(1500))
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ PASS no_tight_loop: Anti-pattern TightLoop not detected",
+        ));
+}
+
+#[test]
+fn test_clean_run_passes_every_pattern() {
+    let config = r#"
+[[assertion]]
+name = "no_god_process"
+type = "anti_pattern"
+pattern = "GodProcess"
+threshold = 0.8
+
+[[assertion]]
+name = "no_tight_loop"
+type = "anti_pattern"
+pattern = "TightLoop"
+threshold = 0.1
+
+[[assertion]]
+name = "no_pcie_bottleneck"
+type = "anti_pattern"
+pattern = "PcieBottleneck"
+threshold = 0.5
+"#;
+    check(config, &["true"])
+        .success()
+        .stdout(predicate::str::contains("✅ All 3 assertions passed"));
+}
@@ -0,0 +1,77 @@
+// Sprint 67: memory_usage assertions from address-space growth and RSS samples
+
+mod common;
+
+use common::{check, project};
+use predicates::prelude::*;
+
+/// Refills a 50 MB buffer for long enough that RSS samples catch it resident
+const ALLOCATE: &[&str] = &[
+    "dd",
+    "if=/dev/zero",
+    "of=/dev/null",
+    "bs=50M",
+    "count=8",
+    "status=none",
+];
+
+fn memory_usage(max_bytes: u64, tracking_mode: &str) -> String {
+    format!(
+        r#"
+[[assertion]]
+name = "memory"
+type = "memory_usage"
+max_bytes = {}
+tracking_mode = "{}"
+"#,
+        max_bytes, tracking_mode
+    )
+}
+
+#[test]
+fn test_address_space_growth_exceeds_limit() {
+    check(&memory_usage(10_000_000, "allocations"), ALLOCATE)
+        .code(1)
+        .stdout(predicate::str::contains(
+            "❌ FAIL memory: Peak address-space growth",
+        ))
+        .stdout(predicate::str::contains(
+            "exceeds maximum 10000000, timeline ",
+        ));
+}
+
+#[test]
+fn test_address_space_growth_within_limit() {
+    check(&memory_usage(1_000_000_000, "allocations"), &["true"])
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ PASS memory: Peak address-space growth",
+        ))
+        .stdout(predicate::str::contains("<= 1000000000"));
+}
+
+#[test]
+fn test_rss_peak_exceeds_limit() {
+    check(&memory_usage(10_000_000, "rss"), ALLOCATE)
+        .code(1)
+        .stdout(predicate::str::contains("❌ FAIL memory: Peak RSS"))
+        .stdout(predicate::str::is_match(r"actual \d{8,} bytes, expected 10000000 bytes").unwrap());
+}
+
+#[test]
+fn test_rss_samples_survive_record_and_check_trace() {
+    let dir = project(&memory_usage(1_000_000_000, "rss"));
+
+    let mut record = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    record
+        .current_dir(dir.path())
+        .args(["record", "-o", "run.rnc", "--", "true"]);
+    record.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .args(["check", "--trace", "run.rnc"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("✅ PASS memory: Peak RSS"));
+}
@@ -0,0 +1,63 @@
+// Sprint 68: expression language for custom assertions
+
+mod common;
+
+use common::{check, shell_writes};
+use predicates::prelude::*;
+
+fn custom(expression: &str) -> String {
+    format!(
+        r#"
+[[assertion]]
+name = "custom"
+type = "custom"
+expression = '{}'
+"#,
+        expression
+    )
+}
+
+#[test]
+fn test_failed_opens_are_counted() {
+    check(
+        &custom(r#"count(syscall == "openat" && result < 0) == 0"#),
+        &["cat", "/nonexistent/renacer-sprint68"],
+    )
+    .code(1)
+    .stdout(predicate::str::contains(
+        r#"❌ FAIL custom: Custom assertion failed: count(syscall == "openat" && result < 0) is "#,
+    ))
+    .stdout(predicate::str::contains("expected == 0"));
+}
+
+#[test]
+fn test_aggregates_with_units_pass() {
+    check(
+        &custom(r#"sum(bytes, name="write") < 1MB && p99(duration, name="write") < 10s"#),
+        &["echo", "hello"],
+    )
+    .success()
+    .stdout(predicate::str::contains(
+        r#"✅ PASS custom: sum(bytes, name="write") < 1MB && p99(duration, name="write") < 10s holds"#,
+    ));
+}
+
+#[test]
+fn test_process_filter() {
+    check(
+        &custom(r#"count(process =~ "sh$", name="write") >= 100"#),
+        &shell_writes(100),
+    )
+    .success();
+}
+
+#[test]
+fn test_invalid_expression_points_into_config() {
+    check(&custom("p99(duration) < 5"), &["true"])
+        .failure()
+        .stderr(predicate::str::contains(
+            "error in assertion 'custom': cannot compare a duration with a number",
+        ))
+        .stderr(predicate::str::contains("--> renacer.toml:5:15"))
+        .stderr(predicate::str::contains("^^^^^^^^^^^^^^^^^"));
+}
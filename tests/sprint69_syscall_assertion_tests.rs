@@ -0,0 +1,92 @@
+// Sprint 69: forbidden_syscall, error_budget, latency_percentile and syscall_count assertions
+
+mod common;
+
+use common::{check, shell_writes};
+use predicates::prelude::*;
+
+/// Opens three paths below a regular file
+const NOT_DIRECTORIES: &[&str] = &[
+    "cat",
+    "/etc/passwd/renacer-sprint69-1",
+    "/etc/passwd/renacer-sprint69-2",
+    "/etc/passwd/renacer-sprint69-3",
+];
+
+#[test]
+fn test_forbidden_syscall() {
+    let config = r#"
+[[assertion]]
+name = "no_sync"
+type = "forbidden_syscall"
+syscall_pattern = "sync|syncfs"
+"#;
+    check(config, &["sync"])
+        .code(1)
+        .stdout(predicate::str::contains(
+            "❌ FAIL no_sync: Forbidden syscall matching 'sync|syncfs' called 1 time (sync x1). Top call sites: 1x sync: sync",
+        ));
+    check(config, &["true"])
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ PASS no_sync: No calls matching 'sync|syncfs'",
+        ));
+}
+
+#[test]
+fn test_error_budget() {
+    let config = r#"
+[[assertion]]
+name = "missing_files"
+type = "error_budget"
+syscall_pattern = "open|openat"
+errno = "ENOTDIR"
+max_errors = 1
+"#;
+    check(config, NOT_DIRECTORIES)
+        .code(1)
+        .stdout(predicate::str::contains(
+            "❌ FAIL missing_files: 3 ENOTDIR errors from 'open|openat' exceed budget of 1. Top call sites: 3x cat: openat",
+        ));
+}
+
+#[test]
+fn test_latency_percentile() {
+    let config = r#"
+[[assertion]]
+name = "slow_sleeps"
+type = "latency_percentile"
+syscall_pattern = "clock_nanosleep|nanosleep|pselect6|select"
+percentile = 95
+max_duration_us = 1000
+"#;
+    check(config, &["sleep", "0.02"])
+        .code(1)
+        .stdout(predicate::str::contains(
+            "❌ FAIL slow_sleeps: p95 latency of",
+        ))
+        .stdout(predicate::str::contains(
+            "exceeds maximum 1.000ms. Slowest call sites: 1x sleep:",
+        ));
+}
+
+#[test]
+fn test_syscall_count() {
+    let config = r#"
+[[assertion]]
+name = "few_writes"
+type = "syscall_count"
+syscall_pattern = "write"
+max_count = 150
+"#;
+    check(config, &shell_writes(100))
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ PASS few_writes: 'write' called 100 times <= 150",
+        ));
+    check(config, &shell_writes(200))
+        .code(1)
+        .stdout(predicate::str::contains(
+            "'write' called 200 times, exceeds maximum 150. Top call sites: 200x sh: write",
+        ));
+}
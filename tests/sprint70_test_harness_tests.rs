@@ -0,0 +1,59 @@
+// Sprint 70: renacer::testing runs renacer.toml assertions from cargo test
+
+mod common;
+
+use common::shell_writes;
+use renacer::testing::{trace_and_assert, TraceHarness};
+
+#[test]
+fn test_trace_and_assert_passes() {
+    let run = trace_and_assert(&shell_writes(20), "tests/fixtures/harness/renacer.toml");
+    assert!(!run.report.has_failures());
+    let writes = run
+        .trace
+        .syscall_spans
+        .iter()
+        .filter(|span| span.name == "write")
+        .count();
+    assert_eq!(writes, 20);
+}
+
+#[test]
+#[should_panic(expected = "1 of 2 assertions in tests/fixtures/harness/failing.toml failed")]
+fn test_trace_and_assert_panics_on_failure() {
+    trace_and_assert(&shell_writes(20), "tests/fixtures/harness/failing.toml");
+}
+
+#[test]
+fn test_failure_diff() {
+    let run = TraceHarness::new(&shell_writes(20))
+        .run("tests/fixtures/harness/failing.toml")
+        .unwrap();
+    let diff = run.failure_diff();
+    assert!(
+        diff.contains("for `sh -c exec >/dev/null; i=0;"),
+        "{}",
+        diff
+    );
+    assert!(diff.contains("write_budget: 'write' called 20 times, exceeds maximum 5"));
+    assert!(diff.contains("  - expected: 5 calls\n  + actual:   20 calls\n"));
+    assert!(!diff.contains("offline"));
+}
+
+#[test]
+fn test_command_failure_is_an_error() {
+    let error = TraceHarness::new(&["false"])
+        .run("tests/fixtures/harness/renacer.toml")
+        .unwrap_err();
+    assert!(format!("{:#}", error).contains("`false` failed under trace"));
+}
+
+#[test]
+fn test_invalid_config_is_an_error() {
+    let error = TraceHarness::new(&shell_writes(20))
+        .run("tests/fixtures/harness/missing.toml")
+        .unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("Failed to read tests/fixtures/harness/missing.toml"));
+}
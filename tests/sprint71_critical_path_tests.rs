@@ -0,0 +1,71 @@
+// Sprint 71: Critical path through the processes of a traced run
+
+use predicates::prelude::*;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// A shell running two steps, 0.2s then 0.1s
+const PIPELINE: &str = "sleep 0.2; sleep 0.1; true";
+
+#[test]
+fn test_critical_path_follows_child_processes() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["--critical-path", "--", "sh", "-c", PIPELINE])
+        .timeout(Duration::from_secs(30));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Critical path: "))
+        .stderr(predicate::str::contains("3 processes traced"))
+        .stderr(predicate::str::contains(
+            "% path     seconds  syscalls step",
+        ))
+        .stderr(predicate::str::is_match(r" 0\.2\d{5} +\d+ sleep \(pid \d+\), longest ").unwrap())
+        .stderr(predicate::str::is_match(r" 0\.1\d{5} +\d+ sleep \(pid \d+\), longest ").unwrap())
+        .stderr(predicate::str::is_match(r"\d+ sh \(pid \d+\)").unwrap());
+}
+
+#[test]
+fn test_background_job_joined_by_shell_wait() {
+    // The shell sleeps in rt_sigsuspend until SIGCHLD, so signals must reach it
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args([
+        "--critical-path",
+        "--",
+        "sh",
+        "-c",
+        "sleep 0.3 & sleep 0.05; wait",
+    ])
+    .timeout(Duration::from_secs(30));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::is_match(r" 0\.[3-9]\d{5} +\d+ sleep \(pid \d+\)").unwrap());
+}
+
+#[test]
+fn test_critical_path_from_recorded_trace() {
+    let dir = TempDir::new().unwrap();
+
+    let mut record = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    record
+        .current_dir(dir.path())
+        .args(["record", "-f", "-o", "run.rnc", "--", "sh", "-c", PIPELINE]);
+    record.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .args(["replay", "run.rnc", "--critical-path"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("3 processes traced"))
+        .stderr(predicate::str::is_match(r" 0\.2\d{5} +\d+ sleep \(pid \d+\), longest ").unwrap());
+}
+
+#[test]
+fn test_single_process_critical_path() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["--critical-path", "--", "true"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("1 process traced"))
+        .stderr(predicate::str::is_match(r"100\.00 +\d\.\d{6} +\d+ true \(pid \d+\)").unwrap());
+}
@@ -0,0 +1,55 @@
+// Sprint 72: Wait-for edges between processes (pipes, socketpairs, futexes)
+
+use predicates::prelude::*;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// The writer sleeps 0.3s and writes; the reader blocks reading, then sleeps 0.2s
+const PIPE: &str = "{ sleep 0.3; echo done; } | { read line; sleep 0.2; }";
+
+#[test]
+fn test_critical_path_follows_pipe_write_to_read() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["--critical-path", "--", "sh", "-c", PIPE])
+        .timeout(Duration::from_secs(30));
+    cmd.assert()
+        .success()
+        .stderr(
+            predicate::str::is_match(r" 0\.3\d{5} +\d+ sleep \(pid \d+\), longest clock_nanosleep")
+                .unwrap(),
+        )
+        .stderr(
+            predicate::str::is_match(
+                r" 0\.2\d{5} +\d+ sleep \(pid \d+\), longest .*, via pipe:\[\d+\]",
+            )
+            .unwrap(),
+        );
+}
+
+#[test]
+fn test_pipe_wait_for_edge_from_recorded_trace() {
+    let dir = TempDir::new().unwrap();
+
+    let mut record = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    record
+        .current_dir(dir.path())
+        .args(["record", "-f", "-o", "run.rnc", "--", "sh", "-c", PIPE])
+        .timeout(Duration::from_secs(30));
+    record.assert().success();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.current_dir(dir.path())
+        .args(["replay", "run.rnc", "--critical-path"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::is_match(r", via pipe:\[\d+\]").unwrap());
+}
+
+#[test]
+fn test_json_shows_created_fd_pair() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["--format", "json", "--", "sh", "-c", "true | true"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"\[\d+<pipe:\[\d+\]>, \d+<pipe:\[\d+\]>\]").unwrap());
+}
@@ -0,0 +1,140 @@
+// Sprint 73: Lock contention analysis from futex syscalls
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Three threads taking turns on a static mutex, holding it for 20ms each time
+const CONTENDED: &str = r#"
+#include <pthread.h>
+#include <unistd.h>
+
+pthread_mutex_t counter_lock = PTHREAD_MUTEX_INITIALIZER;
+long counter;
+
+static void *worker(void *arg) {
+    for (int i = 0; i < 5; i++) {
+        pthread_mutex_lock(&counter_lock);
+        counter++;
+        usleep(20000);
+        pthread_mutex_unlock(&counter_lock);
+    }
+    return arg;
+}
+
+static int contend(void) {
+    pthread_t threads[3];
+    for (int i = 0; i < 3; i++)
+        pthread_create(&threads[i], NULL, worker, NULL);
+    for (int i = 0; i < 3; i++)
+        pthread_join(threads[i], NULL);
+    return counter == 15 ? 0 : 1;
+}
+
+#ifdef FORKED
+#include <sys/wait.h>
+
+/* Parent and child each contend on their own copy of counter_lock */
+int main(void) {
+    pid_t child = fork();
+    int failed = contend();
+    if (child == 0)
+        return failed;
+    int status;
+    waitpid(child, &status, 0);
+    return failed || status != 0;
+}
+#else
+int main(void) {
+    return contend();
+}
+#endif
+"#;
+
+fn compile(dir: &TempDir, defines: &[&str]) -> PathBuf {
+    let source = dir.path().join("contended.c");
+    let program = dir.path().join("contended");
+    fs::write(&source, CONTENDED).unwrap();
+    let status = std::process::Command::new("gcc")
+        .args(["-g", "-O0", "-fno-omit-frame-pointer", "-pthread"])
+        .args(defines)
+        .arg(&source)
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("Failed to compile test program");
+    assert!(status.success());
+    program
+}
+
+#[test]
+fn test_contended_mutex_named_after_its_symbol() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, &[]);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--locks")
+        .arg("--")
+        .arg(&program)
+        .timeout(Duration::from_secs(60));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("=== Lock Contention"))
+        .stderr(
+            predicate::str::is_match(r"Lock counter_lock \(0x[0-9a-f]+, pid \d+\): \d+ waits, ")
+                .unwrap(),
+        )
+        .stderr(predicate::str::is_match(r"\[\d+\.\d+ms, \d+\.\d+ms\) +\d+ \|@+").unwrap())
+        .stderr(predicate::str::is_match(r"Waiters: \d+ \(\d+ waits, ").unwrap())
+        .stderr(predicate::str::is_match(r"Wakers: \d+ \(woke \d+\)").unwrap())
+        .stderr(predicate::str::contains("→ FUTEX_WAIT"));
+}
+
+#[test]
+fn test_forked_processes_have_separate_locks() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, &["-DFORKED"]);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["-f", "--locks", "--"])
+        .arg(&program)
+        .timeout(Duration::from_secs(60));
+    let output = cmd.assert().success().get_output().stderr.clone();
+    let stderr = String::from_utf8_lossy(&output);
+
+    // Both copies of counter_lock sit at the same address, one per process
+    let lock = regex::Regex::new(r"Lock counter_lock \((0x[0-9a-f]+), pid (\d+)\)").unwrap();
+    let locks: Vec<(&str, &str)> = lock
+        .captures_iter(&stderr)
+        .map(|c| c.extract().1.into())
+        .collect();
+    assert_eq!(locks.len(), 2, "{}", stderr);
+    assert_eq!(locks[0].0, locks[1].0);
+    assert_ne!(locks[0].1, locks[1].1);
+}
+
+#[test]
+fn test_futex_ops_decoded_in_trace() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, &[]);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("-f")
+        .arg("--")
+        .arg(&program)
+        .timeout(Duration::from_secs(60));
+    cmd.assert().success().stdout(
+        predicate::str::is_match(r"futex\(0x[0-9a-f]+, FUTEX_WAKE_PRIVATE, 1\) = ").unwrap(),
+    );
+}
+
+#[test]
+fn test_no_contention() {
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.args(["--locks", "--", "true"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("No contended locks recorded"));
+}
@@ -0,0 +1,166 @@
+// Sprint 74: Syscall-level anti-patterns with recommendations and call sites
+
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// One loop per detector, each well above its default threshold
+const WASTEFUL: &str = r#"
+#include <fcntl.h>
+#include <sched.h>
+#include <stdio.h>
+#include <sys/mman.h>
+#include <sys/stat.h>
+#include <time.h>
+#include <unistd.h>
+
+static void emit(int fd, char c) { write(fd, &c, 1); }
+
+int main(int argc, char **argv) {
+    int out = open("/dev/null", O_WRONLY);
+    for (int i = 0; i < 100; i++) emit(out, 'x');
+
+    int in = open("/etc/hostname", O_RDONLY);
+    char c;
+    for (int i = 0; i < 30; i++) { lseek(in, 0, SEEK_SET); read(in, &c, 1); }
+
+    struct stat st;
+    for (int i = 0; i < 12; i++) stat("/etc/hostname", &st);
+
+    const char *dirs[] = {"/nope/a", "/nope/b", "/nope/c", "/nope/d", "/nope/e", "/nope/f"};
+    char path[64];
+    for (int i = 0; i < 6; i++) {
+        snprintf(path, sizeof path, "%s/plugin.so", dirs[i]);
+        open(path, O_RDONLY);
+    }
+
+    int log = open(argv[1], O_WRONLY | O_CREAT | O_TRUNC, 0644);
+    for (int i = 0; i < 12; i++) { write(log, "entry\n", 6); fsync(log); }
+
+    struct timespec tick = {0, 1000};
+    for (int i = 0; i < 25; i++) nanosleep(&tick, NULL);
+    for (int i = 0; i < 25; i++) sched_yield();
+
+    for (int i = 0; i < 25; i++) {
+        void *p = mmap(NULL, 1 << 20, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+        munmap(p, 1 << 20);
+    }
+    return 0;
+}
+"#;
+
+/// A single buffered write: nothing to report
+const TIDY: &str = r#"
+#include <unistd.h>
+
+int main(void) {
+    char line[4096] = "all at once\n";
+    write(1, line, sizeof line);
+    return 0;
+}
+"#;
+
+fn compile(dir: &TempDir, name: &str, code: &str) -> PathBuf {
+    let source = dir.path().join(format!("{}.c", name));
+    let program = dir.path().join(name);
+    fs::write(&source, code).unwrap();
+    let status = std::process::Command::new("gcc")
+        .args(["-g", "-O0", "-fno-omit-frame-pointer"])
+        .arg(&source)
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("Failed to compile test program");
+    assert!(status.success());
+    program
+}
+
+#[test]
+fn test_every_detector_fires_with_a_recommendation() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, "wasteful", WASTEFUL);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--anti-patterns")
+        .arg("--")
+        .arg(&program)
+        .arg(dir.path().join("journal.log"))
+        .timeout(Duration::from_secs(60));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("=== Syscall Anti-Patterns ==="))
+        .stderr(predicate::str::contains(
+            "Unbuffered tiny writes: 100 writes of at most 64 bytes",
+        ))
+        .stderr(predicate::str::contains("(/dev/null)"))
+        .stderr(predicate::str::contains("1-byte reads: 30 reads"))
+        .stderr(
+            predicate::str::is_match(r"Repeated path lookups: \d+ lookups of /etc/hostname")
+                .unwrap(),
+        )
+        .stderr(predicate::str::contains(
+            "Search path probing: plugin.so missing from 6 directories",
+        ))
+        .stderr(predicate::str::contains("fsync in a loop: 12 fsync calls"))
+        .stderr(predicate::str::is_match(r"Polling loop: 25 (clock_)?nanosleep calls").unwrap())
+        .stderr(predicate::str::contains(
+            "Polling loop: 25 sched_yield calls",
+        ))
+        .stderr(predicate::str::contains("mmap/munmap churn"))
+        .stderr(predicate::str::contains("Recommendation: Buffer output"));
+}
+
+#[test]
+fn test_findings_attributed_to_the_call_site() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, "wasteful", WASTEFUL);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.arg("--anti-patterns")
+        .arg("--")
+        .arg(&program)
+        .arg(dir.path().join("journal.log"))
+        .timeout(Duration::from_secs(60));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::is_match(r"pid \d+ at \S*wasteful\.c:\d+ \(main\)").unwrap());
+}
+
+#[test]
+fn test_tidy_program_has_no_findings() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, "tidy", TIDY);
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    // cargo test points LD_LIBRARY_PATH at its target dirs, which ld.so probes
+    cmd.env_remove("LD_LIBRARY_PATH")
+        .arg("--anti-patterns")
+        .arg("--")
+        .arg(&program)
+        .timeout(Duration::from_secs(60));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("No syscall anti-patterns found."));
+}
+
+#[test]
+fn test_library_search_path_probing() {
+    let dir = TempDir::new().unwrap();
+    let program = compile(&dir, "tidy", TIDY);
+    let search_path: Vec<String> = (0..8)
+        .map(|i| dir.path().join(format!("lib{}", i)).display().to_string())
+        .collect();
+
+    let mut cmd = assert_cmd::cargo::cargo_bin_cmd!("renacer");
+    cmd.env("LD_LIBRARY_PATH", search_path.join(":"))
+        .arg("--anti-patterns")
+        .arg("--")
+        .arg(&program)
+        .timeout(Duration::from_secs(60));
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::is_match(r"Search path probing: libc\.so\.6 missing from \d+ directories .*, found at /\S+libc\.so\.6").unwrap())
+        .stderr(predicate::str::contains("LD_LIBRARY_PATH"));
+}